@@ -8,6 +8,9 @@ use embedded_graphics::{
 use embedded_text::{prelude::*, rendering::RendererFactory};
 
 const TEXT: &str = "Benchmark text!";
+const PARAGRAPH: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+    tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud \
+    exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.";
 
 fn benchmark_render_text(c: &mut Criterion) {
     let style = TextStyleBuilder::new(Font6x8)
@@ -98,6 +101,26 @@ fn benchmark_render_textbox_both_aligned(c: &mut Criterion) {
     });
 }
 
+fn benchmark_render_textbox_paragraph(c: &mut Criterion) {
+    // no background color: only the pixels of the glyphs and the underline are drawn
+    let style = TextBoxStyleBuilder::new(Font6x8)
+        .text_color(BinaryColor::On)
+        .underlined(true)
+        .build();
+
+    c.bench_function("TextBox, transparent background", |b| {
+        b.iter(|| {
+            let obj = TextBox::new(
+                black_box(PARAGRAPH),
+                Rectangle::new(Point::zero(), Point::new(127, 63)),
+            )
+            .into_styled(style);
+            let object = obj.create_renderer();
+            object.collect::<Vec<Pixel<BinaryColor>>>()
+        })
+    });
+}
+
 criterion_group!(
     render,
     benchmark_render_text,
@@ -105,5 +128,6 @@ criterion_group!(
     benchmark_render_textbox_aligned,
     benchmark_render_textbox_vertical_aligned,
     benchmark_render_textbox_both_aligned,
+    benchmark_render_textbox_paragraph,
 );
 criterion_main!(render);
@@ -0,0 +1,34 @@
+use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+use embedded_graphics_simulator::{
+    BinaryColorTheme, OutputSettingsBuilder, SimulatorDisplay, Window,
+};
+use embedded_text::prelude::*;
+
+fn main() -> Result<(), core::convert::Infallible> {
+    let text = "Lorem Ipsum\nLorem Ipsum is simply dummy text of the printing and typesetting \
+    industry. Lorem Ipsum has been the industry's standard dummy text ever since the 1500s, when \
+    an unknown printer took a galley of type and scrambled it to make a type specimen book.";
+
+    // The title is centered, the rest of the text uses the alignment of the style.
+    let alignments = [(0..11, DynamicAlignment::Center)];
+
+    let textbox_style = TextBoxStyleBuilder::new(Font6x8)
+        .alignment(DynamicAlignment::Justified)
+        .height_mode(FitToText)
+        .text_color(BinaryColor::On)
+        .build();
+
+    let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(128, 0)))
+        .into_styled(textbox_style)
+        .with_alignments(&alignments);
+
+    // Create a window just tall enough to fit the text.
+    let mut display: SimulatorDisplay<BinaryColor> = SimulatorDisplay::new(text_box.size());
+    text_box.draw(&mut display).unwrap();
+
+    let output_settings = OutputSettingsBuilder::new()
+        .theme(BinaryColorTheme::OledBlue)
+        .build();
+    Window::new("TextBox with a centered title", &output_settings).show_static(&display);
+    Ok(())
+}
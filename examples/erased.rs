@@ -0,0 +1,52 @@
+use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+use embedded_graphics_simulator::{
+    BinaryColorTheme, OutputSettingsBuilder, SimulatorDisplay, Window,
+};
+use embedded_text::{erased::ErasedTextBox, prelude::*};
+
+fn main() -> Result<(), core::convert::Infallible> {
+    let text = "Lorem Ipsum is simply dummy text of the printing and typesetting industry.";
+
+    let bounds = Rectangle::new(Point::zero(), Point::new(128, 0));
+
+    // Place the text boxes below each other.
+    let left = TextBox::new(text, bounds).into_styled(
+        TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build(),
+    );
+    let center = TextBox::new(text, bounds.translate(Point::new(0, 40))).into_styled(
+        TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build(),
+    );
+    let right = TextBox::new(text, bounds.translate(Point::new(0, 80))).into_styled(
+        TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build(),
+    );
+
+    // The text boxes have different types, but they can be stored in the same array.
+    let text_boxes = [
+        ErasedTextBox::new(&left),
+        ErasedTextBox::new(&center),
+        ErasedTextBox::new(&right),
+    ];
+
+    let mut display: SimulatorDisplay<BinaryColor> = SimulatorDisplay::new(Size::new(129, 112));
+
+    for text_box in text_boxes.iter() {
+        text_box.draw(&mut display).unwrap();
+    }
+
+    let output_settings = OutputSettingsBuilder::new()
+        .theme(BinaryColorTheme::OledBlue)
+        .build();
+    Window::new("Erased TextBoxes", &output_settings).show_static(&display);
+    Ok(())
+}
@@ -12,7 +12,7 @@ use embedded_graphics::{
     prelude::*,
     style::PrimitiveStyle,
 };
-use embedded_text::{prelude::*, style::vertical_overdraw::FullRowsOnly};
+use embedded_text::prelude::*;
 use sdl2::keyboard::Keycode;
 use std::{thread, time::Duration};
 
@@ -58,12 +58,7 @@ impl ProcessedEvent {
     }
 }
 
-fn demo_loop<A>(window: &mut Window, bounds: &mut Rectangle, alignment: A) -> bool
-where
-    A: HorizontalTextAlignment + core::fmt::Debug,
-    for<'a> &'a StyledTextBox<'a, BinaryColor, Font6x8, A, TopAligned, Exact<FullRowsOnly>>:
-        Drawable<BinaryColor>,
-{
+fn demo_loop(window: &mut Window, bounds: &mut Rectangle, alignment: DynamicAlignment) -> bool {
     let text = "Hello, World!\nLorem Ipsum is simply dummy text of the printing and typesetting \
     industry. Lorem Ipsum has been the industry's standard dummy text ever since the 1500s, when \
     an unknown printer took a galley of type and scrambled it to make a type specimen book. \n\
@@ -114,19 +109,14 @@ fn main() -> Result<(), core::convert::Infallible> {
 
     let mut bounds = Rectangle::new(Point::new(0, 8), Point::new(128, 200));
 
-    'running: loop {
-        if !demo_loop(&mut window, &mut bounds, Justified) {
-            break 'running;
-        }
-        if !demo_loop(&mut window, &mut bounds, LeftAligned) {
-            break 'running;
-        }
-        if !demo_loop(&mut window, &mut bounds, CenterAligned) {
-            break 'running;
-        }
-        if !demo_loop(&mut window, &mut bounds, RightAligned) {
-            break 'running;
-        }
+    let mut alignment = DynamicAlignment::Justified;
+    while demo_loop(&mut window, &mut bounds, alignment) {
+        alignment = match alignment {
+            DynamicAlignment::Justified => DynamicAlignment::Left,
+            DynamicAlignment::Left => DynamicAlignment::Center,
+            DynamicAlignment::Center => DynamicAlignment::Right,
+            DynamicAlignment::Right => DynamicAlignment::Justified,
+        };
     }
 
     Ok(())
@@ -3,12 +3,19 @@ use crate::{
     alignment::{HorizontalTextAlignment, VerticalTextAlignment},
     rendering::cursor::Cursor,
     style::height_mode::HeightMode,
+    utils::rect_ext::RectExt,
     StyledTextBox,
 };
 use embedded_graphics::prelude::*;
 
 /// Align text to the bottom of the TextBox.
-#[derive(Copy, Clone, Debug)]
+///
+/// If the text is taller than the TextBox, the last lines are displayed, unless the end of the
+/// text is replaced by an ellipsis, see [`TextOverflow`].
+///
+/// [`TextOverflow`]: ../../style/enum.TextOverflow.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct BottomAligned;
 
 impl VerticalTextAlignment for BottomAligned {
@@ -22,12 +29,9 @@ impl VerticalTextAlignment for BottomAligned {
         A: HorizontalTextAlignment,
         H: HeightMode,
     {
-        let text_height = styled_text_box
-            .style
-            .measure_text_height(styled_text_box.text_box.text, cursor.line_width())
-            as i32;
+        let text_height = styled_text_box.aligned_text_height() as i32;
 
-        let box_height = styled_text_box.size().height as i32;
+        let box_height = RectExt::size(styled_text_box.layout_area()).height as i32;
         let offset = box_height - text_height;
 
         cursor.position.y += offset
@@ -42,10 +46,36 @@ mod test {
     };
 
     use crate::{
-        alignment::BottomAligned, style::height_mode::Exact, style::vertical_overdraw::Visible,
-        style::TextBoxStyleBuilder, TextBox,
+        alignment::BottomAligned,
+        style::height_mode::Exact,
+        style::vertical_overdraw::{FullRowsOnly, Hidden, VerticalOverdraw, Visible},
+        style::TextBoxStyleBuilder,
+        TextBox,
     };
 
+    /// Draws two lines of text into a 12px tall box, so only half of the first line fits.
+    fn draw_partially_visible_first_line<OV: VerticalOverdraw>(
+        overdraw: OV,
+    ) -> MockDisplay<BinaryColor> {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(BottomAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .height_mode(Exact(overdraw))
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            "word\nand",
+            Rectangle::new(Point::new(0, 4), Point::new(23, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        display
+    }
+
     #[test]
     fn test_bottom_alignment() {
         let mut display = MockDisplay::new();
@@ -164,4 +194,183 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn changing_the_text_invalidates_the_measured_height() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(BottomAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(30, 23));
+
+        let mut text_box = TextBox::new("word", bounds).into_styled(style);
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+
+        // draw the same text again, this time the measured height is reused
+        let mut redrawn = MockDisplay::new();
+        text_box.draw(&mut redrawn).unwrap();
+        assert_eq!(display, redrawn);
+
+        text_box.text_box.text = "word1 word2";
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("word1 word2", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn the_height_is_measured_when_the_text_box_is_created() {
+        fn is_sync<T: Sync>(_: &T) -> bool {
+            true
+        }
+
+        let bounds = Rectangle::new(Point::zero(), Point::new(30, 23));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(BottomAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut text_box = TextBox::new("word", bounds).into_styled(style);
+        assert_eq!(text_box.height_cache.map(|(_, height)| height), Some(8));
+        assert!(is_sync(&text_box));
+
+        text_box.set_text("word1 word2");
+        assert_eq!(text_box.height_cache.map(|(_, height)| height), Some(16));
+
+        // top aligned text doesn't need to be measured
+        let top_aligned = TextBox::new("word", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        assert_eq!(top_aligned.height_cache, None);
+    }
+
+    #[test]
+    fn trailing_empty_line() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(BottomAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::new("a\n\n", Rectangle::new(Point::zero(), Point::new(11, 23)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "......      ",
+                "......      ",
+                ".###..      ",
+                "....#.      ",
+                ".####.      ",
+                "#...#.      ",
+                ".####.      ",
+                "......      ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+            ])
+        );
+    }
+
+    #[test]
+    fn partially_visible_first_line_is_not_drawn_with_full_rows_only() {
+        assert_eq!(
+            draw_partially_visible_first_line(FullRowsOnly),
+            MockDisplay::from_pattern(&[
+                "                        ",
+                "                        ",
+                "                        ",
+                "                        ",
+                "                        ",
+                "                        ",
+                "                        ",
+                "                        ",
+                "................#.      ",
+                "................#.      ",
+                ".###..#.##...##.#.      ",
+                "....#.##..#.#..##.      ",
+                ".####.#...#.#...#.      ",
+                "#...#.#...#.#...#.      ",
+                ".####.#...#..####.      ",
+                "..................      ",
+            ])
+        );
+    }
+
+    #[test]
+    fn partially_visible_first_line_is_clipped_with_hidden() {
+        assert_eq!(
+            draw_partially_visible_first_line(Hidden),
+            MockDisplay::from_pattern(&[
+                "                        ",
+                "                        ",
+                "                        ",
+                "                        ",
+                "#.#.#.#...#.#.....#...#.",
+                "#.#.#.#...#.#.....#...#.",
+                ".#.#...###..#......####.",
+                "........................",
+                "................#.      ",
+                "................#.      ",
+                ".###..#.##...##.#.      ",
+                "....#.##..#.#..##.      ",
+                ".####.#...#.#...#.      ",
+                "#...#.#...#.#...#.      ",
+                ".####.#...#..####.      ",
+                "..................      ",
+            ])
+        );
+    }
+
+    #[test]
+    fn partially_visible_first_line_is_drawn_above_the_box_with_visible() {
+        assert_eq!(
+            draw_partially_visible_first_line(Visible),
+            MockDisplay::from_pattern(&[
+                "......................#.",
+                "......................#.",
+                "#...#..###..#.##...##.#.",
+                "#...#.#...#.##..#.#..##.",
+                "#.#.#.#...#.#.....#...#.",
+                "#.#.#.#...#.#.....#...#.",
+                ".#.#...###..#......####.",
+                "........................",
+                "................#.      ",
+                "................#.      ",
+                ".###..#.##...##.#.      ",
+                "....#.##..#.#..##.      ",
+                ".####.#...#.#...#.      ",
+                "#...#.#...#.#...#.      ",
+                ".####.#...#..####.      ",
+                "..................      ",
+            ])
+        );
+    }
 }
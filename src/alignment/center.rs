@@ -6,6 +6,7 @@ use crate::{
         RendererFactory, StyledTextBoxIterator,
     },
     style::{color::Rgb, height_mode::HeightMode},
+    utils::rect_ext::RectExt,
     StyledTextBox,
 };
 use embedded_graphics::prelude::*;
@@ -13,11 +14,19 @@ use embedded_graphics::prelude::*;
 /// Marks text to be rendered center aligned.
 ///
 /// This alignment can be used as both horizontal or vertical alignment.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct CenterAligned;
 impl HorizontalTextAlignment for CenterAligned {
-    const STARTING_SPACES: bool = false;
-    const ENDING_SPACES: bool = false;
+    #[inline]
+    fn starting_spaces(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn ending_spaces(&self) -> bool {
+        false
+    }
 }
 
 impl<'a, C, F, V, H> RendererFactory<'a, C> for StyledTextBox<'a, C, F, CenterAligned, V, H>
@@ -27,21 +36,21 @@ where
     V: VerticalTextAlignment,
     H: HeightMode,
 {
-    type Renderer = StyledTextBoxIterator<'a, C, F, CenterAligned, V, H, UniformSpaceConfig<F>>;
+    type Renderer = StyledTextBoxIterator<'a, C, F, CenterAligned, V, H>;
 
     #[inline]
     #[must_use]
     fn create_renderer(&self) -> Self::Renderer {
         StyledTextBoxIterator::new(self, |style, carried, mut cursor, parser| {
             let max_line_width = cursor.line_width();
-            let (width, _, _, _) =
-                style.measure_line(&mut parser.clone(), carried.clone(), max_line_width);
-            cursor.advance_unchecked((max_line_width - width + 1) / 2);
+            let (width, _, _, _) = style.measure_line(&mut parser.clone(), carried, max_line_width);
+            let space = max_line_width - width;
+            cursor.advance_unchecked(space / 2 + space % 2);
 
             StyledLinePixelIterator::new(
                 parser,
                 cursor,
-                UniformSpaceConfig::default(),
+                UniformSpaceConfig::new(style.word_spacing),
                 style,
                 carried,
             )
@@ -60,12 +69,9 @@ impl VerticalTextAlignment for CenterAligned {
         A: HorizontalTextAlignment,
         H: HeightMode,
     {
-        let text_height = styled_text_box
-            .style
-            .measure_text_height(styled_text_box.text_box.text, cursor.line_width())
-            as i32;
+        let text_height = styled_text_box.aligned_text_height() as i32;
 
-        let box_height = styled_text_box.size().height as i32;
+        let box_height = RectExt::size(styled_text_box.layout_area()).height as i32;
         let offset = (box_height - text_height) / 2;
 
         cursor.position.y += offset;
@@ -264,6 +270,72 @@ mod test_horizontal {
             ])
         );
     }
+
+    #[test]
+    fn zero_width_space_at_end_of_line() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "a bc\u{200B}de",
+            Rectangle::new(Point::zero(), Point::new(23, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "            #          ",
+                "            #          ",
+                " ###        # ##   ### ",
+                "    #       ##  # #    ",
+                " ####       #   # #    ",
+                "#   #       #   # #   #",
+                " ####       ####   ### ",
+                "                       ",
+                "          #            ",
+                "          #            ",
+                "       ## #  ###       ",
+                "      #  ## #   #      ",
+                "      #   # #####      ",
+                "      #   # #          ",
+                "       ####  ###       ",
+            ])
+        );
+    }
+
+    #[test]
+    fn inserted_hyphen_is_part_of_the_line_width() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // "abc-" is 24px, "abc" + "def" is 36px wide
+        for width in 24..36 {
+            let bounds = Rectangle::new(Point::zero(), Point::new(width - 1, 15));
+
+            let mut display = MockDisplay::new();
+            TextBox::new("abc\u{AD}def", bounds)
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+
+            let mut expected = MockDisplay::new();
+            TextBox::new("abc-\ndef", bounds)
+                .into_styled(style)
+                .draw(&mut expected)
+                .unwrap();
+
+            assert_eq!(display, expected, "width: {}", width);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -311,4 +383,61 @@ mod test_vertical {
             ])
         );
     }
+
+    #[test]
+    fn empty_lines_are_centered() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // the empty line between the words is counted, the trailing newline is not
+        TextBox::new(
+            "a\n\nb\n",
+            Rectangle::new(Point::zero(), Point::new(11, 31)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "......      ",
+                "......      ",
+                ".###..      ",
+                "....#.      ",
+                ".####.      ",
+                "#...#.      ",
+                ".####.      ",
+                "......      ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+                "#.....      ",
+                "#.....      ",
+                "#.##..      ",
+                "##..#.      ",
+                "#...#.      ",
+                "#...#.      ",
+                "####..      ",
+                "......      ",
+                "            ",
+                "            ",
+                "            ",
+                "            ",
+            ])
+        );
+    }
 }
@@ -0,0 +1,320 @@
+//! Horizontal alignment selected at runtime.
+use crate::{
+    alignment::{justified::JustifiedSpaceConfig, HorizontalTextAlignment, VerticalTextAlignment},
+    rendering::{line::StyledLinePixelIterator, RendererFactory, StyledTextBoxIterator},
+    style::{color::Rgb, height_mode::HeightMode},
+    StyledTextBox,
+};
+use core::ops::Range;
+use embedded_graphics::{fonts::Font, pixelcolor::PixelColor};
+
+/// Horizontal alignment that can be changed without changing the type of the style.
+///
+/// The static alignment types ([`LeftAligned`], [`CenterAligned`], [`RightAligned`] and
+/// [`Justified`]) are resolved at compile time. Use `DynamicAlignment` when the alignment is only
+/// known at runtime, for example when it's loaded from a configuration or selected by the user.
+///
+/// [`LeftAligned`]: ../left/struct.LeftAligned.html
+/// [`CenterAligned`]: ../center/struct.CenterAligned.html
+/// [`RightAligned`]: ../right/struct.RightAligned.html
+/// [`Justified`]: ../justified/struct.Justified.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DynamicAlignment {
+    /// Render text left aligned.
+    Left,
+
+    /// Render text center aligned.
+    Center,
+
+    /// Render text right aligned.
+    Right,
+
+    /// Render text fully justified.
+    Justified,
+}
+
+impl HorizontalTextAlignment for DynamicAlignment {
+    #[inline]
+    fn starting_spaces(&self) -> bool {
+        *self == DynamicAlignment::Left
+    }
+
+    #[inline]
+    fn ending_spaces(&self) -> bool {
+        *self == DynamicAlignment::Left
+    }
+}
+
+impl<'a, C, F, V, H> StyledTextBox<'a, C, F, DynamicAlignment, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// Sets the horizontal alignment of the lines that start in the given byte ranges of the text.
+    ///
+    /// The ranges are byte offsets into the text of the [`TextBox`]. A line uses the alignment of
+    /// the first span that contains the first character of the line, or the alignment of the style
+    /// if there is no such span. An alignment change in the middle of a line takes effect at the
+    /// next line. This can be used to display a centered title above a paragraph of text in a single
+    /// text box.
+    ///
+    /// The [`HeightMode`] of the style is applied again, because the alignment decides whether
+    /// spaces at the ends of the lines are rendered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let text = "Title\nThe paragraph below the title is justified.";
+    /// let spans = [(0..5, DynamicAlignment::Center)];
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .alignment(DynamicAlignment::Justified)
+    ///     .height_mode(FitToText)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(95, 0)))
+    ///     .into_styled(style)
+    ///     .with_alignments(&spans);
+    /// ```
+    ///
+    /// [`TextBox`]: ../../struct.TextBox.html
+    /// [`HeightMode`]: ../../style/height_mode/trait.HeightMode.html
+    #[inline]
+    #[must_use]
+    pub fn with_alignments(self, alignment_spans: &'a [(Range<usize>, DynamicAlignment)]) -> Self {
+        let mut styled = Self {
+            alignment_spans,
+            ..self
+        };
+        styled.apply_height_mode();
+
+        styled
+    }
+
+    /// Changes the horizontal alignment of the text box.
+    ///
+    /// This can be used to update a text box when the user selects a different alignment, without
+    /// building a new style. The alignment spans set by [`with_alignments`] are kept. The
+    /// [`HeightMode`] of the style is applied again, because the alignment decides whether spaces
+    /// at the ends of the lines are rendered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .alignment(DynamicAlignment::Left)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let mut text_box = TextBox::new("Settings", Rectangle::new(Point::zero(), Point::new(59, 7)))
+    ///     .into_styled(style);
+    ///
+    /// // The alignment selected in a settings menu
+    /// let selected = 2;
+    /// text_box.set_alignment(match selected {
+    ///     0 => DynamicAlignment::Left,
+    ///     1 => DynamicAlignment::Center,
+    ///     2 => DynamicAlignment::Right,
+    ///     _ => DynamicAlignment::Justified,
+    /// });
+    /// assert_eq!(text_box.style.alignment, DynamicAlignment::Right);
+    /// ```
+    ///
+    /// [`with_alignments`]: #method.with_alignments
+    /// [`HeightMode`]: ../../style/height_mode/trait.HeightMode.html
+    #[inline]
+    pub fn set_alignment(&mut self, alignment: DynamicAlignment) -> &mut Self {
+        self.style.alignment = alignment;
+        self.apply_height_mode();
+
+        self
+    }
+}
+
+impl<'a, C, F, V, H> RendererFactory<'a, C> for StyledTextBox<'a, C, F, DynamicAlignment, V, H>
+where
+    C: PixelColor + From<Rgb>,
+    F: Font + Copy,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    type Renderer = StyledTextBoxIterator<'a, C, F, DynamicAlignment, V, H>;
+
+    #[inline]
+    fn create_renderer(&self) -> Self::Renderer {
+        StyledTextBoxIterator::new(self, |style, carried, mut cursor, parser| {
+            let max_line_width = cursor.line_width();
+            let space_info = match style.alignment {
+                DynamicAlignment::Left => JustifiedSpaceConfig::uniform(style.word_spacing),
+
+                DynamicAlignment::Center => {
+                    let (width, _, _, _) =
+                        style.measure_line(&mut parser.clone(), carried, max_line_width);
+                    let space = max_line_width - width;
+                    cursor.advance_unchecked(space / 2 + space % 2);
+
+                    JustifiedSpaceConfig::uniform(style.word_spacing)
+                }
+
+                DynamicAlignment::Right => {
+                    let (width, _, _, _) =
+                        style.measure_line(&mut parser.clone(), carried, max_line_width);
+                    cursor.advance_unchecked(max_line_width - width);
+
+                    JustifiedSpaceConfig::uniform(style.word_spacing)
+                }
+
+                DynamicAlignment::Justified => {
+                    JustifiedSpaceConfig::for_line(&style, &parser, &carried, max_line_width)
+                }
+            };
+
+            StyledLinePixelIterator::new(parser, cursor, space_info, style, carried)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        alignment::{CenterAligned, DynamicAlignment, Justified, LeftAligned, RightAligned},
+        style::{height_mode::FitToText, TextBoxStyleBuilder},
+        TextBox,
+    };
+
+    macro_rules! render {
+        ($alignment:expr) => {{
+            let mut display = MockDisplay::new();
+            let style = TextBoxStyleBuilder::new(Font6x8)
+                .alignment($alignment)
+                .text_color(BinaryColor::On)
+                .background_color(BinaryColor::Off)
+                .build();
+
+            TextBox::new(
+                "  a  word that \twraps\ninto   multiple lines  ",
+                Rectangle::new(Point::zero(), Point::new(59, 31)),
+            )
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+            display
+        }};
+    }
+
+    #[test]
+    fn renders_same_as_static_alignment() {
+        assert_eq!(render!(DynamicAlignment::Left), render!(LeftAligned));
+        assert_eq!(render!(DynamicAlignment::Center), render!(CenterAligned));
+        assert_eq!(render!(DynamicAlignment::Right), render!(RightAligned));
+        assert_eq!(render!(DynamicAlignment::Justified), render!(Justified));
+    }
+
+    #[test]
+    fn set_alignment_renders_same_as_building_with_the_alignment() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(DynamicAlignment::Left)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let mut text_box = TextBox::new(
+            "  a  word that \twraps\ninto   multiple lines  ",
+            Rectangle::new(Point::zero(), Point::new(59, 0)),
+        )
+        .into_styled(style);
+        text_box.set_alignment(DynamicAlignment::Center);
+
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+
+        let expected_text_box = TextBox::new(
+            "  a  word that \twraps\ninto   multiple lines  ",
+            Rectangle::new(Point::zero(), Point::new(59, 0)),
+        )
+        .into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(CenterAligned)
+                .height_mode(FitToText)
+                .text_color(BinaryColor::On)
+                .background_color(BinaryColor::Off)
+                .build(),
+        );
+
+        let mut expected = MockDisplay::new();
+        expected_text_box.draw(&mut expected).unwrap();
+
+        assert_eq!(text_box.size(), expected_text_box.size());
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn alignment_spans_apply_to_the_lines_that_start_in_them() {
+        let title = "Title";
+        let paragraph = "A justified paragraph of text";
+        let text = "Title\nA justified paragraph of text";
+        let spans = [(0..5, DynamicAlignment::Center)];
+
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(DynamicAlignment::Justified)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 0)))
+            .into_styled(style)
+            .with_alignments(&spans);
+        assert_eq!(text_box.size(), Size::new(60, 40));
+
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(title, Rectangle::new(Point::zero(), Point::new(59, 0)))
+            .into_styled(
+                TextBoxStyleBuilder::new(Font6x8)
+                    .alignment(CenterAligned)
+                    .height_mode(FitToText)
+                    .text_color(BinaryColor::On)
+                    .background_color(BinaryColor::Off)
+                    .build(),
+            )
+            .draw(&mut expected)
+            .unwrap();
+        TextBox::new(
+            paragraph,
+            Rectangle::new(Point::new(0, 8), Point::new(59, 8)),
+        )
+        .into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(Justified)
+                .height_mode(FitToText)
+                .text_color(BinaryColor::On)
+                .background_color(BinaryColor::Off)
+                .build(),
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        assert_eq!(display, expected);
+    }
+}
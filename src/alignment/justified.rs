@@ -1,12 +1,14 @@
 //! Fully justified text.
 use crate::{
     alignment::{HorizontalTextAlignment, VerticalTextAlignment},
-    parser::Token,
+    parser::Parser,
     rendering::{
-        line::StyledLinePixelIterator, space_config::SpaceConfig, RendererFactory,
-        StyledTextBoxIterator,
+        line::StyledLinePixelIterator,
+        line_iter::CarriedToken,
+        space_config::{SpaceConfig, UniformSpaceConfig},
+        RendererFactory, StyledTextBoxIterator,
     },
-    style::{color::Rgb, height_mode::HeightMode},
+    style::{color::Rgb, height_mode::HeightMode, JustifyWidth, TextBoxStyle},
     utils::font_ext::FontExt,
     StyledTextBox,
 };
@@ -14,11 +16,19 @@ use core::marker::PhantomData;
 use embedded_graphics::{fonts::Font, pixelcolor::PixelColor};
 
 /// Marks text to be rendered fully justified.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Justified;
 impl HorizontalTextAlignment for Justified {
-    const STARTING_SPACES: bool = false;
-    const ENDING_SPACES: bool = false;
+    #[inline]
+    fn starting_spaces(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn ending_spaces(&self) -> bool {
+        false
+    }
 }
 
 /// Internal state information used to store width of whitespace characters when rendering fully
@@ -32,22 +42,111 @@ impl HorizontalTextAlignment for Justified {
 pub struct JustifiedSpaceConfig<F: Font + Copy> {
     _font: PhantomData<F>,
 
+    /// The width of the whitespace characters before stretching the line.
+    base_width: u32,
+
     /// The width of the whitespace characters.
     space_width: u32,
 
     /// Stores how many characters are rendered using the space_width width. This field changes
     /// during rendering.
     space_count: u32,
+
+    /// The number of spaces before the last tab of the line. These are not stretched, and are
+    /// rendered before the other spaces. This field changes during rendering.
+    unstretched_count: u32,
+
+    /// The number of stretched spaces. The spaces after them are at the end of the line, and are
+    /// not stretched. This field changes during rendering.
+    stretched_count: u32,
 }
 
 impl<F: Font + Copy> JustifiedSpaceConfig<F> {
     #[inline]
     #[must_use]
-    fn new(space_width: u32, extra_pixel_count: u32) -> Self {
+    fn new(base_width: u32, space_width: u32, extra_pixel_count: u32) -> Self {
         JustifiedSpaceConfig {
             _font: PhantomData,
+            base_width,
             space_width,
             space_count: extra_pixel_count,
+            unstretched_count: 0,
+            stretched_count: 0,
+        }
+    }
+
+    /// Sets the number of spaces at the start of the line that are not stretched, and the number
+    /// of stretched spaces after them.
+    fn with_space_counts(self, unstretched_count: u32, stretched_count: u32) -> Self {
+        Self {
+            unstretched_count,
+            stretched_count,
+            ..self
+        }
+    }
+
+    /// Creates a space config that does not stretch the line.
+    #[inline]
+    #[must_use]
+    pub(crate) fn uniform(space_width: u32) -> Self {
+        Self::new(space_width, space_width, 0)
+    }
+
+    /// Calculates the whitespace widths that stretch the next line to `max_line_width`, or to the
+    /// width set using [`TextBoxStyleBuilder::justify_to`].
+    ///
+    /// [`TextBoxStyleBuilder::justify_to`]: ../../style/builder/struct.TextBoxStyleBuilder.html#method.justify_to
+    #[inline]
+    #[must_use]
+    pub(crate) fn for_line<'a, C, A, V, H>(
+        style: &TextBoxStyle<C, F, A, V, H>,
+        parser: &Parser<'a>,
+        carried: &Option<CarriedToken<'a>>,
+        max_line_width: u32,
+    ) -> Self
+    where
+        C: PixelColor,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        // tabs are not stretched, so only the spaces after the last tab stretch the line
+        let (width, unstretched_count, total_whitespace_count, t, _, _, _) =
+            style.measure_line_segments(&mut parser.clone(), *carried, max_line_width);
+
+        let justify_width = match style.justify_width {
+            JustifyWidth::Bounds => max_line_width,
+            JustifyWidth::Pixels(justify_width) => justify_width.min(max_line_width),
+        };
+
+        // Only the last line of a paragraph and lines ended by a vertical tab are left
+        // unstretched. Every other line has been wrapped, regardless of the token that caused the
+        // wrap.
+        let stretch_line = match t {
+            None
+            | Some(CarriedToken::NewLine)
+            | Some(CarriedToken::FormFeed)
+            | Some(CarriedToken::VerticalTab(_)) => false,
+            _ => width < justify_width,
+        };
+
+        if stretch_line && total_whitespace_count != 0 {
+            let space = justify_width - (width - total_whitespace_count * style.word_spacing);
+            let space_width = space / total_whitespace_count;
+            let extra_pixels = space % total_whitespace_count;
+
+            let config = match style.max_word_spacing {
+                // Stretching the spaces too much leaves large gaps. Stretch up to the limit and
+                // leave the rest of the line empty.
+                Some(max) if space_width >= max.max(style.word_spacing) => {
+                    Self::new(style.word_spacing, max.max(style.word_spacing), 0)
+                }
+                _ => Self::new(style.word_spacing, space_width, extra_pixels),
+            };
+
+            config.with_space_counts(unstretched_count, total_whitespace_count)
+        } else {
+            Self::uniform(style.word_spacing)
         }
     }
 }
@@ -56,22 +155,45 @@ impl<F: Font + Copy> Default for JustifiedSpaceConfig<F> {
     #[inline]
     #[must_use]
     fn default() -> Self {
-        Self::new(F::total_char_width(' '), 0)
+        Self::uniform(F::total_char_width(' '))
+    }
+}
+
+impl<F: Font + Copy> From<UniformSpaceConfig<F>> for JustifiedSpaceConfig<F> {
+    #[inline]
+    fn from(config: UniformSpaceConfig<F>) -> Self {
+        Self::uniform(config.space_width)
     }
 }
 
 impl<F: Font + Copy> SpaceConfig for JustifiedSpaceConfig<F> {
     type Font = F;
 
+    #[inline]
+    fn base_width(&self) -> u32 {
+        self.base_width
+    }
+
     #[inline]
     fn peek_next_width(&self, whitespace_count: u32) -> u32 {
-        whitespace_count * self.space_width + self.space_count.min(whitespace_count)
+        let unstretched = self.unstretched_count.min(whitespace_count);
+        let stretched = self.stretched_count.min(whitespace_count - unstretched);
+        // spaces at the end of the line, e.g. a non-breaking space, keep their width
+        let trailing = whitespace_count - unstretched - stretched;
+
+        (unstretched + trailing) * self.base_width
+            + stretched * self.space_width
+            + self.space_count.min(stretched)
     }
 
     #[inline]
     fn consume(&mut self, n: u32) -> u32 {
         let w = self.peek_next_width(n);
-        self.space_count = self.space_count.saturating_sub(n);
+        let unstretched = self.unstretched_count.min(n);
+        let stretched = self.stretched_count.min(n - unstretched);
+        self.unstretched_count -= unstretched;
+        self.stretched_count -= stretched;
+        self.space_count = self.space_count.saturating_sub(stretched);
         w
     }
 }
@@ -83,27 +205,14 @@ where
     V: VerticalTextAlignment,
     H: HeightMode,
 {
-    type Renderer = StyledTextBoxIterator<'a, C, F, Justified, V, H, JustifiedSpaceConfig<F>>;
+    type Renderer = StyledTextBoxIterator<'a, C, F, Justified, V, H>;
 
     #[inline]
     #[must_use]
     fn create_renderer(&self) -> Self::Renderer {
         StyledTextBoxIterator::new(self, |style, carried, cursor, parser| {
-            let max_line_width = cursor.line_width();
-            let (width, total_whitespace_count, t, _) =
-                style.measure_line(&mut parser.clone(), carried.clone(), max_line_width);
-
-            let space =
-                max_line_width - (width - total_whitespace_count * F::total_char_width(' '));
-            let stretch_line = t.is_some() && t != Some(Token::NewLine);
-
-            let space_info = if stretch_line && total_whitespace_count != 0 {
-                let space_width = space / total_whitespace_count;
-                let extra_pixels = space % total_whitespace_count;
-                JustifiedSpaceConfig::new(space_width, extra_pixels)
-            } else {
-                JustifiedSpaceConfig::default()
-            };
+            let space_info =
+                JustifiedSpaceConfig::for_line(&style, &parser, &carried, cursor.line_width());
 
             StyledLinePixelIterator::new(parser, cursor, space_info, style, carried)
         })
@@ -117,7 +226,11 @@ mod test {
         primitives::Rectangle,
     };
 
-    use crate::{alignment::Justified, style::TextBoxStyleBuilder, TextBox};
+    use crate::{
+        alignment::Justified,
+        style::{Direction, JustifyWidth, TextBoxStyle, TextBoxStyleBuilder},
+        TextBox,
+    };
 
     #[test]
     fn simple_render() {
@@ -207,6 +320,261 @@ mod test {
         );
     }
 
+    #[test]
+    fn right_to_left_justified() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .line_direction(Direction::Rtl)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::new(
+            "a bb ccc",
+            Rectangle::new(Point::zero(), Point::new(6 * 7 - 1, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "#.....#...................................",
+                "#.....#...................................",
+                "#.##..#.##...........................###..",
+                "##..#.##..#.............................#.",
+                "#...#.#...#..........................####.",
+                "#...#.#...#.........................#...#.",
+                "####..####...........................####.",
+                "..........................................",
+                "                        ..................",
+                "                        ..................",
+                "                        .###...###...###..",
+                "                        #.....#.....#.....",
+                "                        #.....#.....#.....",
+                "                        #...#.#...#.#...#.",
+                "                        .###...###...###..",
+                "                        ..................",
+            ])
+        );
+    }
+
+    #[test]
+    fn lines_wrapped_around_long_word_are_stretched() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // only the last line is a paragraph end, every other line must be stretched
+        TextBox::new(
+            "a bb cccccccccc dd ee",
+            Rectangle::new(Point::zero(), Point::new(6 * 7 - 1, 31)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "..............................#.....#.....",
+                "..............................#.....#.....",
+                ".###..........................#.##..#.##..",
+                "....#.........................##..#.##..#.",
+                ".####.........................#...#.#...#.",
+                "#...#.........................#...#.#...#.",
+                ".####.........................####..####..",
+                "..........................................",
+                "..........................................",
+                "..........................................",
+                ".###...###...###...###...###...###...###..",
+                "#.....#.....#.....#.....#.....#.....#.....",
+                "#.....#.....#.....#.....#.....#.....#.....",
+                "#...#.#...#.#...#.#...#.#...#.#...#.#...#.",
+                ".###...###...###...###...###...###...###..",
+                "..........................................",
+                "..................................#.....#.",
+                "..................................#.....#.",
+                ".###...###...###...............##.#..##.#.",
+                "#.....#.....#.................#..##.#..##.",
+                "#.....#.....#.................#...#.#...#.",
+                "#...#.#...#.#...#.............#...#.#...#.",
+                ".###...###...###...............####..####.",
+                "..........................................",
+                "............                              ",
+                "............                              ",
+                ".###...###..                              ",
+                "#...#.#...#.                              ",
+                "#####.#####.                              ",
+                "#.....#.....                              ",
+                ".###...###..                              ",
+                "............                              ",
+            ])
+        );
+    }
+
+    #[test]
+    fn word_spacing_is_used_as_base_space_width() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .word_spacing(7)
+            .build();
+
+        // "A word" would fit into a single line with the default space width
+        TextBox::new(
+            "A word",
+            Rectangle::new(Point::zero(), Point::new(6 * 6 - 1, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                ".###..                  ",
+                "#...#.                  ",
+                "#...#.                  ",
+                "#####.                  ",
+                "#...#.                  ",
+                "#...#.                  ",
+                "#...#.                  ",
+                "......                  ",
+                "......................#.",
+                "......................#.",
+                "#...#..###..#.##...##.#.",
+                "#...#.#...#.##..#.#..##.",
+                "#.#.#.#...#.#.....#...#.",
+                "#.#.#.#...#.#.....#...#.",
+                ".#.#...###..#......####.",
+                "........................",
+            ])
+        );
+    }
+
+    #[test]
+    fn justify_to_narrower_width() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .justify_to(JustifyWidth::Pixels(54))
+            .build();
+
+        // the first line is stretched from 48 to 54 pixels, not to the 60 pixel wide box
+        TextBox::new(
+            "ab cd ef gh",
+            Rectangle::new(Point::zero(), Point::new(59, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      #                        #                  ## ",
+                "      #                        #                 #  #",
+                " ###  # ##            ###   ## #           ###   #   ",
+                "    # ##  #          #     #  ##          #   # ###  ",
+                " #### #   #          #     #   #          #####  #   ",
+                "#   # #   #          #   # #   #          #      #   ",
+                " #### ####            ###   ####           ###   #   ",
+                "                                                     ",
+                "      #                                              ",
+                "      #                                              ",
+                " #### # ##                                           ",
+                "#   # ##  #                                          ",
+                "#   # #   #                                          ",
+                " #### #   #                                          ",
+                "    # #   #                                          ",
+                " ###                                                 ",
+            ])
+        );
+    }
+
+    #[test]
+    fn indented_lines_are_stretched_to_the_reduced_width() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            "ab cd ef gh",
+            Rectangle::new(Point::zero(), Point::new(59, 15)),
+        )
+        .into_styled(TextBoxStyle {
+            first_line_indent: 6,
+            hanging_indent: 6,
+            ..style
+        })
+        .draw(&mut display)
+        .unwrap();
+
+        // the lines are laid out as if the text box was narrower
+        let mut expected = MockDisplay::new();
+        TextBox::new(
+            "ab cd ef gh",
+            Rectangle::new(Point::new(6, 0), Point::new(59, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut expected)
+        .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn max_word_spacing_limits_stretching() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .max_word_spacing(12)
+            .build();
+
+        // stretching the first line would need a 42px wide space
+        TextBox::new(
+            "ab cd efghijk",
+            Rectangle::new(Point::zero(), Point::new(65, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      #                           #     ",
+                "      #                           #     ",
+                " ###  # ##               ###   ## #     ",
+                "    # ##  #             #     #  ##     ",
+                " #### #   #             #     #   #     ",
+                "#   # #   #             #   # #   #     ",
+                " #### ####               ###   ####     ",
+                "                                        ",
+                "        ##        #       #      #  #   ",
+                "       #  #       #                 #   ",
+                " ###   #     #### # ##   ##     ##  #  #",
+                "#   # ###   #   # ##  #   #      #  # # ",
+                "#####  #    #   # #   #   #      #  ##  ",
+                "#      #     #### #   #   #      #  # # ",
+                " ###   #        # #   #  ###  #  #  #  #",
+                "             ###               ##       ",
+            ])
+        );
+    }
+
     #[test]
     fn simple_word_wrapping() {
         let mut display = MockDisplay::new();
@@ -382,4 +750,128 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn tabs_are_not_stretched() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // "b" at the first tab stop, 4 * 6 = 24 pixels
+        let mut expected = MockDisplay::new();
+        TextBox::new("b", Rectangle::new(Point::new(24, 0), Point::new(29, 7)))
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        let mut gaps = Vec::new();
+        for &width in &[60, 63, 72, 90] {
+            let text_box = TextBox::new(
+                "a\tb c d eeeeeeeeeeeeeeee",
+                Rectangle::new(Point::zero(), Point::new(width - 1, 31)),
+            )
+            .into_styled(style);
+
+            let mut display = MockDisplay::new();
+            text_box.draw(&mut display).unwrap();
+
+            for y in 0..8 {
+                for x in 24..30 {
+                    let point = Point::new(x, y);
+                    assert_eq!(
+                        display.get_pixel(point),
+                        expected.get_pixel(point),
+                        "width: {}, {:?}",
+                        width,
+                        point
+                    );
+                }
+            }
+
+            // the line is stretched between "b", "c" and "d"
+            gaps.push(text_box.caret_position(4).x - 30);
+        }
+
+        assert_eq!(gaps, [9, 11, 15, 24]);
+    }
+
+    #[test]
+    fn only_spaces_after_the_last_tab_are_stretched() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let carets = |text: &str, width: i32, offsets: &[usize]| {
+            let text_box = TextBox::new(
+                text,
+                Rectangle::new(Point::zero(), Point::new(width - 1, 15)),
+            )
+            .into_styled(style);
+
+            offsets
+                .iter()
+                .map(|&offset| text_box.caret_position(offset).x)
+                .collect::<Vec<_>>()
+        };
+
+        // "b" and the tab keep their natural positions, "c d" fills the rest of the line
+        assert_eq!(
+            carets("a b\tc d eeeeeeeeeeee", 72, &[2, 4, 6, 7]),
+            [12, 24, 66, 72]
+        );
+
+        // two tabs: the second tab stop is at 48 pixels, "d e f" is stretched
+        assert_eq!(
+            carets(
+                "a b\tc\td e f gggggggggggggggggg",
+                120,
+                &[2, 4, 6, 8, 10, 11]
+            ),
+            [12, 24, 48, 81, 114, 120]
+        );
+
+        // spaces that precede a tab are not stretched either
+        assert_eq!(carets("a \tb c dddddddddd", 60, &[3, 5, 6]), [24, 54, 60]);
+    }
+
+    #[test]
+    fn zero_width_space_at_end_of_line() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "a bc\u{200B}de",
+            Rectangle::new(Point::zero(), Point::new(23, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "            #          ",
+                "            #          ",
+                " ###        # ##   ### ",
+                "    #       ##  # #    ",
+                " ####       #   # #    ",
+                "#   #       #   # #   #",
+                " ####       ####   ### ",
+                "                       ",
+                "    #                  ",
+                "    #                  ",
+                " ## #  ###             ",
+                "#  ## #   #            ",
+                "#   # #####            ",
+                "#   # #                ",
+                " ####  ###             ",
+            ])
+        );
+    }
 }
@@ -11,11 +11,19 @@ use crate::{
 use embedded_graphics::{fonts::Font, pixelcolor::PixelColor};
 
 /// Marks text to be rendered left aligned.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct LeftAligned;
 impl HorizontalTextAlignment for LeftAligned {
-    const STARTING_SPACES: bool = true;
-    const ENDING_SPACES: bool = true;
+    #[inline]
+    fn starting_spaces(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn ending_spaces(&self) -> bool {
+        true
+    }
 }
 
 impl<'a, C, F, V, H> RendererFactory<'a, C> for StyledTextBox<'a, C, F, LeftAligned, V, H>
@@ -25,7 +33,7 @@ where
     V: VerticalTextAlignment,
     H: HeightMode,
 {
-    type Renderer = StyledTextBoxIterator<'a, C, F, LeftAligned, V, H, UniformSpaceConfig<F>>;
+    type Renderer = StyledTextBoxIterator<'a, C, F, LeftAligned, V, H>;
 
     #[inline]
     #[must_use]
@@ -34,7 +42,7 @@ where
             StyledLinePixelIterator::new(
                 parser,
                 cursor,
-                UniformSpaceConfig::default(),
+                UniformSpaceConfig::new(style.word_spacing),
                 style,
                 carried,
             )
@@ -49,7 +57,11 @@ mod test {
         primitives::Rectangle,
     };
 
-    use crate::{alignment::LeftAligned, style::TextBoxStyleBuilder, TextBox};
+    use crate::{
+        alignment::LeftAligned,
+        style::{Direction, TextBoxStyleBuilder},
+        TextBox,
+    };
 
     #[test]
     fn simple_render() {
@@ -270,6 +282,288 @@ mod test {
         );
     }
 
+    #[test]
+    fn letter_spacing() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .letter_spacing(2)
+            .build();
+
+        TextBox::new("AB", Rectangle::new(Point::zero(), Point::new(47, 7)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                ".###....####....",
+                "#...#...#...#...",
+                "#...#...#...#...",
+                "#####...####....",
+                "#...#...#...#...",
+                "#...#...#...#...",
+                "#...#...####....",
+                "................",
+            ])
+        );
+    }
+
+    #[test]
+    fn letter_spacing_is_measured_when_wrapping() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .letter_spacing(1)
+            .build();
+
+        // "AB CD" would fit into 30px without letter spacing
+        TextBox::new("AB CD", Rectangle::new(Point::zero(), Point::new(29, 15)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                " ###   #### ",
+                "#   #  #   #",
+                "#   #  #   #",
+                "#####  #### ",
+                "#   #  #   #",
+                "#   #  #   #",
+                "#   #  #### ",
+                "            ",
+                " ###   ###  ",
+                "#   #  #  # ",
+                "#      #   #",
+                "#      #   #",
+                "#      #   #",
+                "#   #  #  # ",
+                " ###   ###  ",
+            ])
+        );
+    }
+
+    #[test]
+    fn right_to_left_word_wrapping() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .line_direction(Direction::Rtl)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::new(
+            "ab cd ef",
+            Rectangle::new(Point::zero(), Point::new(6 * 6 - 1, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      ....#.............#...........",
+                "      ....#.............#...........",
+                "      .##.#..###........#.##...###..",
+                "      #..##.#...........##..#.....#.",
+                "      #...#.#...........#...#..####.",
+                "      #...#.#...#.......#...#.#...#.",
+                "      .####..###........####...####.",
+                "      ..............................",
+                "                        ..##........",
+                "                        .#..#.......",
+                "                        .#.....###..",
+                "                        ###...#...#.",
+                "                        .#....#####.",
+                "                        .#....#.....",
+                "                        .#.....###..",
+                "                        ............",
+            ])
+        );
+    }
+
+    #[test]
+    fn paragraph_spacing() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .paragraph_spacing(2)
+            .build();
+
+        // paragraph spacing is only applied after the explicit line break
+        TextBox::new("A\nB C", Rectangle::new(Point::zero(), Point::new(11, 31)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                " ###  ", "#   # ", "#   # ", "##### ", "#   # ", "#   # ", "#   # ", "      ",
+                "      ", "      ", "####  ", "#   # ", "#   # ", "####  ", "#   # ", "#   # ",
+                "####  ", "      ", " ###  ", "#   # ", "#     ", "#     ", "#     ", "#   # ",
+                " ###  ",
+            ])
+        );
+    }
+
+    #[test]
+    fn hanging_indent() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .hanging_indent(3 * Font6x8::CHARACTER_SIZE.width)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            "1. a bunch of wrapping words",
+            Rectangle::new(Point::zero(), Point::new(83, 23)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        // the wrapped lines start below the `a`, after the width of "1. "
+        let mut expected = MockDisplay::new();
+        for &(text, x, y) in &[
+            ("1. a bunch of", 0, 0),
+            ("wrapping", 18, 8),
+            ("words", 18, 16),
+        ] {
+            TextBox::new(
+                text,
+                Rectangle::new(Point::new(x, y), Point::new(83, y + 7)),
+            )
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+        }
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn first_line_indent() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .first_line_indent(6)
+            .build();
+
+        // every paragraph is indented, wrapped lines are not
+        TextBox::new("A B\nC", Rectangle::new(Point::zero(), Point::new(11, 23)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "       ###  ",
+                "      #   # ",
+                "      #   # ",
+                "      ##### ",
+                "      #   # ",
+                "      #   # ",
+                "      #   # ",
+                "            ",
+                "####        ",
+                "#   #       ",
+                "#   #       ",
+                "####        ",
+                "#   #       ",
+                "#   #       ",
+                "####        ",
+                "            ",
+                "       ###  ",
+                "      #   # ",
+                "      #     ",
+                "      #     ",
+                "      #     ",
+                "      #   # ",
+                "       ###  ",
+            ])
+        );
+    }
+
+    #[test]
+    fn word_spacing() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .word_spacing(2)
+            .build();
+
+        TextBox::new("A B", Rectangle::new(Point::zero(), Point::new(47, 7)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                ".###....####..",
+                "#...#...#...#.",
+                "#...#...#...#.",
+                "#####...####..",
+                "#...#...#...#.",
+                "#...#...#...#.",
+                "#...#...####..",
+                "..............",
+            ])
+        );
+    }
+
+    #[test]
+    fn escape_sequences_in_single_line_box() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        for &text in &[
+            "\x1b[97mword",
+            "\x1b[97mword \x1b[97mwrap",
+            "\x1b[97mword\x1b[97m\nwrap",
+        ] {
+            let mut display = MockDisplay::new();
+            TextBox::new(text, Rectangle::new(Point::zero(), Point::new(23, 7)))
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+
+            assert_eq!(
+                display,
+                MockDisplay::from_pattern(&[
+                    "......................#.",
+                    "......................#.",
+                    "#...#..###..#.##...##.#.",
+                    "#...#.#...#.##..#.#..##.",
+                    "#.#.#.#...#.#.....#...#.",
+                    "#.#.#.#...#.#.....#...#.",
+                    ".#.#...###..#......####.",
+                    "........................",
+                ]),
+                "{:?}",
+                text
+            );
+        }
+    }
+
     #[test]
     fn whitespace_word_wrapping() {
         let mut display = MockDisplay::new();
@@ -310,6 +604,55 @@ mod test {
         );
     }
 
+    #[test]
+    fn wrapped_whitespace_is_not_rendered_at_line_start() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // spaces after a line break are kept as indentation
+        TextBox::new(
+            "word    wrap\n  x",
+            Rectangle::new(Point::zero(), Point::new(29, 23)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "......................#.",
+                "......................#.",
+                "#...#..###..#.##...##.#.",
+                "#...#.#...#.##..#.#..##.",
+                "#.#.#.#...#.#.....#...#.",
+                "#.#.#.#...#.#.....#...#.",
+                ".#.#...###..#......####.",
+                "........................",
+                "........................",
+                "........................",
+                "#...#.#.##...###..####..",
+                "#...#.##..#.....#.#...#.",
+                "#.#.#.#......####.#...#.",
+                "#.#.#.#.....#...#.####..",
+                ".#.#..#......####.#.....",
+                "..................#.....",
+                "..................      ",
+                "..................      ",
+                "............#...#.      ",
+                ".............#.#..      ",
+                "..............#...      ",
+                ".............#.#..      ",
+                "............#...#.      ",
+                "..................      ",
+            ])
+        );
+    }
+
     #[test]
     fn word_longer_than_line_wraps_word_and_removes_a_space() {
         let mut display = MockDisplay::new();
@@ -397,4 +740,279 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn zero_width_space_at_end_of_line() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "a bc\u{200B}de",
+            Rectangle::new(Point::zero(), Point::new(23, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "            #          ",
+                "            #          ",
+                " ###        # ##   ### ",
+                "    #       ##  # #    ",
+                " ####       #   # #    ",
+                "#   #       #   # #   #",
+                " ####       ####   ### ",
+                "                       ",
+                "    #                  ",
+                "    #                  ",
+                " ## #  ###             ",
+                "#  ## #   #            ",
+                "#   # #####            ",
+                "#   # #                ",
+                " ####  ###             ",
+            ])
+        );
+    }
+
+    #[test]
+    fn soft_hyphen_displays_break_character() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(17, 15));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .break_character(Some('='))
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("ab\u{AD}cd", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("ab=\ncd", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn wraps_after_break_after_characters() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(35, 23));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .break_after(&['/', '-'])
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("foo/bar-baz", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("foo/\nbar-\nbaz", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(style.measure_text_height("foo/bar-baz", 36), 24);
+    }
+
+    #[test]
+    fn sized_spaces_depend_on_the_font_height() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // em space: 8px, en space: 4px, thin space: 2px
+        for &(text, b_offset, b_x) in &[
+            ("a\u{2003}b", 4, 14),
+            ("a\u{2002}b", 4, 10),
+            ("a\u{2009}b", 4, 8),
+            ("a\u{2002}\u{2009}b", 7, 12),
+        ] {
+            let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 7)))
+                .into_styled(style);
+            assert_eq!(
+                text_box.caret_position(b_offset),
+                Point::new(b_x, 0),
+                "{:?}",
+                text
+            );
+
+            let mut display = MockDisplay::new();
+            text_box.draw(&mut display).unwrap();
+
+            let mut expected = MockDisplay::new();
+            TextBox::new("a", Rectangle::new(Point::zero(), Point::new(b_x - 1, 7)))
+                .into_styled(style)
+                .draw(&mut expected)
+                .unwrap();
+            TextBox::new(
+                "b",
+                Rectangle::new(Point::new(b_x, 0), Point::new(b_x + 5, 7)),
+            )
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+            assert_eq!(display, expected, "{:?}", text);
+        }
+
+        // lines are wrapped at sized spaces
+        assert_eq!(style.measure_text_height("a\u{2003}b", 20), 8);
+        assert_eq!(style.measure_text_height("a\u{2003}b", 19), 16);
+    }
+
+    #[test]
+    fn non_breaking_hyphen_and_narrow_space() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(35, 15));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // the non-breaking hyphen is displayed as a hyphen, and the number is not wrapped
+        let mut display = MockDisplay::new();
+        TextBox::new("a 12\u{2011}34", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("a\n12-34", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+
+        // the narrow space is as wide as a thin space, 2px
+        let text_box = TextBox::new("1\u{202f}234", bounds).into_styled(style);
+        assert_eq!(text_box.caret_position(4), Point::new(8, 0));
+        assert_eq!(style.measure_text_height("1\u{202f}234", 26), 8);
+        assert_eq!(style.measure_text_height("a 1\u{202f}234", 36), 16);
+    }
+
+    #[test]
+    fn form_feed_and_vertical_tab() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(35, 31));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // a form feed is followed by an empty line
+        let mut display = MockDisplay::new();
+        TextBox::new("ab\x0ccd", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("ab\n\ncd", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(style.measure_text_height("ab\x0ccd", 36), 24);
+        assert_eq!(style.measure_text_height("ab\n\x0ccd", 36), 24);
+
+        // a vertical tab continues in the next line, at the same horizontal position
+        let mut display = MockDisplay::new();
+        TextBox::new("ab\x0bcd", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("ab\n\u{A0}\u{A0}cd", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(style.measure_text_height("ab\x0bcd", 36), 16);
+    }
+
+    #[test]
+    fn soft_hyphen_without_break_character() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(23, 15));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .break_character(None)
+            .build();
+
+        // "cd" fits the line because no hyphen needs to be displayed after it
+        let mut display = MockDisplay::new();
+        TextBox::new("ab\u{AD}cd\u{AD}ef", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("abcd\nef", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(style.measure_text_width("ab\u{AD}cd\u{AD}ef", 24), 24);
+
+        // with a hyphen, the first line ends after "ab-"
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("ab\u{AD}cd\u{AD}ef", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("ab-\ncdef", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn letter_and_word_spacing() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .letter_spacing(1)
+            .word_spacing(3)
+            .build();
+
+        TextBox::new("ij j", Rectangle::new(Point::zero(), Point::new(23, 7)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "  #       #         #",
+                "                     ",
+                " ##      ##        ##",
+                "  #       #         #",
+                "  #       #         #",
+                "  #       #         #",
+                " ###   #  #      #  #",
+                "        ##        ## ",
+            ])
+        );
+    }
 }
@@ -4,9 +4,11 @@ use embedded_graphics::prelude::*;
 
 pub mod bottom;
 pub mod center;
+pub mod dynamic;
 pub mod justified;
 pub mod left;
 pub mod right;
+pub mod space_between;
 pub mod top;
 
 /// Horizontal text alignment base trait.
@@ -17,10 +19,10 @@ pub mod top;
 /// [`TextBoxStyleBuilder`]: ../style/builder/struct.TextBoxStyleBuilder.html
 pub trait HorizontalTextAlignment: Copy {
     /// Whether or not render spaces in the start of the line.
-    const STARTING_SPACES: bool;
+    fn starting_spaces(&self) -> bool;
 
     /// Whether or not render spaces in the end of the line.
-    const ENDING_SPACES: bool;
+    fn ending_spaces(&self) -> bool;
 }
 
 /// Vertical text alignment base trait.
@@ -39,11 +41,24 @@ pub trait VerticalTextAlignment: Copy {
         F: Font + Copy,
         A: HorizontalTextAlignment,
         H: HeightMode;
+
+    /// Returns whether the alignment depends on the height of the text.
+    ///
+    /// If it does, the text is measured when the [`StyledTextBox`] is created, so drawing it
+    /// doesn't measure the text again.
+    ///
+    /// [`StyledTextBox`]: ../struct.StyledTextBox.html
+    #[inline]
+    fn uses_text_height() -> bool {
+        true
+    }
 }
 
 pub use bottom::BottomAligned;
 pub use center::CenterAligned;
+pub use dynamic::DynamicAlignment;
 pub use justified::Justified;
 pub use left::LeftAligned;
 pub use right::RightAligned;
+pub use space_between::SpaceBetween;
 pub use top::TopAligned;
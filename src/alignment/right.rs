@@ -11,11 +11,19 @@ use crate::{
 use embedded_graphics::{fonts::Font, pixelcolor::PixelColor};
 
 /// Marks text to be rendered right aligned.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RightAligned;
 impl HorizontalTextAlignment for RightAligned {
-    const STARTING_SPACES: bool = false;
-    const ENDING_SPACES: bool = false;
+    #[inline]
+    fn starting_spaces(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn ending_spaces(&self) -> bool {
+        false
+    }
 }
 
 impl<'a, C, F, V, H> RendererFactory<'a, C> for StyledTextBox<'a, C, F, RightAligned, V, H>
@@ -25,21 +33,20 @@ where
     V: VerticalTextAlignment,
     H: HeightMode,
 {
-    type Renderer = StyledTextBoxIterator<'a, C, F, RightAligned, V, H, UniformSpaceConfig<F>>;
+    type Renderer = StyledTextBoxIterator<'a, C, F, RightAligned, V, H>;
 
     #[inline]
     #[must_use]
     fn create_renderer(&self) -> Self::Renderer {
         StyledTextBoxIterator::new(self, |style, carried, mut cursor, parser| {
             let max_line_width = cursor.line_width();
-            let (width, _, _, _) =
-                style.measure_line(&mut parser.clone(), carried.clone(), max_line_width);
+            let (width, _, _, _) = style.measure_line(&mut parser.clone(), carried, max_line_width);
             cursor.advance_unchecked(max_line_width - width);
 
             StyledLinePixelIterator::new(
                 parser,
                 cursor,
-                UniformSpaceConfig::default(),
+                UniformSpaceConfig::new(style.word_spacing),
                 style,
                 carried,
             )
@@ -239,4 +246,186 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn zero_width_space_at_end_of_line() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "a bc\u{200B}de",
+            Rectangle::new(Point::zero(), Point::new(23, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "            #          ",
+                "            #          ",
+                " ###        # ##   ### ",
+                "    #       ##  # #    ",
+                " ####       #   # #    ",
+                "#   #       #   # #   #",
+                " ####       ####   ### ",
+                "                       ",
+                "                #      ",
+                "                #      ",
+                "             ## #  ### ",
+                "            #  ## #   #",
+                "            #   # #####",
+                "            #   # #    ",
+                "             ####  ### ",
+            ])
+        );
+    }
+
+    #[test]
+    fn soft_hyphen_at_end_of_line() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new(
+            "ab cdef\u{AD}gh",
+            Rectangle::new(Point::zero(), Point::new(29, 23)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "                        #    ",
+                "                        #    ",
+                "                   ###  # ## ",
+                "                      # ##  #",
+                "                   #### #   #",
+                "                  #   # #   #",
+                "                   #### #### ",
+                "                             ",
+                "          #         ##       ",
+                "          #        #  #      ",
+                " ###   ## #  ###   #         ",
+                "#     #  ## #   # ###   #####",
+                "#     #   # #####  #         ",
+                "#   # #   # #      #         ",
+                " ###   ####  ###   #         ",
+                "                             ",
+                "                        #    ",
+                "                        #    ",
+                "                   #### # ## ",
+                "                  #   # ##  #",
+                "                  #   # #   #",
+                "                   #### #   #",
+                "                      # #   #",
+                "                   ###       ",
+            ])
+        );
+    }
+
+    #[test]
+    fn inserted_hyphen_is_part_of_the_line_width() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // "abc-" is 24px, "abc" + "def" is 36px wide
+        for width in 24..36 {
+            let bounds = Rectangle::new(Point::zero(), Point::new(width - 1, 15));
+
+            let mut display = MockDisplay::new();
+            TextBox::new("abc\u{AD}def", bounds)
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+
+            let mut expected = MockDisplay::new();
+            TextBox::new("abc-\ndef", bounds)
+                .into_styled(style)
+                .draw(&mut expected)
+                .unwrap();
+
+            assert_eq!(display, expected, "width: {}", width);
+        }
+    }
+
+    #[test]
+    fn escape_sequence_inside_word() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // the escape sequence must not allow wrapping the line after "a:"
+        TextBox::new(
+            "x a:\x1b[31mBC",
+            Rectangle::new(Point::zero(), Point::new(29, 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "                             ",
+                "                             ",
+                "                        #   #",
+                "                         # # ",
+                "                          #  ",
+                "                         # # ",
+                "                        #   #",
+                "                             ",
+                "                  ....   ... ",
+                "             ##   .   . .   .",
+                "       ###   ##   .   . .    ",
+                "          #       ....  .    ",
+                "       ####  ##   .   . .    ",
+                "      #   #  ##   .   . .   .",
+                "       ####       ....   ... ",
+            ])
+        );
+    }
+
+    #[test]
+    fn letter_spacing_is_not_drawn_after_the_last_character() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .letter_spacing(1)
+            .build();
+
+        TextBox::new("ij", Rectangle::new(Point::zero(), Point::new(17, 7)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "     ..#.......#..",
+                "     .............",
+                "     .##......##..",
+                "     ..#.......#..",
+                "     ..#.......#..",
+                "     ..#.......#..",
+                "     .###...#..#..",
+                "     ........##...",
+            ])
+        );
+    }
 }
@@ -0,0 +1,142 @@
+//! Vertically justified text.
+use crate::{
+    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
+    rendering::cursor::Cursor,
+    style::height_mode::HeightMode,
+    utils::rect_ext::RectExt,
+    StyledTextBox,
+};
+use embedded_graphics::prelude::*;
+
+/// Distribute the lines of text evenly to fill the height of the TextBox.
+///
+/// The first line is aligned to the top, the last line is aligned to the bottom of the TextBox and
+/// the remaining vertical space is evenly distributed between the lines. Text that consists of a
+/// single line, or that does not fit into the TextBox is top aligned.
+///
+/// If the space can't be divided evenly, the remaining pixels are added to the last gaps, one
+/// pixel each.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SpaceBetween;
+
+impl VerticalTextAlignment for SpaceBetween {
+    #[inline]
+    fn apply_vertical_alignment<'a, C, F, A, H>(
+        cursor: &mut Cursor<F>,
+        styled_text_box: &'a StyledTextBox<'a, C, F, A, Self, H>,
+    ) where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        H: HeightMode,
+    {
+        let line_count = styled_text_box.style.line_count(
+            styled_text_box.text_box.parser(),
+            cursor.line_width(),
+            styled_text_box.alignment_spans,
+        );
+        if line_count < 2 {
+            return;
+        }
+
+        let text_height = styled_text_box.text_height();
+        let box_height = RectExt::size(styled_text_box.layout_area()).height;
+
+        if box_height > text_height {
+            cursor.distribute_line_gap(box_height - text_height, line_count - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        alignment::{SpaceBetween, TopAligned},
+        style::TextBoxStyleBuilder,
+        TextBox,
+    };
+
+    #[test]
+    fn remaining_pixels_are_added_to_the_last_gaps() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(SpaceBetween)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // 4 lines and 7 spare pixels: the gaps are 2, 2 and 3 pixels taller than the line height
+        let text_box = TextBox::new(
+            "a\nb\nc\nd",
+            Rectangle::new(Point::zero(), Point::new(5, 38)),
+        )
+        .into_styled(style);
+
+        let tops = text_box
+            .line_bounds()
+            .map(|line| line.top_left.y)
+            .collect::<Vec<_>>();
+        assert_eq!(tops, [0, 10, 20, 31]);
+
+        // 3 lines and 7 spare pixels: 3 and 4 pixels
+        let text_box = TextBox::new("a\nb\nc", Rectangle::new(Point::zero(), Point::new(5, 30)))
+            .into_styled(style);
+
+        let tops = text_box
+            .line_bounds()
+            .map(|line| line.top_left.y)
+            .collect::<Vec<_>>();
+        assert_eq!(tops, [0, 11, 23]);
+    }
+
+    #[test]
+    fn distributes_lines() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(SpaceBetween)
+            .text_color(BinaryColor::On)
+            .build();
+
+        TextBox::new("a\nb\nc", Rectangle::new(Point::zero(), Point::new(5, 32)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      ", "      ", " ###  ", "    # ", " #### ", "#   # ", " #### ", "      ",
+                "      ", "      ", "      ", "      ", "#     ", "#     ", "# ##  ", "##  # ",
+                "#   # ", "#   # ", "####  ", "      ", "      ", "      ", "      ", "      ",
+                "      ", "      ", "      ", " ###  ", "#     ", "#     ", "#   # ", " ###  ",
+                "      ",
+            ])
+        );
+    }
+
+    macro_rules! render {
+        ($alignment:expr) => {{
+            let mut display = MockDisplay::new();
+            let style = TextBoxStyleBuilder::new(Font6x8)
+                .vertical_alignment($alignment)
+                .text_color(BinaryColor::On)
+                .build();
+
+            TextBox::new("word", Rectangle::new(Point::zero(), Point::new(54, 23)))
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+
+            display
+        }};
+    }
+
+    #[test]
+    fn single_line_is_top_aligned() {
+        assert_eq!(render!(SpaceBetween), render!(TopAligned));
+    }
+}
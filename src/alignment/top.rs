@@ -8,7 +8,8 @@ use crate::{
 use embedded_graphics::prelude::*;
 
 /// Align text to the top of the TextBox.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct TopAligned;
 
 impl VerticalTextAlignment for TopAligned {
@@ -24,6 +25,11 @@ impl VerticalTextAlignment for TopAligned {
     {
         // nothing to do here
     }
+
+    #[inline]
+    fn uses_text_height() -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,133 @@
+//! Special characters and helpers to build text that wraps nicely.
+//!
+//! These characters control where lines may be wrapped. See the [parser documentation] for the
+//! complete list of characters that are handled specially.
+//!
+//! ```rust
+//! use embedded_text::chars::{insert_break_hints, SOFT_HYPHEN, ZWSP};
+//!
+//! let mut text = String::from("embedded");
+//! text.push(SOFT_HYPHEN);
+//! text.push_str("text ");
+//! insert_break_hints("TEXT_BOX_RENDERER", 4, &mut text).unwrap();
+//!
+//! assert_eq!(text, "embedded\u{ad}text TEXT\u{200b}_BOX\u{200b}_REN\u{200b}DERE\u{200b}R");
+//! ```
+//!
+//! [parser documentation]: ../parser/index.html
+use crate::parser::is_space_char;
+use core::fmt::{self, Write};
+
+/// U+00A0 NO-BREAK SPACE. Displayed as a space, but lines are not wrapped at it.
+pub const NBSP: char = '\u{a0}';
+
+/// U+202F NARROW NO-BREAK SPACE. Displayed as a thin space, but lines are not wrapped at it.
+pub const NNBSP: char = '\u{202f}';
+
+/// U+200B ZERO WIDTH SPACE. Not displayed, but lines may be wrapped at it.
+pub const ZWSP: char = '\u{200b}';
+
+/// U+00AD SOFT HYPHEN. Lines may be wrapped at it, in which case a `-` is displayed at the end of
+/// the line. Not displayed otherwise.
+pub const SOFT_HYPHEN: char = '\u{ad}';
+
+/// U+2011 NON-BREAKING HYPHEN. Displayed as a `-`, but lines are not wrapped at it.
+pub const NON_BREAKING_HYPHEN: char = '\u{2011}';
+
+/// Japanese punctuation that must not start a line: commas, full stops, closing brackets,
+/// exclamation and question marks.
+///
+/// This is a minimal set of the line breaking rules of Japanese text (kinsoku shori). Pass it to
+/// [`TextBoxStyleBuilder::no_break_before`] to enable them.
+///
+/// [`TextBoxStyleBuilder::no_break_before`]: ../style/builder/struct.TextBoxStyleBuilder.html#method.no_break_before
+pub const CJK_NO_BREAK_BEFORE: &[char] = &[
+    '、', '。', '，', '．', '：', '；', '！', '？', '）', '」', '』', '】', '〕', '〉', '》',
+];
+
+/// Japanese opening brackets that must not end a line.
+///
+/// This is a minimal set of the line breaking rules of Japanese text (kinsoku shori). Pass it to
+/// [`TextBoxStyleBuilder::no_break_after`] to enable them.
+///
+/// [`TextBoxStyleBuilder::no_break_after`]: ../style/builder/struct.TextBoxStyleBuilder.html#method.no_break_after
+pub const CJK_NO_BREAK_AFTER: &[char] = &['（', '「', '『', '【', '〔', '〈', '《'];
+
+/// Writes `word` into `buf`, with a [zero width space] inserted after every `every_n_chars`
+/// characters, so that long words, like identifiers or URLs, can be wrapped.
+///
+/// Characters are counted as Unicode scalar values, so multi-byte characters are never split. The
+/// count restarts after characters that already allow the line to be wrapped: breaking spaces,
+/// tabs, line breaks, zero width spaces and soft hyphens. No zero width space is written after
+/// the last character. If `every_n_chars` is 0, `word` is written unchanged.
+///
+/// [zero width space]: constant.ZWSP.html
+#[inline]
+pub fn insert_break_hints(word: &str, every_n_chars: usize, buf: &mut impl Write) -> fmt::Result {
+    if every_n_chars == 0 {
+        return buf.write_str(word);
+    }
+
+    let mut count = 0;
+    let mut chunk_start = 0;
+    for (idx, c) in word.char_indices() {
+        if count == every_n_chars {
+            buf.write_str(&word[chunk_start..idx])?;
+            buf.write_char(ZWSP)?;
+            chunk_start = idx;
+            count = 0;
+        }
+
+        if is_break_opportunity(c) {
+            count = 0;
+        } else {
+            count += 1;
+        }
+    }
+
+    buf.write_str(&word[chunk_start..])
+}
+
+/// Returns true if lines may be wrapped after `c`.
+fn is_break_opportunity(c: char) -> bool {
+    is_space_char(c) || c == SOFT_HYPHEN || c.is_ascii_whitespace()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hinted(word: &str, every_n_chars: usize) -> String {
+        let mut buf = String::new();
+        insert_break_hints(word, every_n_chars, &mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(hinted("", 3), "");
+        assert_eq!(hinted("abc", 3), "abc");
+        assert_eq!(hinted("abcdefg", 3), "abc\u{200b}def\u{200b}g");
+        assert_eq!(hinted("abcdef", 0), "abcdef");
+    }
+
+    #[test]
+    fn multibyte_characters_are_not_split() {
+        assert_eq!(
+            hinted("árvíztűrő", 2),
+            "ár\u{200b}ví\u{200b}zt\u{200b}űr\u{200b}ő"
+        );
+        assert_eq!(
+            hinted("日本語テキスト", 3),
+            "日本語\u{200b}テキス\u{200b}ト"
+        );
+        assert_eq!(hinted("🦀🦀🦀", 1), "🦀\u{200b}🦀\u{200b}🦀");
+    }
+
+    #[test]
+    fn count_restarts_at_break_opportunities() {
+        assert_eq!(hinted("ab cdef", 3), "ab cde\u{200b}f");
+        assert_eq!(hinted("ab\u{ad}cdef", 3), "ab\u{ad}cde\u{200b}f");
+        assert_eq!(hinted("ab\u{a0}cdef", 3), "ab\u{a0}\u{200b}cde\u{200b}f");
+    }
+}
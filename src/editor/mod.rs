@@ -0,0 +1,501 @@
+//! Editable text box.
+//!
+//! [`EditableTextBox`] owns its text in a fixed capacity buffer and keeps track of a caret, which
+//! makes it possible to build simple text input fields. The caret is moved using the layout of the
+//! text, so moving up and down follows the wrapped lines as they are displayed.
+//!
+//! This module is only available if the `editor` feature is enabled.
+//!
+//! ```rust
+//! use embedded_text::{
+//!     editor::{CaretMovement, EditableTextBox},
+//!     prelude::*,
+//! };
+//! use embedded_graphics::{
+//!     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+//! };
+//! use heapless::consts::U32;
+//!
+//! let style = TextBoxStyleBuilder::new(Font6x8)
+//!     .text_color(BinaryColor::On)
+//!     .build();
+//!
+//! let mut editor: EditableTextBox<U32, _, _, _, _, _> =
+//!     EditableTextBox::new(Rectangle::new(Point::zero(), Point::new(59, 15)), style);
+//!
+//! for c in "Hello, World!".chars() {
+//!     editor.insert_char(c);
+//! }
+//! editor.move_caret(CaretMovement::Up);
+//! editor.backspace();
+//!
+//! assert_eq!(editor.text(), "Hello World!");
+//!
+//! let mut display = MockDisplay::new();
+//! editor.draw(&mut display).unwrap();
+//! ```
+//!
+//! [`EditableTextBox`]: struct.EditableTextBox.html
+use crate::{
+    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
+    rendering::{RendererFactory, StyledTextBoxIterator},
+    style::{color::Rgb, height_mode::HeightMode, TextBoxStyle},
+    utils::rect_ext::IntoWellFormed,
+    StyledTextBox, TextBox,
+};
+use core::ops::Range;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use heapless::{ArrayLength, String};
+
+/// Directions the caret can be moved in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CaretMovement {
+    /// Move to the previous character.
+    Left,
+
+    /// Move to the next character.
+    Right,
+
+    /// Move to the closest position in the previous line.
+    ///
+    /// In the first line, the caret is moved to the start of the text.
+    Up,
+
+    /// Move to the closest position in the next line.
+    ///
+    /// In the last line, the caret is moved to the end of the text.
+    Down,
+}
+
+/// A text box that owns its text and can be edited.
+///
+/// The text is stored in a buffer that can hold `N` bytes. The caret and the selection are byte
+/// offsets into the text.
+///
+/// See the [module-level documentation] for an example.
+///
+/// [module-level documentation]: index.html
+#[derive(Clone, Debug)]
+pub struct EditableTextBox<N, C, F, A, V, H>
+where
+    N: ArrayLength<u8>,
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// The bounding box of the text box.
+    pub bounds: Rectangle,
+
+    /// The style of the text box.
+    pub style: TextBoxStyle<C, F, A, V, H>,
+
+    text: String<N>,
+    caret: usize,
+    selection: Option<Range<usize>>,
+    selection_color: Option<C>,
+}
+
+impl<N, C, F, A, V, H> EditableTextBox<N, C, F, A, V, H>
+where
+    N: ArrayLength<u8>,
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// Creates an empty text box.
+    #[inline]
+    #[must_use]
+    pub fn new(bounds: Rectangle, style: TextBoxStyle<C, F, A, V, H>) -> Self {
+        Self {
+            bounds: bounds.into_well_formed(),
+            style,
+            text: String::new(),
+            caret: 0,
+            selection: None,
+            selection_color: None,
+        }
+    }
+
+    /// Sets the text color of the selected characters.
+    ///
+    /// The selection is not highlighted if this color is not set.
+    #[inline]
+    #[must_use]
+    pub fn with_selection_color(self, selection_color: C) -> Self {
+        Self {
+            selection_color: Some(selection_color),
+            ..self
+        }
+    }
+
+    /// Returns the text.
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Replaces the text and moves the caret to its end.
+    ///
+    /// Returns `false` and leaves the text box unchanged if the text doesn't fit into the buffer.
+    #[inline]
+    pub fn set_text(&mut self, text: &str) -> bool {
+        let mut new_text = String::new();
+        if new_text.push_str(text).is_err() {
+            return false;
+        }
+
+        self.text = new_text;
+        self.caret = text.len();
+        self.selection = None;
+
+        true
+    }
+
+    /// Returns the byte offset of the caret.
+    #[inline]
+    #[must_use]
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Moves the caret to the given byte offset and clears the selection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not at a character boundary of the text.
+    #[inline]
+    pub fn set_caret(&mut self, offset: usize) {
+        assert!(self.text.is_char_boundary(offset));
+
+        self.caret = offset;
+        self.selection = None;
+    }
+
+    /// Returns the selected byte range of the text.
+    #[inline]
+    #[must_use]
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.selection.clone()
+    }
+
+    /// Selects the given byte range of the text, or clears the selection.
+    ///
+    /// The selection is replaced by the next inserted character, or removed by [`backspace`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bounds of `selection` are not at character boundaries of the text.
+    ///
+    /// [`backspace`]: #method.backspace
+    #[inline]
+    pub fn set_selection(&mut self, selection: Option<Range<usize>>) {
+        if let Some(ref selection) = selection {
+            assert!(selection.start <= selection.end);
+            assert!(self.text.is_char_boundary(selection.start));
+            assert!(self.text.is_char_boundary(selection.end));
+        }
+
+        self.selection = selection;
+    }
+
+    /// Inserts a character at the caret and moves the caret after it.
+    ///
+    /// If some text is selected, it is removed first. Returns `false` if the character doesn't
+    /// fit into the buffer.
+    #[inline]
+    pub fn insert_char(&mut self, c: char) -> bool {
+        self.remove_selection();
+
+        let mut buffer = [0; 4];
+        let bytes = c.encode_utf8(&mut buffer).as_bytes();
+
+        // SAFETY: the bytes of a whole character are inserted at a character boundary, so the text
+        // remains valid UTF-8
+        let vec = unsafe { self.text.as_mut_vec() };
+        if vec.extend_from_slice(bytes).is_err() {
+            return false;
+        }
+        vec[self.caret..].rotate_right(bytes.len());
+        self.caret += bytes.len();
+
+        true
+    }
+
+    /// Removes the selected text, or the character before the caret if nothing is selected.
+    #[inline]
+    pub fn backspace(&mut self) {
+        if !self.remove_selection() {
+            let start = self.previous_char_boundary();
+            self.remove(start..self.caret);
+        }
+    }
+
+    /// Moves the caret and clears the selection.
+    #[inline]
+    pub fn move_caret(&mut self, movement: CaretMovement)
+    where
+        C: From<Rgb>,
+        for<'a> StyledTextBox<'a, C, F, A, V, H>:
+            RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        self.caret = match movement {
+            CaretMovement::Left => self.previous_char_boundary(),
+            CaretMovement::Right => self.text[self.caret..]
+                .chars()
+                .next()
+                .map_or(self.caret, |c| self.caret + c.len_utf8()),
+            CaretMovement::Up | CaretMovement::Down => self.vertical_neighbor(movement),
+        };
+        self.selection = None;
+    }
+
+    /// Returns a [`StyledTextBox`] that displays the text.
+    ///
+    /// [`StyledTextBox`]: ../struct.StyledTextBox.html
+    #[inline]
+    #[must_use]
+    pub fn styled(&self) -> StyledTextBox<'_, C, F, A, V, H> {
+        TextBox::new(self.text(), self.bounds).into_styled(self.style)
+    }
+
+    /// Draws the text, the selection and the caret.
+    ///
+    /// The caret is drawn as a vertical line using the text color of the style. If the style
+    /// doesn't have a text color, the caret is not drawn.
+    #[inline]
+    pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        for<'a> StyledTextBox<'a, C, F, A, V, H>:
+            RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let color_spans;
+        let mut styled = self.styled();
+        if let (Some(selection), Some(color)) = (self.selection(), self.selection_color) {
+            color_spans = [(selection, color)];
+            styled = styled.with_colors(&color_spans);
+        }
+
+        match self.style.text_style.text_color {
+            Some(color) => styled.draw_with_caret(display, self.caret, color),
+            None => display.draw_iter(styled.create_renderer()),
+        }
+    }
+
+    /// Returns the byte offset of the character before the caret.
+    fn previous_char_boundary(&self) -> usize {
+        self.text[..self.caret]
+            .chars()
+            .next_back()
+            .map_or(self.caret, |c| self.caret - c.len_utf8())
+    }
+
+    /// Returns the caret position in the previous or next line that is closest to the caret.
+    fn vertical_neighbor(&self, movement: CaretMovement) -> usize
+    where
+        C: From<Rgb>,
+        for<'a> StyledTextBox<'a, C, F, A, V, H>:
+            RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let caret = self.caret;
+        let styled = self.styled();
+        let renderer = styled.create_renderer();
+        let x = styled.layout_caret_position(caret).x;
+
+        let (start, next_start, _) = match renderer.clone().find_line(|start, _| start <= caret) {
+            Some(line) => line,
+            None => return caret,
+        };
+
+        let target = match movement {
+            CaretMovement::Up => renderer.find_line(|s, _| s < start),
+            _ => renderer.find_line(|s, _| s <= next_start),
+        };
+
+        match target {
+            // there is no line in that direction
+            Some((target_start, _, _)) if target_start == start => match movement {
+                CaretMovement::Up => 0,
+                _ => self.text.len(),
+            },
+            Some((target_start, _, mut line)) => line.offset_at_x(x, target_start),
+            None => caret,
+        }
+    }
+
+    /// Removes the selected text and moves the caret to its place.
+    ///
+    /// Returns `false` if nothing is selected.
+    fn remove_selection(&mut self) -> bool {
+        match self.selection.take() {
+            Some(selection) => {
+                self.caret = selection.start;
+                self.remove(selection);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the given byte range of the text and moves the caret accordingly.
+    fn remove(&mut self, range: Range<usize>) {
+        let removed = range.end - range.start;
+
+        // SAFETY: the range starts and ends at character boundaries, so the text remains valid
+        // UTF-8
+        let vec = unsafe { self.text.as_mut_vec() };
+        vec[range.start..].rotate_left(removed);
+        for _ in 0..removed {
+            vec.pop();
+        }
+
+        if self.caret >= range.end {
+            self.caret -= removed;
+        } else if self.caret > range.start {
+            self.caret = range.start;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CaretMovement, EditableTextBox};
+    use crate::{
+        alignment::{HorizontalTextAlignment, LeftAligned, TopAligned, VerticalTextAlignment},
+        style::{
+            height_mode::{Exact, HeightMode},
+            vertical_overdraw::FullRowsOnly,
+            TextBoxStyleBuilder,
+        },
+    };
+    use embedded_graphics::{
+        fonts::Font6x8, pixelcolor::BinaryColor, prelude::*, primitives::Rectangle,
+    };
+    use heapless::{consts::U32, ArrayLength};
+
+    type Editor =
+        EditableTextBox<U32, BinaryColor, Font6x8, LeftAligned, TopAligned, Exact<FullRowsOnly>>;
+
+    fn editor() -> Editor {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        EditableTextBox::new(Rectangle::new(Point::zero(), Point::new(35, 23)), style)
+    }
+
+    fn type_text<N, C, F, A, V, H>(editor: &mut EditableTextBox<N, C, F, A, V, H>, text: &str)
+    where
+        N: ArrayLength<u8>,
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        for c in text.chars() {
+            assert!(editor.insert_char(c));
+        }
+    }
+
+    #[test]
+    fn typing_session() {
+        let mut editor = editor();
+        let caret = |editor: &Editor| editor.styled().caret_position(editor.caret());
+
+        type_text(&mut editor, "abc");
+        assert_eq!(caret(&editor), Point::new(18, 0));
+
+        type_text(&mut editor, " d");
+        assert_eq!(caret(&editor), Point::new(30, 0));
+
+        // "def" doesn't fit into the first line
+        type_text(&mut editor, "ef");
+        assert_eq!(caret(&editor), Point::new(18, 8));
+
+        type_text(&mut editor, " gh");
+        assert_eq!(editor.text(), "abc def gh");
+        assert_eq!(caret(&editor), Point::new(36, 8));
+
+        editor.move_caret(CaretMovement::Up);
+        assert_eq!(editor.caret(), 3);
+
+        editor.move_caret(CaretMovement::Down);
+        assert_eq!(editor.caret(), 7);
+
+        editor.move_caret(CaretMovement::Left);
+        editor.move_caret(CaretMovement::Left);
+        assert_eq!(editor.caret(), 5);
+
+        // inserting a character wraps "gh" into the third line
+        type_text(&mut editor, "X");
+        assert_eq!(editor.text(), "abc dXef gh");
+        assert_eq!(caret(&editor), Point::new(12, 8));
+
+        editor.move_caret(CaretMovement::Down);
+        assert_eq!(editor.caret(), 11);
+        assert_eq!(caret(&editor), Point::new(12, 16));
+
+        editor.move_caret(CaretMovement::Down);
+        assert_eq!(editor.caret(), 11);
+
+        editor.move_caret(CaretMovement::Up);
+        assert_eq!(editor.caret(), 6);
+
+        editor.backspace();
+        assert_eq!(editor.text(), "abc def gh");
+        assert_eq!(editor.caret(), 5);
+
+        editor.move_caret(CaretMovement::Up);
+        editor.move_caret(CaretMovement::Up);
+        assert_eq!(editor.caret(), 0);
+    }
+
+    #[test]
+    fn caret_moves_across_tabs() {
+        let mut editor = editor();
+        type_text(&mut editor, "\tx\nabcdef");
+
+        editor.move_caret(CaretMovement::Up);
+        assert_eq!(editor.caret(), 2);
+        assert_eq!(
+            editor.styled().caret_position(editor.caret()),
+            Point::new(30, 0)
+        );
+
+        editor.move_caret(CaretMovement::Down);
+        assert_eq!(editor.caret(), 8);
+    }
+
+    #[test]
+    fn typing_replaces_selection() {
+        let mut editor = editor();
+        type_text(&mut editor, "abc def");
+
+        editor.set_selection(Some(4..7));
+        assert!(editor.insert_char('x'));
+        assert_eq!(editor.text(), "abc x");
+        assert_eq!(editor.caret(), 5);
+
+        editor.set_selection(Some(0..2));
+        editor.backspace();
+        assert_eq!(editor.text(), "c x");
+        assert_eq!(editor.caret(), 0);
+    }
+
+    #[test]
+    fn full_buffer() {
+        let mut editor = editor();
+        type_text(&mut editor, "0123456789012345678901234567890");
+
+        assert!(editor.insert_char('1'));
+        assert!(!editor.insert_char('2'));
+        assert_eq!(editor.text().len(), 32);
+    }
+}
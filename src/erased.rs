@@ -0,0 +1,306 @@
+//! Text boxes with erased style types.
+//!
+//! The alignment and height mode options are part of the type of a [`StyledTextBox`], so text
+//! boxes with different options can't be stored in the same array. An [`ErasedTextBox`] refers to
+//! any [`StyledTextBox`] with a given color type and uses dynamic dispatch to draw it. No
+//! allocation is needed: the erased text box only holds a reference.
+//!
+//! `embedded-graphics`' `Drawable` trait can't be used as a trait object, because its `draw` method
+//! is generic over the display type. `ErasedTextBox` is `Drawable` itself, and it can also be used
+//! to implement object safe widget traits for a specific display type.
+//!
+//! ```rust
+//! use embedded_text::{erased::ErasedTextBox, prelude::*};
+//! use embedded_graphics::{
+//!     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+//! };
+//!
+//! let bounds = Rectangle::new(Point::zero(), Point::new(59, 7));
+//!
+//! let left = TextBox::new("Left", bounds).into_styled(
+//!     TextBoxStyleBuilder::new(Font6x8)
+//!         .text_color(BinaryColor::On)
+//!         .build(),
+//! );
+//! let right = TextBox::new("Right", bounds.translate(Point::new(0, 8))).into_styled(
+//!     TextBoxStyleBuilder::new(Font6x8)
+//!         .alignment(RightAligned)
+//!         .text_color(BinaryColor::On)
+//!         .build(),
+//! );
+//!
+//! let text_boxes = [ErasedTextBox::new(&left), ErasedTextBox::new(&right)];
+//!
+//! let mut display = MockDisplay::new();
+//! for text_box in text_boxes.iter() {
+//!     text_box.draw(&mut display).unwrap();
+//! }
+//! ```
+//!
+//! [`StyledTextBox`]: ../struct.StyledTextBox.html
+//! [`ErasedTextBox`]: struct.ErasedTextBox.html
+use crate::{
+    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
+    rendering::RendererFactory,
+    style::height_mode::HeightMode,
+    utils::rect_ext::RectExt,
+    StyledTextBox,
+};
+use core::fmt;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+/// Object safe interface of a [`StyledTextBox`].
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+trait ErasedDrawable<C: PixelColor> {
+    /// Calls `f` with an iterator over the pixels of the text box.
+    fn with_pixels(&self, f: &mut dyn FnMut(&mut dyn Iterator<Item = Pixel<C>>));
+
+    /// Returns the bounding box.
+    fn bounds(&self) -> Rectangle;
+}
+
+impl<'a, C, F, A, V, H> ErasedDrawable<C> for StyledTextBox<'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+    Self: RendererFactory<'a, C>,
+{
+    fn with_pixels(&self, f: &mut dyn FnMut(&mut dyn Iterator<Item = Pixel<C>>)) {
+        f(&mut self.create_renderer());
+    }
+
+    fn bounds(&self) -> Rectangle {
+        self.text_box.bounds
+    }
+}
+
+/// A reference to a [`StyledTextBox`] of any alignment and height mode.
+///
+/// See the [module level documentation](index.html) for more information.
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+#[derive(Copy, Clone)]
+pub struct ErasedTextBox<'a, C: PixelColor> {
+    text_box: &'a dyn ErasedDrawable<C>,
+}
+
+impl<'a, C> ErasedTextBox<'a, C>
+where
+    C: PixelColor,
+{
+    /// Creates an erased reference to `text_box`.
+    #[inline]
+    #[must_use]
+    pub fn new<F, A, V, H>(text_box: &'a StyledTextBox<'a, C, F, A, V, H>) -> Self
+    where
+        C: 'a,
+        F: Font + Copy + 'a,
+        A: HorizontalTextAlignment + 'a,
+        V: VerticalTextAlignment + 'a,
+        H: HeightMode + 'a,
+        StyledTextBox<'a, C, F, A, V, H>: RendererFactory<'a, C>,
+    {
+        Self { text_box }
+    }
+}
+
+impl<'a, C, F, A, V, H> From<&'a StyledTextBox<'a, C, F, A, V, H>> for ErasedTextBox<'a, C>
+where
+    C: PixelColor + 'a,
+    F: Font + Copy + 'a,
+    A: HorizontalTextAlignment + 'a,
+    V: VerticalTextAlignment + 'a,
+    H: HeightMode + 'a,
+    StyledTextBox<'a, C, F, A, V, H>: RendererFactory<'a, C>,
+{
+    #[inline]
+    fn from(text_box: &'a StyledTextBox<'a, C, F, A, V, H>) -> Self {
+        Self::new(text_box)
+    }
+}
+
+impl<C> Drawable<C> for &ErasedTextBox<'_, C>
+where
+    C: PixelColor,
+{
+    #[inline]
+    fn draw<D: DrawTarget<C>>(self, display: &mut D) -> Result<(), D::Error> {
+        let mut result = Ok(());
+
+        self.text_box
+            .with_pixels(&mut |pixels| result = display.draw_iter(pixels));
+
+        result
+    }
+}
+
+impl<C> Dimensions for ErasedTextBox<'_, C>
+where
+    C: PixelColor,
+{
+    #[inline]
+    fn top_left(&self) -> Point {
+        self.text_box.bounds().top_left
+    }
+
+    #[inline]
+    fn bottom_right(&self) -> Point {
+        self.text_box.bounds().bottom_right
+    }
+
+    #[inline]
+    fn size(&self) -> Size {
+        RectExt::size(self.text_box.bounds())
+    }
+}
+
+impl<C> fmt::Debug for ErasedTextBox<'_, C>
+where
+    C: PixelColor,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedTextBox")
+            .field("bounds", &self.text_box.bounds())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{erased::ErasedTextBox, prelude::*, style::vertical_overdraw::FullRowsOnly};
+    use core::convert::Infallible;
+    use embedded_graphics::{
+        fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*, DrawTarget,
+    };
+
+    /// Counts the calls of the drawing methods.
+    #[derive(Default)]
+    struct CallCounter {
+        draw_pixel: usize,
+        draw_iter: usize,
+    }
+
+    impl DrawTarget<BinaryColor> for CallCounter {
+        type Error = Infallible;
+
+        fn draw_pixel(&mut self, _: Pixel<BinaryColor>) -> Result<(), Self::Error> {
+            self.draw_pixel += 1;
+            Ok(())
+        }
+
+        fn draw_iter<T>(&mut self, item: T) -> Result<(), Self::Error>
+        where
+            T: IntoIterator<Item = Pixel<BinaryColor>>,
+        {
+            self.draw_iter += 1;
+            item.into_iter().for_each(drop);
+            Ok(())
+        }
+
+        fn size(&self) -> Size {
+            Size::new(64, 64)
+        }
+    }
+
+    #[test]
+    fn erased_text_boxes_draw_like_the_original() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+        let text = "Hello, World!";
+
+        let left = TextBox::new(text, bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        let center = TextBox::new(text, bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(CenterAligned)
+                .height_mode(FitToText)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+
+        let erased: [ErasedTextBox<'_, BinaryColor>; 2] = [(&left).into(), (&center).into()];
+
+        let mut expected = MockDisplay::new();
+        left.draw(&mut expected).unwrap();
+        let mut display = MockDisplay::new();
+        erased[0].draw(&mut display).unwrap();
+        assert_eq!(display, expected);
+        assert_eq!(erased[0].size(), left.size());
+
+        let mut expected = MockDisplay::new();
+        center.draw(&mut expected).unwrap();
+        let mut display = MockDisplay::new();
+        erased[1].draw(&mut display).unwrap();
+        assert_eq!(display, expected);
+        assert_eq!(erased[1].bottom_right(), center.bottom_right());
+    }
+
+    #[test]
+    fn text_boxes_of_different_types_in_one_array() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 23));
+
+        let left = TextBox::new("Left", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        let right = TextBox::new("Right", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(RightAligned)
+                .vertical_alignment(CenterAligned)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        let justified = TextBox::new("Justified text", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(Justified)
+                .vertical_alignment(BottomAligned)
+                .height_mode(ShrinkToText(FullRowsOnly))
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+
+        let widgets: [ErasedTextBox<'_, BinaryColor>; 3] = [
+            ErasedTextBox::new(&left),
+            ErasedTextBox::new(&right),
+            ErasedTextBox::new(&justified),
+        ];
+
+        let mut display = MockDisplay::new();
+        for widget in widgets.iter() {
+            widget.draw(&mut display).unwrap();
+        }
+
+        let mut expected = MockDisplay::new();
+        left.draw(&mut expected).unwrap();
+        right.draw(&mut expected).unwrap();
+        justified.draw(&mut expected).unwrap();
+
+        assert_eq!(display, expected);
+    }
+    #[test]
+    fn erased_text_box_draws_pixels_using_a_single_draw_iter_call() {
+        let text_box = TextBox::new(
+            "Hello, World!",
+            Rectangle::new(Point::zero(), Point::new(59, 15)),
+        )
+        .into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+
+        let mut display = CallCounter::default();
+        ErasedTextBox::new(&text_box).draw(&mut display).unwrap();
+
+        assert_eq!(display.draw_iter, 1);
+        assert_eq!(display.draw_pixel, 0);
+    }
+}
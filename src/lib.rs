@@ -13,14 +13,18 @@
 //!      - [`TopAligned`]
 //!      - [`CenterAligned`]
 //!      - [`BottomAligned`]
+//!      - [`SpaceBetween`]
 //!
 //! [`TextBox`] also supports some special characters not handled by embedded-graphics' `Text`:
-//!  - non-breaking space (`\u{200b}`)
-//!  - zero-width space (`\u{a0}`)
+//!  - non-breaking space (`\u{a0}`)
+//!  - zero-width space (`\u{200b}`)
 //!  - soft hyphen (`\u{ad}`)
 //!  - carriage return (`\r`)
 //!  - tab (`\t`) with configurable tab size
 //!
+//! The [`chars`] module provides constants for these characters, and helpers to insert them into
+//! text.
+//!
 //! `TextBox` also supports text coloring using [ANSI escape codes](https://en.wikipedia.org/wiki/ANSI_escape_code).
 //!
 //! ### Example
@@ -65,6 +69,25 @@
 //! }
 //! ```
 //!
+//! If the default settings are enough, the text box can be created in a single step:
+//!
+//! ```rust
+//! use embedded_graphics::{
+//!     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+//! };
+//! use embedded_text::prelude::*;
+//!
+//! let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+//! let text_box = TextBox::with_text_style(
+//!     "Hello, World!",
+//!     bounds,
+//!     TextStyle::new(Font6x8, BinaryColor::On),
+//! );
+//!
+//! let mut display = MockDisplay::new();
+//! text_box.draw(&mut display).unwrap();
+//! ```
+//!
 //! [embedded-graphics]: https://github.com/jamwaffles/embedded-graphics/
 //! [the embedded-graphics simulator]: https://github.com/jamwaffles/embedded-graphics/tree/master/simulator
 //! [simulator README]: https://github.com/jamwaffles/embedded-graphics/tree/master/simulator#usage-without-sdl2
@@ -75,6 +98,8 @@
 //! [`Justified`]: ./alignment/justified/struct.Justified.html
 //! [`TopAligned`]: ./alignment/top/struct.TopAligned.html
 //! [`BottomAligned`]: ./alignment/bottom/struct.BottomAligned.html
+//! [`SpaceBetween`]: ./alignment/space_between/struct.SpaceBetween.html
+//! [`chars`]: ./chars/index.html
 
 #![cfg_attr(not(test), no_std)]
 #![deny(clippy::missing_inline_in_public_items)]
@@ -83,16 +108,39 @@
 #![warn(clippy::all)]
 
 pub mod alignment;
+pub mod chars;
+#[cfg(feature = "editor")]
+pub mod editor;
+pub mod erased;
 pub mod parser;
 pub mod rendering;
 pub mod style;
 pub mod utils;
+#[cfg(feature = "writer")]
+pub mod writer;
 
-use alignment::{HorizontalTextAlignment, VerticalTextAlignment};
-use embedded_graphics::{prelude::*, primitives::Rectangle};
-use rendering::RendererFactory;
-use style::{height_mode::HeightMode, TextBoxStyle};
-use utils::rect_ext::RectExt;
+use alignment::{HorizontalTextAlignment, LeftAligned, TopAligned, VerticalTextAlignment};
+use core::{
+    any::type_name,
+    hash::{Hash, Hasher},
+    ops::Range,
+};
+use embedded_graphics::{prelude::*, primitives::Rectangle, style::TextStyle};
+use parser::Parser;
+use rendering::{
+    budget::{BudgetedRender, RenderBudget, RenderProgress},
+    LineBounds, RenderResult, RendererFactory, StyledTextBoxIterator,
+};
+use style::{
+    color::Rgb,
+    height_mode::{Exact, HeightMode},
+    vertical_overdraw::FullRowsOnly,
+    MeasurementKey, TextBoxStyle, TextLayout, TextOverflow,
+};
+use utils::{
+    fnv::Fnv32,
+    rect_ext::{IntoWellFormed, RectExt},
+};
 
 /// Prelude.
 ///
@@ -103,9 +151,13 @@ pub mod prelude {
     pub use crate::{
         alignment::*,
         style::{
-            height_mode::{Exact, FitToText, HeightMode, ShrinkToText},
+            height_mode::{
+                DynamicHeightMode, Exact, FitToText, FitToTextLimited, HeightMode, ShrinkToContent,
+                ShrinkToText,
+            },
             TextBoxStyle, TextBoxStyleBuilder,
         },
+        utils::rect_ext::IntoWellFormed,
         StyledTextBox, TextBox,
     };
 
@@ -135,8 +187,30 @@ pub struct TextBox<'a> {
     /// The text to be displayed in this `TextBox`
     pub text: &'a str,
 
+    /// Additional pieces of text that are displayed after `text`.
+    ///
+    /// See [`from_parts`] for more information.
+    ///
+    /// [`from_parts`]: #method.from_parts
+    pub continuation: &'a [&'a str],
+
     /// The bounding box of this `TextBox`
     pub bounds: Rectangle,
+
+    /// Whether backspace characters draw two characters in the same cell. Disabled by default.
+    ///
+    /// See [`with_overstrike`] for more information.
+    ///
+    /// [`with_overstrike`]: #method.with_overstrike
+    pub overstrike: bool,
+
+    /// Whether longer words are hyphenated. Disabled by default.
+    ///
+    /// See the [`parser` module] for more information.
+    ///
+    /// [`parser` module]: parser/index.html#hyphenation
+    #[cfg(feature = "hyphenation")]
+    pub hyphenation: bool,
 }
 
 impl<'a> TextBox<'a> {
@@ -146,10 +220,194 @@ impl<'a> TextBox<'a> {
     pub fn new(text: &'a str, bounds: Rectangle) -> Self {
         Self {
             text,
+            continuation: &[],
+            bounds: bounds.into_well_formed(),
+            overstrike: false,
+            #[cfg(feature = "hyphenation")]
+            hyphenation: false,
+        }
+    }
+
+    /// Creates a `StyledTextBox` that draws `text` using `text_style` and the default settings.
+    ///
+    /// The text is [`LeftAligned`] and [`TopAligned`] and the height of the text box is [`Exact`].
+    /// To change any of these settings, use [`TextBoxStyleBuilder`] and [`into_styled`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let text_box = TextBox::with_text_style(
+    ///     "Hello, World!",
+    ///     Rectangle::new(Point::zero(), Point::new(59, 15)),
+    ///     TextStyle::new(Font6x8, BinaryColor::On),
+    /// );
+    ///
+    /// let mut display = MockDisplay::new();
+    /// text_box.draw(&mut display).unwrap();
+    /// ```
+    ///
+    /// [`LeftAligned`]: alignment/left/struct.LeftAligned.html
+    /// [`TopAligned`]: alignment/top/struct.TopAligned.html
+    /// [`Exact`]: style/height_mode/struct.Exact.html
+    /// [`TextBoxStyleBuilder`]: style/builder/struct.TextBoxStyleBuilder.html
+    /// [`into_styled`]: #method.into_styled
+    #[inline]
+    #[must_use]
+    pub fn with_text_style<C, F>(
+        text: &'a str,
+        bounds: Rectangle,
+        text_style: TextStyle<C, F>,
+    ) -> StyledTextBox<'a, C, F, LeftAligned, TopAligned, Exact<FullRowsOnly>>
+    where
+        C: PixelColor,
+        F: Font + Copy,
+    {
+        Self::new(text, bounds).into_styled(TextBoxStyle::from(text_style))
+    }
+
+    /// Creates a new `TextBox` instance that displays multiple pieces of text, one after the other.
+    ///
+    /// This is useful when the text is assembled from parts that are stored in different places,
+    /// because the parts don't need to be concatenated into a buffer. The parts are laid out as if
+    /// they were a single piece of text: whitespace is merged across parts and a word that is split
+    /// between parts is wrapped as a single word. Byte offsets, like the ones used by color spans,
+    /// are counted as if the parts were concatenated.
+    ///
+    /// *Note:* escape sequences must not be split between parts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let parts = ["Temperature: ", "21", "°C"];
+    /// let text_box = TextBox::from_parts(&parts, Rectangle::new(Point::zero(), Point::new(59, 15)));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_parts(parts: &'a [&'a str], bounds: Rectangle) -> Self {
+        let (text, continuation) = parts.split_first().map_or(("", &[][..]), |(t, c)| (*t, c));
+
+        Self {
+            text,
+            continuation,
             bounds: bounds.into_well_formed(),
+            overstrike: false,
+            #[cfg(feature = "hyphenation")]
+            hyphenation: false,
+        }
+    }
+
+    /// Creates a new [`StyledTextBox`] that displays the given text using the given style.
+    ///
+    /// This is a shorthand for `TextBox::new(text, bounds).into_styled(style)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::with_style(
+    ///     "Hello, World!",
+    ///     Rectangle::new(Point::zero(), Point::new(59, 15)),
+    ///     style,
+    /// );
+    /// ```
+    ///
+    /// [`StyledTextBox`]: struct.StyledTextBox.html
+    #[inline]
+    #[must_use]
+    pub fn with_style<C, F, A, V, H>(
+        text: &'a str,
+        bounds: Rectangle,
+        style: TextBoxStyle<C, F, A, V, H>,
+    ) -> StyledTextBox<'a, C, F, A, V, H>
+    where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        Self::new(text, bounds).into_styled(style)
+    }
+
+    /// Enables or disables hyphenation of the text.
+    ///
+    /// Hyphenation should only be enabled for English prose, not for code or URLs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::prelude::*;
+    ///
+    /// let text_box = TextBox::new(
+    ///     "Automatic hyphenation of extraordinarily long words",
+    ///     Rectangle::new(Point::zero(), Point::new(59, 31)),
+    /// )
+    /// .with_hyphenation(true);
+    /// ```
+    #[cfg(feature = "hyphenation")]
+    #[inline]
+    #[must_use]
+    pub fn with_hyphenation(self, enabled: bool) -> Self {
+        Self {
+            hyphenation: enabled,
+            ..self
+        }
+    }
+
+    /// Enables or disables overstrike.
+    ///
+    /// When enabled, the characters before and after a backspace (`\x08`) are drawn in the same
+    /// cell, like in formatted manual pages: `X\x08X` is displayed as a bold `X` and `_\x08X` as
+    /// an underlined `X`. The pair takes up the width of a single character. Any other pair of
+    /// characters is drawn on top of each other.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::prelude::*;
+    ///
+    /// let text_box = TextBox::new(
+    ///     "N\x08NA\x08AM\x08ME\x08E ls - list _\x08d_\x08i_\x08r contents",
+    ///     Rectangle::new(Point::zero(), Point::new(127, 31)),
+    /// )
+    /// .with_overstrike(true);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_overstrike(self, enabled: bool) -> Self {
+        Self {
+            overstrike: enabled,
+            ..self
         }
     }
 
+    /// Returns a parser that processes every part of the text.
+    pub(crate) fn parser(&self) -> Parser<'a> {
+        let parser =
+            Parser::parse_continued(self.text, self.continuation).with_overstrike(self.overstrike);
+
+        #[cfg(feature = "hyphenation")]
+        let parser = parser.with_hyphenation(self.hyphenation);
+
+        parser
+    }
+
     /// Creates a [`StyledTextBox`] by attaching a [`TextBoxStyle`] to the `TextBox` object.
     ///
     /// By default, the size of the [`StyledTextBox`] is equal to the size of the [`TextBox`]. Use
@@ -200,8 +458,12 @@ impl<'a> TextBox<'a> {
         let mut styled = StyledTextBox {
             text_box: self,
             style,
+            color_spans: &[],
+            alignment_spans: &[],
+            vertical_offset: 0,
+            height_cache: None,
         };
-        H::apply(&mut styled);
+        styled.apply_height_mode();
 
         styled
     }
@@ -268,9 +530,32 @@ where
 
     /// The style of the [`TextBox`].
     pub style: TextBoxStyle<C, F, A, V, H>,
+
+    /// Text colors that apply to byte ranges of the text.
+    ///
+    /// See [`with_colors`] for more information.
+    ///
+    /// [`with_colors`]: #method.with_colors
+    pub color_spans: &'a [(Range<usize>, C)],
+
+    /// Horizontal alignments that apply to the lines that start in byte ranges of the text.
+    ///
+    /// See [`with_alignments`] for more information.
+    ///
+    /// [`with_alignments`]: #method.with_alignments
+    pub alignment_spans: &'a [(Range<usize>, A)],
+
+    /// Vertical offset of the text, in pixels.
+    ///
+    /// See [`with_vertical_offset`] for more information.
+    ///
+    /// [`with_vertical_offset`]: #method.with_vertical_offset
+    pub vertical_offset: i32,
+
+    height_cache: Option<(MeasurementKey, u32)>,
 }
 
-impl<C, F, A, V, H> StyledTextBox<'_, C, F, A, V, H>
+impl<'a, C, F, A, V, H> StyledTextBox<'a, C, F, A, V, H>
 where
     C: PixelColor,
     F: Font + Copy,
@@ -278,32 +563,1094 @@ where
     V: VerticalTextAlignment,
     H: HeightMode,
 {
-    /// Sets the height of the [`StyledTextBox`] to the height of the text.
+    /// Creates a new `StyledTextBox` that displays the given text using the given style.
+    ///
+    /// This is equivalent to `TextBox::new(text, bounds).into_styled(style)`, so the
+    /// [`HeightMode`] of the style is applied to the new text box.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .height_mode(FitToText)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = StyledTextBox::new(
+    ///     "Hello, World!",
+    ///     Rectangle::new(Point::zero(), Point::new(59, 0)),
+    ///     style,
+    /// );
+    /// assert_eq!(text_box.size(), Size::new(60, 16));
+    /// ```
+    ///
+    /// [`HeightMode`]: style/height_mode/trait.HeightMode.html
     #[inline]
-    pub fn fit_height(&mut self) -> &mut Self {
-        self.fit_height_limited(u32::max_value())
+    #[must_use]
+    pub fn new(text: &'a str, bounds: Rectangle, style: TextBoxStyle<C, F, A, V, H>) -> Self {
+        TextBox::new(text, bounds).into_styled(style)
     }
 
-    /// Sets the height of the [`StyledTextBox`] to the height of the text, limited to `max_height`.
+    /// Creates a new `StyledTextBox` that displays the text of a [`TextLayout`].
     ///
-    /// This method allows you to set a maximum height. The [`StyledTextBox`] will take up at most
-    /// `max_height` pixel vertical space.
+    /// The height stored in the layout is used instead of measuring the text, unless the width of
+    /// the text area or the style differs from the ones the layout was created with. The
+    /// [`HeightMode`] of the style is applied to the new text box.
+    ///
+    /// See [`TextBoxStyle::layout`] for an example.
+    ///
+    /// [`TextLayout`]: style/struct.TextLayout.html
+    /// [`HeightMode`]: style/height_mode/trait.HeightMode.html
+    /// [`TextBoxStyle::layout`]: style/struct.TextBoxStyle.html#method.layout
     #[inline]
-    pub fn fit_height_limited(&mut self, max_height: u32) -> &mut Self {
-        // Measure text given the width of the textbox
-        let text_height = self
-            .style
-            .measure_text_height(self.text_box.text, self.text_box.size().width)
-            .min(max_height)
-            .min(i32::max_value() as u32) as i32;
+    #[must_use]
+    pub fn from_layout(
+        layout: &TextLayout<'a>,
+        bounds: Rectangle,
+        style: TextBoxStyle<C, F, A, V, H>,
+    ) -> Self {
+        let mut styled = StyledTextBox {
+            text_box: TextBox::new(layout.text, bounds),
+            style,
+            color_spans: &[],
+            alignment_spans: &[],
+            vertical_offset: 0,
+            height_cache: Some((layout.key, layout.height)),
+        };
+        styled.apply_height_mode();
 
-        // Apply height
-        let y = self.text_box.bounds.top_left.y;
-        let new_y = y.saturating_add(text_height - 1);
-        self.text_box.bounds.bottom_right.y = new_y;
+        styled
+    }
+
+    /// Sets the text color of characters in the given byte ranges of the text.
+    ///
+    /// The ranges are byte offsets into the text of the [`TextBox`]. The color of a span overrides
+    /// both the text color set in the style and any color set by ANSI escape sequences. If
+    /// multiple spans contain the same character, the first one is used. Spans may cross line
+    /// breaks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::Rgb565, prelude::*};
+    ///
+    /// let spans = [(0..5, Rgb565::RED), (7..12, Rgb565::GREEN)];
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(Rgb565::WHITE)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(95, 7)))
+    ///     .into_styled(style)
+    ///     .with_colors(&spans);
+    /// ```
+    ///
+    /// [`TextBox`]: struct.TextBox.html
+    #[inline]
+    #[must_use]
+    pub fn with_colors(self, color_spans: &'a [(Range<usize>, C)]) -> Self {
+        Self {
+            color_spans,
+            ..self
+        }
+    }
+
+    /// Shifts the text vertically by `offset` pixels inside the text box.
+    ///
+    /// The text is laid out as usual and is then moved down by `offset` pixels, or up if `offset`
+    /// is negative. This can be used to scroll a long text smoothly. The offset is applied after
+    /// the vertical alignment, and the vertical overdraw setting of the style is applied to the
+    /// moved lines. When the offset is not zero, pixels outside of the bounds of the text box are
+    /// never drawn, and lines that are moved out of the text box are not rendered at all.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{
+    ///     prelude::*,
+    ///     style::{height_mode::Exact, vertical_overdraw::Hidden},
+    /// };
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .height_mode(Exact(Hidden))
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// // Show the bottom half of the first line and the top half of the second one.
+    /// let mut display = MockDisplay::new();
+    /// TextBox::new("Hello,\nWorld!", Rectangle::new(Point::zero(), Point::new(59, 7)))
+    ///     .into_styled(style)
+    ///     .with_vertical_offset(-4)
+    ///     .draw(&mut display)
+    ///     .unwrap();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_vertical_offset(self, offset: i32) -> Self {
+        Self {
+            vertical_offset: offset,
+            ..self
+        }
+    }
+
+    /// Replaces the text and applies the [`HeightMode`] of the style again.
+    ///
+    /// This can be used to update frequently changing text, like a clock, without building the
+    /// [`StyledTextBox`] again. The new text must live as long as the [`StyledTextBox`]. Additional
+    /// parts of the text set using [`TextBox::from_parts`] are removed, and color spans set using
+    /// [`with_colors`] apply to the same byte ranges of the new text.
+    ///
+    /// The [`HeightMode`] starts from the current size of the text box. For example,
+    /// [`ShrinkToContent`] can make the text box narrower, but never wider than it currently is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::height_mode::FitToText};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .height_mode(FitToText)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let mut text_box = TextBox::new("12:00", Rectangle::new(Point::zero(), Point::new(59, 0)))
+    ///     .into_styled(style);
+    /// assert_eq!(text_box.size(), Size::new(60, 8));
+    ///
+    /// text_box.set_text("12:01\nAlarm");
+    /// assert_eq!(text_box.size(), Size::new(60, 16));
+    /// ```
+    ///
+    /// [`HeightMode`]: style/height_mode/trait.HeightMode.html
+    /// [`ShrinkToContent`]: style/height_mode/struct.ShrinkToContent.html
+    /// [`TextBox::from_parts`]: struct.TextBox.html#method.from_parts
+    /// [`with_colors`]: #method.with_colors
+    #[inline]
+    pub fn set_text(&mut self, text: &'a str) -> &mut Self {
+        self.text_box.text = text;
+        self.text_box.continuation = &[];
+        self.apply_height_mode();
 
         self
     }
+
+    /// Draws the text box and returns information about the rendered text.
+    ///
+    /// The returned [`RenderResult`] contains the position where the next character would be
+    /// drawn, which can be used to continue drawing text after the contents of the text box.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let mut display = MockDisplay::new();
+    /// let result = TextBox::new("Hello,\nWorld!", Rectangle::new(Point::zero(), Point::new(59, 15)))
+    ///     .into_styled(style)
+    ///     .draw_with_result(&mut display)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result.next_position, Point::new(36, 8));
+    /// assert_eq!(result.lines, 2);
+    /// ```
+    ///
+    /// [`RenderResult`]: rendering/struct.RenderResult.html
+    #[inline]
+    pub fn draw_with_result<D>(&'a self, display: &mut D) -> Result<RenderResult, D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let mut renderer = self.create_renderer();
+        display.draw_iter(&mut renderer)?;
+
+        Ok(renderer.result())
+    }
+
+    /// Draws the text box until it is complete or `budget` runs out.
+    ///
+    /// Every pixel sent to the display is subtracted from `budget`. If the budget runs out before
+    /// the text box is completely drawn, [`RenderProgress::Incomplete`] is returned with the state
+    /// needed to resume drawing later, for example in the next time slot of a cooperative
+    /// scheduler. The state also reports how many lines are already drawn.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{
+    ///     prelude::*,
+    ///     rendering::budget::{RenderBudget, RenderProgress},
+    /// };
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello,\nWorld!", Rectangle::new(Point::zero(), Point::new(59, 15)))
+    ///     .into_styled(style);
+    ///
+    /// let mut display = MockDisplay::new();
+    /// let mut budget = RenderBudget::pixels(50);
+    /// let mut progress = text_box.draw_budgeted(&mut display, &mut budget).unwrap();
+    ///
+    /// let result = loop {
+    ///     match progress {
+    ///         RenderProgress::Complete(result) => break result,
+    ///         RenderProgress::Incomplete(render) => {
+    ///             // Do some other work, then continue drawing.
+    ///             let mut budget = RenderBudget::pixels(50);
+    ///             progress = render.resume(&mut display, &mut budget).unwrap();
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(result.lines, 2);
+    /// ```
+    ///
+    /// [`RenderProgress::Incomplete`]: rendering/budget/enum.RenderProgress.html#variant.Incomplete
+    #[inline]
+    pub fn draw_budgeted<D>(
+        &'a self,
+        display: &mut D,
+        budget: &mut RenderBudget,
+    ) -> Result<RenderProgress<'a, C, F, A, V, H>, D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let width = RectExt::size(self.layout_area()).width;
+        let total_lines =
+            self.style
+                .line_count(self.text_box.parser(), width, self.alignment_spans);
+
+        BudgetedRender::new(self.create_renderer(), total_lines).resume(display, budget)
+    }
+
+    /// Draws the part of the text box that is inside `clip`.
+    ///
+    /// Only pixels inside the clipping rectangle are sent to the display, which is useful to
+    /// redraw a small part of the screen. Lines that are completely outside of `clip` are laid
+    /// out but not rendered, so the result is the same as drawing the whole text box and
+    /// throwing away the pixels outside of `clip`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// // Only redraw the second line.
+    /// let mut display = MockDisplay::new();
+    /// TextBox::new("Hello,\nWorld!", Rectangle::new(Point::zero(), Point::new(59, 15)))
+    ///     .into_styled(style)
+    ///     .draw_clipped(&mut display, Rectangle::new(Point::new(0, 8), Point::new(59, 15)))
+    ///     .unwrap();
+    /// ```
+    #[inline]
+    pub fn draw_clipped<D>(&'a self, display: &mut D, clip: Rectangle) -> Result<(), D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        display.draw_iter(self.create_renderer().clipped(clip))
+    }
+
+    /// Draws the text box using the colors of `palette` for the palette slots of the style.
+    ///
+    /// Styles that only use direct colors are drawn the same way as by `draw`. Palette slots that
+    /// are not present in `palette` are transparent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::color::ColorRef};
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color_ref(ColorRef::Slot(0))
+    ///     .background_color_ref(ColorRef::Slot(1))
+    ///     .build();
+    ///
+    /// let day = [BinaryColor::Off, BinaryColor::On];
+    /// let night = [BinaryColor::On, BinaryColor::Off];
+    ///
+    /// let text_box = TextBox::new("Hello!", Rectangle::new(Point::zero(), Point::new(59, 7)))
+    ///     .into_styled(style);
+    ///
+    /// let mut display = MockDisplay::new();
+    /// text_box.draw_with_palette(&mut display, &day).unwrap();
+    /// text_box.draw_with_palette(&mut display, &night).unwrap();
+    /// ```
+    #[inline]
+    pub fn draw_with_palette<D>(&'a self, display: &mut D, palette: &[C]) -> Result<(), D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        display.draw_iter(self.create_renderer().with_palette(palette))
+    }
+
+    /// Draws the text box and a caret at the given byte offset of the text.
+    ///
+    /// The caret is a vertical line that is one pixel wide and as tall as the font, drawn at the
+    /// position returned by [`caret_position`]. A caret after the last character of a full line is
+    /// moved back inside the text area.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello", Rectangle::new(Point::zero(), Point::new(59, 7)))
+    ///     .into_styled(style);
+    ///
+    /// // the caret is drawn between "He" and "llo"
+    /// let mut display = MockDisplay::new();
+    /// text_box.draw_with_caret(&mut display, 2, BinaryColor::On).unwrap();
+    ///
+    /// assert_eq!(display.get_pixel(Point::new(12, 7)), Some(BinaryColor::On));
+    /// ```
+    ///
+    /// [`caret_position`]: #method.caret_position
+    #[inline]
+    pub fn draw_with_caret<D>(
+        &'a self,
+        display: &mut D,
+        offset: usize,
+        color: C,
+    ) -> Result<(), D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        display.draw_iter(self.create_renderer())?;
+
+        let caret = self.layout_caret_position(offset);
+        // a caret at the end of a full line would be outside of the text box
+        let x = caret.x.min(self.layout_area().bottom_right.x);
+        let height = F::CHARACTER_SIZE.height as i32;
+        let orientation = self.style.orientation;
+        let bounds = self.text_box.bounds;
+
+        display.draw_iter((0..height).map(|y| {
+            Pixel(
+                orientation.to_display(Point::new(x, caret.y + y), bounds),
+                color,
+            )
+        }))
+    }
+
+    /// Returns a hash of everything that affects how the text box is drawn.
+    ///
+    /// The token covers the text, the bounds, every field of the style including the font and
+    /// alignment types, the color spans and the vertical offset. If the token of a text box is the same as the one it had when it was last
+    /// drawn, drawing it again would most likely produce the same pixels, so the text box doesn't
+    /// need to be redrawn. See [`draw_if_changed`] for a method that does this comparison.
+    ///
+    /// The token is a 32 bit FNV-1a hash. It is only meant to be compared to other tokens of the
+    /// same program, not to be stored.
+    ///
+    /// [`draw_if_changed`]: #method.draw_if_changed
+    #[inline]
+    #[must_use]
+    pub fn content_token(&self) -> u32
+    where
+        C: Hash,
+        A: Hash,
+        TextBoxStyle<C, F, A, V, H>: Hash,
+    {
+        let mut hasher = Fnv32::new();
+
+        // the same text split into different parts is displayed the same way
+        let mut text_len = self.text_box.text.len();
+        hasher.write(self.text_box.text.as_bytes());
+        for part in self.text_box.continuation {
+            text_len += part.len();
+            hasher.write(part.as_bytes());
+        }
+        hasher.write_usize(text_len);
+
+        self.text_box.overstrike.hash(&mut hasher);
+        #[cfg(feature = "hyphenation")]
+        self.text_box.hyphenation.hash(&mut hasher);
+
+        self.text_box.bounds.hash(&mut hasher);
+        // the font and the alignments are usually types without any data
+        type_name::<TextBoxStyle<C, F, A, V, H>>().hash(&mut hasher);
+        self.style.hash(&mut hasher);
+        self.color_spans.hash(&mut hasher);
+        self.alignment_spans.hash(&mut hasher);
+        self.vertical_offset.hash(&mut hasher);
+
+        hasher.finish32()
+    }
+
+    /// Draws the text box if its [`content_token`] is different from `previous_token`.
+    ///
+    /// Returns `true` and updates `previous_token` if the text box was drawn. This can be used to
+    /// skip redrawing text boxes that haven't changed since the last frame.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello!", Rectangle::new(Point::zero(), Point::new(59, 7)))
+    ///     .into_styled(style);
+    ///
+    /// let mut token = 0;
+    /// let mut display = MockDisplay::new();
+    ///
+    /// assert!(text_box.draw_if_changed(&mut display, &mut token).unwrap());
+    ///
+    /// // nothing has changed since the last call
+    /// assert!(!text_box.draw_if_changed(&mut display, &mut token).unwrap());
+    /// ```
+    ///
+    /// [`content_token`]: #method.content_token
+    #[inline]
+    pub fn draw_if_changed<D>(
+        &'a self,
+        display: &mut D,
+        previous_token: &mut u32,
+    ) -> Result<bool, D::Error>
+    where
+        A: Hash,
+        C: Hash,
+        D: DrawTarget<C>,
+        TextBoxStyle<C, F, A, V, H>: Hash,
+        Self: RendererFactory<'a, C>,
+    {
+        let token = self.content_token();
+        if token == *previous_token {
+            return Ok(false);
+        }
+
+        display.draw_iter(self.create_renderer())?;
+        *previous_token = token;
+
+        Ok(true)
+    }
+
+    /// Draws the text box line by line and reports the area of each line after it is drawn.
+    ///
+    /// `on_line_complete` is called with the rows of the text area a line is displayed in, right
+    /// after the last pixel of that line is sent to the display. The reported area spans the whole
+    /// width of the text area. This can be used to refresh parts of a display while the text box is
+    /// being drawn. Lines that are not displayed are not reported, and lines that are drawn over
+    /// each other because of a carriage return report the same area again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let mut display = MockDisplay::new();
+    /// let mut lines = 0;
+    /// TextBox::new("Hello,\nWorld!", Rectangle::new(Point::zero(), Point::new(59, 15)))
+    ///     .into_styled(style)
+    ///     .draw_with_progress(&mut display, |_rows| lines += 1)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(lines, 2);
+    /// ```
+    #[inline]
+    pub fn draw_with_progress<D, P>(
+        &'a self,
+        display: &mut D,
+        mut on_line_complete: P,
+    ) -> Result<(), D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+        P: FnMut(Rectangle),
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let mut renderer = self.create_renderer();
+
+        while !renderer.is_finished() {
+            if let Some(rows) = renderer.draw_line(display)? {
+                on_line_complete(rows);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the pixels of the text box.
+    ///
+    /// The iterator yields the same pixels that drawing the text box sends to the display, which
+    /// makes it possible to post-process them before drawing. Creating the iterator doesn't modify
+    /// the text box, so it can be called repeatedly and always yields the same pixels.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(59, 15)))
+    ///     .into_styled(style);
+    ///
+    /// // Draw the text with inverted colors.
+    /// let mut display = MockDisplay::new();
+    /// display
+    ///     .draw_iter(text_box.pixels().map(|Pixel(point, color)| Pixel(point, color.invert())))
+    ///     .unwrap();
+    /// ```
+    #[inline]
+    pub fn pixels(&'a self) -> impl Iterator<Item = Pixel<C>> + 'a
+    where
+        Self: RendererFactory<'a, C>,
+        <Self as RendererFactory<'a, C>>::Renderer: 'a,
+    {
+        self.create_renderer()
+    }
+
+    /// Returns the area covered by the drawn text.
+    ///
+    /// The returned rectangle is the smallest one that contains every character and rendered space
+    /// of the text, after applying the alignments, line spacing and padding. Only the parts of the
+    /// text that are displayed according to the [`HeightMode`] are taken into account. If no text
+    /// is displayed, the returned rectangle has zero size.
+    ///
+    /// Unlike [`fit_height`], this method does not modify the [`StyledTextBox`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .alignment(CenterAligned)
+    ///     .vertical_alignment(CenterAligned)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello", Rectangle::new(Point::zero(), Point::new(59, 23)))
+    ///     .into_styled(style);
+    ///
+    /// assert_eq!(
+    ///     text_box.text_bounds(),
+    ///     Rectangle::new(Point::new(15, 8), Point::new(44, 15))
+    /// );
+    /// ```
+    ///
+    /// [`HeightMode`]: style/height_mode/trait.HeightMode.html
+    /// [`fit_height`]: #method.fit_height
+    #[inline]
+    #[must_use]
+    pub fn text_bounds(&'a self) -> Rectangle
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        self.create_renderer().remaining_bounds()
+    }
+
+    /// Returns an iterator over the areas covered by the lines of text.
+    ///
+    /// The iterator returns one rectangle for every displayed line, after wrapping and applying
+    /// the alignments. The width of a rectangle is the width of the laid out line, so for example
+    /// the rectangles of [`RightAligned`] lines are flush with the right edge of the text area.
+    /// Empty lines have zero width. Lines that are overwritten because of a carriage return are
+    /// returned separately.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .alignment(RightAligned)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(59, 23)))
+    ///     .into_styled(style);
+    ///
+    /// let mut lines = text_box.line_bounds();
+    /// assert_eq!(lines.next(), Some(Rectangle::new(Point::new(24, 0), Point::new(59, 7))));
+    /// assert_eq!(lines.next(), Some(Rectangle::new(Point::new(24, 8), Point::new(59, 15))));
+    /// assert_eq!(lines.next(), None);
+    /// ```
+    ///
+    /// [`RightAligned`]: alignment/right/struct.RightAligned.html
+    #[inline]
+    #[must_use]
+    pub fn line_bounds(&'a self) -> LineBounds<'a, C, F, A, V, H>
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        LineBounds::new(self.create_renderer())
+    }
+
+    /// Returns the position of a caret placed at the given byte offset of the text.
+    ///
+    /// The returned point is the top left corner of a caret that is as tall as the font. The caret
+    /// is placed in front of the character that starts at `offset`, so it is on the left side of
+    /// the character in left-to-right lines, and on the right side in right-to-left lines.
+    /// Offsets that don't belong to a printed character, like whitespace or the end of the text,
+    /// are placed after the preceding character. Each byte of whitespace moves the caret by one
+    /// space, but never past the next character or the end of the line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(59, 23)))
+    ///     .into_styled(style);
+    ///
+    /// // "World!" is wrapped into the second line
+    /// assert_eq!(text_box.caret_position(9), Point::new(12, 8));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn caret_position(&'a self, offset: usize) -> Point
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let position = self.layout_caret_position(offset);
+
+        self.style
+            .orientation
+            .to_display(position, self.text_box.bounds)
+    }
+
+    /// Returns the position of the caret before the given byte offset, before the text is rotated.
+    pub(crate) fn layout_caret_position(&'a self, offset: usize) -> Point
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        self.create_renderer()
+            .find_line(|start, _| start <= offset)
+            .map_or_else(
+                || self.layout_area().top_left,
+                |(start, _, mut line)| {
+                    let y = line.glyph_top();
+                    Point::new(line.caret_x(offset, start), y)
+                },
+            )
+    }
+
+    /// Returns the byte offset of the caret position that is closest to the given point.
+    ///
+    /// The closest line is selected first, then the closest caret position in that line. This
+    /// method can be used to place the caret where the user touched the display.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(59, 23)))
+    ///     .into_styled(style);
+    ///
+    /// // between "o" and "r" in the second line
+    /// assert_eq!(text_box.offset_at(Point::new(13, 10)), 9);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn offset_at(&'a self, point: Point) -> usize
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let point = self
+            .style
+            .orientation
+            .to_layout(point, self.text_box.bounds);
+
+        self.create_renderer()
+            .find_line(|_, top| top <= point.y)
+            .map_or(0, |(start, _, mut line)| line.offset_at_x(point.x, start))
+    }
+
+    /// Returns the byte offset of the character drawn at the given point.
+    ///
+    /// Each printed character covers the area returned by [`char_bounds`]: its advance, including
+    /// letter spacing, and the height of the font. Points that aren't covered by a printed
+    /// character return `None`. This includes whitespace, hyphens inserted by the wrapping
+    /// algorithm, line spacing and the space outside of the text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(59, 23)))
+    ///     .into_styled(style);
+    ///
+    /// // "o" in the second line
+    /// assert_eq!(text_box.char_at(Point::new(8, 10)), Some(8));
+    ///
+    /// // the space after "Hello,"
+    /// assert_eq!(text_box.char_at(Point::new(38, 2)), None);
+    /// ```
+    ///
+    /// [`char_bounds`]: #method.char_bounds
+    #[inline]
+    #[must_use]
+    pub fn char_at(&'a self, point: Point) -> Option<usize>
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let point = self
+            .style
+            .orientation
+            .to_layout(point, self.text_box.bounds);
+        let (_, _, mut line) = self.create_renderer().find_line(|_, top| top <= point.y)?;
+
+        let top = line.glyph_top();
+        if point.y < top || point.y >= top + F::CHARACTER_SIZE.height as i32 {
+            return None;
+        }
+
+        line.char_at_x(point.x)
+    }
+
+    /// Returns the area covered by the character that starts at the given byte offset.
+    ///
+    /// The area is as wide as the advance of the character, including letter spacing, and as tall
+    /// as the font. Returns `None` if no printed character starts at `offset`, e.g. for
+    /// whitespace, offsets inside a multi-byte character, or characters that are not displayed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let text_box = TextBox::new("Hello, World!", Rectangle::new(Point::zero(), Point::new(59, 23)))
+    ///     .into_styled(style);
+    ///
+    /// assert_eq!(
+    ///     text_box.char_bounds(8),
+    ///     Some(Rectangle::new(Point::new(6, 8), Point::new(11, 15)))
+    /// );
+    /// assert_eq!(text_box.char_bounds(6), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn char_bounds(&'a self, offset: usize) -> Option<Rectangle>
+    where
+        C: From<Rgb>,
+        Self: RendererFactory<'a, C, Renderer = StyledTextBoxIterator<'a, C, F, A, V, H>>,
+    {
+        let (_, _, mut line) = self
+            .create_renderer()
+            .find_line(|start, _| start <= offset)?;
+        let bounds = line.char_bounds(offset)?;
+
+        Some(
+            self.style
+                .orientation
+                .rect_to_display(bounds, self.text_box.bounds),
+        )
+    }
+}
+
+impl<C, F, A, V, H> StyledTextBox<'_, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// Applies the [`HeightMode`] of the style, and measures the text if the vertical alignment
+    /// needs its height.
+    ///
+    /// [`HeightMode`]: style/height_mode/trait.HeightMode.html
+    pub(crate) fn apply_height_mode(&mut self) {
+        let height_mode = self.style.height_mode;
+        height_mode.apply(self);
+
+        if V::uses_text_height() {
+            self.update_height_cache();
+        }
+    }
+
+    /// Sets the height of the [`StyledTextBox`] to the height of the text.
+    #[inline]
+    pub fn fit_height(&mut self) -> &mut Self {
+        self.fit_height_limited(u32::max_value())
+    }
+
+    /// Sets the height of the [`StyledTextBox`] to the height of the text, limited to `max_height`.
+    ///
+    /// This method allows you to set a maximum height. The [`StyledTextBox`] will take up at most
+    /// `max_height` pixel vertical space.
+    ///
+    /// The height includes the vertical padding set in the [`TextBoxStyle`]. If there is no text
+    /// to display, the height of the text is zero, so only the padding remains.
+    ///
+    /// If the text is rotated by 90 or 270 degrees, the width of the [`StyledTextBox`] is set
+    /// instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    /// let bounds = Rectangle::new(Point::zero(), Point::new(59, 59));
+    ///
+    /// // two lines of 8 pixels
+    /// let mut text_box = TextBox::new("Hello,\nWorld!", bounds).into_styled(style);
+    /// text_box.fit_height_limited(32);
+    /// assert_eq!(text_box.size(), Size::new(60, 16));
+    ///
+    /// text_box.fit_height_limited(12);
+    /// assert_eq!(text_box.size(), Size::new(60, 12));
+    ///
+    /// let mut empty = TextBox::new("", bounds).into_styled(style);
+    /// empty.fit_height_limited(32);
+    /// assert_eq!(empty.size(), Size::new(60, 0));
+    ///
+    /// // line spacing is only added between lines, so empty text is still zero pixels high
+    /// let spaced = TextBoxStyleBuilder::new(Font6x8)
+    ///     .line_spacing(2)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    /// let mut empty = TextBox::new("", bounds).into_styled(spaced);
+    /// empty.fit_height_limited(32);
+    /// assert_eq!(empty.size(), Size::new(60, 0));
+    /// ```
+    ///
+    /// [`TextBoxStyle`]: style/struct.TextBoxStyle.html
+    #[inline]
+    pub fn fit_height_limited(&mut self, max_height: u32) -> &mut Self {
+        let padding = self.style.padding;
+
+        // Measure text given the width of the text area
+        let text_height = self
+            .update_height_cache()
+            .saturating_add(padding.vertical())
+            .min(max_height);
+
+        // Apply height
+        let bounds = self.text_box.bounds;
+        let mut size = RectExt::size(bounds);
+        if self.style.orientation.is_vertical() {
+            size.width = text_height;
+        } else {
+            size.height = text_height;
+        }
+        self.text_box.bounds = Rectangle::from_size(bounds.top_left, size);
+
+        self
+    }
+
+    /// Sets the width of the [`StyledTextBox`] to the width of the widest line of text.
+    ///
+    /// The text is measured using the current width, so this method never makes the
+    /// [`StyledTextBox`] wider. The width includes the horizontal padding set in the
+    /// [`TextBoxStyle`].
+    ///
+    /// If the text is rotated by 90 or 270 degrees, the height of the [`StyledTextBox`] is set
+    /// instead.
+    ///
+    /// [`TextBoxStyle`]: style/struct.TextBoxStyle.html
+    #[inline]
+    pub fn fit_width(&mut self) -> &mut Self {
+        let padding = self.style.padding;
+
+        let text_width = RectExt::size(self.layout_area()).width;
+        let width = self
+            .style
+            .measure_width(self.text_box.parser(), text_width, self.alignment_spans)
+            .saturating_add(padding.horizontal());
+
+        let bounds = self.text_box.bounds;
+        let mut size = RectExt::size(bounds);
+        if self.style.orientation.is_vertical() {
+            size.height = width;
+        } else {
+            size.width = width;
+        }
+        self.text_box.bounds = Rectangle::from_size(bounds.top_left, size);
+
+        self
+    }
+
+    /// Returns whether the text is taller than the text area of the [`StyledTextBox`].
+    ///
+    /// If the text is truncated, some of it is drawn outside of the text area, or not drawn at all,
+    /// depending on the [`VerticalOverdraw`] option.
+    ///
+    /// [`VerticalOverdraw`]: style/vertical_overdraw/trait.VerticalOverdraw.html
+    #[inline]
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.text_height() > RectExt::size(self.layout_area()).height
+    }
+
+    /// Returns the height of the text when laid out in the text area.
+    ///
+    /// The height stored when the text box was created or changed is used if the text, the width
+    /// of the text area and the style are unchanged. Otherwise, the text is measured again.
+    pub(crate) fn text_height(&self) -> u32 {
+        let (key, width) = self.measurement_key();
+
+        match self.height_cache {
+            Some((cached_key, height)) if cached_key == key => height,
+            _ => self
+                .style
+                .measure_height(self.text_box.parser(), width, self.alignment_spans),
+        }
+    }
+
+    /// Measures the height of the text, unless the stored height is up to date, and stores it.
+    fn update_height_cache(&mut self) -> u32 {
+        let height = self.text_height();
+        self.height_cache = Some((self.measurement_key().0, height));
+
+        height
+    }
+
+    /// Returns the key of the stored height, and the width of the text area.
+    fn measurement_key(&self) -> (MeasurementKey, u32) {
+        let width = RectExt::size(self.layout_area()).width;
+        let key = self
+            .style
+            .measurement_key(&self.text_box, width, self.alignment_spans);
+
+        (key, width)
+    }
+
+    /// Returns the height of the text that is positioned by the vertical alignment.
+    ///
+    /// If the text doesn't fit into the text area and its end is replaced by an ellipsis, only
+    /// the lines that are displayed completely are positioned, so the text is cut off at its end.
+    pub(crate) fn aligned_text_height(&self) -> u32 {
+        let text_height = self.text_height();
+        let area = RectExt::size(self.layout_area());
+
+        if self.style.text_overflow == TextOverflow::Ellipsis && text_height > area.height {
+            self.style.measure_displayed_height(
+                self.text_box.parser(),
+                area.width,
+                self.alignment_spans,
+                area.height,
+            )
+        } else {
+            text_height
+        }
+    }
+
+    /// Returns the area of the [`StyledTextBox`] where text is laid out.
+    ///
+    /// This is the bounding box of the [`StyledTextBox`] shrunk by the padding set in the
+    /// [`TextBoxStyle`].
+    ///
+    /// [`TextBoxStyle`]: style/struct.TextBoxStyle.html
+    #[inline]
+    #[must_use]
+    pub fn text_area(&self) -> Rectangle {
+        self.style
+            .orientation
+            .rect_to_display(self.layout_area(), self.text_box.bounds)
+    }
+
+    /// Returns the bounding box of the [`StyledTextBox`] before the text is rotated.
+    ///
+    /// [`StyledTextBox`]: struct.StyledTextBox.html
+    pub(crate) fn layout_bounds(&self) -> Rectangle {
+        self.style.orientation.layout_bounds(self.text_box.bounds)
+    }
+
+    /// Returns the area where text is laid out, before the text is rotated.
+    pub(crate) fn layout_area(&self) -> Rectangle {
+        self.style.padding.apply(self.layout_bounds())
+    }
 }
 
 impl<'a, C, F, A, V, H> Drawable<C> for &'a StyledTextBox<'a, C, F, A, V, H>
@@ -335,6 +1682,10 @@ where
         Self {
             text_box: self.text_box.translate(by),
             style: self.style,
+            color_spans: self.color_spans,
+            alignment_spans: self.alignment_spans,
+            vertical_offset: self.vertical_offset,
+            height_cache: self.height_cache,
         }
     }
 
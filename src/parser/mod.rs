@@ -16,8 +16,81 @@
 //!     tokens
 //! );
 //! ```
+//!
+//! # Whitespace
+//!
+//! Only the whitespace characters listed below are handled specially. Every other character,
+//! including the rest of the Unicode whitespace characters (e.g. U+1680 OGHAM SPACE MARK or
+//! U+2028 LINE SEPARATOR), is treated as part of a word and is rendered by the font.
+//!
+//! | Character                        | Width         | Line break  | Notes                     |
+//! |----------------------------------|---------------|-------------|---------------------------|
+//! | U+0020 SPACE                     | one space     | allowed     | sequences are merged      |
+//! | U+2000, U+2001, U+2004 - U+2006  | one space     | allowed     | sequences are merged      |
+//! | U+2002 EN SPACE                  | 1/2 em        | allowed     | not stretched             |
+//! | U+2003 EM SPACE                  | 1 em          | allowed     | not stretched             |
+//! | U+2008, U+200A                   | one space     | allowed     | sequences are merged      |
+//! | U+2009 THIN SPACE                | 1/5 em        | allowed     | not stretched             |
+//! | U+205F MEDIUM MATHEMATICAL SPACE | one space     | allowed     | sequences are merged      |
+//! | U+3000 IDEOGRAPHIC SPACE         | one space     | allowed     | sequences are merged      |
+//! | U+00A0 NO-BREAK SPACE            | one space     | not allowed | part of the word          |
+//! | U+2007 FIGURE SPACE              | one space     | not allowed | part of the word          |
+//! | U+202F NARROW NO-BREAK SPACE     | 1/5 em        | not allowed | not stretched             |
+//! | U+200B ZERO WIDTH SPACE          | zero          | allowed     | merged into spaces        |
+//! | U+00AD SOFT HYPHEN               | zero          | allowed     | `-` drawn when wrapped    |
+//! | `\t`                             | next tab stop | allowed     |                           |
+//! | `\n`                             | -             | always      | starts a new paragraph    |
+//! | `\r`                             | -             | -           | returns to the line start |
+//! | `\x0b` VERTICAL TAB              | -             | always      | keeps the x position      |
+//! | `\x0c` FORM FEED                 | -             | always      | followed by an empty line |
+//!
+//! Breaking spaces are not drawn at the start and end of lines, unless the alignment says
+//! otherwise.
+//!
+//! The width of an em is the height of the font, see [`SpaceWidth`].
+//!
+//! U+2011 NON-BREAKING HYPHEN is also part of the word, and is displayed as a `-` character.
+//!
+//! Lines may also be wrapped after additional characters, see [`Parser::with_break_after`].
+//! Characters that must not start or end a line can be set using
+//! [`Parser::with_no_break_before`] and [`Parser::with_no_break_after`].
+//!
+//! Backspace characters (`\x08`) are part of the words, unless overstrike is enabled using
+//! [`Parser::with_overstrike`].
+//!
+//! # Hyphenation
+//!
+//! The `hyphenation` cargo feature adds support for automatic hyphenation, which can be enabled
+//! using [`Parser::with_hyphenation`]. When enabled, the parser inserts a
+//! [`Break(Some('-'))`](enum.Token.html#variant.Break) token at every hyphenation point of longer
+//! words, using Liang's algorithm and an embedded set of English patterns. This works as if the
+//! words contained soft hyphens. Only words that consist of at least 6 letters (and optionally
+//! leading and trailing punctuation) and are at most 41 bytes long are hyphenated.
+//!
+//! Hyphenation is disabled by default, so enabling the feature does not change how existing text
+//! is laid out.
+//!
+//! *Note:* the `hypher` crate that implements the algorithm requires a newer compiler than the
+//! rest of this crate.
+//!
+//! # Logging
+//!
+//! The `defmt` cargo feature implements `defmt::Format` for [`Token`] and [`SpaceWidth`], so the
+//! token stream can be logged on the target device, for example when debugging line wrapping.
+//! Escape sequences are formatted using their `Debug` implementation.
+//!
+//! [`Parser::with_break_after`]: struct.Parser.html#method.with_break_after
+//! [`Parser::with_no_break_before`]: struct.Parser.html#method.with_no_break_before
+//! [`Parser::with_no_break_after`]: struct.Parser.html#method.with_no_break_after
+//! [`Parser::with_overstrike`]: struct.Parser.html#method.with_overstrike
+//! [`SpaceWidth`]: enum.SpaceWidth.html
+//! [`Token`]: enum.Token.html
+//! [`Parser::with_hyphenation`]: struct.Parser.html#method.with_hyphenation
+use crate::chars;
 use ansi_parser::AnsiSequence;
-use core::str::Chars;
+use core::{ops::Range, str::Chars};
+
+pub mod stream;
 
 /// A text token
 #[derive(Debug, PartialEq, Clone)]
@@ -28,12 +101,21 @@ pub enum Token<'a> {
     /// A \r character.
     CarriageReturn,
 
+    /// A form feed (`\x0c`) character.
+    FormFeed,
+
+    /// A vertical tab (`\x0b`) character.
+    VerticalTab,
+
     /// A \t character.
     Tab,
 
     /// A number of whitespace characters.
     Whitespace(u32),
 
+    /// A space character with a width that depends on the size of the font.
+    SizedSpace(SpaceWidth),
+
     /// A word (a sequence of non-whitespace characters).
     Word(&'a str),
 
@@ -45,32 +127,264 @@ pub enum Token<'a> {
 
     /// An ANSI escape sequence
     EscapeSequence(AnsiSequence),
+
+    /// A backspace (`\x08`) character.
+    ///
+    /// Only returned if overstrike is enabled, see [`Parser::with_overstrike`].
+    ///
+    /// [`Parser::with_overstrike`]: struct.Parser.html#method.with_overstrike
+    Backspace,
+
+    /// A complete control sequence (`ESC [` ... final byte) that is not supported.
+    ///
+    /// The token contains the whole sequence, including the escape character. Unknown sequences
+    /// are not displayed.
+    UnknownEscape(&'a str),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Token<'_> {
+    #[inline]
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Token::NewLine => defmt::write!(f, "NewLine"),
+            Token::CarriageReturn => defmt::write!(f, "CarriageReturn"),
+            Token::FormFeed => defmt::write!(f, "FormFeed"),
+            Token::VerticalTab => defmt::write!(f, "VerticalTab"),
+            Token::Tab => defmt::write!(f, "Tab"),
+            Token::Whitespace(n) => defmt::write!(f, "Whitespace({=u32})", n),
+            Token::SizedSpace(width) => defmt::write!(f, "SizedSpace({})", width),
+            Token::Word(word) => defmt::write!(f, "Word({=str})", word),
+            Token::Break(c) => defmt::write!(f, "Break({})", c),
+            Token::ExtraCharacter(c) => defmt::write!(f, "ExtraCharacter({})", c),
+            // `AnsiSequence` is defined by `ansi-parser`, which doesn't support defmt.
+            Token::EscapeSequence(seq) => {
+                defmt::write!(f, "EscapeSequence({})", defmt::Debug2Format(seq))
+            }
+            Token::Backspace => defmt::write!(f, "Backspace"),
+            Token::UnknownEscape(seq) => defmt::write!(f, "UnknownEscape({=str})", seq),
+        }
+    }
+}
+
+/// The width of a [`SizedSpace`].
+///
+/// [`SizedSpace`]: enum.Token.html#variant.SizedSpace
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SpaceWidth {
+    /// U+2003 EM SPACE, as wide as the font is tall.
+    Em,
+
+    /// U+2002 EN SPACE, half an em.
+    En,
+
+    /// U+2009 THIN SPACE, a fifth of an em.
+    Thin,
+}
+
+impl SpaceWidth {
+    /// Returns the width of the space in a font that is `em` pixels tall, rounded to the nearest
+    /// pixel.
+    #[inline]
+    #[must_use]
+    pub fn width(self, em: u32) -> u32 {
+        match self {
+            SpaceWidth::Em => em,
+            SpaceWidth::En => em / 2 + em % 2,
+            SpaceWidth::Thin => (em + 2) / 5,
+        }
+    }
+
+    fn of(c: char) -> Option<Self> {
+        match c {
+            '\u{2002}' => Some(SpaceWidth::En),
+            '\u{2003}' => Some(SpaceWidth::Em),
+            '\u{2009}' => Some(SpaceWidth::Thin),
+            _ => None,
+        }
+    }
 }
 
 /// Text parser. Turns a string into a stream of [`Token`] objects.
 ///
+/// The parser can also process text that is split into multiple parts, see [`parse_parts`].
+///
 /// [`Token`]: enum.Token.html
+/// [`parse_parts`]: #method.parse_parts
 #[derive(Clone, Debug)]
 pub struct Parser<'a> {
+    source: &'a str,
     inner: Chars<'a>,
+
+    /// Byte offset of `source` in the complete text.
+    source_offset: usize,
+
+    /// Parts of the text that come after `source`.
+    continuation: &'a [&'a str],
+
+    /// Byte offset that the text is cut off at.
+    end: usize,
+
+    /// Text that is processed after the text is cut off or fully processed.
+    suffix: &'a str,
+
+    /// Characters that lines may be wrapped after.
+    break_after: &'a [char],
+
+    /// Characters that must not start a line.
+    no_break_before: &'a [char],
+
+    /// Characters that must not end a line.
+    no_break_after: &'a [char],
+
+    /// Whether a `Break` token must be returned before the next word.
+    break_pending: bool,
+
+    /// The token returned by `peek`, and the byte offset it starts at.
+    peeked: Option<(usize, Token<'a>)>,
+
+    /// Whether backspace characters are returned as `Backspace` tokens.
+    overstrike: bool,
+
+    /// Whether words are split at their hyphenation points.
+    #[cfg(feature = "hyphenation")]
+    hyphenation: bool,
+
+    /// The ends of the remaining syllables of the current word, as a bit mask of byte offsets
+    /// relative to the first unprocessed character.
+    #[cfg(feature = "hyphenation")]
+    syllable_ends: u64,
+
+    /// Whether a hyphenation point must be returned before the next syllable.
+    #[cfg(feature = "hyphenation")]
+    hyphen_pending: bool,
 }
 
-pub(crate) const SPEC_CHAR_NBSP: char = '\u{a0}';
-pub(crate) const SPEC_CHAR_ZWSP: char = '\u{200b}';
-pub(crate) const SPEC_CHAR_SHY: char = '\u{ad}';
+pub(crate) const SPEC_CHAR_ZWSP: char = chars::ZWSP;
+pub(crate) const SPEC_CHAR_SHY: char = chars::SOFT_HYPHEN;
 pub(crate) const SPEC_CHAR_ESCAPE: char = '\x1b';
+pub(crate) const SPEC_CHAR_NNBSP: char = chars::NNBSP;
+pub(crate) const SPEC_CHAR_NBHY: char = chars::NON_BREAKING_HYPHEN;
+pub(crate) const SPEC_CHAR_BACKSPACE: char = '\x08';
+
+/// Spaces that lines may be wrapped at. Each of them is as wide as a regular space, except the
+/// ones that have a [`SpaceWidth`].
+const BREAKING_SPACES: &[char] = &[
+    '\u{20}', '\u{2000}', '\u{2001}', '\u{2002}', '\u{2003}', '\u{2004}', '\u{2005}', '\u{2006}',
+    '\u{2008}', '\u{2009}', '\u{200a}', '\u{205f}', '\u{3000}',
+];
+
+/// Spaces that glue words together. Each of them is as wide as a regular space, except the narrow
+/// no-break space, which is as wide as a thin space.
+const NON_BREAKING_SPACES: &[char] = &[chars::NBSP, '\u{2007}', SPEC_CHAR_NNBSP];
+
+/// The number of letters a word must have to be hyphenated.
+#[cfg(feature = "hyphenation")]
+const HYPHENATION_MIN_LETTERS: usize = 6;
+
+/// The length of the longest word that can be hyphenated, in bytes.
+#[cfg(feature = "hyphenation")]
+const HYPHENATION_MAX_BYTES: usize = 41;
+
+/// Returns true if `c` is a non-breaking space that is as wide as a regular space, and is laid
+/// out as part of a word.
+#[inline]
+pub(crate) fn is_non_breaking_space(c: char) -> bool {
+    c != SPEC_CHAR_NNBSP && NON_BREAKING_SPACES.contains(&c)
+}
+
+/// Returns the character that is displayed in place of `c`.
+#[inline]
+pub(crate) fn displayed_char(c: char) -> char {
+    if c == SPEC_CHAR_NBHY {
+        '-'
+    } else {
+        c
+    }
+}
+
+/// Returns the length of the control sequence at the start of `string`, or `None` if `string`
+/// does not start with a complete control sequence.
+///
+/// A control sequence is `ESC [`, followed by any number of parameter bytes (`0x30`-`0x3F`), any
+/// number of intermediate bytes (`0x20`-`0x2F`), and a final byte (`0x40`-`0x7E`).
+fn control_sequence_len(string: &str) -> Option<usize> {
+    let bytes = string.as_bytes();
+    if !bytes.starts_with(b"\x1b[") {
+        return None;
+    }
+
+    let mut len = 2;
+    while let Some(0x30..=0x3f) = bytes.get(len) {
+        len += 1;
+    }
+    while let Some(0x20..=0x2f) = bytes.get(len) {
+        len += 1;
+    }
+
+    match bytes.get(len) {
+        Some(b) if (0x40..=0x7e).contains(b) => Some(len + 1),
+        _ => None,
+    }
+}
 
 fn is_word_char(c: char) -> bool {
-    // Word tokens are terminated when a whitespace, zwsp or shy character is found. An exception
-    // to this rule is the nbsp, which is whitespace but is included in the word.
-    (!c.is_whitespace() || c == SPEC_CHAR_NBSP)
-        && ![SPEC_CHAR_ZWSP, SPEC_CHAR_SHY, SPEC_CHAR_ESCAPE].contains(&c)
+    // Word tokens are terminated when a breaking space or a special character is found.
+    // Everything else, including non-breaking spaces, is part of the word.
+    !BREAKING_SPACES.contains(&c)
+        && ![
+            '\n',
+            '\r',
+            '\t',
+            '\x0b',
+            '\x0c',
+            SPEC_CHAR_ZWSP,
+            SPEC_CHAR_SHY,
+            SPEC_CHAR_ESCAPE,
+        ]
+        .contains(&c)
+}
+
+/// Returns the ends of the syllables of `word` as a bit mask of byte offsets, or 0 if the word
+/// should not be hyphenated.
+///
+/// Leading and trailing punctuation is kept together with the first and last syllables.
+#[cfg(feature = "hyphenation")]
+fn syllable_ends(word: &str) -> u64 {
+    let start = match word.find(char::is_alphabetic) {
+        Some(start) => start,
+        None => return 0,
+    };
+    let letters = word[start..].trim_end_matches(|c: char| !c.is_alphabetic());
+
+    if word.len() > HYPHENATION_MAX_BYTES
+        || letters.chars().count() < HYPHENATION_MIN_LETTERS
+        || !letters.chars().all(char::is_alphabetic)
+    {
+        return 0;
+    }
+
+    let mut ends = 1 << word.len();
+    let mut end = start;
+    for syllable in hypher::hyphenate(letters, hypher::Lang::English) {
+        ends |= 1 << end;
+        end += syllable.len();
+    }
+
+    // the first "end" is the start of the word
+    ends & !(1 << start)
 }
 
-fn is_space_char(c: char) -> bool {
+pub(crate) fn is_space_char(c: char) -> bool {
     // zero-width space breaks whitespace sequences - this works as long as
     // space handling is symmetrical (i.e. starting == ending behaviour)
-    c.is_whitespace() && !['\n', '\r', '\t', SPEC_CHAR_NBSP].contains(&c) || c == SPEC_CHAR_ZWSP
+    BREAKING_SPACES.contains(&c) || c == SPEC_CHAR_ZWSP
+}
+
+/// Returns true if `c` is merged with the surrounding spaces into a single `Whitespace` token.
+fn is_merged_space(c: char) -> bool {
+    is_space_char(c) && SpaceWidth::of(c).is_none()
 }
 
 impl<'a> Parser<'a> {
@@ -78,94 +392,589 @@ impl<'a> Parser<'a> {
     #[inline]
     #[must_use]
     pub fn parse(text: &'a str) -> Self {
+        Self::parse_continued(text, &[])
+    }
+
+    /// Create a new parser object to process text that is split into multiple parts.
+    ///
+    /// The parts are processed as if they were concatenated, without copying them. This means
+    /// whitespace sequences are merged across the boundaries of parts. A word that is split
+    /// between two parts is returned as two [`Word`] tokens, but it is laid out as a single word.
+    ///
+    /// *Note:* escape sequences must not be split between parts.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let parser = Parser::parse_parts(&["Hello, wo", "rld! ", " Bye"]);
+    /// let tokens = parser.collect::<Vec<Token<'_>>>();
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         Token::Word("Hello,"),
+    ///         Token::Whitespace(1),
+    ///         Token::Word("wo"),
+    ///         Token::Word("rld!"),
+    ///         Token::Whitespace(2),
+    ///         Token::Word("Bye"),
+    ///     ],
+    ///     tokens
+    /// );
+    /// ```
+    ///
+    /// [`Word`]: enum.Token.html#variant.Word
+    #[inline]
+    #[must_use]
+    pub fn parse_parts(parts: &'a [&'a str]) -> Self {
+        match parts.split_first() {
+            Some((first, rest)) => Self::parse_continued(first, rest),
+            None => Self::parse(""),
+        }
+    }
+
+    /// Create a new parser object to process `text`, followed by the parts in `continuation`.
+    pub(crate) fn parse_continued(text: &'a str, continuation: &'a [&'a str]) -> Self {
         Self {
+            source: text,
             inner: text.chars(),
+            source_offset: 0,
+            continuation,
+            end: usize::max_value(),
+            suffix: "",
+            break_after: &[],
+            no_break_before: &[],
+            no_break_after: &[],
+            break_pending: false,
+            peeked: None,
+            overstrike: false,
+            #[cfg(feature = "hyphenation")]
+            hyphenation: false,
+            #[cfg(feature = "hyphenation")]
+            syllable_ends: 0,
+            #[cfg(feature = "hyphenation")]
+            hyphen_pending: false,
+        }
+    }
+
+    /// Enables or disables hyphenation.
+    ///
+    /// Hyphenation is disabled by default. It should only be enabled for English prose, not for
+    /// code or URLs.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let tokens = Parser::parse("hyphenation")
+    ///     .with_hyphenation(true)
+    ///     .collect::<Vec<Token<'_>>>();
+    /// assert_eq!(
+    ///     vec![
+    ///         Token::Word("hy"),
+    ///         Token::Break(Some('-')),
+    ///         Token::Word("phen"),
+    ///         Token::Break(Some('-')),
+    ///         Token::Word("ation"),
+    ///     ],
+    ///     tokens
+    /// );
+    ///
+    /// let tokens = Parser::parse("hyphenation").collect::<Vec<Token<'_>>>();
+    /// assert_eq!(vec![Token::Word("hyphenation")], tokens);
+    /// ```
+    #[cfg(feature = "hyphenation")]
+    #[inline]
+    #[must_use]
+    pub fn with_hyphenation(mut self, enabled: bool) -> Self {
+        self.hyphenation = enabled;
+        self
+    }
+
+    /// Enables or disables overstrike.
+    ///
+    /// Overstrike is disabled by default, and backspace characters are part of the words. When
+    /// enabled, the parser returns a [`Backspace`] token for every backspace character. The
+    /// renderer draws the characters before and after a backspace in the same cell, which is how
+    /// formatted text, e.g. manual pages, is written for terminals: `X\x08X` is displayed as a bold
+    /// `X` and `_\x08X` as an underlined `X`.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let tokens = Parser::parse("_\x08ab")
+    ///     .with_overstrike(true)
+    ///     .collect::<Vec<Token<'_>>>();
+    /// assert_eq!(
+    ///     vec![Token::Word("_"), Token::Backspace, Token::Word("ab")],
+    ///     tokens
+    /// );
+    /// ```
+    ///
+    /// [`Backspace`]: enum.Token.html#variant.Backspace
+    #[inline]
+    #[must_use]
+    pub fn with_overstrike(mut self, enabled: bool) -> Self {
+        self.overstrike = enabled;
+        self
+    }
+
+    /// Sets the characters that lines may be wrapped after.
+    ///
+    /// The parser returns a [`Break(None)`] token after any of these characters if they are
+    /// followed by a word. The characters themselves stay attached to the end of the preceding
+    /// word.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let tokens = Parser::parse("example.com/a-b")
+    ///     .with_break_after(&['/', '-'])
+    ///     .collect::<Vec<Token<'_>>>();
+    /// assert_eq!(
+    ///     vec![
+    ///         Token::Word("example.com/"),
+    ///         Token::Break(None),
+    ///         Token::Word("a-"),
+    ///         Token::Break(None),
+    ///         Token::Word("b"),
+    ///     ],
+    ///     tokens
+    /// );
+    /// ```
+    ///
+    /// [`Break(None)`]: enum.Token.html#variant.Break
+    #[inline]
+    #[must_use]
+    pub fn with_break_after(mut self, chars: &'a [char]) -> Self {
+        self.break_after = chars;
+        self
+    }
+
+    /// Sets the characters that must not start a line.
+    ///
+    /// Break opportunities before these characters, like zero width spaces, soft hyphens and
+    /// the characters set by [`with_break_after`], are removed, so the characters stay attached
+    /// to the preceding word. This can be used to implement the line breaking rules of Japanese
+    /// text (kinsoku shori), where punctuation like `、` and `。` never starts a line.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let tokens = Parser::parse("です\u{200b}。\u{200b}次")
+    ///     .with_no_break_before(&['。'])
+    ///     .collect::<Vec<Token<'_>>>();
+    /// assert_eq!(
+    ///     vec![
+    ///         Token::Word("です"),
+    ///         Token::Word("。"),
+    ///         Token::Break(None),
+    ///         Token::Word("次"),
+    ///     ],
+    ///     tokens
+    /// );
+    /// ```
+    ///
+    /// [`with_break_after`]: #method.with_break_after
+    #[inline]
+    #[must_use]
+    pub fn with_no_break_before(mut self, chars: &'a [char]) -> Self {
+        self.no_break_before = chars;
+        self
+    }
+
+    /// Sets the characters that must not end a line.
+    ///
+    /// Break opportunities after these characters are removed, so the characters stay attached to
+    /// the following word. In Japanese text, opening brackets like `「` never end a line.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let tokens = Parser::parse("「\u{200b}引用」")
+    ///     .with_no_break_after(&['「'])
+    ///     .collect::<Vec<Token<'_>>>();
+    /// assert_eq!(vec![Token::Word("「"), Token::Word("引用」")], tokens);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_no_break_after(mut self, chars: &'a [char]) -> Self {
+        self.no_break_after = chars;
+        self
+    }
+
+    /// Returns true if a line may be wrapped between `prev` and `next`.
+    pub(crate) fn may_break_between(&self, prev: Option<char>, next: Option<char>) -> bool {
+        match (prev, next) {
+            (Some(prev), _) if self.no_break_after.contains(&prev) => false,
+            (_, Some(next)) if self.no_break_before.contains(&next) => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the byte offset of the given slice of the parsed text.
+    ///
+    /// If the text is split into multiple parts, the offset is counted from the start of the first
+    /// part, as if the parts were concatenated.
+    ///
+    /// *Note:* `slice` must be a part of the text piece that is currently being processed. Peeking
+    /// may move the parser to the next part of the text.
+    pub(crate) fn offset_of(&self, slice: &str) -> usize {
+        self.source_offset + slice.as_ptr() as usize - self.source.as_ptr() as usize
+    }
+
+    /// Returns the byte offset of the first unprocessed character of the text.
+    pub(crate) fn offset(&self) -> usize {
+        match self.peeked {
+            Some((offset, _)) => offset,
+            None => self.offset_of(self.inner.as_str()),
         }
     }
 
+    /// Returns `true` if the text doesn't continue a word at the byte offset `offset`, because
+    /// there is a space or the end of the text.
+    ///
+    /// Unlike when lines are wrapped, non-breaking spaces separate words, and soft hyphens don't.
+    pub(crate) fn is_word_end(&self, offset: usize) -> bool {
+        let mut part_start = self.source_offset;
+        let next = core::iter::once(self.source)
+            .chain(self.continuation.iter().copied())
+            .find_map(|part| {
+                let start = offset.checked_sub(part_start);
+                part_start += part.len();
+                part.get(start?..)?.chars().next()
+            });
+
+        match next {
+            Some(c) => {
+                is_non_breaking_space(c)
+                    || c == SPEC_CHAR_NNBSP
+                    || !(is_word_char(c) || c == SPEC_CHAR_SHY || c == SPEC_CHAR_ESCAPE)
+            }
+            None => true,
+        }
+    }
+
+    /// Returns a parser that processes the text from the byte offset `start` to the byte offset
+    /// `end`, followed by `suffix`.
+    ///
+    /// Returns `None` if `start` is not a character boundary of the text piece that is currently
+    /// being processed. `end` must be a character boundary after `start`.
+    pub(crate) fn truncated(&self, start: usize, end: usize, suffix: &'a str) -> Option<Self> {
+        let rest = self.source.get(start.checked_sub(self.source_offset)?..)?;
+
+        // only keep the parts that start before `end`
+        let mut part_start = self.source_offset + self.source.len();
+        let parts = self
+            .continuation
+            .iter()
+            .take_while(|part| {
+                let starts_before_end = part_start < end;
+                part_start += part.len();
+                starts_before_end
+            })
+            .count();
+
+        let mut parser = Self {
+            inner: rest.chars(),
+            continuation: &self.continuation[..parts],
+            end,
+            suffix,
+            break_pending: false,
+            peeked: None,
+            #[cfg(feature = "hyphenation")]
+            syllable_ends: 0,
+            #[cfg(feature = "hyphenation")]
+            hyphen_pending: false,
+            ..self.clone()
+        };
+        parser.cut_off_source();
+
+        Some(parser)
+    }
+
+    /// Cuts the text piece that is currently being processed off at `end`.
+    fn cut_off_source(&mut self) {
+        let len = self.end.saturating_sub(self.source_offset);
+        if len < self.source.len() {
+            let position = self.source.len() - self.inner.as_str().len();
+            self.source = &self.source[..len];
+            self.inner = self.source[position.min(len)..].chars();
+            self.continuation = &[];
+        }
+    }
+
+    /// Returns the next token without consuming it.
+    ///
+    /// The token is only parsed once, it is stored until the next call to `next`.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let mut parser = Parser::parse("Hello, world!");
+    ///
+    /// assert_eq!(parser.peek(), Some(&Token::Word("Hello,")));
+    /// assert_eq!(parser.next(), Some(Token::Word("Hello,")));
+    /// assert_eq!(parser.peek(), Some(&Token::Whitespace(1)));
+    /// ```
+    #[inline]
+    pub fn peek(&mut self) -> Option<&Token<'a>> {
+        if self.peeked.is_none() {
+            let offset = self.offset();
+            self.peeked = self.parse_token().map(|token| (offset, token));
+        }
+
+        self.peeked.as_ref().map(|(_, token)| token)
+    }
+
+    /// Returns an iterator that yields the tokens together with the byte range of the text they
+    /// were parsed from.
+    ///
+    /// The ranges cover the complete source of the tokens, e.g. every character of a whitespace
+    /// sequence or an escape sequence. If the text is split into multiple parts, the ranges are
+    /// counted as if the parts were concatenated. Tokens that don't correspond to a character of
+    /// the text, like the breaks inserted by [`with_break_after`] or by hyphenation, have an empty
+    /// range.
+    ///
+    /// ```rust
+    /// use embedded_text::parser::{Parser, Token};
+    ///
+    /// let mut tokens = Parser::parse("Hello,  \x1b[4mwörld").spanned();
+    ///
+    /// assert_eq!(tokens.next(), Some((0..6, Token::Word("Hello,"))));
+    /// assert_eq!(tokens.next(), Some((6..8, Token::Whitespace(2))));
+    ///
+    /// let (range, token) = tokens.next().unwrap();
+    /// assert_eq!(range, 8..12);
+    /// assert!(if let Token::EscapeSequence(_) = token { true } else { false });
+    ///
+    /// assert_eq!(tokens.next(), Some((12..18, Token::Word("wörld"))));
+    /// assert_eq!(tokens.next(), None);
+    /// ```
+    ///
+    /// [`with_break_after`]: #method.with_break_after
+    #[inline]
+    #[must_use]
+    pub fn spanned(self) -> Spanned<'a> {
+        Spanned { parser: self }
+    }
+
     /// Returns true if there are no tokens to process.
     #[inline]
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.inner.as_str().is_empty()
+        self.peeked.is_none()
+            && self.inner.as_str().is_empty()
+            && self.continuation.iter().all(|part| part.is_empty())
+            && self.suffix.is_empty()
     }
-}
 
-impl<'a> Iterator for Parser<'a> {
-    type Item = Token<'a>;
+    /// Moves to the next part of the text if the current one is fully processed.
+    ///
+    /// Returns false if there is no text left to process.
+    fn skip_to_next_part(&mut self) -> bool {
+        while self.inner.as_str().is_empty() {
+            match self.continuation.split_first() {
+                Some((next, rest)) => {
+                    self.source_offset += self.source.len();
+                    self.source = next;
+                    self.inner = next.chars();
+                    self.continuation = rest;
+                    self.cut_off_source();
+                }
+                None if !self.suffix.is_empty() => {
+                    self.source_offset += self.source.len();
+                    self.source = self.suffix;
+                    self.inner = self.suffix.chars();
+                    self.end = usize::max_value();
+                    self.suffix = "";
+                }
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns a `Word` token for `word`, which has just been processed.
+    ///
+    /// If hyphenation is enabled, only the first syllable of the word is returned, the rest of
+    /// the word is returned by subsequent calls to `next`.
+    fn word(&mut self, word: &'a str) -> Token<'a> {
+        #[cfg(feature = "hyphenation")]
+        {
+            let syllable_ends = if self.hyphenation {
+                syllable_ends(word)
+            } else {
+                0
+            };
+
+            if syllable_ends != 0 {
+                // rewind to the start of the word
+                let start = self.offset_of(word) - self.source_offset;
+                self.inner = self.source[start..].chars();
+                self.syllable_ends = syllable_ends;
+
+                return self.next_syllable();
+            }
+        }
+
+        Token::Word(word)
+    }
+
+    /// Returns the next syllable of a hyphenated word, or the hyphenation point that precedes it.
+    #[cfg(feature = "hyphenation")]
+    fn next_syllable(&mut self) -> Token<'a> {
+        if self.hyphen_pending {
+            self.hyphen_pending = false;
+            return Token::Break(Some('-'));
+        }
+
+        let string = self.inner.as_str();
+        let len = self.syllable_ends.trailing_zeros() as usize;
+        self.inner = string[len..].chars();
+        self.syllable_ends = (self.syllable_ends >> len) & !1;
+        self.hyphen_pending = self.syllable_ends != 0;
+
+        Token::Word(&string[..len])
+    }
+
+    /// Returns true if `c` is part of a word. Backspace characters end words if overstrike is
+    /// enabled.
+    fn is_word_char(&self, c: char) -> bool {
+        is_word_char(c) && !(self.overstrike && c == SPEC_CHAR_BACKSPACE)
+    }
+
+    /// Returns the character before `string`, which is a part of the text piece that is currently
+    /// being processed.
+    fn char_before(&self, string: &str) -> Option<char> {
+        let start = string.as_ptr() as usize - self.source.as_ptr() as usize;
+        self.source[..start].chars().next_back()
+    }
+
+    /// Returns the next character of the text, without consuming it.
+    fn peek_char(&self) -> Option<char> {
+        self.inner.clone().next().or_else(|| {
+            self.continuation
+                .iter()
+                .chain(core::iter::once(&self.suffix))
+                .find_map(|part| part.chars().next())
+        })
+    }
+
+    /// Parses the next token of the text.
+    fn parse_token(&mut self) -> Option<Token<'a>> {
+        if !self.skip_to_next_part() {
+            return None;
+        }
+
+        #[cfg(feature = "hyphenation")]
+        {
+            if self.syllable_ends != 0 {
+                return Some(self.next_syllable());
+            }
+        }
+
+        if self.break_pending {
+            self.break_pending = false;
+            return Some(Token::Break(None));
+        }
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
         let string = self.inner.as_str();
+        let mut chars = string.char_indices();
 
-        if let Some(c) = self.inner.next() {
-            if is_word_char(c) {
+        if let Some((_, c)) = chars.next() {
+            if self.is_word_char(c) {
                 // find the longest consecutive slice of text for a Word token
-                while let Some(c) = self.inner.next() {
-                    if !is_word_char(c) {
-                        // pointer arithmetic to get the offset of `c` relative to `string`
-                        let offset = {
-                            let ptr_start = string.as_ptr() as usize;
-                            let ptr_cur = self.inner.as_str().as_ptr() as usize;
-                            ptr_cur - ptr_start - c.len_utf8()
-                        };
-                        self.inner = unsafe {
-                            // SAFETY: we only work with character boundaries and
-                            // offset is <= length
-                            string.get_unchecked(offset..).chars()
-                        };
-                        return Some(Token::Word(unsafe {
-                            // SAFETY: we only work with character boundaries and
-                            // offset is <= length
-                            string.get_unchecked(0..offset)
-                        }));
+                let mut prev = c;
+                for (offset, c) in chars {
+                    let breaks = self.break_after.contains(&prev)
+                        && is_word_char(c)
+                        && self.may_break_between(Some(prev), Some(c));
+                    prev = c;
+
+                    if breaks || !self.is_word_char(c) {
+                        self.inner = string[offset..].chars();
+                        self.break_pending = breaks;
+                        return Some(self.word(&string[..offset]));
                     }
                 }
+                self.inner = string[string.len()..].chars();
+
+                // a word that is split between parts may continue after a break character
+                let next = self.peek_char();
+                self.break_pending = self.break_after.contains(&prev)
+                    && next.filter(|&c| is_word_char(c)).is_some()
+                    && self.may_break_between(Some(prev), next);
 
                 // consumed all the text
-                Some(Token::Word(string))
+                Some(self.word(string))
             } else {
+                self.inner = string[c.len_utf8()..].chars();
+
                 match c {
                     // special characters
                     '\n' => Some(Token::NewLine),
                     '\r' => Some(Token::CarriageReturn),
+                    '\x0b' => Some(Token::VerticalTab),
+                    '\x0c' => Some(Token::FormFeed),
                     '\t' => Some(Token::Tab),
+                    SPEC_CHAR_ZWSP | SPEC_CHAR_SHY
+                        if !self.may_break_between(self.char_before(string), self.peek_char()) =>
+                    {
+                        // the break is not allowed, the surrounding words are laid out as one
+                        self.parse_token()
+                    }
                     SPEC_CHAR_ZWSP => Some(Token::Break(None)),
                     SPEC_CHAR_SHY => Some(Token::Break(Some('-'))),
-                    SPEC_CHAR_ESCAPE => ansi_parser::parse_escape(string).map_or(
-                        Some(Token::EscapeSequence(AnsiSequence::Escape)),
-                        |(string, output)| {
+                    SPEC_CHAR_BACKSPACE => Some(Token::Backspace),
+                    SPEC_CHAR_ESCAPE => match ansi_parser::parse_escape(string) {
+                        Ok((string, output)) => {
                             self.inner = string.chars();
                             Some(Token::EscapeSequence(output))
+                        }
+                        Err(_) => match control_sequence_len(string) {
+                            Some(len) => {
+                                // control sequences only contain ASCII characters
+                                self.inner = string[len..].chars();
+                                Some(Token::UnknownEscape(&string[..len]))
+                            }
+                            None => Some(Token::EscapeSequence(AnsiSequence::Escape)),
                         },
-                    ),
+                    },
 
-                    // count consecutive whitespace
+                    // spaces that are not as wide as a regular space are not merged
+                    c if SpaceWidth::of(c).is_some() => SpaceWidth::of(c).map(Token::SizedSpace),
+
+                    // count consecutive whitespace, which may continue in the next part of the text
                     _ => {
                         let mut len = 1;
-                        while let Some(c) = self.inner.next() {
-                            if is_space_char(c) {
+                        loop {
+                            let rest = self.inner.as_str();
+                            let mut end = rest.len();
+                            for (offset, c) in rest.char_indices() {
+                                if !is_merged_space(c) {
+                                    end = offset;
+                                    break;
+                                }
+
                                 if c != SPEC_CHAR_ZWSP {
                                     len += 1;
                                 }
-                            } else {
-                                // pointer arithmetic to get the offset of `c` relative to `string`
-                                let offset = {
-                                    let ptr_start = string.as_ptr() as usize;
-                                    let ptr_cur = self.inner.as_str().as_ptr() as usize;
-                                    ptr_cur - ptr_start - c.len_utf8()
-                                };
-                                // consume the whitespaces
-                                self.inner = unsafe {
-                                    // SAFETY: we only work with character boundaries and
-                                    // offset is <= length
-                                    string.get_unchecked(offset..).chars()
-                                };
-                                return Some(Token::Whitespace(len));
+                            }
+
+                            // consume the whitespace
+                            self.inner = rest[end..].chars();
+                            if end < rest.len() {
+                                break;
+                            }
+
+                            match self.peek_char() {
+                                Some(c) if is_merged_space(c) => {
+                                    self.skip_to_next_part();
+                                }
+                                _ => break,
                             }
                         }
 
-                        // consumed all the text
                         Some(Token::Whitespace(len))
                     }
                 }
@@ -176,9 +985,43 @@ impl<'a> Iterator for Parser<'a> {
     }
 }
 
+impl<'a> Iterator for Parser<'a> {
+    type Item = Token<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.peeked.take() {
+            Some((_, token)) => Some(token),
+            None => self.parse_token(),
+        }
+    }
+}
+
+/// Iterator over the tokens of a text, together with their byte ranges.
+///
+/// This struct is created by [`Parser::spanned`].
+///
+/// [`Parser::spanned`]: struct.Parser.html#method.spanned
+#[derive(Clone, Debug)]
+pub struct Spanned<'a> {
+    parser: Parser<'a>,
+}
+
+impl<'a> Iterator for Spanned<'a> {
+    type Item = (Range<usize>, Token<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.parser.offset();
+        let token = self.parser.next()?;
+
+        Some((start..self.parser.offset(), token))
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{Parser, Token};
+    use super::{Parser, SpaceWidth, Token};
     use ansi_parser::AnsiSequence;
     use heapless::Vec;
 
@@ -217,6 +1060,132 @@ mod test {
         );
     }
 
+    #[test]
+    fn spanned_tokens() {
+        let text = "Lorem ipsum \r dolor sit am\u{00AD}et,\tconse😅ctetur adipiscing\nelit";
+        let spans = Parser::parse(text)
+            .spanned()
+            .map(|(range, _)| range)
+            .collect::<std::vec::Vec<_>>();
+
+        assert_eq!(
+            spans,
+            vec![
+                0..5,
+                5..6,
+                6..11,
+                11..12,
+                12..13,
+                13..14,
+                14..19,
+                19..20,
+                20..23,
+                23..24,
+                24..26,
+                26..28,
+                28..31,
+                31..32,
+                32..47,
+                47..48,
+                48..58,
+                58..59,
+                59..63,
+            ]
+        );
+
+        // the ranges cover the source of each token
+        for ((range, token), expected) in Parser::parse(text).spanned().zip(Parser::parse(text)) {
+            assert_eq!(token, expected);
+            if let Token::Word(w) = token {
+                assert_eq!(&text[range], w);
+            }
+        }
+    }
+
+    #[test]
+    fn spanned_whitespace_and_escape_sequences() {
+        let parts = ["a \u{2001}", "\u{200B} \x1b[31m", "b"];
+        assert_eq!(
+            Parser::parse_parts(&parts)
+                .spanned()
+                .collect::<std::vec::Vec<_>>(),
+            vec![
+                (0..1, Token::Word("a")),
+                (1..9, Token::Whitespace(3)),
+                (
+                    9..14,
+                    Token::EscapeSequence(AnsiSequence::SetGraphicsMode(
+                        Vec::from_slice(&[31]).unwrap()
+                    ))
+                ),
+                (14..15, Token::Word("b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn spanned_special_characters() {
+        assert_eq!(
+            Parser::parse("a/b\r\n\t\u{ad}c\u{200b}d")
+                .with_break_after(&['/'])
+                .spanned()
+                .collect::<std::vec::Vec<_>>(),
+            vec![
+                (0..2, Token::Word("a/")),
+                (2..2, Token::Break(None)),
+                (2..3, Token::Word("b")),
+                (3..4, Token::CarriageReturn),
+                (4..5, Token::NewLine),
+                (5..6, Token::Tab),
+                (6..8, Token::Break(Some('-'))),
+                (8..9, Token::Word("c")),
+                (9..12, Token::Break(None)),
+                (12..13, Token::Word("d")),
+            ]
+        );
+    }
+
+    #[test]
+    fn peeking_does_not_change_the_tokens() {
+        let parts = ["a \u{ad}b", "c\n"];
+        let expected = Parser::parse_parts(&parts)
+            .spanned()
+            .collect::<std::vec::Vec<_>>();
+
+        // peek before every token, and twice before some of them
+        let mut parser = Parser::parse_parts(&parts);
+        let mut tokens = std::vec::Vec::new();
+        for i in 0.. {
+            let peeked = parser.peek().cloned();
+            if i % 2 == 0 {
+                assert_eq!(parser.peek().cloned(), peeked);
+            }
+
+            let start = parser.offset();
+            let token = parser.next();
+            assert_eq!(token, peeked);
+
+            match token {
+                Some(token) => tokens.push((start..parser.offset(), token)),
+                None => break,
+            }
+        }
+
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn peeked_token_is_not_empty() {
+        let mut parser = Parser::parse("a");
+
+        assert_eq!(parser.peek(), Some(&Token::Word("a")));
+        assert!(!parser.is_empty());
+
+        assert_eq!(parser.next(), Some(Token::Word("a")));
+        assert!(parser.is_empty());
+        assert_eq!(parser.peek(), None);
+    }
+
     #[test]
     fn parse_zwsp() {
         assert_eq!(9, "two\u{200B}words".chars().count());
@@ -226,7 +1195,36 @@ mod test {
             vec![Token::Word("two"), Token::Break(None), Token::Word("words")],
         );
 
+        // zero width spaces are not counted, the whitespace token only counts the regular spaces
         assert_tokens("  \u{200B} ", vec![Token::Whitespace(3)]);
+        assert_tokens(" \u{200B} ", vec![Token::Whitespace(2)]);
+        assert_tokens("  \u{200B}", vec![Token::Whitespace(2)]);
+        assert_tokens("\u{200B}  ", vec![Token::Break(None), Token::Whitespace(2)]);
+    }
+
+    #[test]
+    fn parse_form_feed_and_vertical_tab() {
+        assert_tokens(
+            "page\x0cnext\x0b  tab",
+            vec![
+                Token::Word("page"),
+                Token::FormFeed,
+                Token::Word("next"),
+                Token::VerticalTab,
+                Token::Whitespace(2),
+                Token::Word("tab"),
+            ],
+        );
+
+        assert_tokens(
+            "\x0c\x0c\x0b\n",
+            vec![
+                Token::FormFeed,
+                Token::FormFeed,
+                Token::VerticalTab,
+                Token::NewLine,
+            ],
+        );
     }
 
     #[test]
@@ -244,6 +1242,92 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_whitespace_table() {
+        for &c in super::BREAKING_SPACES {
+            let text = format!("a{}{}b", c, c);
+            let tokens = match SpaceWidth::of(c) {
+                Some(space) => vec![
+                    Token::Word("a"),
+                    Token::SizedSpace(space),
+                    Token::SizedSpace(space),
+                    Token::Word("b"),
+                ],
+                None => vec![Token::Word("a"), Token::Whitespace(2), Token::Word("b")],
+            };
+            assert_tokens(&text, tokens);
+        }
+
+        for &c in super::NON_BREAKING_SPACES {
+            let text = format!("a{}b", c);
+            assert_tokens(&text, vec![Token::Word(&text)]);
+        }
+
+        assert_tokens(
+            "a\u{200B}b\u{AD}c\td\re\nf",
+            vec![
+                Token::Word("a"),
+                Token::Break(None),
+                Token::Word("b"),
+                Token::Break(Some('-')),
+                Token::Word("c"),
+                Token::Tab,
+                Token::Word("d"),
+                Token::CarriageReturn,
+                Token::Word("e"),
+                Token::NewLine,
+                Token::Word("f"),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_sized_spaces() {
+        assert_tokens(
+            "a \u{2003}\u{2002} \u{2009}b",
+            vec![
+                Token::Word("a"),
+                Token::Whitespace(1),
+                Token::SizedSpace(SpaceWidth::Em),
+                Token::SizedSpace(SpaceWidth::En),
+                Token::Whitespace(1),
+                Token::SizedSpace(SpaceWidth::Thin),
+                Token::Word("b"),
+            ],
+        );
+
+        assert_eq!(
+            [SpaceWidth::Em, SpaceWidth::En, SpaceWidth::Thin]
+                .iter()
+                .map(|space| space.width(8))
+                .collect::<std::vec::Vec<_>>(),
+            [8, 4, 2]
+        );
+    }
+
+    #[test]
+    fn parse_non_breaking_hyphen_and_narrow_space() {
+        assert_tokens("12\u{2011}34", vec![Token::Word("12\u{2011}34")]);
+        assert_tokens("1\u{202f}234", vec![Token::Word("1\u{202f}234")]);
+        assert_tokens(
+            "call 555\u{2011}1234",
+            vec![
+                Token::Word("call"),
+                Token::Whitespace(1),
+                Token::Word("555\u{2011}1234"),
+            ],
+        );
+    }
+
+    #[test]
+    fn whitespace_not_in_table_is_word_char() {
+        assert!('\u{1680}'.is_whitespace());
+        assert!('\u{2028}'.is_whitespace());
+
+        assert_tokens("a\u{1680}b", vec![Token::Word("a\u{1680}b")]);
+        assert_tokens("a\u{2028}b", vec![Token::Word("a\u{2028}b")]);
+    }
+
     #[test]
     fn parse_shy_issue_42() {
         assert_tokens(
@@ -268,11 +1352,11 @@ mod test {
         );
 
         assert_tokens(
-            "foo\x1b[bar",
+            "foo\x1b[1;\u{e4}bar",
             vec![
                 Token::Word("foo"),
                 Token::EscapeSequence(AnsiSequence::Escape),
-                Token::Word("[bar"),
+                Token::Word("[1;\u{e4}bar"),
             ],
         );
 
@@ -287,6 +1371,64 @@ mod test {
         );
     }
 
+    #[test]
+    fn backspace_tokens() {
+        assert_eq!(
+            Parser::parse("B\x08Bo\x08o\x08 _\x08x")
+                .with_overstrike(true)
+                .collect::<std::vec::Vec<Token>>(),
+            vec![
+                Token::Word("B"),
+                Token::Backspace,
+                Token::Word("Bo"),
+                Token::Backspace,
+                Token::Word("o"),
+                Token::Backspace,
+                Token::Whitespace(1),
+                Token::Word("_"),
+                Token::Backspace,
+                Token::Word("x"),
+            ]
+        );
+
+        assert_tokens("B\x08B", vec![Token::Word("B\x08B")]);
+    }
+
+    #[test]
+    fn control_sequences_are_consumed() {
+        for &text in &["a\x1b[2Jb", "a\x1b[1;2Hb"] {
+            let tokens = Parser::parse(text).collect::<std::vec::Vec<Token>>();
+
+            assert_eq!(tokens.len(), 3, "{:?}", text);
+            assert_eq!(tokens[0], Token::Word("a"));
+            assert!(
+                if let Token::EscapeSequence(_) | Token::UnknownEscape(_) = tokens[1] {
+                    true
+                } else {
+                    false
+                },
+                "{:?}",
+                text
+            );
+            assert_eq!(tokens[2], Token::Word("b"));
+        }
+    }
+
+    #[test]
+    fn unsupported_control_sequences() {
+        assert_tokens(
+            "a\x1b[6nb\x1b[3 q\x1b[?2004hc",
+            vec![
+                Token::Word("a"),
+                Token::UnknownEscape("\x1b[6n"),
+                Token::Word("b"),
+                Token::UnknownEscape("\x1b[3 q"),
+                Token::UnknownEscape("\x1b[?2004h"),
+                Token::Word("c"),
+            ],
+        );
+    }
+
     #[test]
     fn escape_char_colors() {
         assert_tokens(
@@ -320,4 +1462,198 @@ mod test {
             ],
         );
     }
+
+    #[test]
+    fn escape_char_cursor_movement() {
+        assert_tokens(
+            "foo\x1b[2Cbar\x1b[3Dbaz",
+            vec![
+                Token::Word("foo"),
+                Token::EscapeSequence(AnsiSequence::CursorForward(2)),
+                Token::Word("bar"),
+                Token::EscapeSequence(AnsiSequence::CursorBackward(3)),
+                Token::Word("baz"),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_parts() {
+        let parts = ["foo ", "", " bar", "baz\n", "\u{200B} x"];
+        assert_eq!(
+            Parser::parse_parts(&parts).collect::<std::vec::Vec<Token>>(),
+            vec![
+                Token::Word("foo"),
+                Token::Whitespace(2),
+                Token::Word("bar"),
+                Token::Word("baz"),
+                Token::NewLine,
+                Token::Break(None),
+                Token::Whitespace(1),
+                Token::Word("x"),
+            ]
+        );
+
+        assert!(Parser::parse_parts(&["", ""]).is_empty());
+        assert_eq!(None, Parser::parse_parts(&[]).next());
+    }
+
+    #[test]
+    fn whitespace_is_merged_across_parts() {
+        let parts = ["a  ", " \u{200B} ", "", "  b \u{2003} "];
+        assert_eq!(
+            Parser::parse_parts(&parts).collect::<std::vec::Vec<Token>>(),
+            vec![
+                Token::Word("a"),
+                Token::Whitespace(6),
+                Token::Word("b"),
+                Token::Whitespace(1),
+                Token::SizedSpace(SpaceWidth::of('\u{2003}').unwrap()),
+                Token::Whitespace(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_break_after() {
+        let parse = |text| {
+            Parser::parse(text)
+                .with_break_after(&['/', '–'])
+                .collect::<std::vec::Vec<Token>>()
+        };
+
+        // multi-byte characters before and after the break
+        assert_eq!(
+            parse("ä/ö–ü"),
+            vec![
+                Token::Word("ä/"),
+                Token::Break(None),
+                Token::Word("ö–"),
+                Token::Break(None),
+                Token::Word("ü"),
+            ]
+        );
+
+        // no break if the next token is not a word
+        assert_eq!(
+            parse("/a/ b//\n"),
+            vec![
+                Token::Word("/"),
+                Token::Break(None),
+                Token::Word("a/"),
+                Token::Whitespace(1),
+                Token::Word("b/"),
+                Token::Break(None),
+                Token::Word("/"),
+                Token::NewLine,
+            ]
+        );
+
+        // words may continue in the next part
+        assert_eq!(
+            Parser::parse_parts(&["a/", "b/", " c"])
+                .with_break_after(&['/'])
+                .collect::<std::vec::Vec<Token>>(),
+            vec![
+                Token::Word("a/"),
+                Token::Break(None),
+                Token::Word("b/"),
+                Token::Whitespace(1),
+                Token::Word("c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_no_break_before_and_after() {
+        let parse = |text| {
+            Parser::parse(text)
+                .with_break_after(&['/'])
+                .with_no_break_before(&['。'])
+                .with_no_break_after(&['「'])
+                .collect::<std::vec::Vec<Token>>()
+        };
+
+        // break opportunities before and after the characters are removed
+        assert_eq!(
+            parse("a/。\u{ad}。 「/b「\u{200b}c"),
+            vec![
+                Token::Word("a/。"),
+                Token::Word("。"),
+                Token::Whitespace(1),
+                Token::Word("「/"),
+                Token::Break(None),
+                Token::Word("b「"),
+                Token::Word("c"),
+            ]
+        );
+
+        // spaces still allow the line to be wrapped
+        assert_eq!(
+            parse("a 。"),
+            vec![Token::Word("a"), Token::Whitespace(1), Token::Word("。")]
+        );
+    }
+    #[test]
+    #[cfg(feature = "hyphenation")]
+    fn parse_with_hyphenation() {
+        let text = "(Hyphenation), short words and https://example.com stay";
+        let parser = Parser::parse(text).with_hyphenation(true);
+        let tokens = parser.clone().collect::<std::vec::Vec<Token>>();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Word("(Hy"),
+                Token::Break(Some('-')),
+                Token::Word("phen"),
+                Token::Break(Some('-')),
+                Token::Word("ation),"),
+                Token::Whitespace(1),
+                Token::Word("short"),
+                Token::Whitespace(1),
+                Token::Word("words"),
+                Token::Whitespace(1),
+                Token::Word("and"),
+                Token::Whitespace(1),
+                Token::Word("https://example.com"),
+                Token::Whitespace(1),
+                Token::Word("stay"),
+            ]
+        );
+
+        // syllables are slices of the original text
+        let mut parser = parser;
+        let mut offsets = std::vec::Vec::new();
+        while let Some(token) = parser.next() {
+            if let Token::Word(w) = token {
+                offsets.push(parser.offset_of(w));
+            }
+        }
+        assert_eq!(offsets, vec![0, 3, 7, 15, 21, 27, 31, 51]);
+
+        assert_tokens("Hyphenation", vec![Token::Word("Hyphenation")]);
+    }
+
+    #[test]
+    fn truncated_parts() {
+        let parser = Parser::parse_parts(&["Hello, wo", "rld! ", " Bye"]);
+
+        assert_eq!(
+            parser
+                .truncated(7, 11, "...")
+                .unwrap()
+                .collect::<std::vec::Vec<Token>>(),
+            vec![Token::Word("wo"), Token::Word("rl"), Token::Word("...")]
+        );
+        assert_eq!(
+            parser
+                .truncated(0, 5, "")
+                .unwrap()
+                .collect::<std::vec::Vec<Token>>(),
+            vec![Token::Word("Hello")]
+        );
+
+        // the start must be in the current part
+        assert!(parser.truncated(10, 11, "").is_none());
+    }
 }
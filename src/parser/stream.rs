@@ -0,0 +1,312 @@
+//! Parse text that is received in chunks.
+//!
+//! See [`StreamParser`] for more information.
+//!
+//! [`StreamParser`]: struct.StreamParser.html
+use crate::parser::{Parser, Spanned, Token};
+use ansi_parser::AnsiSequence;
+use core::str;
+
+/// The number of bytes an unrecognized escape sequence is held back for.
+///
+/// Escape sequences are parsed only when they are complete. If an escape character is followed by
+/// this many bytes that are not a valid escape sequence, it is returned on its own.
+const MAX_ESCAPE_SEQUENCE_LEN: usize = 32;
+
+/// Text parser for text that is received in chunks of bytes, e.g. from a serial port.
+///
+/// The received bytes are copied into a buffer that is provided by the caller. Tokens are returned
+/// as soon as they are complete: a word or a whitespace sequence at the end of the received text
+/// may continue in the next chunk, so it is held back until more bytes arrive or the stream is
+/// [flushed]. Multi-byte UTF-8 characters and escape sequences may also be split between chunks.
+///
+/// As long as every word fits in the buffer, the parser returns the same tokens as
+/// [`Parser::parse`] would for the concatenated text. A word or whitespace sequence that does not
+/// fit is returned as multiple tokens, like text that is split into parts, see
+/// [`Parser::parse_parts`]. Invalid UTF-8 sequences are dropped.
+///
+/// ```rust
+/// use embedded_text::parser::{stream::StreamParser, Token};
+///
+/// let mut buffer = [0; 32];
+/// let mut parser = StreamParser::new(&mut buffer);
+///
+/// // "wor" may be the start of a longer word
+/// let mut tokens = parser.push(b"Hello, wor");
+/// assert_eq!(tokens.accepted(), 10);
+/// assert_eq!(tokens.next(), Some(Token::Word("Hello,")));
+/// assert_eq!(tokens.next(), Some(Token::Whitespace(1)));
+/// assert_eq!(tokens.next(), None);
+///
+/// // "ö" is split between chunks
+/// let mut tokens = parser.push(b"ld \xc3");
+/// assert_eq!(tokens.next(), Some(Token::Word("world")));
+/// assert_eq!(tokens.next(), None);
+///
+/// let mut tokens = parser.push(b"\xb6");
+/// assert_eq!(tokens.next(), Some(Token::Whitespace(1)));
+/// assert_eq!(tokens.next(), None);
+///
+/// let mut tokens = parser.flush();
+/// assert_eq!(tokens.next(), Some(Token::Word("ö")));
+/// assert_eq!(tokens.next(), None);
+/// ```
+///
+/// [flushed]: #method.flush
+/// [`Parser::parse`]: ../struct.Parser.html#method.parse
+/// [`Parser::parse_parts`]: ../struct.Parser.html#method.parse_parts
+#[derive(Debug)]
+pub struct StreamParser<'b> {
+    buffer: &'b mut [u8],
+
+    /// The number of buffered bytes.
+    len: usize,
+
+    /// The number of buffered bytes that have already been returned as tokens.
+    consumed: usize,
+}
+
+impl<'b> StreamParser<'b> {
+    /// Creates a new parser that stores the received bytes in `buffer`.
+    ///
+    /// The buffer needs to be at least 4 bytes long to be able to hold any UTF-8 character, and
+    /// it should be longer than the longest expected word.
+    #[inline]
+    #[must_use]
+    pub fn new(buffer: &'b mut [u8]) -> Self {
+        Self {
+            buffer,
+            len: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Adds `bytes` to the end of the stream and returns the tokens that are complete.
+    ///
+    /// Only as many bytes are accepted as there is free space in the buffer, the rest need to be
+    /// pushed again after the returned tokens are processed. See [`Tokens::accepted`].
+    ///
+    /// Tokens that are not read from the returned iterator are returned again by the next call.
+    ///
+    /// [`Tokens::accepted`]: struct.Tokens.html#method.accepted
+    #[inline]
+    pub fn push(&mut self, bytes: &[u8]) -> Tokens<'_> {
+        self.compact();
+
+        let accepted = bytes.len().min(self.buffer.len() - self.len);
+        self.buffer[self.len..self.len + accepted].copy_from_slice(&bytes[..accepted]);
+        self.len += accepted;
+
+        let text_len = self.drop_invalid_sequences();
+        let full = self.len == self.buffer.len();
+
+        self.tokens(text_len, accepted, false, full)
+    }
+
+    /// Returns every remaining token, including the ones that were held back.
+    ///
+    /// An incomplete UTF-8 sequence at the end of the stream is dropped.
+    #[inline]
+    pub fn flush(&mut self) -> Tokens<'_> {
+        self.compact();
+
+        self.len = self.drop_invalid_sequences();
+
+        self.tokens(self.len, 0, true, false)
+    }
+
+    /// Removes the bytes that have already been returned as tokens from the buffer.
+    fn compact(&mut self) {
+        self.buffer.copy_within(self.consumed..self.len, 0);
+        self.len -= self.consumed;
+        self.consumed = 0;
+    }
+
+    /// Removes invalid UTF-8 sequences from the buffer.
+    ///
+    /// Returns the length of the valid text, which may be followed by an incomplete sequence.
+    fn drop_invalid_sequences(&mut self) -> usize {
+        loop {
+            match str::from_utf8(&self.buffer[..self.len]) {
+                Ok(text) => return text.len(),
+                Err(error) => match error.error_len() {
+                    Some(invalid) => {
+                        let start = error.valid_up_to();
+                        self.buffer.copy_within(start + invalid..self.len, start);
+                        self.len -= invalid;
+                    }
+                    None => return error.valid_up_to(),
+                },
+            }
+        }
+    }
+
+    fn tokens(&mut self, text_len: usize, accepted: usize, flush: bool, full: bool) -> Tokens<'_> {
+        let text = unsafe {
+            // SAFETY: `drop_invalid_sequences` returned the length of the valid text
+            str::from_utf8_unchecked(&self.buffer[..text_len])
+        };
+
+        Tokens {
+            tokens: Parser::parse(text).spanned(),
+            consumed: &mut self.consumed,
+            text_len,
+            accepted,
+            flush,
+            full,
+            done: false,
+        }
+    }
+}
+
+/// Iterator over the complete tokens of a [`StreamParser`].
+///
+/// This struct is created by [`StreamParser::push`] and [`StreamParser::flush`].
+///
+/// [`StreamParser`]: struct.StreamParser.html
+/// [`StreamParser::push`]: struct.StreamParser.html#method.push
+/// [`StreamParser::flush`]: struct.StreamParser.html#method.flush
+#[derive(Debug)]
+pub struct Tokens<'s> {
+    tokens: Spanned<'s>,
+    consumed: &'s mut usize,
+    text_len: usize,
+    accepted: usize,
+
+    /// Whether the tokens at the end of the text are returned.
+    flush: bool,
+
+    /// Whether the buffer is full.
+    full: bool,
+
+    done: bool,
+}
+
+impl Tokens<'_> {
+    /// Returns the number of pushed bytes that were copied into the buffer.
+    #[inline]
+    #[must_use]
+    pub fn accepted(&self) -> usize {
+        self.accepted
+    }
+}
+
+impl<'s> Iterator for Tokens<'s> {
+    type Item = Token<'s>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (range, token) = self.tokens.next()?;
+
+        let incomplete = match token {
+            Token::Word(_) | Token::Whitespace(_) => range.end == self.text_len,
+            Token::EscapeSequence(AnsiSequence::Escape) => {
+                range.len() == 1 && self.text_len - range.start < MAX_ESCAPE_SEQUENCE_LEN
+            }
+            _ => false,
+        };
+
+        // a token that fills the buffer can't be completed, it is returned in parts
+        if incomplete && !self.flush && !(self.full && range.start == 0) {
+            self.done = true;
+            return None;
+        }
+
+        *self.consumed = range.end;
+
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StreamParser;
+    use crate::parser::{Parser, Token};
+
+    fn describe(token: Token<'_>) -> String {
+        format!("{:?}", token)
+    }
+
+    /// Pushes `text` in chunks of `chunk_size` bytes, and returns the description of every token.
+    fn stream(text: &[u8], chunk_size: usize, buffer_size: usize) -> Vec<String> {
+        let mut buffer = vec![0; buffer_size];
+        let mut parser = StreamParser::new(&mut buffer);
+        let mut tokens = Vec::new();
+
+        for mut chunk in text.chunks(chunk_size) {
+            while !chunk.is_empty() {
+                let pushed = parser.push(chunk);
+                chunk = &chunk[pushed.accepted()..];
+                tokens.extend(pushed.map(describe));
+            }
+        }
+        tokens.extend(parser.flush().map(describe));
+
+        tokens
+    }
+
+    #[test]
+    fn streamed_tokens_match_the_batch_parser() {
+        let text = "Hello,  wörld!\n\x1b[4munder\u{ad}lined\x1b[24m\ttext \u{2003}\u{200b} end\r";
+        let expected = Parser::parse(text).map(describe).collect::<Vec<_>>();
+
+        for chunk_size in 1..=text.len() {
+            assert_eq!(
+                stream(text.as_bytes(), chunk_size, 32),
+                expected,
+                "chunk size: {}",
+                chunk_size
+            );
+        }
+    }
+
+    #[test]
+    fn tokens_are_returned_when_complete() {
+        let mut buffer = [0; 16];
+        let mut parser = StreamParser::new(&mut buffer);
+
+        assert_eq!(
+            parser.push(b"a\n\x1b[").collect::<Vec<_>>(),
+            [Token::Word("a"), Token::NewLine]
+        );
+
+        // tokens that are not read are returned by the next call
+        assert_eq!(
+            parser.push(b"4m  ").next().map(describe),
+            Some(String::from("EscapeSequence(SetGraphicsMode([4]))"))
+        );
+        assert_eq!(parser.push(b"").collect::<Vec<_>>(), []);
+        assert_eq!(
+            parser.push(b"b").collect::<Vec<_>>(),
+            [Token::Whitespace(2)]
+        );
+        assert_eq!(parser.flush().collect::<Vec<_>>(), [Token::Word("b")]);
+        assert_eq!(parser.flush().collect::<Vec<_>>(), []);
+    }
+
+    #[test]
+    fn long_words_are_split_when_the_buffer_is_full() {
+        let mut buffer = [0; 4];
+        let mut parser = StreamParser::new(&mut buffer);
+
+        let mut tokens = parser.push(b"abcdef");
+        assert_eq!(tokens.accepted(), 4);
+        assert_eq!(tokens.next(), Some(Token::Word("abcd")));
+        assert_eq!(tokens.next(), None);
+
+        assert_eq!(parser.push(b"ef").collect::<Vec<_>>(), []);
+        assert_eq!(parser.flush().collect::<Vec<_>>(), [Token::Word("ef")]);
+    }
+
+    #[test]
+    fn invalid_utf8_is_dropped() {
+        assert_eq!(
+            stream(b"a\xffb \xe2\x82", 1, 8),
+            ["Word(\"ab\")", "Whitespace(1)"]
+        );
+    }
+}
@@ -1,10 +1,25 @@
 //! ANSI escape sequence related types and functions.
-use crate::style::color::Rgb;
+//!
+//! The functions of this module use the same parser that is used to render text, so other widgets
+//! can interpret escape sequences exactly like a [`TextBox`] does. [`segments`] splits a text into
+//! plain text and parsed commands, and [`AnsiColor::to_rgb`] converts the colors of the commands,
+//! including 8 bit (`38;5;n`) and 24 bit (`38;2;r;g;b`) colors. Nothing in this module allocates,
+//! and malformed sequences never cause a panic.
+//!
+//! [`TextBox`]: ../../struct.TextBox.html
+//! [`segments`]: fn.segments.html
+//! [`AnsiColor::to_rgb`]: ../../style/color/enum.AnsiColor.html#method.to_rgb
+use crate::{
+    parser::{Parser, Token},
+    style::color::{AnsiColor, Rgb},
+};
+use ansi_parser::AnsiSequence;
+use core::ops::Range;
 
 /// List of supported SGR (Select Graphics Rendition) sequences
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Sgr {
-    /// Reset all styling options
+    /// Reset all styling options to the ones of the style
     Reset,
 
     /// Draw a line under the text
@@ -19,83 +34,38 @@ pub enum Sgr {
     /// Disable crossing out
     NotCrossedOut,
 
+    /// Draw the text using the bold font of the style
+    Bold,
+
+    /// Draw the text using the regular font
+    NormalIntensity,
+
     /// Change the text color
-    ChangeTextColor(Rgb),
+    ChangeTextColor(AnsiColor),
 
-    /// Reset the text color to transparent
+    /// Reset the text color to the text color of the style
     DefaultTextColor,
 
     /// Change the background color
-    ChangeBackgroundColor(Rgb),
+    ChangeBackgroundColor(AnsiColor),
 
-    /// Reset the background color to transparent
+    /// Reset the background color to the background color of the style
     DefaultBackgroundColor,
 }
 
-fn try_parse_8b_color(v: &[u8]) -> Option<Rgb> {
-    let color = *v.get(0)?;
-    match color {
-        //   0-  7:  standard colors (as in ESC [ 30–37 m)
-        //   8- 15:  high intensity colors (as in ESC [ 90–97 m)
-        0..=15 => Some(standard_to_rgb(color)),
-
-        //  16-231:  6 × 6 × 6 cube (216 colors): 16 + 36 × r + 6 × g + b (0 ≤ r, g, b ≤ 5)
-        16..=231 => {
-            let color = color - 16;
-            let extend_6 = |c| c * 51;
-
-            let b = extend_6(color % 6);
-            let color = color / 6;
-
-            let g = extend_6(color % 6);
-            let color = color / 6;
-
-            let r = extend_6(color % 6);
-
-            Some(Rgb::new(r, g, b))
-        }
-
-        // 232-255:  grayscale from black to white in 24 steps
-        232..=255 => {
-            let level = color - 232;
-            let g = if level == 23 { 255 } else { level * 11 };
-            Some(Rgb::new(g, g, g))
-        }
-    }
+fn try_parse_8b_color(v: &[u8]) -> Option<AnsiColor> {
+    v.get(0).map(|&index| AnsiColor::Indexed(index))
 }
 
-fn try_parse_rgb(v: &[u8]) -> Option<Rgb> {
+fn try_parse_rgb(v: &[u8]) -> Option<AnsiColor> {
     let r = *v.get(0)?;
     let g = *v.get(1)?;
     let b = *v.get(2)?;
 
-    Some(Rgb::new(r, g, b))
-}
-
-fn standard_to_rgb(idx: u8) -> Rgb {
-    // These colors are used in PowerShell 6 in Windows 10
-    match idx {
-        0 => Rgb::new(12, 12, 12),
-        1 => Rgb::new(197, 15, 31),
-        2 => Rgb::new(19, 161, 14),
-        3 => Rgb::new(193, 156, 0),
-        4 => Rgb::new(0, 55, 218),
-        5 => Rgb::new(136, 23, 152),
-        6 => Rgb::new(58, 150, 221),
-        7 => Rgb::new(204, 204, 204),
-
-        8 => Rgb::new(118, 118, 118),
-        9 => Rgb::new(231, 72, 86),
-        10 => Rgb::new(22, 198, 12),
-        11 => Rgb::new(249, 241, 165),
-        12 => Rgb::new(59, 120, 255),
-        13 => Rgb::new(180, 0, 158),
-        14 => Rgb::new(97, 214, 214),
-        _ => Rgb::new(242, 242, 242),
-    }
+    Some(AnsiColor::Rgb(Rgb::new(r, g, b)))
 }
 
-fn try_parse_color(v: &[u8]) -> Option<Rgb> {
+fn try_parse_color(v: &[u8]) -> Option<AnsiColor> {
     let color_type = *v.get(0)?;
 
     match color_type {
@@ -112,24 +82,419 @@ pub fn try_parse_sgr(v: &[u8]) -> Option<Sgr> {
     let code = *v.get(0)?;
     match code {
         0 => Some(Sgr::Reset),
+        1 => Some(Sgr::Bold),
         4 => Some(Sgr::Underline),
         9 => Some(Sgr::CrossedOut),
+        22 => Some(Sgr::NormalIntensity),
         24 => Some(Sgr::UnderlineOff),
         29 => Some(Sgr::NotCrossedOut),
         39 => Some(Sgr::DefaultTextColor),
         49 => Some(Sgr::DefaultBackgroundColor),
-        30..=37 => Some(Sgr::ChangeTextColor(standard_to_rgb(code - 30))),
+        30..=37 => Some(Sgr::ChangeTextColor(AnsiColor::Indexed(code - 30))),
         38 => {
             let color = try_parse_color(&v[1..])?;
             Some(Sgr::ChangeTextColor(color))
         }
-        90..=97 => Some(Sgr::ChangeTextColor(standard_to_rgb(code - 82))),
-        40..=47 => Some(Sgr::ChangeBackgroundColor(standard_to_rgb(code - 40))),
+        90..=97 => Some(Sgr::ChangeTextColor(AnsiColor::Indexed(code - 82))),
+        40..=47 => Some(Sgr::ChangeBackgroundColor(AnsiColor::Indexed(code - 40))),
         48 => {
             let color = try_parse_color(&v[1..])?;
             Some(Sgr::ChangeBackgroundColor(color))
         }
-        100..=107 => Some(Sgr::ChangeBackgroundColor(standard_to_rgb(code - 92))),
+        100..=107 => Some(Sgr::ChangeBackgroundColor(AnsiColor::Indexed(code - 92))),
         _ => None,
     }
 }
+
+/// Describes how an escape sequence is handled when the text is rendered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SequenceSupport {
+    /// The escape sequence is supported.
+    Supported,
+
+    /// The escape sequence is recognized, or is a complete but unsupported control sequence, and has
+    /// no effect on the rendered text.
+    Ignored,
+
+    /// The escape sequence could not be parsed. The `\x1b` character is ignored and the rest of
+    /// the sequence is displayed as normal text.
+    Malformed,
+}
+
+/// An escape sequence found in a text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EscapeSequence {
+    /// The byte range of the escape sequence in the text.
+    pub range: Range<usize>,
+
+    /// How the escape sequence is handled when the text is rendered.
+    pub support: SequenceSupport,
+}
+
+/// Iterator over the escape sequences of a text.
+///
+/// This struct is created by the [`escape_sequences`] function.
+///
+/// [`escape_sequences`]: fn.escape_sequences.html
+#[derive(Clone, Debug)]
+pub struct EscapeSequences<'a> {
+    parser: Parser<'a>,
+}
+
+impl Iterator for EscapeSequences<'_> {
+    type Item = EscapeSequence;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.parser.offset();
+            let support = match self.parser.next()? {
+                Token::EscapeSequence(seq) => sequence_support(&seq),
+                Token::UnknownEscape(_) => SequenceSupport::Ignored,
+                _ => continue,
+            };
+
+            return Some(EscapeSequence {
+                range: start..self.parser.offset(),
+                support,
+            });
+        }
+    }
+}
+
+/// Summary of the escape sequences of a text.
+///
+/// This struct is created by the [`validate`] function.
+///
+/// [`validate`]: fn.validate.html
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ValidationResult {
+    /// The number of supported escape sequences.
+    pub supported: u32,
+
+    /// The number of escape sequences that have no effect on the rendered text.
+    pub ignored: u32,
+
+    /// The number of escape sequences that could not be parsed.
+    pub malformed: u32,
+}
+
+impl ValidationResult {
+    /// Returns `true` if every escape sequence of the text is supported.
+    #[inline]
+    #[must_use]
+    pub fn is_fully_supported(&self) -> bool {
+        self.ignored == 0 && self.malformed == 0
+    }
+}
+
+/// Returns how the renderer handles the given escape sequence.
+fn sequence_support(seq: &AnsiSequence) -> SequenceSupport {
+    match seq {
+        AnsiSequence::Escape => SequenceSupport::Malformed,
+        AnsiSequence::SetGraphicsMode(v) if try_parse_sgr(v).is_some() => {
+            SequenceSupport::Supported
+        }
+        AnsiSequence::CursorForward(_) | AnsiSequence::CursorBackward(_) => {
+            SequenceSupport::Supported
+        }
+        _ => SequenceSupport::Ignored,
+    }
+}
+
+/// Returns an iterator over the escape sequences of a text.
+///
+/// The text is processed by the same parser that is used to render it, so the result describes
+/// what happens when the text is drawn.
+///
+/// # Example
+///
+/// ```rust
+/// use embedded_text::rendering::ansi::{escape_sequences, SequenceSupport};
+///
+/// let text = "\x1b[31mred\x1b[2J";
+/// let mut sequences = escape_sequences(text);
+///
+/// let seq = sequences.next().unwrap();
+/// assert_eq!(&text[seq.range], "\x1b[31m");
+/// assert_eq!(seq.support, SequenceSupport::Supported);
+///
+/// let seq = sequences.next().unwrap();
+/// assert_eq!(&text[seq.range], "\x1b[2J");
+/// assert_eq!(seq.support, SequenceSupport::Ignored);
+///
+/// assert_eq!(sequences.next(), None);
+/// ```
+#[inline]
+#[must_use]
+pub fn escape_sequences(text: &str) -> EscapeSequences<'_> {
+    EscapeSequences {
+        parser: Parser::parse(text),
+    }
+}
+
+/// Counts the supported, ignored and malformed escape sequences of a text.
+///
+/// This function can be used to check text that comes from an untrusted source before displaying
+/// it. Use [`escape_sequences`] to find the sequences themselves.
+///
+/// # Example
+///
+/// ```rust
+/// use embedded_text::rendering::ansi::{validate, ValidationResult};
+///
+/// let result = validate("\x1b[4mSupported\x1b[24m, \x1b[3mignored\x1b[0m and malformed\x1b[31");
+///
+/// assert_eq!(
+///     result,
+///     ValidationResult {
+///         supported: 3,
+///         ignored: 1,
+///         malformed: 1,
+///     }
+/// );
+/// assert!(!result.is_fully_supported());
+/// ```
+///
+/// [`escape_sequences`]: fn.escape_sequences.html
+#[inline]
+#[must_use]
+pub fn validate(text: &str) -> ValidationResult {
+    escape_sequences(text).fold(ValidationResult::default(), |mut result, seq| {
+        match seq.support {
+            SequenceSupport::Supported => result.supported += 1,
+            SequenceSupport::Ignored => result.ignored += 1,
+            SequenceSupport::Malformed => result.malformed += 1,
+        }
+        result
+    })
+}
+
+/// A part of a text, split at escape sequences.
+///
+/// This enum is created by the [`Segments`] iterator.
+///
+/// [`Segments`]: struct.Segments.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// Text that is displayed.
+    Text(&'a str),
+
+    /// A supported SGR sequence.
+    Sgr(Sgr),
+
+    /// Move the cursor forward by the given number of characters.
+    CursorForward(u32),
+
+    /// Move the cursor backward by the given number of characters.
+    CursorBackward(u32),
+
+    /// An escape sequence that has no effect on the rendered text.
+    ///
+    /// If a sequence is malformed, only the escape character is ignored, and the rest of the
+    /// sequence is returned as text.
+    Ignored(&'a str),
+}
+
+/// Iterator over the parts of a text, split at escape sequences.
+///
+/// This struct is created by the [`segments`] function.
+///
+/// [`segments`]: fn.segments.html
+#[derive(Clone, Debug)]
+pub struct Segments<'a> {
+    text: &'a str,
+    parser: Parser<'a>,
+
+    /// The byte offset of the first character that has not been returned yet.
+    position: usize,
+
+    /// A command that is returned after the text that precedes it, and the offset after it.
+    pending: Option<(Segment<'a>, usize)>,
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Segment<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((segment, end)) = self.pending.take() {
+            self.position = end;
+            return Some(segment);
+        }
+
+        while let Some((range, token)) = self.next_token() {
+            let segment = match token {
+                Token::EscapeSequence(AnsiSequence::SetGraphicsMode(v)) => {
+                    match try_parse_sgr(&v) {
+                        Some(sgr) => Segment::Sgr(sgr),
+                        None => Segment::Ignored(&self.text[range.clone()]),
+                    }
+                }
+                Token::EscapeSequence(AnsiSequence::CursorForward(n)) => Segment::CursorForward(n),
+                Token::EscapeSequence(AnsiSequence::CursorBackward(n)) => {
+                    Segment::CursorBackward(n)
+                }
+                Token::EscapeSequence(_) | Token::UnknownEscape(_) => {
+                    Segment::Ignored(&self.text[range.clone()])
+                }
+                _ => continue,
+            };
+
+            if range.start == self.position {
+                self.position = range.end;
+                return Some(segment);
+            }
+
+            // return the text before the escape sequence first
+            let text = &self.text[self.position..range.start];
+            self.position = range.start;
+            self.pending = Some((segment, range.end));
+
+            return Some(Segment::Text(text));
+        }
+
+        if self.position < self.text.len() {
+            let text = &self.text[self.position..];
+            self.position = self.text.len();
+
+            Some(Segment::Text(text))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Segments<'a> {
+    /// Returns the next token of the text and its byte range.
+    fn next_token(&mut self) -> Option<(Range<usize>, Token<'a>)> {
+        let start = self.parser.offset();
+        let token = self.parser.next()?;
+
+        Some((start..self.parser.offset(), token))
+    }
+}
+
+/// Splits a text into plain text and the commands of its escape sequences.
+///
+/// Consecutive text is returned as a single [`Segment::Text`], so the text of a [`Segment`] never
+/// contains an escape sequence. The segments cover the whole text.
+///
+/// # Example
+///
+/// ```rust
+/// use embedded_text::{
+///     rendering::ansi::{segments, Segment, Sgr},
+///     style::color::{AnsiColor, Rgb},
+/// };
+///
+/// let mut parts = segments("T=\x1b[38;2;255;0;0m21°C\x1b[0m");
+///
+/// assert_eq!(parts.next(), Some(Segment::Text("T=")));
+/// assert_eq!(
+///     parts.next(),
+///     Some(Segment::Sgr(Sgr::ChangeTextColor(AnsiColor::Rgb(Rgb::new(255, 0, 0)))))
+/// );
+/// assert_eq!(parts.next(), Some(Segment::Text("21°C")));
+/// assert_eq!(parts.next(), Some(Segment::Sgr(Sgr::Reset)));
+/// assert_eq!(parts.next(), None);
+/// ```
+///
+/// [`Segment`]: enum.Segment.html
+/// [`Segment::Text`]: enum.Segment.html#variant.Text
+#[inline]
+#[must_use]
+pub fn segments(text: &str) -> Segments<'_> {
+    Segments {
+        text,
+        parser: Parser::parse(text),
+        position: 0,
+        pending: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sequences_are_categorized() {
+        let text = "\x1b[38;5;16mcolor\x1b[3C\x1b[3mitalic\x1b[2Jcleared\x1b[6n\x1bx\x1b[4m";
+
+        let mut sequences = escape_sequences(text);
+        let mut expect = |seq: &str, support: SequenceSupport| {
+            let found = sequences.next().unwrap();
+            assert_eq!(&text[found.range], seq);
+            assert_eq!(found.support, support);
+        };
+
+        expect("\x1b[38;5;16m", SequenceSupport::Supported);
+        expect("\x1b[3C", SequenceSupport::Supported);
+        expect("\x1b[3m", SequenceSupport::Ignored);
+        expect("\x1b[2J", SequenceSupport::Ignored);
+        expect("\x1b[6n", SequenceSupport::Ignored);
+        // only the escape character is consumed, "x" is displayed as text
+        expect("\x1b", SequenceSupport::Malformed);
+        expect("\x1b[4m", SequenceSupport::Supported);
+
+        assert_eq!(sequences.next(), None);
+    }
+
+    #[test]
+    fn text_without_sequences_is_valid() {
+        assert_eq!(validate("plain text\n"), ValidationResult::default());
+        assert!(validate("plain text\n").is_fully_supported());
+    }
+
+    fn collect(text: &str) -> std::vec::Vec<Segment<'_>> {
+        segments(text).collect()
+    }
+
+    #[test]
+    fn text_and_commands_are_separated() {
+        assert_eq!(
+            collect("a b\x1b[4mc\x1b[38;5;16m\x1b[3C\x1b[2Dd\n"),
+            vec![
+                Segment::Text("a b"),
+                Segment::Sgr(Sgr::Underline),
+                Segment::Text("c"),
+                Segment::Sgr(Sgr::ChangeTextColor(AnsiColor::Indexed(16))),
+                Segment::CursorForward(3),
+                Segment::CursorBackward(2),
+                Segment::Text("d\n"),
+            ]
+        );
+        assert_eq!(collect(""), vec![]);
+    }
+
+    #[test]
+    fn truncated_sequences_are_returned_as_text() {
+        assert_eq!(
+            collect("\x1b["),
+            vec![Segment::Ignored("\x1b"), Segment::Text("[")]
+        );
+        assert_eq!(
+            collect("a\x1b[38;5"),
+            vec![
+                Segment::Text("a"),
+                Segment::Ignored("\x1b"),
+                Segment::Text("[38;5"),
+            ]
+        );
+        assert_eq!(collect("\x1b"), vec![Segment::Ignored("\x1b")]);
+    }
+
+    #[test]
+    fn unsupported_sequences_are_ignored() {
+        assert_eq!(
+            collect("\x1b[5Xa\x1b[38;5mb\x1b[3m"),
+            vec![
+                Segment::Ignored("\x1b[5X"),
+                Segment::Text("a"),
+                Segment::Ignored("\x1b[38;5m"),
+                Segment::Text("b"),
+                Segment::Ignored("\x1b[3m"),
+            ]
+        );
+    }
+}
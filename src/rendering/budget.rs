@@ -0,0 +1,292 @@
+//! Rendering in limited steps.
+//!
+//! See [`StyledTextBox::draw_budgeted`] for more information.
+//!
+//! [`StyledTextBox::draw_budgeted`]: ../../struct.StyledTextBox.html#method.draw_budgeted
+use crate::{
+    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
+    rendering::{RenderResult, State, StyledTextBoxIterator},
+    style::{color::Rgb, height_mode::HeightMode},
+};
+use embedded_graphics::prelude::*;
+
+/// The number of pixels a single budgeted draw call may send to the display.
+///
+/// The budget is shared by every call it is passed to: each drawn pixel is subtracted from it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RenderBudget {
+    pixels: u32,
+}
+
+impl RenderBudget {
+    /// Creates a budget that allows drawing `pixels` pixels.
+    #[inline]
+    #[must_use]
+    pub fn pixels(pixels: u32) -> Self {
+        Self { pixels }
+    }
+
+    /// Returns the number of pixels that can still be drawn.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> u32 {
+        self.pixels
+    }
+
+    /// Returns `true` if no more pixels can be drawn.
+    #[inline]
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.pixels == 0
+    }
+}
+
+/// The outcome of a budgeted draw call.
+// The state can't be boxed without an allocator.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum RenderProgress<'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// Every pixel of the text box has been drawn.
+    Complete(RenderResult),
+
+    /// The budget ran out before the text box was completely drawn.
+    ///
+    /// Call [`BudgetedRender::resume`] with a new budget to continue drawing.
+    ///
+    /// [`BudgetedRender::resume`]: struct.BudgetedRender.html#method.resume
+    Incomplete(BudgetedRender<'a, C, F, A, V, H>),
+}
+
+/// A partially drawn text box.
+///
+/// The state only borrows the text, so it can be stored until drawing is resumed.
+#[derive(Clone, Debug)]
+pub struct BudgetedRender<'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    renderer: StyledTextBoxIterator<'a, C, F, A, V, H>,
+
+    /// The pixel that was generated after the budget ran out.
+    pending: Option<Pixel<C>>,
+
+    total_lines: u32,
+}
+
+impl<'a, C, F, A, V, H> BudgetedRender<'a, C, F, A, V, H>
+where
+    C: PixelColor + From<Rgb>,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    pub(crate) fn new(
+        renderer: StyledTextBoxIterator<'a, C, F, A, V, H>,
+        total_lines: u32,
+    ) -> Self {
+        Self {
+            renderer,
+            pending: None,
+            total_lines,
+        }
+    }
+
+    /// Continues drawing until the text box is complete or `budget` runs out.
+    ///
+    /// If drawing fails, the state is lost and the text box needs to be drawn from the start.
+    #[inline]
+    pub fn resume<D>(
+        mut self,
+        display: &mut D,
+        budget: &mut RenderBudget,
+    ) -> Result<RenderProgress<'a, C, F, A, V, H>, D::Error>
+    where
+        D: DrawTarget<C>,
+    {
+        display.draw_iter(BudgetedPixels {
+            render: &mut self,
+            budget,
+        })?;
+
+        // the budget may have run out exactly at the last pixel
+        if self.pending.is_none() {
+            self.pending = self.renderer.next();
+        }
+
+        Ok(if self.pending.is_some() {
+            RenderProgress::Incomplete(self)
+        } else {
+            RenderProgress::Complete(self.renderer.result())
+        })
+    }
+
+    /// Returns the number of lines that have been completely drawn.
+    ///
+    /// Together with [`total_lines`] this can be used to display the progress of rendering.
+    ///
+    /// [`total_lines`]: #method.total_lines
+    #[inline]
+    #[must_use]
+    pub fn lines_completed(&self) -> u32 {
+        let lines = match self.renderer.state {
+            State::DrawLine(_) => self.renderer.lines.saturating_sub(1),
+            State::NextLine(..) => self.renderer.lines,
+        };
+
+        lines.min(self.total_lines)
+    }
+
+    /// Returns the number of lines of the text box.
+    #[inline]
+    #[must_use]
+    pub fn total_lines(&self) -> u32 {
+        self.total_lines
+    }
+}
+
+/// Pixel iterator that stops when the budget runs out.
+struct BudgetedPixels<'r, 'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    render: &'r mut BudgetedRender<'a, C, F, A, V, H>,
+    budget: &'r mut RenderBudget,
+}
+
+impl<C, F, A, V, H> Iterator for BudgetedPixels<'_, '_, C, F, A, V, H>
+where
+    C: PixelColor + From<Rgb>,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.budget.is_exhausted() {
+            return None;
+        }
+
+        let pixel = match self.render.pending.take() {
+            Some(pixel) => pixel,
+            None => self.render.renderer.next()?,
+        };
+        self.budget.pixels -= 1;
+
+        Some(pixel)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        rendering::budget::{RenderBudget, RenderProgress},
+        style::TextBoxStyleBuilder,
+        TextBox,
+    };
+    use embedded_graphics::{
+        fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+        primitives::Rectangle,
+    };
+
+    const TEXT: &str = "Hello, World!\nBudgeted rendering draws text in steps.";
+
+    fn text_box() -> TextBox<'static> {
+        TextBox::new(TEXT, Rectangle::new(Point::zero(), Point::new(59, 47)))
+    }
+
+    #[test]
+    fn budgeted_drawing_matches_full_drawing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let text_box = text_box().into_styled(style);
+
+        let mut expected = MockDisplay::new();
+        let expected_result = text_box.draw_with_result(&mut expected).unwrap();
+
+        for &step in &[1, 7, 100, 480, 100_000] {
+            let mut display = MockDisplay::new();
+            let mut budget = RenderBudget::pixels(step);
+            let mut progress = text_box.draw_budgeted(&mut display, &mut budget).unwrap();
+
+            let result = loop {
+                match progress {
+                    RenderProgress::Complete(result) => break result,
+                    RenderProgress::Incomplete(render) => {
+                        assert!(budget.is_exhausted());
+
+                        budget = RenderBudget::pixels(step);
+                        progress = render.resume(&mut display, &mut budget).unwrap();
+                    }
+                }
+            };
+
+            assert_eq!(display, expected, "step: {}", step);
+            assert_eq!(result, expected_result, "step: {}", step);
+        }
+    }
+
+    #[test]
+    fn lines_completed_reports_progress() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        // every line is 10 characters, 60 * 8 pixels, wide
+        let text_box = TextBox::new(
+            "0123456789\n0123456789\n0123456789",
+            Rectangle::new(Point::zero(), Point::new(59, 23)),
+        )
+        .into_styled(style);
+
+        let mut display = MockDisplay::new();
+        let mut budget = RenderBudget::pixels(60 * 8);
+        let mut progress = text_box.draw_budgeted(&mut display, &mut budget).unwrap();
+
+        let mut lines = Vec::new();
+        while let RenderProgress::Incomplete(render) = progress {
+            lines.push((render.lines_completed(), render.total_lines()));
+
+            budget = RenderBudget::pixels(60 * 8);
+            progress = render.resume(&mut display, &mut budget).unwrap();
+        }
+
+        assert_eq!(lines, [(1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn empty_budget_draws_nothing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let text_box = text_box().into_styled(style);
+
+        let mut display = MockDisplay::new();
+        let mut budget = RenderBudget::pixels(0);
+        match text_box.draw_budgeted(&mut display, &mut budget).unwrap() {
+            RenderProgress::Incomplete(render) => assert_eq!(render.lines_completed(), 0),
+            RenderProgress::Complete(_) => panic!("nothing should be drawn"),
+        }
+
+        assert_eq!(display, MockDisplay::new());
+    }
+}
@@ -1,5 +1,5 @@
 //! Character rendering.
-use crate::utils::font_ext::FontExt;
+use crate::{style::bold_font::BoldFont, utils::font_ext::FontExt};
 use core::{marker::PhantomData, ops::Range};
 use embedded_graphics::{prelude::*, style::TextStyle};
 
@@ -31,12 +31,39 @@ where
         }
     }
 
+    /// Creates a glyph from its position in the font image.
+    #[inline]
+    pub(crate) fn from_offset(char_glyph_offset: u32) -> Self {
+        Self {
+            _font: PhantomData,
+            char_glyph_offset,
+        }
+    }
+
+    /// Returns the position of the glyph in the font image.
+    #[inline]
+    pub(crate) fn offset(&self) -> u32 {
+        self.char_glyph_offset
+    }
+
     /// Returns the value of a given point:
     ///  * `true` for foreground pixels
     ///  * `false` for background pixels
+    ///
+    /// Points outside of the `CHARACTER_SIZE` of the font are background pixels.
     #[inline]
     #[must_use]
     pub fn point(&self, p: Point) -> bool {
+        // Outside of the cell, the font image contains other glyphs. This happens if the font
+        // reports a character to be wider than its cell.
+        if p.x < 0
+            || p.y < 0
+            || p.x as u32 >= F::CHARACTER_SIZE.width
+            || p.y as u32 >= F::CHARACTER_SIZE.height
+        {
+            return false;
+        }
+
         // Bit index
         // = X pixel offset for char
         // + Character row offset (row 0 = 0, row 1 = (192 * 8) = 1536)
@@ -50,6 +77,34 @@ where
 
         F::FONT_IMAGE[bitmap_byte as usize] & (0x80 >> bitmap_bit) != 0
     }
+
+    /// Returns `true` if the first `width` pixels of the row `y` are background pixels.
+    ///
+    /// The font image is read byte by byte instead of pixel by pixel.
+    #[inline]
+    pub(crate) fn is_row_empty(&self, y: i32, width: u32) -> bool {
+        if y < 0 || y as u32 >= F::CHARACTER_SIZE.height {
+            return true;
+        }
+
+        let start = self.char_glyph_offset + y as u32 * F::FONT_IMAGE_WIDTH;
+        let end = start + width.min(F::CHARACTER_SIZE.width);
+
+        let mut bit = start;
+        while bit < end {
+            let first = bit % 8;
+            let count = (8 - first).min(end - bit);
+            let mask = (0xFF_u32 >> first) & (0xFF_u32 << (8 - first - count));
+
+            if u32::from(F::FONT_IMAGE[(bit / 8) as usize]) & mask != 0 {
+                return false;
+            }
+
+            bit += count;
+        }
+
+        true
+    }
 }
 
 /// Pixel iterator to render a single styled character.
@@ -71,6 +126,12 @@ where
     max_coordinates: Point,
     underline: bool,
     strikethrough: bool,
+
+    /// A glyph that is drawn over the character, and its horizontal offset.
+    overstrike: Option<(Glyph<F>, i32)>,
+
+    /// The bold font that the character is drawn with, and the position of its glyph.
+    bold: Option<(BoldFont, u32)>,
 }
 
 impl<C, F> CharacterIterator<C, F>
@@ -104,10 +165,67 @@ where
             max_coordinates: Point::new(F::char_width(character) as i32 - 1, max_height),
             underline,
             strikethrough,
+            overstrike: None,
+            bold: None,
+        }
+    }
+
+    /// Draws the character using the glyph of the bold font.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_bold_font(self, character: char, font: BoldFont) -> Self {
+        Self {
+            max_coordinates: Point::new(
+                font.glyph_width(character) as i32 - 1,
+                self.max_coordinates.y,
+            ),
+            bold: Some((font, font.glyph_offset(character))),
+            ..self
+        }
+    }
+
+    /// Draws the glyph of `previous` in the same cell, below the character.
+    ///
+    /// If `previous` is the character itself, its glyph is drawn one pixel to the right, which
+    /// makes the character bold.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_overstrike(self, character: char, previous: char) -> Self {
+        let offset = if previous == character { 1 } else { 0 };
+
+        Self {
+            overstrike: Some((Glyph::new(previous), offset)),
+            ..self
         }
     }
 }
 
+impl<C, F> CharacterIterator<C, F>
+where
+    C: PixelColor,
+    F: Font + Copy,
+{
+    /// Returns `true` if no pixel of the row `y` is drawn, so the row can be skipped.
+    fn is_row_transparent(&self, y: i32) -> bool {
+        if self.style.background_color.is_some()
+            || self.bold.is_some()
+            || (self.underline && y as u32 == F::CHARACTER_SIZE.height)
+            || (self.strikethrough && y as u32 == F::strikethrough_pos())
+        {
+            return false;
+        }
+
+        // every row is visited from x = 0 to max(max_coordinates.x, 0)
+        let width = self.max_coordinates.x.max(0) as u32 + 1;
+        let overstrike_is_empty = match self.overstrike {
+            Some((glyph, _)) => glyph.is_row_empty(y, width),
+            None => true,
+        };
+
+        overstrike_is_empty && self.character.is_row_empty(y, width)
+    }
+}
+
 impl<C, F> Iterator for CharacterIterator<C, F>
 where
     C: PixelColor,
@@ -122,6 +240,10 @@ where
                 // Done with this char, move on to the next one
                 break None;
             }
+            if self.char_walk.x == 0 && self.is_row_transparent(self.char_walk.y) {
+                self.char_walk.y += 1;
+                continue;
+            }
             let pos = self.char_walk;
 
             if pos.x < self.max_coordinates.x {
@@ -134,7 +256,17 @@ where
             let is_underline = self.underline && pos.y as u32 == F::CHARACTER_SIZE.height;
             let is_strikethrough = self.strikethrough && pos.y as u32 == F::strikethrough_pos();
 
-            let color = if is_underline || is_strikethrough || self.character.point(pos) {
+            let is_overstrike = match self.overstrike {
+                Some((glyph, offset)) => glyph.point(pos - Point::new(offset, 0)),
+                None => false,
+            };
+
+            let is_glyph = match self.bold {
+                Some((font, offset)) => font.glyph_point(offset, pos),
+                None => self.character.point(pos),
+            };
+
+            let color = if is_underline || is_strikethrough || is_overstrike || is_glyph {
                 self.style.text_color
             } else {
                 self.style.background_color
@@ -151,12 +283,44 @@ where
 
 #[cfg(test)]
 mod test {
-    use super::CharacterIterator;
+    use super::{CharacterIterator, Glyph};
+    use crate::{rendering::RendererFactory, style::TextBoxStyleBuilder, TextBox};
     use embedded_graphics::{
-        fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+        fonts::{Font12x16, Font6x8},
+        mock_display::MockDisplay,
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
         style::TextStyleBuilder,
     };
 
+    /// A font with 4x2 pixel glyphs that reports `a`, an empty glyph, to be 5 pixels wide. The
+    /// glyph next to it in the font image, `b`, is completely filled.
+    #[derive(Copy, Clone, Debug)]
+    struct LyingFont;
+
+    impl Font for LyingFont {
+        const FONT_IMAGE: &'static [u8] = &[0b0000_1111, 0b0000_1111];
+        const FONT_IMAGE_WIDTH: u32 = 8;
+        const CHARACTER_SIZE: Size = Size::new(4, 2);
+
+        fn char_offset(c: char) -> u32 {
+            if c == 'b' {
+                1
+            } else {
+                0
+            }
+        }
+
+        fn char_width(c: char) -> u32 {
+            if c == 'a' {
+                5
+            } else {
+                4
+            }
+        }
+    }
+
     #[test]
     fn transparent_char() {
         let mut display = MockDisplay::new();
@@ -190,6 +354,49 @@ mod test {
         );
     }
 
+    #[test]
+    fn pixels_outside_of_the_glyph_cell_are_not_read() {
+        let mut display = MockDisplay::new();
+        let style = TextStyleBuilder::new(LyingFont)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        CharacterIterator::new('a', Point::zero(), style, 0..2, false, false)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(display, MockDisplay::from_pattern(&[".....", "....."]));
+    }
+
+    #[test]
+    fn glyphs_stay_in_their_layout_cells() {
+        let style = TextBoxStyleBuilder::new(LyingFont)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let text_box = TextBox::new("abab", Rectangle::new(Point::zero(), Point::new(17, 1)))
+            .into_styled(style);
+
+        // every pixel is drawn exactly once
+        let mut pixels = text_box
+            .create_renderer()
+            .map(|Pixel(p, _)| p)
+            .collect::<Vec<_>>();
+        let count = pixels.len();
+        pixels.sort_by_key(|p| (p.y, p.x));
+        pixels.dedup();
+        assert_eq!(pixels.len(), count);
+
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[".....####.....####", ".....####.....####"])
+        );
+    }
+
     #[test]
     fn partial_draw() {
         let mut display = MockDisplay::new();
@@ -222,4 +429,80 @@ mod test {
             ])
         );
     }
+
+    fn check_empty_rows<F: Font>() {
+        for c in (' '..='~').chain("äöü€".chars()) {
+            let glyph = Glyph::<F>::new(c);
+            for y in -1..=F::CHARACTER_SIZE.height as i32 {
+                for width in 0..=F::CHARACTER_SIZE.width + 1 {
+                    let is_empty = (0..width as i32).all(|x| !glyph.point(Point::new(x, y)));
+
+                    assert_eq!(
+                        glyph.is_row_empty(y, width),
+                        is_empty,
+                        "row {} of {:?} with width {}",
+                        y,
+                        c,
+                        width
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_rows_are_found_bytewise() {
+        check_empty_rows::<Font6x8>();
+        check_empty_rows::<Font12x16>();
+    }
+
+    #[test]
+    fn transparent_rows_are_skipped() {
+        let style = TextStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let rows = 0..Font6x8::CHARACTER_SIZE.height as i32 + 1;
+
+        // only the middle row of the dash is drawn
+        let dash = CharacterIterator::new('-', Point::zero(), style, rows.clone(), true, false);
+        let drawn_rows = rows
+            .clone()
+            .filter(|&y| !dash.is_row_transparent(y))
+            .collect::<Vec<_>>();
+        assert_eq!(drawn_rows, vec![3, 8]);
+
+        // every pixel that is visited is drawn
+        let mut pixels = CharacterIterator::new('-', Point::zero(), style, rows, false, false);
+        assert_eq!(
+            pixels.next(),
+            Some(Pixel(Point::new(0, 3), BinaryColor::On))
+        );
+        assert_eq!(pixels.char_walk, Point::new(1, 3));
+    }
+
+    #[test]
+    fn only_foreground_pixels_are_yielded_without_background() {
+        let style = TextStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let opaque = TextStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let rows = 0..Font6x8::CHARACTER_SIZE.height as i32;
+
+        for c in ' '..='~' {
+            for &underline in &[false, true] {
+                let yielded =
+                    CharacterIterator::new(c, Point::zero(), style, rows.clone(), underline, true)
+                        .collect::<Vec<_>>();
+                let foreground =
+                    CharacterIterator::new(c, Point::zero(), opaque, rows.clone(), underline, true)
+                        .filter(|&Pixel(_, color)| color == BinaryColor::On)
+                        .collect::<Vec<_>>();
+
+                assert_eq!(yielded, foreground, "{:?}", c);
+            }
+        }
+    }
 }
@@ -1,4 +1,5 @@
 //! Cursor to track rendering position.
+use crate::{rendering::ansi::Sgr, style::bold_font::BoldFont, utils::font_ext::FontExt};
 use core::marker::PhantomData;
 use embedded_graphics::{fonts::Font, geometry::Point, primitives::Rectangle};
 
@@ -15,6 +16,25 @@ pub struct Cursor<F: Font> {
 
     line_spacing: i32,
 
+    letter_spacing: i32,
+
+    paragraph_spacing: i32,
+
+    line_gap: u32,
+
+    line_gaps: u32,
+
+    lines_started: u32,
+
+    /// The indentation of the current line.
+    indent: u32,
+
+    /// The font of bold characters.
+    bold_font: Option<BoldFont>,
+
+    /// Whether the next characters are bold.
+    bold: bool,
+
     _marker: PhantomData<F>,
 }
 
@@ -22,11 +42,24 @@ impl<F: Font> Cursor<F> {
     /// Creates a new `Cursor` object located at the top left of the given bounding [`Rectangle`].
     #[inline]
     #[must_use]
-    pub fn new(bounds: Rectangle, line_spacing: i32) -> Self {
+    pub fn new(
+        bounds: Rectangle,
+        line_spacing: i32,
+        letter_spacing: i32,
+        paragraph_spacing: i32,
+    ) -> Self {
         Self {
             _marker: PhantomData,
             position: bounds.top_left,
             line_spacing,
+            letter_spacing,
+            paragraph_spacing,
+            line_gap: 0,
+            line_gaps: 0,
+            lines_started: 0,
+            indent: 0,
+            bold_font: None,
+            bold: false,
             bounds: Rectangle::new(
                 bounds.top_left,
                 bounds.bottom_right + Point::new(1, 1 - F::CHARACTER_SIZE.height as i32),
@@ -41,10 +74,111 @@ impl<F: Font> Cursor<F> {
         (self.bounds.bottom_right.x - self.bounds.top_left.x) as u32
     }
 
+    /// Returns the horizontal space taken up by the given character, including letter spacing.
+    #[inline]
+    #[must_use]
+    pub fn char_width(&self, c: char) -> u32 {
+        (self.font_char_width(c) as i32 + self.letter_spacing).max(0) as u32
+    }
+
+    /// Returns the letter spacing after the given character.
+    ///
+    /// The letter spacing after the last character of a line is not displayed, so it does not
+    /// need to fit into the line. Negative letter spacing makes the glyph stick out past the
+    /// cursor.
+    pub(crate) fn trailing_spacing(&self, c: char) -> i32 {
+        self.char_width(c) as i32 - self.font_char_width(c) as i32
+    }
+
+    /// Returns the width of the given character in the current font, without letter spacing.
+    pub(crate) fn font_char_width(&self, c: char) -> u32 {
+        match self.bold_font() {
+            Some(font) => font.total_char_width(c),
+            None => F::total_char_width(c),
+        }
+    }
+
+    /// Sets the font of bold characters, and whether the next characters are bold.
+    #[must_use]
+    pub(crate) fn with_bold_font(self, bold_font: Option<BoldFont>, bold: bool) -> Self {
+        Self {
+            bold_font,
+            bold,
+            ..self
+        }
+    }
+
+    /// Returns the bold font if the next characters are drawn with it.
+    pub(crate) fn bold_font(&self) -> Option<BoldFont> {
+        self.bold_font.filter(|_| self.bold)
+    }
+
+    /// Switches between the regular and the bold font.
+    pub(crate) fn apply_sgr(&mut self, sgr: Sgr) {
+        match sgr {
+            Sgr::Bold => self.bold = true,
+            Sgr::Reset | Sgr::NormalIntensity => self.bold = false,
+            _ => {}
+        }
+    }
+
+    /// Returns the horizontal space taken up by the given string, including letter spacing.
+    ///
+    /// *Note:* carriage return characters are not handled by this function.
+    #[inline]
+    #[must_use]
+    pub fn str_width(&self, s: &str) -> u32 {
+        s.chars().map(|c| self.char_width(c)).sum()
+    }
+
+    /// Distributes `space` pixels of additional spacing evenly over the next `gaps` line breaks.
+    pub(crate) fn distribute_line_gap(&mut self, space: u32, gaps: u32) {
+        self.line_gap = space;
+        self.line_gaps = gaps;
+        self.lines_started = 0;
+    }
+
+    /// Returns the share of the distributed spacing that belongs to the next line break.
+    fn next_line_gap(&mut self) -> i32 {
+        if self.lines_started >= self.line_gaps {
+            return 0;
+        }
+
+        let before = self.line_gap * self.lines_started / self.line_gaps;
+        self.lines_started += 1;
+        let after = self.line_gap * self.lines_started / self.line_gaps;
+
+        (after - before) as i32
+    }
+
     /// Starts a new line.
     #[inline]
     pub fn new_line(&mut self) {
-        self.position.y += F::CHARACTER_SIZE.height as i32 + self.line_spacing;
+        self.position.y +=
+            F::CHARACTER_SIZE.height as i32 + self.line_spacing + self.next_line_gap();
+    }
+
+    /// Starts a new paragraph.
+    ///
+    /// Paragraph spacing is added on top of the line spacing, but the cursor never moves upwards.
+    #[inline]
+    pub fn new_paragraph(&mut self) {
+        self.position.y +=
+            (F::CHARACTER_SIZE.height as i32 + self.line_spacing + self.paragraph_spacing).max(0)
+                + self.next_line_gap();
+    }
+
+    /// Indents the line by `indent` pixels and moves the cursor to the start of the line.
+    ///
+    /// The indentation replaces the indentation of the previous line. At least one pixel of the
+    /// line is kept, like in the narrowest possible text box.
+    pub(crate) fn indent_line(&mut self, indent: u32) {
+        let left = self.bounds.top_left.x - self.indent as i32;
+        let indent = indent.min((self.bounds.bottom_right.x - left - 1).max(0) as u32);
+
+        self.indent = indent;
+        self.bounds.top_left.x = left + indent as i32;
+        self.position.x = self.bounds.top_left.x;
     }
 
     /// Moves the cursor back to the start of the line.
@@ -77,7 +211,7 @@ impl<F: Font> Cursor<F> {
     #[inline]
     #[must_use]
     pub fn space(&self) -> u32 {
-        (self.bounds.bottom_right.x - self.position.x) as u32
+        (self.bounds.bottom_right.x - self.position.x).max(0) as u32
     }
 
     /// Advances the cursor by a given amount.
@@ -103,6 +237,19 @@ impl<F: Font> Cursor<F> {
         }
     }
 
+    /// Advances the cursor past the given character, if its glyph fits into the line.
+    ///
+    /// The letter spacing after the character does not need to fit into the line.
+    #[inline]
+    pub(crate) fn advance_char(&mut self, c: char) -> bool {
+        if self.fits_in_line(self.font_char_width(c)) {
+            self.advance_unchecked(self.char_width(c));
+            true
+        } else {
+            false
+        }
+    }
+
     /// Rewinds the cursor by a given amount.
     #[inline]
     pub fn rewind(&mut self, by: u32) -> bool {
@@ -125,7 +272,7 @@ mod test {
     fn fits_in_line() {
         // 6px width
         let cursor: Cursor<Font6x8> =
-            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0);
+            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0, 0, 0);
 
         assert!(cursor.fits_in_line(6));
         assert!(!cursor.fits_in_line(7));
@@ -135,7 +282,7 @@ mod test {
     fn advance_moves_position() {
         // 6px width
         let mut cursor: Cursor<Font6x8> =
-            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0);
+            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0, 0, 0);
 
         assert!(cursor.fits_in_line(1));
         cursor.advance(6);
@@ -146,7 +293,7 @@ mod test {
     fn rewind_moves_position_back() {
         // 6px width
         let mut cursor: Cursor<Font6x8> =
-            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0);
+            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0, 0, 0);
 
         cursor.advance(6);
         assert_eq!(6, cursor.position.x);
@@ -162,7 +309,7 @@ mod test {
     fn in_display_area() {
         // 6px width
         let mut cursor: Cursor<Font6x8> =
-            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0);
+            Cursor::new(Rectangle::new(Point::zero(), Point::new(5, 7)), 0, 0, 0);
 
         let data = [(0, true), (-8, false), (-1, false), (1, false)];
         for &(pos, inside) in data.iter() {
@@ -1,23 +1,27 @@
 //! Line rendering.
 use crate::{
-    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
-    parser::{Parser, Token},
+    alignment::{justified::JustifiedSpaceConfig, HorizontalTextAlignment, VerticalTextAlignment},
+    parser::{displayed_char, Parser},
     rendering::{
         ansi::Sgr,
         character::CharacterIterator,
         cursor::Cursor,
-        line_iter::{LineElementIterator, RenderElement},
+        line_iter::{CarriedToken, LineElementIterator, RenderElement},
         modified_whitespace::ModifiedEmptySpaceIterator,
-        space_config::*,
         whitespace::EmptySpaceIterator,
     },
-    style::{color::Rgb, height_mode::HeightMode, TextBoxStyle},
+    style::{
+        color::{AnsiColor, AnsiPalette, Rgb},
+        height_mode::HeightMode,
+        Direction, TextBoxStyle,
+    },
+    utils::rect_ext::RectExt,
 };
 use core::ops::Range;
-use embedded_graphics::prelude::*;
+use embedded_graphics::{prelude::*, primitives::Rectangle, style::TextStyle};
 
 /// Internal state used to render a line.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 enum State<C, F>
 where
     C: PixelColor,
@@ -26,8 +30,11 @@ where
     /// Fetch next render element.
     FetchNext,
 
-    /// Render a character.
-    Char(CharacterIterator<C, F>),
+    /// Render a character, followed by the gap caused by letter spacing.
+    Char(
+        CharacterIterator<C, F>,
+        Option<ModifiedEmptySpaceIterator<C, F>>,
+    ),
 
     /// Render a block of whitespace.
     Space(EmptySpaceIterator<C, F>),
@@ -36,60 +43,173 @@ where
     ModifiedSpace(ModifiedEmptySpaceIterator<C, F>),
 }
 
+/// The parts of the text box style that a line is rendered with.
+///
+/// The style of a line doesn't depend on the alignments or the height mode of the text box, so the
+/// same line renderer is used for every text box with the same color and font types.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct LineStyle<C, F>
+where
+    C: PixelColor,
+    F: Font + Copy,
+{
+    pub(crate) text_style: TextStyle<C, F>,
+    pub(crate) underlined: bool,
+    pub(crate) strikethrough: bool,
+    pub(crate) bold: bool,
+    letter_spacing: i32,
+    line_direction: Direction,
+    glyph_offset: i32,
+    ansi_palette: Option<AnsiPalette<C>>,
+}
+
+impl<C, F> LineStyle<C, F>
+where
+    C: PixelColor,
+    F: Font + Copy,
+{
+    fn new<A, V, H>(style: &TextBoxStyle<C, F, A, V, H>) -> Self
+    where
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        Self {
+            text_style: style.text_style,
+            underlined: style.underlined,
+            strikethrough: style.strikethrough,
+            bold: style.bold,
+            letter_spacing: style.letter_spacing,
+            line_direction: style.line_direction,
+            glyph_offset: style.glyph_offset(),
+            ansi_palette: style.ansi_palette,
+        }
+    }
+
+    /// Returns the display color of a color set by an escape sequence.
+    fn ansi_color(&self, color: AnsiColor) -> Option<C>
+    where
+        C: From<Rgb>,
+    {
+        match self.ansi_palette {
+            Some(palette) => palette.map(color),
+            None => Some(color.to_rgb().into()),
+        }
+    }
+}
+
 /// Pixel iterator to render a single line of styled text.
-#[derive(Debug)]
-pub struct StyledLinePixelIterator<'a, C, F, SP, A, V, H>
+///
+/// The iterator is only generic over the color and the font, so every horizontal alignment shares
+/// the same line rendering code.
+#[derive(Clone, Debug)]
+pub struct StyledLinePixelIterator<'a, C, F>
 where
     C: PixelColor,
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
-    V: VerticalTextAlignment,
-    H: HeightMode,
 {
     state: State<C, F>,
-    pub(crate) style: TextBoxStyle<C, F, A, V, H>,
+    pub(crate) style: LineStyle<C, F>,
+
+    /// The style that ANSI reset sequences return to.
+    default_style: LineStyle<C, F>,
+
     display_range: Range<i32>,
-    inner: LineElementIterator<'a, F, SP, A>,
+    inner: LineElementIterator<'a, F>,
+    color_spans: &'a [(Range<usize>, C)],
 }
 
-impl<'a, C, F, SP, A, V, H> StyledLinePixelIterator<'a, C, F, SP, A, V, H>
+impl<'a, C, F> StyledLinePixelIterator<'a, C, F>
 where
     C: PixelColor + From<Rgb>,
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
-    V: VerticalTextAlignment,
-    H: HeightMode,
 {
     /// Creates a new pixel iterator to draw the given character.
     #[inline]
     #[must_use]
-    pub fn new(
+    pub fn new<A, V, H>(
         parser: Parser<'a>,
         cursor: Cursor<F>,
-        config: SP,
+        config: impl Into<JustifiedSpaceConfig<F>>,
         style: TextBoxStyle<C, F, A, V, H>,
-        carried_token: Option<Token<'a>>,
-    ) -> Self {
+        carried_token: Option<CarriedToken<'a>>,
+    ) -> Self
+    where
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        let mut glyph_cursor = cursor;
+        glyph_cursor.position.y += style.glyph_offset();
+
+        let line_style = LineStyle::new(&style);
+
         Self {
             state: State::FetchNext,
-            style,
-            display_range: H::calculate_displayed_row_range(&cursor),
-            inner: LineElementIterator::new(parser, cursor, config, carried_token, style.tab_size),
+            style: line_style,
+            default_style: line_style,
+            display_range: H::calculate_displayed_row_range(&glyph_cursor),
+            inner: LineElementIterator::new(
+                parser,
+                cursor,
+                config,
+                carried_token,
+                style.tab_size,
+                style.break_strategy,
+                style.alignment,
+            )
+            .with_horizontal_overflow(style.horizontal_overflow)
+            .with_word_boundary_truncation(style.truncate_at_word_boundary)
+            .with_break_character(style.break_character),
+            color_spans: &[],
         }
     }
 
-    /// When finished, this method returns the last partially processed [`Token`], or
-    /// `None` if everything was rendered.
+    /// Sets the text color of characters in the given byte ranges of the text.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_color_spans(self, color_spans: &'a [(Range<usize>, C)]) -> Self {
+        Self {
+            color_spans,
+            ..self
+        }
+    }
+
+    /// Sets the style that ANSI reset sequences return to.
     ///
-    /// [`Token`]: ../../parser/enum.Token.html
+    /// By default, this is the style the line starts with.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_default_style<A, V, H>(
+        self,
+        default_style: TextBoxStyle<C, F, A, V, H>,
+    ) -> Self
+    where
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        Self {
+            default_style: LineStyle::new(&default_style),
+            ..self
+        }
+    }
+
+    /// When finished, this method returns how the line ended, or `None` if everything was
+    /// rendered.
     #[must_use]
     #[inline]
-    pub fn remaining_token(&self) -> Option<Token<'a>> {
+    pub fn remaining_token(&self) -> Option<CarriedToken<'a>> {
         self.inner.remaining_token()
     }
 
+    /// Returns `true` if nothing that takes up width has been laid out in the line so far.
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     /// When finished, this method returns the text parser object.
     #[must_use]
     #[inline]
@@ -104,19 +224,309 @@ where
         self.inner.cursor
     }
 
+    /// Returns the rows of the text area that the line is displayed in.
+    ///
+    /// The returned area spans the whole width of the text area. Returns `None` if the line is not
+    /// displayed. This method must be called before the line is processed.
+    pub(crate) fn displayed_rows(&self) -> Option<Rectangle> {
+        if !self.is_anything_displayed() {
+            return None;
+        }
+
+        let bounds = self.inner.cursor.bounds;
+        let y = self.inner.cursor.position.y + self.style.glyph_offset;
+
+        Some(Rectangle::from_size(
+            Point::new(bounds.top_left.x, y + self.display_range.start),
+            Size::new(self.inner.cursor.line_width(), self.displayed_row_count()),
+        ))
+    }
+
+    /// Lays out the rest of the line without drawing it.
+    ///
+    /// Returns the area covered by the displayed part of the line, or `None` if the line is not
+    /// displayed. The area of an empty line has zero width.
+    pub(crate) fn measure_bounds(&mut self) -> Option<Rectangle> {
+        let y = self.inner.cursor.position.y + self.style.glyph_offset;
+        let line_start = self.element_position(self.inner.cursor.position, 0).x;
+        let mut extent: Option<(i32, i32)> = None;
+
+        while let Some(element) = self.inner.next() {
+            let width = match element {
+                RenderElement::PrintedCharacter(c) => self.inner.cursor.font_char_width(c),
+                RenderElement::Space(space_width, _) => space_width,
+                RenderElement::Sgr(sgr) => {
+                    // the style is carried over to the next line
+                    self.apply_sgr(sgr);
+                    continue;
+                }
+            };
+
+            if width == 0 {
+                continue;
+            }
+
+            // clipped characters may not fit into the line completely
+            let bounds = self.inner.cursor.bounds;
+            let x = self.element_position(self.inner.pos, width).x;
+            let end = (x + width as i32).min(bounds.bottom_right.x);
+            let x = x.max(bounds.top_left.x);
+            extent = Some(extent.map_or((x, end), |(start, e)| (start.min(x), e.max(end))));
+        }
+
+        if !self.is_anything_displayed() {
+            return None;
+        }
+
+        let (start, end) = extent.unwrap_or((line_start, line_start));
+
+        Some(Rectangle::from_size(
+            Point::new(start, y + self.display_range.start),
+            Size::new((end - start) as u32, self.displayed_row_count()),
+        ))
+    }
+
+    /// Returns the vertical position of the top of the glyphs in the line.
+    pub(crate) fn glyph_top(&self) -> i32 {
+        self.inner.cursor.position.y + self.style.glyph_offset
+    }
+
+    /// Lays out the rest of the line and returns the horizontal position of a caret placed at
+    /// byte offset `offset` of the text.
+    ///
+    /// `line_start` is the byte offset the line starts at. Offsets that don't belong to a printed
+    /// character, e.g. whitespace, are placed one space per byte after the previous character, but
+    /// never beyond the next character or the end of the line.
+    pub(crate) fn caret_x(&mut self, offset: usize, line_start: usize) -> i32 {
+        let space = self.inner.cursor.char_width(' ') as i32;
+        let mut previous = (line_start, self.line_start_edge());
+        let mut limit = self.line_end_edge();
+
+        while let Some(element) = self.inner.next() {
+            if let RenderElement::PrintedCharacter(c) = element {
+                if !self.inner.printed_text_char {
+                    continue;
+                }
+
+                let char_offset = self.inner.char_offset;
+                let (leading, trailing) =
+                    self.element_edges(self.inner.pos, self.inner.cursor.char_width(c));
+
+                if char_offset == offset {
+                    return leading;
+                } else if char_offset > offset {
+                    limit = leading;
+                    break;
+                }
+
+                previous = (char_offset + c.len_utf8(), trailing);
+            }
+        }
+
+        let (previous_end, edge) = previous;
+        let gap = offset.saturating_sub(previous_end) as i32 * space;
+        match self.style.line_direction {
+            Direction::Ltr => (edge + gap).min(limit),
+            Direction::Rtl => (edge - gap).max(limit),
+        }
+    }
+
+    /// Lays out the rest of the line and returns the byte offset of the caret position that is
+    /// closest to the horizontal position `x`.
+    ///
+    /// `line_start` is the byte offset the line starts at.
+    pub(crate) fn offset_at_x(&mut self, x: i32, line_start: usize) -> usize {
+        let mut closest = (line_start, (self.line_start_edge() - x).abs());
+
+        while let Some(element) = self.inner.next() {
+            if let RenderElement::PrintedCharacter(c) = element {
+                if !self.inner.printed_text_char {
+                    continue;
+                }
+
+                let char_offset = self.inner.char_offset;
+                let (leading, trailing) =
+                    self.element_edges(self.inner.pos, self.inner.cursor.char_width(c));
+
+                for &(offset, edge) in &[
+                    (char_offset, leading),
+                    (char_offset + c.len_utf8(), trailing),
+                ] {
+                    let distance = (edge - x).abs();
+                    if distance < closest.1 {
+                        closest = (offset, distance);
+                    }
+                }
+            }
+        }
+
+        closest.0
+    }
+
+    /// Lays out the rest of the line and returns the area covered by the character that starts at
+    /// byte offset `offset` of the text.
+    ///
+    /// Returns `None` if no printed character starts at `offset` in this line.
+    pub(crate) fn char_bounds(&mut self, offset: usize) -> Option<Rectangle> {
+        while let Some(element) = self.inner.next() {
+            if let RenderElement::PrintedCharacter(c) = element {
+                if !self.inner.printed_text_char {
+                    continue;
+                }
+
+                let char_offset = self.inner.char_offset;
+                if char_offset == offset {
+                    let width = self.inner.cursor.char_width(c);
+                    let top_left = self.element_position(self.inner.pos, width);
+
+                    return Some(Rectangle::from_size(
+                        top_left,
+                        Size::new(width, F::CHARACTER_SIZE.height),
+                    ));
+                } else if char_offset > offset {
+                    break;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Lays out the rest of the line and returns the byte offset of the printed character that
+    /// covers the horizontal position `x`.
+    ///
+    /// Returns `None` if `x` doesn't fall on a printed character.
+    pub(crate) fn char_at_x(&mut self, x: i32) -> Option<usize> {
+        while let Some(element) = self.inner.next() {
+            if let RenderElement::PrintedCharacter(c) = element {
+                if !self.inner.printed_text_char {
+                    continue;
+                }
+
+                let width = self.inner.cursor.char_width(c);
+                let left = self.element_position(self.inner.pos, width).x;
+                if left <= x && x < left + width as i32 {
+                    return Some(self.inner.char_offset);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Applies the style change of an escape sequence.
+    fn apply_sgr(&mut self, sgr: Sgr) {
+        match sgr {
+            Sgr::Reset => {
+                self.style.text_style.text_color = self.default_style.text_style.text_color;
+                self.style.text_style.background_color =
+                    self.default_style.text_style.background_color;
+                self.style.underlined = self.default_style.underlined;
+                self.style.strikethrough = self.default_style.strikethrough;
+                self.style.bold = false;
+            }
+            Sgr::ChangeTextColor(color) => {
+                if let Some(color) = self.style.ansi_color(color) {
+                    self.style.text_style.text_color = Some(color);
+                }
+            }
+            Sgr::DefaultTextColor => {
+                self.style.text_style.text_color = self.default_style.text_style.text_color;
+            }
+            Sgr::ChangeBackgroundColor(color) => {
+                if let Some(color) = self.style.ansi_color(color) {
+                    self.style.text_style.background_color = Some(color);
+                }
+            }
+            Sgr::DefaultBackgroundColor => {
+                self.style.text_style.background_color =
+                    self.default_style.text_style.background_color;
+            }
+            Sgr::Underline => {
+                self.style.underlined = true;
+            }
+            Sgr::UnderlineOff => {
+                self.style.underlined = false;
+            }
+            Sgr::CrossedOut => {
+                self.style.strikethrough = true;
+            }
+            Sgr::NotCrossedOut => {
+                self.style.strikethrough = false;
+            }
+            Sgr::Bold => {
+                self.style.bold = true;
+            }
+            Sgr::NormalIntensity => {
+                self.style.bold = false;
+            }
+        }
+    }
+
+    /// Returns `true` if the given point is between the left and right edges of the line.
+    ///
+    /// Clipped characters, and the letter spacing after them, may not fit into the line
+    /// completely.
+    fn is_inside_line(&self, point: Point) -> bool {
+        let bounds = self.inner.cursor.bounds;
+
+        point.x >= bounds.top_left.x && point.x < bounds.bottom_right.x
+    }
+
     fn is_anything_displayed(&self) -> bool {
         self.display_range.start < self.display_range.end
     }
+
+    /// Returns the number of rows of the line that are displayed.
+    fn displayed_row_count(&self) -> u32 {
+        (self.display_range.end - self.display_range.start).max(0) as u32
+    }
+
+    /// Returns the horizontal position of the first element of the line.
+    fn line_start_edge(&self) -> i32 {
+        self.element_edges(self.inner.cursor.position, 0).0
+    }
+
+    /// Returns the horizontal position where the line ends.
+    fn line_end_edge(&self) -> i32 {
+        let bounds = self.inner.cursor.bounds;
+        match self.style.line_direction {
+            Direction::Ltr => bounds.bottom_right.x,
+            Direction::Rtl => bounds.top_left.x,
+        }
+    }
+
+    /// Returns the leading and trailing edges of an element that starts at `pos` and is `width`
+    /// pixels wide, in the direction of the line.
+    fn element_edges(&self, pos: Point, width: u32) -> (i32, i32) {
+        let x = self.element_position(pos, width).x;
+        match self.style.line_direction {
+            Direction::Ltr => (x, x + width as i32),
+            Direction::Rtl => (x + width as i32, x),
+        }
+    }
+
+    /// Returns the position of an element that starts at `pos` and is `width` pixels wide,
+    /// taking the line direction and the glyph alignment into account.
+    fn element_position(&self, pos: Point, width: u32) -> Point {
+        let y = pos.y + self.style.glyph_offset;
+        match self.style.line_direction {
+            Direction::Ltr => Point::new(pos.x, y),
+            Direction::Rtl => {
+                // mirror the element inside the line
+                let bounds = self.inner.cursor.bounds;
+                let x = bounds.top_left.x + bounds.bottom_right.x - pos.x - width as i32;
+
+                Point::new(x, y)
+            }
+        }
+    }
 }
 
-impl<C, F, SP, A, V, H> Iterator for StyledLinePixelIterator<'_, C, F, SP, A, V, H>
+impl<C, F> Iterator for StyledLinePixelIterator<'_, C, F>
 where
     C: PixelColor + From<Rgb>,
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
-    V: VerticalTextAlignment,
-    H: HeightMode,
 {
     type Item = Pixel<C>;
 
@@ -127,32 +537,76 @@ where
                 // No token being processed, get next one
                 State::FetchNext => {
                     // HACK: avoid drawing the underline outside of the text box
-                    let underlined = if self.style.underlined {
-                        self.inner.cursor.position.y < self.inner.cursor.bounds.bottom_right.y
-                    } else {
-                        false
-                    };
+                    let underline_fits = self.inner.cursor.position.y + self.style.glyph_offset
+                        < self.inner.cursor.bounds.bottom_right.y;
+                    let underlined = self.style.underlined && underline_fits;
 
                     match self.inner.next() {
                         Some(RenderElement::PrintedCharacter(c)) => {
                             if self.is_anything_displayed() {
-                                self.state = State::Char(CharacterIterator::new(
-                                    c,
-                                    self.inner.pos,
-                                    self.style.text_style,
+                                let mut text_style = self.style.text_style;
+                                let offset = self.inner.char_offset;
+                                if let Some((_, color)) =
+                                    self.color_spans.iter().find(|(r, _)| r.contains(&offset))
+                                {
+                                    text_style.text_color = Some(*color);
+                                }
+
+                                let char_width = self.inner.cursor.font_char_width(c);
+                                let gap = if self.style.letter_spacing > 0 {
+                                    let gap_width = self.style.letter_spacing as u32;
+                                    let gap_pos = self.element_position(
+                                        self.inner.pos + Point::new(char_width as i32, 0),
+                                        gap_width,
+                                    );
+
+                                    Some(ModifiedEmptySpaceIterator::new(
+                                        gap_width,
+                                        gap_pos,
+                                        text_style,
+                                        self.display_range.clone(),
+                                        underlined,
+                                        self.style.strikethrough,
+                                    ))
+                                } else {
+                                    None
+                                };
+
+                                // overstriking an underscore underlines the character
+                                let overstruck = self.inner.overstruck;
+                                let underline_char =
+                                    underlined || (underline_fits && overstruck == Some('_'));
+
+                                // `c` is the character of the text, draw the glyph displayed
+                                // in its place
+                                let glyph = displayed_char(c);
+                                let mut character = CharacterIterator::new(
+                                    glyph,
+                                    self.element_position(self.inner.pos, char_width),
+                                    text_style,
                                     self.display_range.clone(),
-                                    underlined,
+                                    underline_char,
                                     self.style.strikethrough,
-                                ));
+                                );
+                                if let Some(font) = self.inner.cursor.bold_font() {
+                                    character = character.with_bold_font(glyph, font);
+                                }
+                                if let Some(previous) = overstruck.filter(|&p| p != '_') {
+                                    character =
+                                        character.with_overstrike(glyph, displayed_char(previous));
+                                }
+
+                                self.state = State::Char(character, gap);
                             }
                         }
 
                         Some(RenderElement::Space(space_width, _)) => {
                             if self.is_anything_displayed() {
+                                let pos = self.element_position(self.inner.pos, space_width);
                                 self.state = if underlined || self.style.strikethrough {
                                     State::ModifiedSpace(ModifiedEmptySpaceIterator::new(
                                         space_width,
-                                        self.inner.pos,
+                                        pos,
                                         self.style.text_style,
                                         self.display_range.clone(),
                                         underlined,
@@ -161,7 +615,7 @@ where
                                 } else {
                                     State::Space(EmptySpaceIterator::new(
                                         space_width,
-                                        self.inner.pos,
+                                        pos,
                                         self.style.text_style,
                                         self.display_range.clone(),
                                     ))
@@ -169,49 +623,24 @@ where
                             }
                         }
 
-                        Some(RenderElement::Sgr(sgr)) => match sgr {
-                            Sgr::Reset => {
-                                self.style.text_style.text_color = None;
-                                self.style.text_style.background_color = None;
-                                self.style.underlined = false;
-                                self.style.strikethrough = false;
-                            }
-                            Sgr::ChangeTextColor(color) => {
-                                self.style.text_style.text_color = Some(color.into());
-                            }
-                            Sgr::DefaultTextColor => {
-                                self.style.text_style.text_color = None;
-                            }
-                            Sgr::ChangeBackgroundColor(color) => {
-                                self.style.text_style.background_color = Some(color.into());
-                            }
-                            Sgr::DefaultBackgroundColor => {
-                                self.style.text_style.background_color = None;
-                            }
-                            Sgr::Underline => {
-                                self.style.underlined = true;
-                            }
-                            Sgr::UnderlineOff => {
-                                self.style.underlined = false;
-                            }
-                            Sgr::CrossedOut => {
-                                self.style.strikethrough = true;
-                            }
-                            Sgr::NotCrossedOut => {
-                                self.style.strikethrough = false;
-                            }
-                        },
+                        Some(RenderElement::Sgr(sgr)) => self.apply_sgr(sgr),
 
                         None => break None,
                     };
                 }
 
-                State::Char(ref mut iter) => {
-                    if let pixel @ Some(_) = iter.next() {
-                        break pixel;
+                State::Char(ref mut iter, ref mut gap) => {
+                    if let Some(pixel) = iter.next() {
+                        if self.is_inside_line(pixel.0) {
+                            break Some(pixel);
+                        }
+                        continue;
                     }
 
-                    self.state = State::FetchNext;
+                    self.state = match gap.take() {
+                        Some(gap) => State::ModifiedSpace(gap),
+                        None => State::FetchNext,
+                    };
                 }
 
                 State::Space(ref mut iter) => {
@@ -223,8 +652,11 @@ where
                 }
 
                 State::ModifiedSpace(ref mut iter) => {
-                    if let pixel @ Some(_) = iter.next() {
-                        break pixel;
+                    if let Some(pixel) = iter.next() {
+                        if self.is_inside_line(pixel.0) {
+                            break Some(pixel);
+                        }
+                        continue;
                     }
 
                     self.state = State::FetchNext;
@@ -240,8 +672,8 @@ mod test {
         alignment::{HorizontalTextAlignment, VerticalTextAlignment},
         parser::{Parser, Token},
         rendering::{
-            cursor::Cursor,
-            line::{StyledLinePixelIterator, UniformSpaceConfig},
+            cursor::Cursor, line::StyledLinePixelIterator, line_iter::CarriedToken,
+            space_config::UniformSpaceConfig,
         },
         style::{color::Rgb, height_mode::HeightMode, TextBoxStyle, TextBoxStyleBuilder},
     };
@@ -255,7 +687,7 @@ mod test {
         bounds: Rectangle,
         style: TextBoxStyle<C, F, A, V, H>,
         pattern: &[&str],
-    ) -> StyledLinePixelIterator<'a, C, F, UniformSpaceConfig<F>, A, V, H>
+    ) -> StyledLinePixelIterator<'a, C, F>
     where
         C: PixelColor + From<Rgb> + embedded_graphics::mock_display::ColorMapping<C>,
         F: Font + Copy,
@@ -266,7 +698,12 @@ mod test {
         let parser = Parser::parse(text);
         let config = UniformSpaceConfig::default();
 
-        let cursor = Cursor::new(bounds, style.line_spacing);
+        let cursor = Cursor::new(
+            bounds,
+            style.line_spacing,
+            style.letter_spacing,
+            style.paragraph_spacing,
+        );
         let mut iter = StyledLinePixelIterator::new(parser, cursor, config, style, None);
         let mut display = MockDisplay::new();
 
@@ -299,7 +736,7 @@ mod test {
                 "..............................",
             ],
         );
-        assert_eq!(Some(Token::Break(None)), iter.remaining_token());
+        assert_eq!(Some(CarriedToken::Wrapped), iter.remaining_token());
         assert_eq!(Some(Token::Word("sample")), iter.inner.parser.next());
     }
 
@@ -315,6 +752,8 @@ mod test {
         let mut cursor = Cursor::new(
             Rectangle::new(Point::new(0, 8), Point::new(6 * 7 - 1, 16)),
             style.line_spacing,
+            style.letter_spacing,
+            style.paragraph_spacing,
         );
         cursor.position.y -= 8;
 
@@ -326,7 +765,7 @@ mod test {
         );
 
         // even though nothing was drawn, the text should be consumed
-        assert_eq!(Some(Token::Break(None)), iter.remaining_token());
+        assert_eq!(Some(CarriedToken::Wrapped), iter.remaining_token());
     }
 
     #[test]
@@ -351,7 +790,7 @@ mod test {
                 "..........................................",
             ],
         );
-        assert_eq!(Some(Token::Word("mple")), iter.remaining_token());
+        assert_eq!(Some(CarriedToken::Word("mple")), iter.remaining_token());
     }
 
     #[test]
@@ -376,7 +815,7 @@ mod test {
                 "............",
             ],
         );
-        assert_eq!(Some(Token::Word("me")), iter.remaining_token());
+        assert_eq!(Some(CarriedToken::Word("me")), iter.remaining_token());
     }
 
     #[test]
@@ -412,7 +851,12 @@ mod test {
             .background_color(BinaryColor::Off)
             .build();
 
-        let cursor = Cursor::new(Rectangle::new(Point::zero(), Point::new(6 * 7 - 1, 7)), 0);
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(6 * 7 - 1, 7)),
+            0,
+            0,
+            0,
+        );
         let mut iter = StyledLinePixelIterator::new(parser, cursor, config, style, None);
         let mut display = MockDisplay::new();
 
@@ -433,6 +877,78 @@ mod test {
         );
     }
 
+    #[test]
+    fn ansi_cursor_backwards_overdraws_characters() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        test_rendered_text(
+            "abc\x1b[2Dxy",
+            Rectangle::new(Point::zero(), Point::new(6 * 7 - 1, 7)),
+            style,
+            &[
+                "..................",
+                "..................",
+                ".###..#...#.#...#.",
+                "....#..#.#..#...#.",
+                ".####...#...#...#.",
+                "#...#..#.#...####.",
+                ".####.#...#.....#.",
+                ".............###..",
+            ],
+        );
+    }
+
+    #[test]
+    fn ansi_cursor_backwards_stops_at_start_of_line() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        test_rendered_text(
+            "ab\x1b[5Dx",
+            Rectangle::new(Point::zero(), Point::new(6 * 7 - 1, 7)),
+            style,
+            &[
+                "......#.....",
+                "......#.....",
+                "#...#.#.##..",
+                ".#.#..##..#.",
+                "..#...#...#.",
+                ".#.#..#...#.",
+                "#...#.####..",
+                "............",
+            ],
+        );
+    }
+
+    #[test]
+    fn ansi_cursor_forward_stops_at_end_of_line() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        test_rendered_text(
+            "a\x1b[9Cb",
+            Rectangle::new(Point::zero(), Point::new(6 * 4 - 1, 7)),
+            style,
+            &[
+                "........................",
+                "........................",
+                ".###....................",
+                "....#...................",
+                ".####...................",
+                "#...#...................",
+                ".####...................",
+                "........................",
+            ],
+        );
+    }
+
     #[test]
     fn carried_over_spaces() {
         let style = TextBoxStyleBuilder::new(Font6x8)
@@ -443,14 +959,19 @@ mod test {
         let parser = Parser::parse("Some  sample text");
         let config = UniformSpaceConfig::default();
         let bounds = Rectangle::new(Point::zero(), Point::new(6 * 5 - 1, 7));
-        let cursor = Cursor::new(bounds, style.line_spacing);
+        let cursor = Cursor::new(
+            bounds,
+            style.line_spacing,
+            style.letter_spacing,
+            style.paragraph_spacing,
+        );
         let mut iter = StyledLinePixelIterator::new(parser, cursor, config, style, None);
         let mut display = MockDisplay::new();
 
         iter.draw(&mut display).unwrap();
 
         // eat one space, so one is rendered at the end of line and nothing in the next
-        assert_eq!(Some(Token::Break(None)), iter.remaining_token());
+        assert_eq!(Some(CarriedToken::Wrapped), iter.remaining_token());
 
         let mut iter = StyledLinePixelIterator::new(
             iter.parser(),
@@ -3,19 +3,18 @@
 //! Provide elements (spaces or characters) to render as long as they fit in the current line
 use super::ansi::{try_parse_sgr, Sgr};
 use crate::{
-    alignment::HorizontalTextAlignment,
-    parser::{Parser, Token, SPEC_CHAR_NBSP},
+    alignment::{justified::JustifiedSpaceConfig, HorizontalTextAlignment},
+    parser::{is_non_breaking_space, Parser, Token, SPEC_CHAR_NNBSP},
     rendering::{cursor::Cursor, space_config::*},
-    style::TabSize,
+    style::{BreakStrategy, HorizontalOverflow, TabAlignment, TabSize},
     utils::font_ext::FontExt,
 };
 use ansi_parser::AnsiSequence;
-use as_slice::AsSlice;
-use core::{marker::PhantomData, str::Chars};
+use core::{ops::Range, str::Chars};
 use embedded_graphics::prelude::*;
 
 /// Internal state used to render a line.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 enum State<'a> {
     /// Decide what to do next.
     ProcessToken(Token<'a>),
@@ -24,7 +23,88 @@ enum State<'a> {
     Word(Chars<'a>),
 
     /// Signal that the renderer has finished, store the token that was consumed but not rendered.
-    Done(Option<Token<'a>>),
+    Done(Option<CarriedToken<'a>>),
+}
+
+/// The way a line ended, and the part of the text that is carried over to the next line.
+///
+/// A line that ends because the text was fully processed has no carried token.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CarriedToken<'a> {
+    /// The line was wrapped and the token that caused the wrap was consumed.
+    ///
+    /// This is the case when the line is wrapped at a space, a zero-width space or a soft hyphen.
+    /// The next line starts with the next token of the parser.
+    Wrapped,
+
+    /// The line was wrapped inside a word, or before a word that did not fit into the line.
+    ///
+    /// The next line starts with the rest of the word.
+    Word(&'a str),
+
+    /// The line was wrapped at whitespace and the given number of spaces did not fit into the line.
+    ///
+    /// The spaces only exist because of the wrapping, so the next line does not start with them.
+    Whitespace(u32),
+
+    /// The line was ended by a line break.
+    NewLine,
+
+    /// The line was ended by a carriage return.
+    CarriageReturn,
+
+    /// The line was ended by a form feed.
+    ///
+    /// The next line starts with the form feed, which ends it as an empty line.
+    FormFeed,
+
+    /// The line was ended by a vertical tab.
+    ///
+    /// The next line is indented by the given number of pixels, the width of the line before the
+    /// vertical tab.
+    VerticalTab(u32),
+}
+
+impl<'a> CarriedToken<'a> {
+    /// Returns the token that the next line starts with, if any.
+    fn into_token(self) -> Option<Token<'a>> {
+        match self {
+            CarriedToken::Word(w) => Some(Token::Word(w)),
+            CarriedToken::FormFeed => Some(Token::FormFeed),
+            CarriedToken::Wrapped
+            | CarriedToken::Whitespace(_)
+            | CarriedToken::NewLine
+            | CarriedToken::CarriageReturn
+            | CarriedToken::VerticalTab(_) => None,
+        }
+    }
+}
+
+/// Returns the byte offset of the first character of the line that starts with `carried`.
+pub(crate) fn line_start_offset(carried: Option<CarriedToken<'_>>, parser: &Parser<'_>) -> usize {
+    match carried {
+        // the rest of the word is laid out in the next line
+        Some(CarriedToken::Word(w)) => parser.offset_of(w),
+        _ => parser.offset(),
+    }
+}
+
+/// A line of text laid out by [`TextBoxStyle::fit_next_line`].
+///
+/// [`TextBoxStyle::fit_next_line`]: ../../style/struct.TextBoxStyle.html#method.fit_next_line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineResult<'a> {
+    /// The byte range of the text that is laid out in the line.
+    ///
+    /// The range includes the whitespace or line break that ended the line, so the next line
+    /// starts where this range ends.
+    pub bytes: Range<usize>,
+
+    /// The width of the line in pixels.
+    pub width: u32,
+
+    /// The way the line ended, or `None` if the line ends the text.
+    pub end: Option<CarriedToken<'a>>,
 }
 
 /// What to draw
@@ -40,13 +120,30 @@ pub enum RenderElement {
     Sgr(Sgr),
 }
 
+/// The spaces that the horizontal alignment renders at the edges of a line.
+#[derive(Copy, Clone, Debug)]
+struct LineSpaces {
+    starting: bool,
+    ending: bool,
+}
+
+impl LineSpaces {
+    fn new<A: HorizontalTextAlignment>(alignment: &A) -> Self {
+        Self {
+            starting: alignment.starting_spaces(),
+            ending: alignment.ending_spaces(),
+        }
+    }
+}
+
 /// Pixel iterator to render a single line of styled text.
-#[derive(Debug)]
-pub struct LineElementIterator<'a, F, SP, A>
+///
+/// The iterator is only generic over the font, so every horizontal alignment shares the same line
+/// layout code.
+#[derive(Clone, Debug)]
+pub struct LineElementIterator<'a, F>
 where
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
 {
     /// Position information.
     pub cursor: Cursor<F>,
@@ -55,31 +152,130 @@ where
     pub parser: Parser<'a>,
 
     pub(crate) pos: Point,
+
+    /// Byte offset of the last printed character in the text.
+    pub(crate) char_offset: usize,
+
+    /// Whether the last returned element is a character of the text, as opposed to an inserted
+    /// hyphen or another element.
+    pub(crate) printed_text_char: bool,
+
+    /// Whether the last returned element is a tab.
+    pub(crate) tab: bool,
+
+    /// The character that the last returned character is drawn over, if it follows a backspace.
+    pub(crate) overstruck: Option<char>,
+
+    /// The last printed character of the current word, which a backspace moves back over.
+    previous_char: Option<char>,
+
+    /// The character that the next printed character is drawn over.
+    overstrike: Option<char>,
+
     current_token: State<'a>,
-    config: SP,
+    config: JustifiedSpaceConfig<F>,
+
+    /// Horizontal position of the cursor at the start of the line, after the alignment offset.
+    line_start: i32,
+
     first_word: bool,
-    alignment: PhantomData<A>,
+    spaces: LineSpaces,
     tab_size: TabSize<F>,
+    word_gaps: u32,
+    max_word_gaps: Option<u32>,
+    break_early: bool,
+    continues_word: bool,
+    horizontal_overflow: HorizontalOverflow,
+    truncate_at_word_boundary: bool,
+    break_character: Option<char>,
+    word_overflows: bool,
+    skip_word: bool,
+
+    /// Whether nothing that takes up width in the line has been returned.
+    empty: bool,
+
+    /// Whether a space at the end of the line was dropped, because it didn't fit.
+    space_dropped: bool,
 }
 
-impl<'a, F, SP, A> LineElementIterator<'a, F, SP, A>
+impl<'a, F> LineElementIterator<'a, F>
 where
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
 {
     /// Creates a new pixel iterator to draw the given character.
     #[inline]
     #[must_use]
-    pub fn new(
-        mut parser: Parser<'a>,
+    pub fn new<A: HorizontalTextAlignment>(
+        parser: Parser<'a>,
         cursor: Cursor<F>,
-        config: SP,
-        carried_token: Option<Token<'a>>,
+        config: impl Into<JustifiedSpaceConfig<F>>,
+        carried_token: Option<CarriedToken<'a>>,
+        tab_size: TabSize<F>,
+        break_strategy: BreakStrategy,
+        alignment: A,
+    ) -> Self {
+        Self::with_spaces(
+            parser,
+            cursor,
+            config.into(),
+            carried_token,
+            tab_size,
+            break_strategy,
+            LineSpaces::new(&alignment),
+        )
+    }
+
+    fn with_spaces(
+        parser: Parser<'a>,
+        cursor: Cursor<F>,
+        config: JustifiedSpaceConfig<F>,
+        carried_token: Option<CarriedToken<'a>>,
+        tab_size: TabSize<F>,
+        break_strategy: BreakStrategy,
+        spaces: LineSpaces,
+    ) -> Self {
+        let max_word_gaps = match break_strategy {
+            BreakStrategy::Greedy => None,
+            BreakStrategy::Balanced => {
+                let measure_config = JustifiedSpaceConfig::uniform(config.base_width());
+                balanced_word_gaps(
+                    &parser,
+                    &carried_token,
+                    cursor,
+                    measure_config,
+                    tab_size,
+                    spaces,
+                )
+            }
+        };
+
+        Self::with_word_gap_limit(
+            parser,
+            cursor,
+            config,
+            carried_token,
+            tab_size,
+            spaces,
+            max_word_gaps,
+        )
+    }
+
+    fn with_word_gap_limit(
+        mut parser: Parser<'a>,
+        mut cursor: Cursor<F>,
+        config: JustifiedSpaceConfig<F>,
+        carried_token: Option<CarriedToken<'a>>,
         tab_size: TabSize<F>,
+        spaces: LineSpaces,
+        max_word_gaps: Option<u32>,
     ) -> Self {
+        let line_start = cursor.position.x;
+        if let Some(CarriedToken::VerticalTab(indent)) = carried_token {
+            cursor.advance_unchecked(indent);
+        }
+
         let current_token = carried_token
-            .filter(|t| ![Token::NewLine, Token::CarriageReturn, Token::Break(None)].contains(t))
+            .and_then(CarriedToken::into_token)
             .or_else(|| parser.next())
             .map_or(State::Done(None), State::ProcessToken);
 
@@ -87,11 +283,59 @@ where
             parser,
             current_token,
             config,
+            line_start,
             cursor,
             first_word: true,
-            alignment: PhantomData,
+            spaces,
             pos: Point::zero(),
+            char_offset: 0,
+            printed_text_char: false,
+            tab: false,
+            overstruck: None,
+            previous_char: None,
+            overstrike: None,
             tab_size,
+            word_gaps: 0,
+            max_word_gaps,
+            break_early: false,
+            continues_word: false,
+            horizontal_overflow: HorizontalOverflow::default(),
+            truncate_at_word_boundary: false,
+            break_character: Some('-'),
+            word_overflows: false,
+            skip_word: false,
+            empty: true,
+            space_dropped: false,
+        }
+    }
+
+    /// Sets how to display words that are wider than the line.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_horizontal_overflow(self, horizontal_overflow: HorizontalOverflow) -> Self {
+        Self {
+            horizontal_overflow,
+            ..self
+        }
+    }
+
+    /// Sets whether words that are wider than the line are only clipped at a non-breaking space.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_word_boundary_truncation(self, truncate_at_word_boundary: bool) -> Self {
+        Self {
+            truncate_at_word_boundary,
+            ..self
+        }
+    }
+
+    /// Sets the character that is displayed when the line is wrapped at a soft hyphen.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_break_character(self, break_character: Option<char>) -> Self {
+        Self {
+            break_character,
+            ..self
         }
     }
 
@@ -102,76 +346,233 @@ where
         }
     }
 
-    /// When finished, this method returns the last partially processed [`Token`], or
-    /// `None` if everything was rendered.
-    ///
-    /// [`Token`]: ../../parser/enum.Token.html
+    /// When finished, this method returns how the line ended, or `None` if everything was
+    /// rendered.
     #[must_use]
     #[inline]
-    pub fn remaining_token(&self) -> Option<Token<'a>> {
+    pub fn remaining_token(&self) -> Option<CarriedToken<'a>> {
         match self.current_token {
-            State::Done(ref t) => t.clone(),
+            State::Done(t) => t,
             _ => None,
         }
     }
 
+    /// Returns `true` if no character, or space that is rendered at the end of lines, has been
+    /// returned so far.
+    ///
+    /// A line that is empty when the whole text is processed is not measured as a line.
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    /// Returns `true` if a space of the given width at the cursor would fit into the line if the
+    /// alignment didn't offset the line.
+    ///
+    /// Spaces at the end of the line are not measured if the alignment doesn't render them, so the
+    /// offset may not leave room for them. Such spaces are dropped instead of wrapping the line.
+    fn is_pushed_out(&self, width: u32) -> bool {
+        !self.spaces.ending
+            && self.cursor.position.x - self.line_start + width as i32
+                <= self.cursor.line_width() as i32
+    }
+
+    /// Returns `true` if a non-breaking space of the given width, that doesn't fit at the cursor,
+    /// is dropped instead of wrapping the line.
+    ///
+    /// If the alignment doesn't render spaces at the end of the line, a non-breaking space after
+    /// the text of the line is dropped like a regular space would be. Otherwise it would start a
+    /// new line that is not measured.
+    fn is_no_break_space_dropped(&self, width: u32) -> bool {
+        self.is_pushed_out(width)
+            || (!self.spaces.ending && self.cursor.position.x > self.line_start)
+    }
+
+    /// Returns the width of `c` if it's a non-breaking space.
+    fn no_break_space_width(&self, c: char) -> Option<u32> {
+        if is_non_breaking_space(c) {
+            Some(self.config.peek_next_width(1))
+        } else if c == SPEC_CHAR_NNBSP {
+            // narrow spaces are not stretched
+            Some(self.cursor.char_width(c))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the width of `s` as it is laid out in a word, where non-breaking spaces are as wide
+    /// as the other spaces of the line.
+    fn str_width(&self, cursor: &Cursor<F>, s: &str) -> u32 {
+        s.chars()
+            .map(|c| {
+                self.no_break_space_width(c)
+                    .unwrap_or_else(|| cursor.char_width(c))
+            })
+            .sum()
+    }
+
+    /// Drops a space of the given width that is pushed out of the line.
+    ///
+    /// The cursor is still moved past the space, so the rest of the line is laid out like it is
+    /// measured. Nothing else fits into the line after the space.
+    fn drop_space(&mut self, width: u32) {
+        self.cursor.advance_unchecked(width);
+        self.space_dropped = true;
+    }
+
     fn finish_end_of_string(&mut self) {
         self.current_token = State::Done(None);
     }
 
-    fn finish_wrapped(&mut self) {
-        self.finish(Token::Break(None));
+    /// Stops the layout, because not even a single character fits into an empty line.
+    fn finish_too_narrow(&mut self) {
+        // Consume the rest of the text, so that the next line does not start with the same word
+        // and measurement agrees with rendering.
+        while self.parser.next().is_some() {}
+        self.finish_end_of_string();
     }
 
-    fn finish(&mut self, t: Token<'a>) {
-        self.current_token = match t {
-            Token::NewLine => {
-                self.cursor.new_line();
-                self.cursor.carriage_return();
-
-                State::Done(Some(Token::NewLine))
-            }
+    fn finish_wrapped(&mut self) {
+        self.finish(CarriedToken::Wrapped);
+    }
 
-            Token::CarriageReturn => {
-                self.cursor.carriage_return();
+    fn finish(&mut self, t: CarriedToken<'a>) {
+        match t {
+            CarriedToken::NewLine | CarriedToken::FormFeed => self.cursor.new_paragraph(),
+            CarriedToken::CarriageReturn => {}
+            _ => self.cursor.new_line(),
+        }
+        self.cursor.carriage_return();
 
-                State::Done(Some(Token::CarriageReturn))
-            }
+        self.current_token = State::Done(Some(t));
+    }
 
-            c => {
-                self.cursor.new_line();
-                self.cursor.carriage_return();
+    /// Measures the text between the current tab and the next tab or line break.
+    fn tab_segment_width(&self) -> u32 {
+        let mut width = 0;
+        let mut previous_char = None;
+        let mut cursor = self.cursor;
+        let mut lookahead = self.parser.clone();
 
-                State::Done(Some(c))
+        loop {
+            match lookahead.next() {
+                Some(Token::Word(w)) => {
+                    width += self.str_width(&cursor, w);
+                    previous_char = w.chars().last();
+                }
+                Some(Token::Backspace) => {
+                    width -= overstrike_width(&cursor, previous_char.take());
+                }
+                Some(Token::Whitespace(n)) => width += self.config.peek_next_width(n),
+                Some(Token::SizedSpace(space)) => width += space.width(F::CHARACTER_SIZE.height),
+                Some(Token::ExtraCharacter(c)) => width += cursor.char_width(c),
+                Some(Token::EscapeSequence(seq)) => {
+                    apply_font_change(&mut cursor, &seq);
+                    width = move_lookahead_cursor::<F>(&seq, width);
+                }
+                Some(Token::Break(_)) | Some(Token::UnknownEscape(_)) => {}
+                Some(Token::Tab)
+                | Some(Token::NewLine)
+                | Some(Token::CarriageReturn)
+                | Some(Token::FormFeed)
+                | Some(Token::VerticalTab)
+                | None => {
+                    break width;
+                }
             }
-        };
+        }
     }
 
     fn next_word_width(&mut self) -> Option<u32> {
         let mut width = None;
+        let mut previous_char = None;
+        let mut trailing_spacing = 0;
+        let mut cursor = self.cursor;
         let mut lookahead = self.parser.clone();
 
         'lookahead: loop {
             match lookahead.next() {
                 Some(Token::Word(w)) => {
-                    let w = F::str_width_nocr(w);
+                    previous_char = w.chars().last();
+                    trailing_spacing = previous_char.map_or(0, |c| cursor.trailing_spacing(c));
+                    let w = self.str_width(&cursor, w);
 
                     width = width.map_or(Some(w), |acc| Some(acc + w));
                 }
 
-                Some(Token::Break(Some(c))) => {
-                    let w = F::total_char_width(c);
-                    width = width.map_or(Some(w), |acc| Some(acc + w));
+                Some(Token::Backspace) if width.is_some() => {
+                    width = width.map(|w| w - overstrike_width(&cursor, previous_char.take()));
+                    trailing_spacing = 0;
+                }
+
+                Some(Token::Break(Some(_))) => {
+                    // the hyphen is only displayed if the line is wrapped before a word
+                    if let (Some(c), Some(Token::Word(_))) =
+                        (self.break_character, lookahead.peek())
+                    {
+                        trailing_spacing = cursor.trailing_spacing(c);
+                        let w = cursor.char_width(c);
+                        width = width.map_or(Some(w), |acc| Some(acc + w));
+                    }
                     break 'lookahead;
                 }
 
-                Some(Token::EscapeSequence(_)) => {}
+                Some(Token::EscapeSequence(seq)) => {
+                    apply_font_change(&mut cursor, &seq);
+                    width = width.map(|w| move_lookahead_cursor::<F>(&seq, w));
+                }
+                Some(Token::UnknownEscape(_)) => {}
 
                 _ => break 'lookahead,
             }
         }
 
-        width
+        // the letter spacing after the last character of the word doesn't need to fit
+        let width = width.map(|w| (w as i32 - trailing_spacing).max(0) as u32);
+
+        match width {
+            // hidden words don't take up space
+            Some(w)
+                if self.horizontal_overflow == HorizontalOverflow::Hide
+                    && w > self.cursor.line_width() =>
+            {
+                Some(0)
+            }
+            width => width,
+        }
+    }
+
+    /// Measures the word that starts with `w`, including the parts that are separated from it
+    /// by escape sequences.
+    ///
+    /// The letter spacing after the last character of the word is not included.
+    fn word_width(&self, w: &str) -> u32 {
+        let mut cursor = self.cursor;
+        let mut width = self.str_width(&cursor, w);
+        let mut previous_char = w.chars().last();
+        let mut trailing_spacing = previous_char.map_or(0, |c| cursor.trailing_spacing(c));
+        let mut lookahead = self.parser.clone();
+
+        loop {
+            match lookahead.next() {
+                Some(Token::Word(w)) => {
+                    width += self.str_width(&cursor, w);
+                    previous_char = w.chars().last();
+                    trailing_spacing = previous_char.map_or(0, |c| cursor.trailing_spacing(c));
+                }
+                Some(Token::Backspace) => {
+                    width -= overstrike_width(&cursor, previous_char.take());
+                    trailing_spacing = 0;
+                }
+                Some(Token::EscapeSequence(seq)) => {
+                    apply_font_change(&mut cursor, &seq);
+                    width = move_lookahead_cursor::<F>(&seq, width);
+                }
+                Some(Token::UnknownEscape(_)) => {}
+                _ => break (width as i32 - trailing_spacing).max(0) as u32,
+            }
+        }
     }
 
     fn count_widest_space_seq(&self, n: u32) -> u32 {
@@ -187,22 +588,291 @@ where
     }
 }
 
-impl<F, SP, A> Iterator for LineElementIterator<'_, F, SP, A>
+/// Measurements of a single line, used to compare line breaking options.
+struct LineMetrics {
+    /// Width of the printed characters, including the last one.
+    width: u32,
+
+    /// Number of whitespace gaps between words in the line.
+    word_gaps: u32,
+
+    /// Whether the line was wrapped, i.e. the paragraph continues in the next line.
+    wrapped: bool,
+}
+
+/// Returns `true` if `c` fits the line, but the line must not be wrapped between `c` and the next
+/// character of the word, which does not fit. The word is then wrapped before `c`.
+fn is_kept_with_next<F: Font>(
+    cursor: &Cursor<F>,
+    parser: &mut Parser<'_>,
+    c: char,
+    rest: &Chars<'_>,
+) -> bool {
+    let next = match rest.clone().next() {
+        Some(next) => Some(next),
+        None => match parser.peek() {
+            Some(Token::Word(w)) => w.chars().next(),
+            _ => None,
+        },
+    };
+
+    if let Some(next) = next {
+        cursor.fits_in_line(cursor.font_char_width(c))
+            && !cursor.fits_in_line(cursor.char_width(c) + cursor.font_char_width(next))
+            && !parser.may_break_between(Some(c), Some(next))
+    } else {
+        false
+    }
+}
+
+/// Returns `true` if the non-breaking space `c` fits the line together with the part of the word
+/// after it, up to the next non-breaking space. Returns `true` if `c` is not a non-breaking space.
+fn is_glued_word_fitting<F: Font + Copy>(
+    cursor: &Cursor<F>,
+    config: &JustifiedSpaceConfig<F>,
+    c: char,
+    rest: &str,
+) -> bool {
+    let space_width = if is_non_breaking_space(c) {
+        config.peek_next_width(1)
+    } else if c == SPEC_CHAR_NNBSP {
+        cursor.char_width(c)
+    } else {
+        return true;
+    };
+
+    let glued = rest
+        .split(|c| is_non_breaking_space(c) || c == SPEC_CHAR_NNBSP)
+        .next()
+        .unwrap_or("");
+    let width: u32 = glued.chars().map(|c| cursor.char_width(c)).sum();
+    // the letter spacing after the last character of the word doesn't need to fit
+    let trailing = glued
+        .chars()
+        .last()
+        .map_or(0, |c| cursor.trailing_spacing(c));
+
+    cursor.fits_in_line(((space_width + width) as i32 - trailing).max(0) as u32)
+}
+
+/// Returns the width that a backspace after `previous_char` moves the cursor back by.
+///
+/// A backspace that does not follow a character of the same word has no effect.
+fn overstrike_width<F: Font>(cursor: &Cursor<F>, previous_char: Option<char>) -> u32 {
+    previous_char.map_or(0, |c| cursor.char_width(c))
+}
+
+/// Switches the font of `cursor` if `seq` turns bold text on or off, to measure text ahead.
+fn apply_font_change<F: Font>(cursor: &mut Cursor<F>, seq: &AnsiSequence) {
+    if let AnsiSequence::SetGraphicsMode(vec) = seq {
+        if let Some(sgr) = try_parse_sgr(vec) {
+            cursor.apply_sgr(sgr);
+        }
+    }
+}
+
+/// Returns the width of the text measured ahead after `seq` moved the cursor.
+///
+/// Cursor movements inside a word change the space the word takes up in the line.
+fn move_lookahead_cursor<F: Font>(seq: &AnsiSequence, width: u32) -> u32 {
+    match *seq {
+        AnsiSequence::CursorForward(n) => width + n * F::total_char_width(' '),
+        AnsiSequence::CursorBackward(n) => width.saturating_sub(n * F::total_char_width(' ')),
+        _ => width,
+    }
+}
+
+/// Lays out a single line with greedy line breaking.
+///
+/// Returns the line metrics and the parser state at the start of the next line.
+fn measure_greedy_line<'a, F>(
+    parser: Parser<'a>,
+    carried_token: Option<CarriedToken<'a>>,
+    cursor: Cursor<F>,
+    config: JustifiedSpaceConfig<F>,
+    tab_size: TabSize<F>,
+    spaces: LineSpaces,
+    max_word_gaps: Option<u32>,
+) -> (LineMetrics, Parser<'a>, Option<CarriedToken<'a>>)
+where
+    F: Font + Copy,
+{
+    let mut iter = LineElementIterator::with_word_gap_limit(
+        parser,
+        cursor,
+        config,
+        carried_token,
+        tab_size,
+        spaces,
+        max_word_gaps,
+    );
+
+    let mut width = 0;
+    while let Some(element) = iter.next() {
+        if let RenderElement::PrintedCharacter(c) = element {
+            // the letter spacing after the last character is not displayed
+            let char_end =
+                iter.pos.x - iter.cursor.bounds.top_left.x + iter.cursor.font_char_width(c) as i32;
+            width = width.max(char_end as u32);
+        }
+    }
+
+    let carried = iter.remaining_token();
+    let wrapped = if let Some(CarriedToken::VerticalTab(_)) = carried {
+        false
+    } else {
+        carried.is_some()
+            && carried != Some(CarriedToken::NewLine)
+            && carried != Some(CarriedToken::CarriageReturn)
+            && carried != Some(CarriedToken::FormFeed)
+    };
+
+    let metrics = LineMetrics {
+        width,
+        word_gaps: iter.word_gaps,
+        wrapped,
+    };
+
+    (metrics, iter.parser, carried)
+}
+
+/// Decides whether the line should be wrapped one word earlier than the greedy algorithm would.
+///
+/// Returns the number of word gaps allowed in the line, or `None` if the line should be filled
+/// greedily.
+fn balanced_word_gaps<'a, F>(
+    parser: &Parser<'a>,
+    carried_token: &Option<CarriedToken<'a>>,
+    mut cursor: Cursor<F>,
+    config: JustifiedSpaceConfig<F>,
+    tab_size: TabSize<F>,
+    spaces: LineSpaces,
+) -> Option<u32>
+where
+    F: Font + Copy,
+{
+    // Measure from the start of the line, regardless of any alignment offset, so that the
+    // measuring and rendering passes come to the same decision.
+    cursor.carriage_return();
+
+    let line_width = cursor.line_width();
+    let cost = |line: &LineMetrics| {
+        if line.wrapped {
+            let leftover = u64::from(line_width.saturating_sub(line.width));
+            leftover * leftover
+        } else {
+            // the last line of a paragraph is allowed to be short
+            0
+        }
+    };
+
+    let (greedy, parser_next, carried_next) = measure_greedy_line(
+        parser.clone(),
+        *carried_token,
+        cursor,
+        config,
+        tab_size,
+        spaces,
+        None,
+    );
+
+    if !greedy.wrapped || greedy.word_gaps == 0 {
+        return None;
+    }
+
+    let (greedy_next, _, _) = measure_greedy_line(
+        parser_next,
+        carried_next,
+        cursor,
+        config,
+        tab_size,
+        spaces,
+        None,
+    );
+
+    if !greedy_next.wrapped {
+        // moving a word would not make the paragraph nicer, only longer
+        return None;
+    }
+
+    let max_word_gaps = greedy.word_gaps - 1;
+    let (early, parser_next, carried_next) = measure_greedy_line(
+        parser.clone(),
+        *carried_token,
+        cursor,
+        config,
+        tab_size,
+        spaces,
+        Some(max_word_gaps),
+    );
+    let (early_next, _, _) = measure_greedy_line(
+        parser_next,
+        carried_next,
+        cursor,
+        config,
+        tab_size,
+        spaces,
+        None,
+    );
+
+    if cost(&early) + cost(&early_next) < cost(&greedy) + cost(&greedy_next) {
+        Some(max_word_gaps)
+    } else {
+        None
+    }
+}
+
+impl<F> Iterator for LineElementIterator<'_, F>
 where
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
 {
     type Item = RenderElement;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        let element = self.next_element();
+
+        match element {
+            Some(RenderElement::PrintedCharacter(_)) => self.empty = false,
+            Some(RenderElement::Space(width, _)) if width > 0 && self.spaces.ending => {
+                self.empty = false
+            }
+            _ => {}
+        }
+
+        element
+    }
+}
+
+impl<F> LineElementIterator<'_, F>
+where
+    F: Font + Copy,
+{
+    fn next_element(&mut self) -> Option<RenderElement> {
+        self.printed_text_char = false;
+        self.tab = false;
+        self.overstruck = None;
         loop {
             self.pos = self.cursor.position;
             match self.current_token {
                 // No token being processed, get next one
                 State::ProcessToken(ref token) => {
                     let token = token.clone();
+
+                    // Escape sequences and backspaces may split a word into multiple tokens.
+                    // Anything else ends the word.
+                    if let Token::Word(_)
+                    | Token::Backspace
+                    | Token::EscapeSequence(_)
+                    | Token::UnknownEscape(_) = token
+                    {
+                    } else {
+                        self.continues_word = false;
+                        self.skip_word = false;
+                        self.previous_char = None;
+                        self.overstrike = None;
+                    }
+
                     match token {
                         Token::Whitespace(n) => {
                             // This mess decides if we want to render whitespace at all.
@@ -210,20 +880,32 @@ where
                             // and end of a line.
                             let mut would_wrap = false;
                             let render_whitespace = if self.first_word {
-                                if A::STARTING_SPACES {
+                                if self.spaces.starting {
                                     self.first_word = false;
                                 }
-                                A::STARTING_SPACES
+                                self.spaces.starting
                             } else if let Some(word_width) = self.next_word_width() {
                                 // Check if space + w fits in line, otherwise it's up to config
                                 let space_width = self.config.peek_next_width(n);
                                 let fits = self.cursor.fits_in_line(space_width + word_width);
 
+                                // The break strategy may decide to wrap before a word that fits.
+                                let fits = if fits && Some(self.word_gaps) == self.max_word_gaps {
+                                    self.break_early = true;
+                                    false
+                                } else {
+                                    fits
+                                };
+
+                                if fits {
+                                    self.word_gaps += 1;
+                                }
+
                                 would_wrap = !fits;
 
-                                A::ENDING_SPACES || fits
+                                self.spaces.ending || fits
                             } else {
-                                A::ENDING_SPACES
+                                self.spaces.ending
                             };
 
                             if render_whitespace {
@@ -240,7 +922,7 @@ where
                                         self.next_token();
                                     } else {
                                         // n > 0 only if not every space was rendered
-                                        self.finish(Token::Whitespace(carried));
+                                        self.finish(CarriedToken::Whitespace(carried));
                                     }
 
                                     break Some(RenderElement::Space(
@@ -251,7 +933,7 @@ where
                                     // there are spaces to render but none fit the line
                                     // eat one as a newline and stop
                                     if n > 1 {
-                                        self.finish(Token::Whitespace(n - 1));
+                                        self.finish(CarriedToken::Whitespace(n - 1));
                                     } else {
                                         self.finish_wrapped();
                                     }
@@ -264,7 +946,51 @@ where
                             }
                         }
 
+                        Token::SizedSpace(space) => {
+                            let width = space.width(F::CHARACTER_SIZE.height);
+
+                            // Sized spaces are skipped at the start and end of lines like other
+                            // whitespace, but they are never stretched.
+                            let (render_space, would_wrap) = if self.first_word {
+                                if self.spaces.starting {
+                                    self.first_word = false;
+                                }
+                                (self.spaces.starting, false)
+                            } else if let Some(word_width) = self.next_word_width() {
+                                let fits = self.cursor.fits_in_line(width + word_width);
+                                (fits || self.spaces.ending, !fits)
+                            } else {
+                                (self.spaces.ending, false)
+                            };
+
+                            if render_space {
+                                let fits = self.cursor.advance(width);
+                                let width = if fits {
+                                    width
+                                } else {
+                                    // render the part of the space that fits the line
+                                    let space = self.cursor.space();
+                                    self.cursor.advance_unchecked(space);
+                                    space
+                                };
+
+                                if would_wrap || !fits {
+                                    self.finish_wrapped();
+                                } else {
+                                    self.next_token();
+                                }
+
+                                break Some(RenderElement::Space(width, 0));
+                            } else if would_wrap {
+                                self.finish_wrapped();
+                            } else {
+                                self.next_token();
+                            }
+                        }
+
                         Token::Break(c) => {
+                            // soft hyphens are displayed using the configured character
+                            let c = c.and(self.break_character);
                             let fits = if let Some(word_width) = self.next_word_width() {
                                 self.cursor.fits_in_line(word_width)
                             } else {
@@ -277,12 +1003,14 @@ where
                             } else if let Some(c) = c {
                                 // If a Break contains a character, display it if the next
                                 // Word token does not fit the line.
-                                if self.cursor.advance(F::total_char_width(c)) {
+                                if self.cursor.advance_char(c) {
                                     self.finish_wrapped();
                                     break Some(RenderElement::PrintedCharacter(c));
                                 } else {
-                                    // this line is done
-                                    self.finish(Token::ExtraCharacter(c));
+                                    // The word was already wrapped at a character boundary,
+                                    // because it doesn't have a break point that fits the line.
+                                    // Don't carry the hyphen over to the start of the next line.
+                                    self.finish_wrapped();
                                 }
                             } else {
                                 // this line is done
@@ -291,7 +1019,7 @@ where
                         }
 
                         Token::ExtraCharacter(c) => {
-                            if self.cursor.advance(F::total_char_width(c)) {
+                            if self.cursor.advance_char(c) {
                                 self.next_token();
                                 break Some(RenderElement::PrintedCharacter(c));
                             }
@@ -302,22 +1030,54 @@ where
                         }
 
                         Token::Word(w) => {
+                            if !self.continues_word {
+                                self.word_overflows = self.horizontal_overflow
+                                    != HorizontalOverflow::Wrap
+                                    && self.word_width(w) > self.cursor.line_width();
+                                self.skip_word = self.word_overflows
+                                    && self.horizontal_overflow == HorizontalOverflow::Hide;
+                            }
+
                             // FIXME: this isn't exactly optimal when outside of the display area
-                            if self.first_word {
+                            if self.skip_word {
+                                // The word, or the rest of it, is not displayed.
+                                self.continues_word = true;
+                                self.next_token();
+                            } else if self.first_word || self.continues_word {
+                                // The rest of a word that was interrupted by an escape sequence
+                                // was already measured with the first part.
                                 self.first_word = false;
                                 self.current_token = State::Word(w.chars());
-                            } else if self.cursor.fits_in_line(F::str_width_nocr(w)) {
+                            } else if !self.break_early
+                                && self.cursor.fits_in_line(self.word_width(w))
+                            {
                                 self.current_token = State::Word(w.chars());
                             } else {
-                                self.finish(token);
+                                self.finish(CarriedToken::Word(w));
                             }
                         }
 
                         Token::Tab => {
-                            let sp_width = self.tab_size.next_width(self.cursor.x_in_line());
+                            // Tab stops are relative to the start of the line, so that the
+                            // alignment offset does not change the width of tabs.
+                            let pos = self.cursor.position.x - self.line_start;
+                            let sp_width = match self.tab_size.next_stop(pos) {
+                                Some((stop, TabAlignment::Left)) => (stop - pos) as u32,
+                                Some((stop, TabAlignment::Right)) => {
+                                    let text_width = self.tab_segment_width() as i32;
+                                    (stop - pos - text_width).max(0) as u32
+                                }
+                                None => self.tab_size.next_width(pos),
+                            };
                             let tab_width = if self.cursor.advance(sp_width) {
                                 self.next_token();
                                 sp_width
+                            } else if self.is_pushed_out(sp_width) {
+                                // The tab is at the end of the line, where it is not rendered
+                                // and not measured.
+                                self.drop_space(sp_width);
+                                self.next_token();
+                                0
                             } else {
                                 // If we can't render the whole tab since we don't fit in the line,
                                 // render it using all the available space - it will be < tab size.
@@ -327,6 +1087,7 @@ where
                             };
 
                             // don't count tabs as spaces
+                            self.tab = true;
                             break Some(RenderElement::Space(tab_width, 0));
                         }
 
@@ -334,7 +1095,8 @@ where
                             self.next_token();
                             match seq {
                                 AnsiSequence::SetGraphicsMode(vec) => {
-                                    if let Some(sgr) = try_parse_sgr(vec.as_slice()) {
+                                    if let Some(sgr) = try_parse_sgr(&vec) {
+                                        self.cursor.apply_sgr(sgr);
                                         break Some(RenderElement::Sgr(sgr));
                                     }
                                 }
@@ -365,9 +1127,43 @@ where
                             }
                         }
 
-                        Token::NewLine | Token::CarriageReturn => {
+                        Token::UnknownEscape(_) => {
+                            self.next_token();
+                        }
+
+                        Token::Backspace => {
+                            self.next_token();
+
+                            // the next character is drawn in the cell of the previous one
+                            if let Some(c) = self.previous_char.take() {
+                                if self.cursor.rewind(self.cursor.char_width(c)) {
+                                    self.overstrike = Some(c);
+                                }
+                            }
+                        }
+
+                        Token::NewLine => {
+                            // we're done
+                            self.finish(CarriedToken::NewLine);
+                        }
+
+                        Token::CarriageReturn => {
                             // we're done
-                            self.finish(token);
+                            self.finish(CarriedToken::CarriageReturn);
+                        }
+
+                        Token::FormFeed => {
+                            if self.cursor.position.x > self.line_start {
+                                // end this line, the next one is the empty line of the form feed
+                                self.finish(CarriedToken::FormFeed);
+                            } else {
+                                self.finish(CarriedToken::NewLine);
+                            }
+                        }
+
+                        Token::VerticalTab => {
+                            let indent = (self.cursor.position.x - self.line_start) as u32;
+                            self.finish(CarriedToken::VerticalTab(indent));
                         }
                     }
                 }
@@ -380,7 +1176,31 @@ where
                             let mut ret_val = None;
                             let pos = self.cursor.position;
 
-                            if c == SPEC_CHAR_NBSP {
+                            // the parser may move to the next part of the text while the
+                            // character is processed
+                            let char_offset = self.parser.offset_of(word);
+
+                            self.previous_char = None;
+                            if self.word_overflows
+                                && self.truncate_at_word_boundary
+                                && self.horizontal_overflow == HorizontalOverflow::Clip
+                                && !is_glued_word_fitting(
+                                    &self.cursor,
+                                    &self.config,
+                                    c,
+                                    chars.as_str(),
+                                )
+                            {
+                                // Cut off the word at the non-breaking space, the part of the
+                                // word after it doesn't fit and is not displayed.
+                                self.skip_word = true;
+                                self.continues_word = true;
+                                self.next_token();
+                                self.cursor.advance_unchecked(self.cursor.space());
+                                continue;
+                            }
+
+                            if is_non_breaking_space(c) {
                                 // nbsp
                                 let sp_width = self.config.peek_next_width(1);
 
@@ -388,8 +1208,32 @@ where
                                     ret_val = Some(RenderElement::Space(sp_width, 1));
                                     self.config.consume(1); // we have peeked the value, consume it
                                 }
-                            } else if self.cursor.advance(F::total_char_width(c)) {
-                                ret_val = Some(RenderElement::PrintedCharacter(c));
+                            } else if c == SPEC_CHAR_NNBSP {
+                                // narrow spaces are not stretched
+                                let sp_width = self.cursor.char_width(c);
+
+                                if self.cursor.advance(sp_width) {
+                                    ret_val = Some(RenderElement::Space(sp_width, 0));
+                                }
+                            } else if (self.word_overflows
+                                || self.cursor.position.x <= self.line_start
+                                || !is_kept_with_next(&self.cursor, &mut self.parser, c, chars))
+                                && self.cursor.advance_char(c)
+                            {
+                                self.char_offset = char_offset;
+                                self.overstruck = self.overstrike.take();
+                                self.previous_char = Some(c);
+
+                                ret_val = if c == '_'
+                                    && chars.as_str().is_empty()
+                                    && self.parser.peek() == Some(&Token::Backspace)
+                                {
+                                    // the next character is underlined instead
+                                    Some(RenderElement::Space(self.cursor.char_width(c), 0))
+                                } else {
+                                    self.printed_text_char = true;
+                                    Some(RenderElement::PrintedCharacter(c))
+                                };
                             }
 
                             if ret_val.is_some() {
@@ -398,19 +1242,47 @@ where
                                 self.current_token = State::Word(chars.clone());
 
                                 break ret_val;
-                            } else if self.cursor.x_in_line() > 0 {
+                            } else if self.word_overflows
+                                && self.horizontal_overflow == HorizontalOverflow::Clip
+                            {
+                                // Cut off the word at the edge of the line. The character that
+                                // doesn't fit is drawn partially, the rest of the word is skipped.
+                                self.skip_word = true;
+                                self.continues_word = true;
+                                self.next_token();
+
+                                if !is_non_breaking_space(c) && c != SPEC_CHAR_NNBSP {
+                                    self.pos = pos;
+                                    self.char_offset = char_offset;
+                                    self.printed_text_char = true;
+                                    self.cursor.advance_unchecked(self.cursor.space());
+                                    break Some(RenderElement::PrintedCharacter(c));
+                                }
+                            } else if let Some(width) = self
+                                .no_break_space_width(c)
+                                .filter(|&width| self.is_no_break_space_dropped(width))
+                            {
+                                // The space is at the end of the line, where it is not rendered
+                                // and not measured. Drop it, the rest of the word is wrapped.
+                                self.drop_space(width);
+                            } else if self.cursor.position.x > self.line_start || self.space_dropped
+                            {
                                 // There's already something in this line, let's carry the whole
                                 // word (the part that wasn't consumed so far) to the next.
                                 // This can happen because words can be longer than the line itself.
-                                self.finish(Token::Word(word));
+                                self.finish(CarriedToken::Word(word));
                             } else {
-                                // Weird case where width doesn't permit drawing anything. Consume
-                                // token to avoid infinite loop.
-                                self.finish_end_of_string();
+                                // Weird case where width doesn't permit drawing anything. The line
+                                // may not start at the left edge, because alignments offset the
+                                // cursor by the free space.
+                                self.finish_too_narrow();
                             }
                         }
 
-                        None => self.next_token(),
+                        None => {
+                            self.continues_word = true;
+                            self.next_token();
+                        }
                     }
                 }
 
@@ -423,7 +1295,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{alignment::LeftAligned, style::color::Rgb};
+    use crate::{alignment::LeftAligned, style::color::AnsiColor};
     use embedded_graphics::fonts::Font6x8;
     use embedded_graphics::primitives::Rectangle;
 
@@ -432,10 +1304,22 @@ mod test {
         let parser = Parser::parse("sam\u{00AD}ple");
         let config: UniformSpaceConfig<Font6x8> = UniformSpaceConfig::default();
 
-        let cursor = Cursor::new(Rectangle::new(Point::zero(), Point::new(6 * 6 - 1, 8)), 0);
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(6 * 6 - 1, 8)),
+            0,
+            0,
+            0,
+        );
 
-        let iter: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let iter: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             iter.collect::<Vec<RenderElement>>(),
@@ -462,10 +1346,22 @@ mod test {
         let parser = Parser::parse("sam\u{00AD}ple");
         let config: UniformSpaceConfig<Font6x8> = UniformSpaceConfig::default();
 
-        let cursor = Cursor::new(Rectangle::new(Point::zero(), Point::new(6 * 6 - 2, 16)), 0);
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(6 * 6 - 2, 16)),
+            0,
+            0,
+            0,
+        );
 
-        let mut line1: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             collect_mut(&mut line1),
@@ -480,12 +1376,14 @@ mod test {
         assert_eq!(line1.cursor.position, Point::new(0, 8));
 
         let carried = line1.remaining_token();
-        let line2: LineElementIterator<'_, _, _, LeftAligned> = LineElementIterator::new(
+        let line2: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
             line1.parser,
             line1.cursor,
             config,
             carried,
             TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
         );
 
         assert_eq!(
@@ -504,10 +1402,22 @@ mod test {
             Parser::parse("super\u{AD}cali\u{AD}fragi\u{AD}listic\u{AD}espeali\u{AD}docious");
         let config: UniformSpaceConfig<Font6x8> = UniformSpaceConfig::default();
 
-        let cursor = Cursor::new(Rectangle::new(Point::zero(), Point::new(5 * 6 - 1, 16)), 0);
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(5 * 6 - 1, 16)),
+            0,
+            0,
+            0,
+        );
 
-        let mut line1: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             collect_mut(&mut line1),
@@ -523,22 +1433,24 @@ mod test {
         assert_eq!(line1.cursor.position, Point::new(0, 8));
 
         let carried = line1.remaining_token();
-        let line2: LineElementIterator<'_, _, _, LeftAligned> = LineElementIterator::new(
+        let line2: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
             line1.parser,
             line1.cursor,
             config,
             carried,
             TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
         );
 
         assert_eq!(
             line2.collect::<Vec<RenderElement>>(),
             vec![
-                RenderElement::PrintedCharacter('-'),
                 RenderElement::PrintedCharacter('c'),
                 RenderElement::PrintedCharacter('a'),
                 RenderElement::PrintedCharacter('l'),
                 RenderElement::PrintedCharacter('i'),
+                RenderElement::PrintedCharacter('-'),
             ]
         );
     }
@@ -555,10 +1467,19 @@ mod test {
                 Point::new(text.chars().count() as i32 * 6 - 1, 16),
             ),
             0,
+            0,
+            0,
         );
 
-        let mut line1: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             collect_mut(&mut line1),
@@ -584,10 +1505,22 @@ mod test {
         let parser = Parser::parse(text);
         let config: UniformSpaceConfig<Font6x8> = UniformSpaceConfig::default();
 
-        let cursor = Cursor::new(Rectangle::new(Point::zero(), Point::new(16 * 6 - 1, 16)), 0);
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(16 * 6 - 1, 16)),
+            0,
+            0,
+            0,
+        );
 
-        let mut line1: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             collect_mut(&mut line1),
@@ -602,12 +1535,14 @@ mod test {
         );
 
         let carried = line1.remaining_token();
-        let mut line2: LineElementIterator<'_, _, _, LeftAligned> = LineElementIterator::new(
+        let mut line2: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
             line1.parser,
             line1.cursor,
             config,
             carried,
             TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
         );
 
         assert_eq!(
@@ -630,6 +1565,68 @@ mod test {
         );
     }
 
+    #[test]
+    fn aligned_tab_stops() {
+        let text = "a\tb\t1\na\tb\t234";
+        let parser = Parser::parse(text);
+        let config: UniformSpaceConfig<Font6x8> = UniformSpaceConfig::default();
+        let tab_size =
+            TabSize::default().with_stops(&[(18, TabAlignment::Left), (48, TabAlignment::Right)]);
+
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(16 * 6 - 1, 16)),
+            0,
+            0,
+            0,
+        );
+
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            tab_size,
+            BreakStrategy::default(),
+            LeftAligned,
+        );
+
+        assert_eq!(
+            collect_mut(&mut line1),
+            vec![
+                RenderElement::PrintedCharacter('a'),
+                RenderElement::Space(12, 0),
+                RenderElement::PrintedCharacter('b'),
+                RenderElement::Space(18, 0),
+                RenderElement::PrintedCharacter('1'),
+            ]
+        );
+
+        let carried = line1.remaining_token();
+        let mut line2: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            line1.parser,
+            line1.cursor,
+            config,
+            carried,
+            tab_size,
+            BreakStrategy::default(),
+            LeftAligned,
+        );
+
+        // the last digits of the numbers are aligned
+        assert_eq!(
+            collect_mut(&mut line2),
+            vec![
+                RenderElement::PrintedCharacter('a'),
+                RenderElement::Space(12, 0),
+                RenderElement::PrintedCharacter('b'),
+                RenderElement::Space(6, 0),
+                RenderElement::PrintedCharacter('2'),
+                RenderElement::PrintedCharacter('3'),
+                RenderElement::PrintedCharacter('4'),
+            ]
+        );
+    }
+
     #[test]
     fn colors() {
         let text = "Lorem \x1b[92mIpsum";
@@ -639,10 +1636,19 @@ mod test {
         let cursor = Cursor::new(
             Rectangle::new(Point::zero(), Point::new(100 * 6 - 1, 16)),
             0,
+            0,
+            0,
         );
 
-        let mut line1: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             collect_mut(&mut line1),
@@ -653,7 +1659,7 @@ mod test {
                 RenderElement::PrintedCharacter('e'),
                 RenderElement::PrintedCharacter('m'),
                 RenderElement::Space(6, 1),
-                RenderElement::Sgr(Sgr::ChangeTextColor(Rgb::new(22, 198, 12))),
+                RenderElement::Sgr(Sgr::ChangeTextColor(AnsiColor::Indexed(10))),
                 RenderElement::PrintedCharacter('I'),
                 RenderElement::PrintedCharacter('p'),
                 RenderElement::PrintedCharacter('s'),
@@ -669,10 +1675,22 @@ mod test {
         let parser = Parser::parse(text);
         let config: UniformSpaceConfig<Font6x8> = UniformSpaceConfig::default();
 
-        let cursor = Cursor::new(Rectangle::new(Point::zero(), Point::new(8 * 6 - 1, 16)), 0);
+        let cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(8 * 6 - 1, 16)),
+            0,
+            0,
+            0,
+        );
 
-        let mut line1: LineElementIterator<'_, _, _, LeftAligned> =
-            LineElementIterator::new(parser, cursor, config, None, TabSize::default());
+        let mut line1: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+            parser,
+            cursor,
+            config,
+            None,
+            TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
+        );
 
         assert_eq!(
             collect_mut(&mut line1),
@@ -686,12 +1704,14 @@ mod test {
         );
 
         let carried = line1.remaining_token();
-        let mut line2: LineElementIterator<'_, _, _, LeftAligned> = LineElementIterator::new(
+        let mut line2: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
             line1.parser,
             line1.cursor,
             config,
             carried,
             TabSize::default(),
+            BreakStrategy::default(),
+            LeftAligned,
         );
 
         assert_eq!(
@@ -700,7 +1720,7 @@ mod test {
                 RenderElement::PrintedCharacter('f'),
                 RenderElement::PrintedCharacter('o'),
                 RenderElement::PrintedCharacter('o'),
-                RenderElement::Sgr(Sgr::ChangeTextColor(Rgb::new(22, 198, 12))),
+                RenderElement::Sgr(Sgr::ChangeTextColor(AnsiColor::Indexed(10))),
                 RenderElement::PrintedCharacter('b'),
                 RenderElement::PrintedCharacter('a'),
                 RenderElement::PrintedCharacter('r'),
@@ -709,4 +1729,136 @@ mod test {
             ]
         );
     }
+
+    fn wrap_lines(text: &str, columns: i32, break_strategy: BreakStrategy) -> Vec<String> {
+        let mut parser = Parser::parse(text);
+        let mut carried = None;
+        let mut cursor = Cursor::new(
+            Rectangle::new(Point::zero(), Point::new(columns * 6 - 1, 200)),
+            0,
+            0,
+            0,
+        );
+        let mut lines = Vec::new();
+
+        loop {
+            let mut line: LineElementIterator<'_, Font6x8> = LineElementIterator::new(
+                parser.clone(),
+                cursor,
+                UniformSpaceConfig::default(),
+                carried.clone(),
+                TabSize::default(),
+                break_strategy,
+                LeftAligned,
+            );
+
+            let mut text = String::new();
+            while let Some(element) = line.next() {
+                match element {
+                    RenderElement::PrintedCharacter(c) => text.push(c),
+                    RenderElement::Space(_, n) => text.extend((0..n).map(|_| ' ')),
+                    _ => {}
+                }
+            }
+            lines.push(text.trim_end().to_owned());
+
+            carried = line.remaining_token();
+            parser = line.parser;
+            cursor = line.cursor;
+
+            if carried.is_none() && parser.is_empty() {
+                break lines;
+            }
+        }
+    }
+
+    #[test]
+    fn balanced_break_strategy() {
+        let text = "the big fox is unstoppable now.";
+
+        // Greedy line breaking leaves the second line almost empty
+        assert_eq!(
+            wrap_lines(text, 12, BreakStrategy::Greedy),
+            vec!["the big fox", "is", "unstoppable", "now."]
+        );
+        assert_eq!(
+            wrap_lines(text, 12, BreakStrategy::Balanced),
+            vec!["the big", "fox is", "unstoppable", "now."]
+        );
+    }
+
+    #[test]
+    fn balanced_break_strategy_keeps_short_last_line() {
+        let text = "the big fox is";
+
+        assert_eq!(
+            wrap_lines(text, 12, BreakStrategy::Greedy),
+            vec!["the big fox", "is"]
+        );
+        assert_eq!(
+            wrap_lines(text, 12, BreakStrategy::Balanced),
+            vec!["the big fox", "is"]
+        );
+    }
+
+    #[test]
+    fn escape_sequences_do_not_affect_wrapping() {
+        for &(text, plain) in &[
+            ("\x1b[92mword wrap", "word wrap"),
+            ("word \x1b[92mwrap", "word wrap"),
+            ("word\x1b[92m wrap", "word wrap"),
+            ("word\x1b[92m\nwrap", "word\nwrap"),
+            ("wordwo\x1b[92mrdword", "wordwordword"),
+            ("ab wor\x1b[92md", "ab word"),
+            ("\x1b[2Jword \x1b[1;2Hwrap", "word wrap"),
+            ("wordwo\x1b[6nrdword", "wordwordword"),
+        ] {
+            assert_eq!(
+                wrap_lines(plain, 4, BreakStrategy::Greedy),
+                wrap_lines(text, 4, BreakStrategy::Greedy),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn long_words_are_wrapped_at_soft_hyphens() {
+        assert_eq!(
+            wrap_lines("super\u{AD}californian", 8, BreakStrategy::Greedy),
+            vec!["super-", "californ", "ian"]
+        );
+        assert_eq!(
+            wrap_lines("ab\u{AD}cdefgh\u{AD}ijk", 8, BreakStrategy::Greedy),
+            vec!["ab-", "cdefgh-", "ijk"]
+        );
+
+        // no break point fits, fall back to wrapping at any character
+        assert_eq!(
+            wrap_lines("abcdefghijkl\u{AD}mn", 8, BreakStrategy::Greedy),
+            vec!["abcdefgh", "ijklmn"]
+        );
+        assert_eq!(
+            wrap_lines("abcdefgh\u{AD}ij", 8, BreakStrategy::Greedy),
+            vec!["abcdefgh", "ij"]
+        );
+    }
+
+    #[test]
+    fn escape_sequences_do_not_split_words() {
+        let text = "ab err:\x1b[31mFAIL\x1b[0m x";
+
+        for &break_strategy in &[BreakStrategy::Greedy, BreakStrategy::Balanced] {
+            // "err:" would fit in the first line if it was a separate word
+            for &columns in &[8, 9] {
+                assert_eq!(
+                    wrap_lines(text, columns, break_strategy),
+                    vec!["ab", "err:FAIL", "x"],
+                    "{} columns, {:?}",
+                    columns,
+                    break_strategy
+                );
+            }
+        }
+    }
 }
@@ -1,39 +1,46 @@
 //! Pixel iterators used for text rendering.
 
 pub mod ansi;
+pub mod budget;
 pub mod character;
 pub mod cursor;
 pub mod line;
 pub mod line_iter;
 pub mod modified_whitespace;
 pub mod space_config;
+pub(crate) mod vertical;
 pub mod whitespace;
 
 use crate::{
     alignment::{HorizontalTextAlignment, VerticalTextAlignment},
     parser::{Parser, Token},
-    rendering::{cursor::Cursor, line::StyledLinePixelIterator, space_config::SpaceConfig},
-    style::{color::Rgb, height_mode::HeightMode, TextBoxStyle},
+    rendering::{
+        cursor::Cursor,
+        line::StyledLinePixelIterator,
+        line_iter::{line_start_offset, CarriedToken, RenderElement},
+        vertical::VerticalLayout,
+    },
+    style::{color::Rgb, height_mode::HeightMode, TextBoxStyle, TextOverflow},
+    utils::rect_ext::RectExt,
     StyledTextBox,
 };
-use embedded_graphics::prelude::*;
+use core::ops::Range;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
 
-/// State variable used by the right aligned text renderer.
-#[derive(Debug)]
-pub enum State<'a, C, F, SP, A, V, H>
+/// State variable used by the text box renderer.
+// The line iterator can't be boxed without an allocator, and the state isn't moved around.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum State<'a, C, F>
 where
     C: PixelColor,
     F: Font + Copy,
-    SP: SpaceConfig<Font = F>,
-    A: HorizontalTextAlignment,
-    V: VerticalTextAlignment,
-    H: HeightMode,
 {
     /// Starts processing a line.
-    NextLine(Option<Token<'a>>, Cursor<F>, Parser<'a>),
+    NextLine(Option<CarriedToken<'a>>, Cursor<F>, Parser<'a>),
 
     /// Renders the processed line.
-    DrawLine(StyledLinePixelIterator<'a, C, F, SP, A, V, H>),
+    DrawLine(StyledLinePixelIterator<'a, C, F>),
 }
 
 /// This trait is used to associate a renderer type to a horizontal alignment option.
@@ -47,37 +54,105 @@ pub trait RendererFactory<'a, C: PixelColor> {
     fn create_renderer(&self) -> Self::Renderer;
 }
 
-type LineIteratorSource<'a, C, F, A, V, H, SP> =
-    fn(
-        TextBoxStyle<C, F, A, V, H>,
-        Option<Token<'a>>,
-        Cursor<F>,
-        Parser<'a>,
-    ) -> StyledLinePixelIterator<'a, C, F, SP, A, V, H>;
+/// The pixel iterator type used to render a [`StyledTextBox`].
+///
+/// This is a shorthand to name the renderer type returned by [`RendererFactory::create_renderer`],
+/// for example to store a partially drawn text box in a struct.
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+/// [`RendererFactory::create_renderer`]: trait.RendererFactory.html#tymethod.create_renderer
+pub type TextBoxRenderer<'a, C, F, A, V, H> =
+    <StyledTextBox<'a, C, F, A, V, H> as RendererFactory<'a, C>>::Renderer;
+
+/// Information about a rendered [`StyledTextBox`].
+///
+/// See [`StyledTextBox::draw_with_result`] for more information.
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+/// [`StyledTextBox::draw_with_result`]: ../struct.StyledTextBox.html#method.draw_with_result
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RenderResult {
+    /// The position where the next character would be drawn.
+    ///
+    /// This is the position after the last character of the text, or the start of the next line if
+    /// the text ends with a line break.
+    pub next_position: Point,
+
+    /// The number of lines the text was laid out in.
+    ///
+    /// Lines that are overwritten because of a carriage return are not counted separately. An
+    /// empty line at the end of the text is not counted, the same way it's not measured by
+    /// [`TextBoxStyle::measure_line_count`].
+    ///
+    /// [`TextBoxStyle::measure_line_count`]: ../style/struct.TextBoxStyle.html#method.measure_line_count
+    pub lines: u32,
+
+    /// The number of bytes of the text that were laid out in lines that are completely inside the
+    /// text box.
+    pub consumed_bytes: usize,
+}
+
+type LineIteratorSource<'a, C, F, A, V, H> = fn(
+    TextBoxStyle<C, F, A, V, H>,
+    Option<CarriedToken<'a>>,
+    Cursor<F>,
+    Parser<'a>,
+) -> StyledLinePixelIterator<'a, C, F>;
 
 /// Pixel iterator for styled text.
-pub struct StyledTextBoxIterator<'a, C, F, A, V, H, SP>
+///
+/// The iterator stores a copy of the style and only borrows the text, so it can be kept around
+/// while the text box is partially drawn. This makes it possible to suspend rendering, for example
+/// to interleave it with other work, and resume it later by continuing to pull pixels from the
+/// iterator. Cloning the iterator creates a snapshot of the rendering state.
+#[derive(Clone, Debug)]
+pub struct StyledTextBoxIterator<'a, C, F, A, V, H>
 where
     C: PixelColor,
     F: Font + Copy,
     A: HorizontalTextAlignment,
     V: VerticalTextAlignment,
     H: HeightMode,
-    SP: SpaceConfig<Font = F>,
 {
     style: TextBoxStyle<C, F, A, V, H>,
-    state: State<'a, C, F, SP, A, V, H>,
-    next_line_fn: LineIteratorSource<'a, C, F, A, V, H, SP>,
+
+    /// The style of the text box, which ANSI reset sequences return to.
+    default_style: TextBoxStyle<C, F, A, V, H>,
+
+    state: State<'a, C, F>,
+    next_line_fn: LineIteratorSource<'a, C, F, A, V, H>,
+    color_spans: &'a [(Range<usize>, C)],
+    alignment_spans: &'a [(Range<usize>, A)],
+    line_visible: bool,
+    lines: u32,
+
+    /// Whether the current line is counted in `lines`.
+    line_counted: bool,
+    consumed_bytes: usize,
+    clip: Option<Rectangle>,
+    line_rows: Option<Rectangle>,
+
+    /// The bounding box of the text box, which the laid out text is rotated into.
+    bounds: Rectangle,
+
+    /// The columns that vertical text is drawn in.
+    vertical: Option<VerticalLayout>,
+
+    /// The starting byte offset of the line that ends with the ellipsis.
+    ellipsis_line: Option<usize>,
+
+    /// The byte offset that the text is cut off at by the ellipsis, once the line with the
+    /// ellipsis is started.
+    ellipsis_cut: Option<usize>,
 }
 
-impl<'a, C, F, A, V, H, SP> StyledTextBoxIterator<'a, C, F, A, V, H, SP>
+impl<'a, C, F, A, V, H> StyledTextBoxIterator<'a, C, F, A, V, H>
 where
     C: PixelColor,
     F: Font + Copy,
     A: HorizontalTextAlignment,
     V: VerticalTextAlignment,
     H: HeightMode,
-    SP: SpaceConfig<Font = F>,
 {
     /// Creates a new pixel iterator to render the styled [`TextBox`].
     ///
@@ -86,62 +161,2556 @@ where
     #[must_use]
     pub fn new(
         styled: &StyledTextBox<'a, C, F, A, V, H>,
-        f: LineIteratorSource<'a, C, F, A, V, H, SP>,
-    ) -> Self {
-        let mut cursor = Cursor::new(styled.text_box.bounds, styled.style.line_spacing);
+        f: LineIteratorSource<'a, C, F, A, V, H>,
+    ) -> Self
+    where
+        C: From<Rgb>,
+    {
+        // vertical text is laid out in horizontal lines, which are drawn as columns
+        let vertical = VerticalLayout::new(styled);
+        let layout_text_box = vertical.map(|vertical| vertical.layout_text_box(styled));
+        let styled = layout_text_box.as_ref().unwrap_or(styled);
+
+        let mut cursor = Cursor::new(
+            styled.layout_area(),
+            styled.style.line_spacing,
+            styled.style.letter_spacing,
+            styled.style.paragraph_spacing,
+        )
+        .with_bold_font(styled.style.bold_font, styled.style.bold);
 
         V::apply_vertical_alignment(&mut cursor, &styled);
+        cursor.position.y += styled.vertical_offset;
 
-        Self {
+        // shifted text must not be drawn outside of the text box
+        let clip = if styled.vertical_offset != 0 {
+            Some(styled.layout_bounds())
+        } else {
+            None
+        };
+
+        let renderer = Self {
             style: styled.style,
-            state: State::NextLine(None, cursor, Parser::parse(styled.text_box.text)),
+            default_style: styled.style,
+            state: State::NextLine(
+                None,
+                cursor,
+                styled.style.line_breaking(styled.text_box.parser()),
+            ),
             next_line_fn: f,
+            color_spans: styled.color_spans,
+            alignment_spans: styled.alignment_spans,
+            line_visible: false,
+            lines: 0,
+            line_counted: false,
+            consumed_bytes: 0,
+            clip,
+            line_rows: None,
+            bounds: styled.text_box.bounds,
+            vertical,
+            ellipsis_line: None,
+            ellipsis_cut: None,
+        };
+
+        match styled.style.text_overflow {
+            TextOverflow::Clip => renderer,
+            TextOverflow::Ellipsis => Self {
+                ellipsis_line: renderer.find_ellipsis_line(),
+                ..renderer
+            },
+        }
+    }
+
+    /// Returns the starting byte offset of the last line that is displayed, if the text continues
+    /// in a line that is not displayed.
+    fn find_ellipsis_line(&self) -> Option<usize>
+    where
+        C: From<Rgb>,
+    {
+        let mut renderer = self.clone();
+        let mut last_displayed = None;
+
+        while let State::NextLine(carried, cursor, ref parser) = renderer.state {
+            if carried.is_none() && parser.is_empty() {
+                break;
+            }
+
+            let mut glyph_cursor = cursor;
+            glyph_cursor.position.y += renderer.style.glyph_offset();
+            if glyph_cursor.in_display_area() {
+                last_displayed = Some(line_start_offset(carried, parser));
+            } else if last_displayed.is_some() {
+                // line breaks and whitespace at the end of the text don't need an ellipsis
+                let continues = match carried {
+                    Some(CarriedToken::Word(_)) => true,
+                    _ => parser.clone().any(|token| match token {
+                        Token::Word(word) => !word.is_empty(),
+                        Token::ExtraCharacter(_) => true,
+                        _ => false,
+                    }),
+                };
+
+                return if continues { last_displayed } else { None };
+            }
+
+            renderer.skip_line();
+        }
+
+        None
+    }
+
+    /// Returns a parser that lays out as much of the line as fits into it together with the
+    /// ellipsis, followed by the ellipsis, and the byte offset the text is cut off at.
+    ///
+    /// The line is laid out once, and the text is cut off after the last printed character that
+    /// leaves enough room for the ellipsis. If the style truncates text at word boundaries, the
+    /// text is cut off after the last word that fits instead, if there is one.
+    fn truncate_line(
+        &self,
+        carried: Option<CarriedToken<'a>>,
+        cursor: &Cursor<F>,
+        parser: &Parser<'a>,
+    ) -> Option<(Option<CarriedToken<'a>>, Parser<'a>, usize)> {
+        let ellipsis = self.style.ellipsis;
+        let fits = |cursor: &Cursor<F>, x: i32| {
+            // the letter spacing after the ellipsis doesn't need to fit into the line
+            let trailing = ellipsis
+                .chars()
+                .last()
+                .map_or(0, |c| cursor.trailing_spacing(c));
+            x + cursor.str_width(ellipsis) as i32 - trailing <= cursor.bounds.bottom_right.x
+        };
+
+        if !fits(cursor, cursor.position.x) {
+            return None;
+        }
+
+        // the text can only be cut off after a printed character
+        let start = line_start_offset(carried, parser);
+        let mut cut = start;
+        let mut word_cut = start;
+        let mut line = self
+            .style
+            .measuring_iterator(parser.clone(), carried, *cursor);
+        while let Some(element) = line.next() {
+            if let RenderElement::PrintedCharacter(c) = element {
+                if !line.printed_text_char {
+                    continue;
+                }
+
+                let end = line.pos.x + line.cursor.char_width(c) as i32;
+                if !fits(&line.cursor, end) {
+                    break;
+                }
+
+                cut = line.char_offset + c.len_utf8();
+                if parser.is_word_end(cut) {
+                    word_cut = cut;
+                }
+            }
+        }
+
+        if self.style.truncate_at_word_boundary && word_cut > start {
+            cut = word_cut;
+        }
+
+        // the truncated parser starts at the beginning of the carried word
+        let carried = match carried {
+            Some(CarriedToken::Word(_)) => Some(CarriedToken::Wrapped),
+            carried => carried,
+        };
+
+        parser
+            .truncated(start, cut, ellipsis)
+            .map(|truncated| (carried, truncated, cut))
+    }
+
+    /// Only renders the pixels inside `clip`.
+    ///
+    /// Lines that don't intersect `clip` are laid out, but no pixels are generated for them.
+    #[inline]
+    #[must_use]
+    pub(crate) fn clipped(self, clip: Rectangle) -> Self {
+        if let Some(vertical) = self.vertical {
+            // columns can't be clipped in the layout
+            return Self {
+                vertical: Some(vertical.clipped(clip)),
+                ..self
+            };
+        }
+
+        let clip = self.style.orientation.rect_to_layout(clip, self.bounds);
+
+        Self {
+            clip: Some(self.clip.map_or(clip, |current| {
+                // nothing is drawn if the clipping areas don't overlap
+                current
+                    .intersection(clip)
+                    .unwrap_or_else(|| Rectangle::from_size(clip.top_left, Size::zero()))
+            })),
+            ..self
+        }
+    }
+
+    /// Replaces the palette slots of the style by the colors of `palette`.
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_palette(self, palette: &[C]) -> Self {
+        Self {
+            style: self.style.with_palette(palette),
+            default_style: self.default_style.with_palette(palette),
+            ..self
+        }
+    }
+
+    /// Returns information about the rendered text.
+    ///
+    /// The result is only complete after every pixel has been drawn.
+    #[inline]
+    #[must_use]
+    pub fn result(&self) -> RenderResult
+    where
+        C: From<Rgb>,
+    {
+        let next_position = match self.state {
+            State::NextLine(_, ref cursor, _) => cursor.position,
+            State::DrawLine(ref line_iterator) => line_iterator.cursor().position,
+        };
+
+        RenderResult {
+            next_position: self.point_to_display(next_position),
+            lines: self.lines,
+            consumed_bytes: self.consumed_bytes,
+        }
+    }
+
+    /// Lays out the text that is left to be drawn and returns the area it covers.
+    ///
+    /// If nothing is left to be drawn, the returned rectangle has zero size.
+    pub(crate) fn remaining_bounds(&self) -> Rectangle
+    where
+        C: From<Rgb>,
+    {
+        let mut renderer = self.clone();
+        let mut bounds: Option<Rectangle> = None;
+
+        while let Some(line_bounds) = renderer.skip_line() {
+            match line_bounds {
+                // empty lines don't cover anything
+                Some(line_bounds) if RectExt::size(line_bounds).width > 0 => {
+                    bounds = Some(bounds.map_or(line_bounds, |b| b.union(line_bounds)));
+                }
+                _ => {}
+            }
+        }
+
+        match bounds {
+            Some(bounds) => self.to_display(bounds),
+            None => Rectangle::from_size(renderer.result().next_position, Size::zero()),
+        }
+    }
+
+    /// Maps an area of the laid out text to the display.
+    fn to_display(&self, area: Rectangle) -> Rectangle {
+        match self.vertical {
+            Some(vertical) => vertical.rect_to_display(area),
+            None => self.style.orientation.rect_to_display(area, self.bounds),
+        }
+    }
+
+    /// Maps a point of the laid out text to the display.
+    fn point_to_display(&self, point: Point) -> Point {
+        match self.vertical {
+            Some(vertical) => vertical.to_display(point),
+            None => self.style.orientation.to_display(point, self.bounds),
+        }
+    }
+
+    /// Lays out the next line without drawing it.
+    ///
+    /// Returns `None` if there are no more lines. Otherwise, returns the area covered by the
+    /// displayed part of the line, or `None` if the line is not displayed.
+    fn skip_line(&mut self) -> Option<Option<Rectangle>>
+    where
+        C: From<Rgb>,
+    {
+        let mut line = match self.state {
+            State::NextLine(carried, cursor, ref parser) => {
+                if carried.is_none() && parser.is_empty() {
+                    return None;
+                }
+
+                self.start_line(carried, cursor, parser.clone())
+            }
+            State::DrawLine(ref line) => line.clone(),
+        };
+
+        let bounds = line.measure_bounds();
+        self.end_line(&line);
+
+        Some(bounds)
+    }
+
+    /// Creates the pixel iterator of the next line.
+    fn start_line(
+        &mut self,
+        carried: Option<CarriedToken<'a>>,
+        cursor: Cursor<F>,
+        mut parser: Parser<'a>,
+    ) -> StyledLinePixelIterator<'a, C, F>
+    where
+        C: From<Rgb>,
+    {
+        let mut cursor = cursor;
+        if let Some(indent) = self.style.line_indent(carried) {
+            cursor.indent_line(indent);
+        }
+
+        self.line_counted = carried != Some(CarriedToken::CarriageReturn);
+        if self.line_counted {
+            self.lines += 1;
+        }
+
+        let mut glyph_cursor = cursor;
+        glyph_cursor.position.y += self.style.glyph_offset();
+        self.line_visible = glyph_cursor.in_display_area();
+
+        let is_ellipsis_line = self.ellipsis_line == Some(line_start_offset(carried, &parser));
+        self.style.skip_blank_lines(carried, &mut parser);
+
+        let mut carried = carried;
+        if is_ellipsis_line {
+            if let Some((truncated_carried, truncated, cut)) =
+                self.truncate_line(carried, &cursor, &parser)
+            {
+                carried = truncated_carried;
+                parser = truncated;
+                self.ellipsis_cut = Some(cut);
+            }
+        }
+
+        let style = self
+            .style
+            .with_alignment_at(self.alignment_spans, parser.offset());
+        let line = (self.next_line_fn)(style, carried, cursor, parser)
+            .with_color_spans(self.color_spans)
+            .with_default_style(self.default_style);
+        self.line_rows = line.displayed_rows();
+
+        line
+    }
+
+    /// Records the state after a line is processed.
+    fn end_line(&mut self, line: &StyledLinePixelIterator<'a, C, F>)
+    where
+        C: From<Rgb>,
+    {
+        // escape sequences in the line change the style of the following lines
+        self.style.text_style = line.style.text_style;
+        self.style.underlined = line.style.underlined;
+        self.style.strikethrough = line.style.strikethrough;
+        self.style.bold = line.style.bold;
+
+        let carried = line.remaining_token();
+        if carried.is_none() && line.is_empty() && self.line_counted {
+            // an empty last line is not measured, so it's not counted either
+            self.lines -= 1;
+            self.line_counted = false;
+        }
+
+        let parser = line.parser();
+        let ellipsis_cut = self.ellipsis_cut.take();
+        if self.line_visible {
+            self.consumed_bytes =
+                ellipsis_cut.unwrap_or_else(|| line_start_offset(carried, &parser));
+        }
+
+        self.state = State::NextLine(carried, line.cursor(), parser);
+    }
+
+    /// Lays out lines and returns the last one that `is_reached` returns `true` for, or the first
+    /// line if there is no such line.
+    ///
+    /// `is_reached` is called with the byte offset and the top of the glyphs of each line after
+    /// the first one, and must keep returning `false` after it returned `false` once. The returned
+    /// line is not processed yet. It is returned with its starting byte offset and the starting byte
+    /// offset of the next line.
+    ///
+    /// This method must be called before any line is processed.
+    pub(crate) fn find_line(
+        mut self,
+        mut is_reached: impl FnMut(usize, i32) -> bool,
+    ) -> Option<(usize, usize, StyledLinePixelIterator<'a, C, F>)>
+    where
+        C: From<Rgb>,
+    {
+        let glyph_offset = self.style.glyph_offset();
+        let mut found: Option<(usize, StyledLinePixelIterator<'a, C, F>)> = None;
+
+        while let State::NextLine(carried, cursor, ref parser) = self.state {
+            let start = line_start_offset(carried, parser);
+
+            if let Some((found_start, ref line)) = found {
+                let is_finished = carried.is_none() && parser.is_empty();
+                if is_finished || !is_reached(start, cursor.position.y + glyph_offset) {
+                    return Some((found_start, start, line.clone()));
+                }
+            }
+
+            let parser = parser.clone();
+            let mut line = self.start_line(carried, cursor, parser);
+            found = Some((start, line.clone()));
+
+            line.measure_bounds();
+            self.end_line(&line);
+        }
+
+        None
+    }
+
+    /// Returns `true` if every line has been processed.
+    pub(crate) fn is_finished(&self) -> bool {
+        match self.state {
+            State::NextLine(carried, _, ref parser) => carried.is_none() && parser.is_empty(),
+            State::DrawLine(_) => false,
+        }
+    }
+
+    /// Returns the next pixel of the current line.
+    ///
+    /// Returns `None` after the last pixel of each line, and after the whole text is drawn.
+    fn next_line_pixel(&mut self) -> Option<Pixel<C>>
+    where
+        C: From<Rgb>,
+    {
+        loop {
+            match self.state {
+                State::NextLine(carried_token, cursor, ref parser) => {
+                    if carried_token.is_none() && parser.is_empty() {
+                        return None;
+                    }
+
+                    if self.is_line_clipped(&cursor) {
+                        self.skip_line();
+                        self.line_rows = None;
+                        return None;
+                    }
+
+                    let line = self.start_line(carried_token, cursor, parser.clone());
+                    self.state = State::DrawLine(line);
+                }
+
+                State::DrawLine(ref mut line_iterator) => {
+                    if let Some(Pixel(point, color)) = line_iterator.next() {
+                        match self.clip {
+                            Some(clip) if !clip.contains_point(point) => continue,
+                            _ => {
+                                let point = self.point_to_display(point);
+                                match self.vertical {
+                                    Some(vertical) if !vertical.is_drawn(point) => continue,
+                                    _ => return Some(Pixel(point, color)),
+                                }
+                            }
+                        }
+                    }
+
+                    let line = line_iterator.clone();
+                    self.end_line(&line);
+
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Draws the rest of the current line and returns the rows of the text area it was displayed
+    /// in.
+    ///
+    /// Returns `Ok(None)` if the line is not displayed. This method must not be called after every
+    /// line has been processed.
+    pub(crate) fn draw_line<D>(&mut self, display: &mut D) -> Result<Option<Rectangle>, D::Error>
+    where
+        C: From<Rgb>,
+        D: DrawTarget<C>,
+    {
+        display.draw_iter(LinePixels { renderer: self })?;
+
+        Ok(self.line_rows.map(|rows| self.to_display(rows)))
+    }
+
+    /// Returns whether the line that starts at `cursor` may have pixels inside the clipping area.
+    fn is_line_clipped(&self, cursor: &Cursor<F>) -> bool {
+        match self.clip {
+            Some(clip) => {
+                let top = cursor.position.y + self.style.glyph_offset();
+                // the underline may be drawn below the line
+                let bottom = top + F::CHARACTER_SIZE.height as i32;
+
+                bottom < clip.top_left.y || top > clip.bottom_right.y
+            }
+            None => false,
+        }
+    }
+}
+
+/// Iterator over the areas covered by the lines of a [`StyledTextBox`].
+///
+/// This struct is created by the [`StyledTextBox::line_bounds`] method.
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+/// [`StyledTextBox::line_bounds`]: ../struct.StyledTextBox.html#method.line_bounds
+#[derive(Clone, Debug)]
+pub struct LineBounds<'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    renderer: StyledTextBoxIterator<'a, C, F, A, V, H>,
+}
+
+impl<'a, C, F, A, V, H> LineBounds<'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// Creates an iterator over the lines of text that `renderer` has left to draw.
+    #[inline]
+    #[must_use]
+    pub fn new(renderer: StyledTextBoxIterator<'a, C, F, A, V, H>) -> Self {
+        Self { renderer }
+    }
+}
+
+impl<C, F, A, V, H> Iterator for LineBounds<'_, C, F, A, V, H>
+where
+    C: PixelColor + From<Rgb>,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    type Item = Rectangle;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bounds) = self.renderer.skip_line()? {
+                return Some(self.renderer.to_display(bounds));
+            }
         }
     }
 }
 
-impl<'a, C, F, A, V, H, SP> Iterator for StyledTextBoxIterator<'a, C, F, A, V, H, SP>
+impl<'a, C, F, A, V, H> Iterator for StyledTextBoxIterator<'a, C, F, A, V, H>
 where
     C: PixelColor + From<Rgb>,
     F: Font + Copy,
     A: HorizontalTextAlignment,
     V: VerticalTextAlignment,
     H: HeightMode,
-    SP: SpaceConfig<Font = F>,
 {
     type Item = Pixel<C>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match self.state {
-                State::NextLine(ref carried_token, ref cursor, ref parser) => {
-                    if carried_token.is_none() && parser.is_empty() {
-                        break None;
-                    }
+            if let pixel @ Some(_) = self.next_line_pixel() {
+                break pixel;
+            }
 
-                    let f = self.next_line_fn;
-                    self.state = State::DrawLine(f(
-                        self.style,
-                        carried_token.clone(),
-                        *cursor,
-                        parser.clone(),
-                    ));
-                }
+            if self.is_finished() {
+                break None;
+            }
+        }
+    }
+}
 
-                State::DrawLine(ref mut line_iterator) => {
-                    if let pixel @ Some(_) = line_iterator.next() {
-                        break pixel;
-                    }
+/// Pixel iterator that draws a single line of a [`StyledTextBoxIterator`].
+struct LinePixels<'r, 'a, C, F, A, V, H>
+where
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    renderer: &'r mut StyledTextBoxIterator<'a, C, F, A, V, H>,
+}
+
+impl<C, F, A, V, H> Iterator for LinePixels<'_, '_, C, F, A, V, H>
+where
+    C: PixelColor + From<Rgb>,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    type Item = Pixel<C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.renderer.next_line_pixel()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alignment::{
+            BottomAligned, CenterAligned, DynamicAlignment, HorizontalTextAlignment, Justified,
+            LeftAligned, RightAligned, TopAligned, VerticalTextAlignment,
+        },
+        parser::Token,
+        rendering::{
+            line::StyledLinePixelIterator, RenderResult, RendererFactory, TextBoxRenderer,
+        },
+        style::{
+            bold_font::BoldFont,
+            color::{AnsiColor, AnsiPalette, ColorRef},
+            height_mode::{Exact, FitToText, FitToTextLimited, ShrinkToContent},
+            vertical_overdraw::{FullRowsOnly, Hidden},
+            BreakStrategy, Direction, GlyphAlignment, HorizontalOverflow, JustifyWidth,
+            Orientation, Padding, TabSize, TextBoxStyle, TextBoxStyleBuilder, TextOverflow,
+            WritingMode,
+        },
+        utils::rect_ext::RectExt,
+        StyledTextBox, TextBox,
+    };
+    use core::cell::RefCell;
+    use embedded_graphics::{
+        fonts::{Font6x6, Font6x8},
+        mock_display::MockDisplay,
+        pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
+    };
+
+    type Renderer<'a> =
+        TextBoxRenderer<'a, BinaryColor, Font6x8, Justified, TopAligned, Exact<FullRowsOnly>>;
+
+    /// A drawing task that renders a limited number of pixels each time it is polled.
+    struct DrawTask<'a> {
+        renderer: Renderer<'a>,
+        display: MockDisplay<BinaryColor>,
+    }
+
+    impl<'a> DrawTask<'a> {
+        fn new(text: &'a str) -> Self {
+            // the style is a temporary, the renderer must not borrow it
+            Self {
+                renderer: TextBox::new(text, bounds())
+                    .into_styled(style())
+                    .create_renderer(),
+                display: MockDisplay::new(),
+            }
+        }
 
-                    self.style = line_iterator.style;
-                    self.state = State::NextLine(
-                        line_iterator.remaining_token(),
-                        line_iterator.cursor(),
-                        line_iterator.parser(),
-                    );
+        fn poll(&mut self) -> bool {
+            for _ in 0..7 {
+                match self.renderer.next() {
+                    Some(pixel) => pixel.draw(&mut self.display).unwrap(),
+                    None => return true,
                 }
             }
+            false
         }
     }
+
+    fn style() -> TextBoxStyle<BinaryColor, Font6x8, Justified, TopAligned, Exact<FullRowsOnly>> {
+        TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build()
+    }
+
+    fn bounds() -> Rectangle {
+        Rectangle::new(Point::zero(), Point::new(59, 31))
+    }
+
+    #[test]
+    fn rendering_can_be_suspended_and_resumed() {
+        let text = "Rendering can be \x1b[4msuspended\x1b[24m and resumed.";
+
+        let mut task = DrawTask::new(text);
+        let mut polls = 0;
+        while !task.poll() {
+            polls += 1;
+        }
+        assert!(polls > 1);
+
+        let mut expected = MockDisplay::new();
+        TextBox::new(text, bounds())
+            .into_styled(style())
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(task.display, expected);
+    }
+
+    #[test]
+    fn color_spans_cross_line_breaks() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("ab\ncd", Rectangle::new(Point::zero(), Point::new(11, 15)))
+            .into_styled(style)
+            .with_colors(&[(1..4, BinaryColor::Off)])
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      .    ",
+                "      .    ",
+                " ###  . .. ",
+                "    # ..  .",
+                " #### .   .",
+                "#   # .   .",
+                " #### .... ",
+                "           ",
+                "          #",
+                "          #",
+                " ...   ## #",
+                ".     #  ##",
+                ".     #   #",
+                ".   . #   #",
+                " ...   ####",
+            ])
+        );
+    }
+
+    #[test]
+    fn color_spans_override_escape_sequences() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // green is displayed as `BinaryColor::Off`, the span overrides the color of "b"
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            "\x1b[32mab",
+            Rectangle::new(Point::zero(), Point::new(11, 7)),
+        )
+        .into_styled(style)
+        .with_colors(&[(6..7, BinaryColor::On)])
+        .draw(&mut display)
+        .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      #    ",
+                "      #    ",
+                " ...  # ## ",
+                "    . ##  #",
+                " .... #   #",
+                ".   . #   #",
+                " .... #### ",
+            ])
+        );
+    }
+
+    #[test]
+    fn reset_restores_the_style_of_the_text_box() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+
+        // red is displayed as `BinaryColor::Off`
+        let mut display = MockDisplay::new();
+        TextBox::new("\x1b[31;47;4ma\x1b[0mb\x1b[31mc\x1b[39md", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("\x1b[31;47;4ma", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+        TextBox::new("b", bounds.translate(Point::new(6, 0)))
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+        TextBox::new("\x1b[31mc", bounds.translate(Point::new(12, 0)))
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+        TextBox::new("d", bounds.translate(Point::new(18, 0)))
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn ansi_palette_maps_escape_sequence_colors() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::Off)
+            .ansi_palette(|color| match color {
+                AnsiColor::Indexed(1) => Some(BinaryColor::Off),
+                _ => Some(BinaryColor::On),
+            })
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+
+        // without the palette, blue is displayed as `BinaryColor::Off`
+        let mut display = MockDisplay::new();
+        TextBox::new("\x1b[31ma\x1b[34mb", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("a", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+        TextBox::new("b", bounds.translate(Point::new(6, 0)))
+            .into_styled(
+                TextBoxStyleBuilder::new(Font6x8)
+                    .text_color(BinaryColor::On)
+                    .build(),
+            )
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn ansi_palette_can_ignore_color_codes() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .ansi_palette(|_| None)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+
+        let mut display = MockDisplay::new();
+        TextBox::new("\x1b[30;107ma\x1b[38;2;0;0;0mb\x1b[39;49mc", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("abc", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    /// `Font6x6` with a gap after each character.
+    #[derive(Copy, Clone, Debug)]
+    struct SpacedFont6x6;
+
+    impl Font for SpacedFont6x6 {
+        const FONT_IMAGE: &'static [u8] = Font6x6::FONT_IMAGE;
+        const FONT_IMAGE_WIDTH: u32 = Font6x6::FONT_IMAGE_WIDTH;
+        const CHARACTER_SIZE: Size = Font6x6::CHARACTER_SIZE;
+        const CHARACTER_SPACING: u32 = 1;
+
+        fn char_offset(c: char) -> u32 {
+            Font6x6::char_offset(c)
+        }
+    }
+
+    #[test]
+    fn bold_text_is_drawn_using_the_bold_font() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .bold_font(SpacedFont6x6)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 7));
+
+        let mut display = MockDisplay::new();
+        TextBox::new("a\x1b[1mbc\x1b[22md\x1b[1me\x1b[0mf", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        for &(text, x, bold) in &[
+            ("a", 0, false),
+            ("bc", 6, true),
+            ("d", 20, false),
+            ("e", 26, true),
+            ("f", 33, false),
+        ] {
+            let bounds = bounds.translate(Point::new(x, 0));
+            if bold {
+                TextBox::new(text, bounds)
+                    .into_styled(
+                        TextBoxStyleBuilder::new(SpacedFont6x6)
+                            .text_color(BinaryColor::On)
+                            .build(),
+                    )
+                    .draw(&mut expected)
+                    .unwrap();
+            } else {
+                TextBox::new(text, bounds)
+                    .into_styled(style)
+                    .draw(&mut expected)
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn bold_text_is_measured_using_the_bold_font() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .bold_font(SpacedFont6x6)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // the bold word only fits into the line using the regular font
+        let text = "\x1b[1mabcd efg";
+        let mut text_box =
+            TextBox::new(text, Rectangle::new(Point::zero(), Point::new(23, 0))).into_styled(style);
+        text_box.fit_height();
+        assert_eq!(text_box.size().height, 24);
+
+        // the last line is still bold, "f" starts after the spacing of the bold "e"
+        assert_eq!(
+            text_box.char_bounds(10).map(|b| b.top_left),
+            Some(Point::new(7, 16))
+        );
+
+        let unstyled = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build();
+        let mut text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(23, 0)))
+            .into_styled(unstyled);
+        text_box.fit_height();
+        assert_eq!(text_box.size().height, 16);
+    }
+
+    #[test]
+    fn text_parts_are_laid_out_as_concatenated_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(47, 31));
+
+        // the word "multiple" is split between parts, and must be wrapped as a single word
+        let parts = ["Text in ", "mul", "tiple", "  parts \x1b[4mis", " fine"];
+        let mut display = MockDisplay::new();
+        TextBox::from_parts(&parts, bounds)
+            .into_styled(style)
+            .with_colors(&[(11..14, BinaryColor::Off)])
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("Text in multiple  parts \x1b[4mis fine", bounds)
+            .into_styled(style)
+            .with_colors(&[(11..14, BinaryColor::Off)])
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(
+            style.measure_parts_height(&parts, 48),
+            style.measure_text_height("Text in multiple  parts \x1b[4mis fine", 48)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hyphenation")]
+    fn hyphenated_text_is_wrapped_like_soft_hyphens() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(47, 31));
+
+        let mut display = MockDisplay::new();
+        let text_box = TextBox::new("Automatic hyphenation", bounds).with_hyphenation(true);
+        text_box.into_styled(style).draw(&mut display).unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("Au\u{AD}to\u{AD}matic hy\u{AD}phen\u{AD}ation", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+
+        // without hyphenation, the word is broken at the end of the line
+        let mut display = MockDisplay::new();
+        TextBox::new("Automatic hyphenation", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_ne!(display, expected);
+    }
+
+    #[test]
+    fn overstrike_draws_bold_and_underlined_characters() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(ShrinkToContent)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+
+        let text_box = TextBox::new("N\x08Na _\x08d", bounds)
+            .with_overstrike(true)
+            .into_styled(style);
+        // every overstruck pair takes up a single cell, the underline adds a row
+        assert_eq!(text_box.size(), Size::new(24, 9));
+
+        let mut display = MockDisplay::new();
+        text_box.draw(&mut display).unwrap();
+
+        // the bold character is drawn twice, one pixel apart
+        let mut expected = MockDisplay::new();
+        TextBox::new("Na \x1b[4md", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+        TextBox::new("N", bounds.translate(Point::new(1, 0)))
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn overstruck_text_is_wrapped_like_plain_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(47, 0));
+
+        let man_page = TextBox::new(
+            "N\x08NA\x08AM\x08ME\x08E\n  l\x08ls\x08s - list _\x08d_\x08i_\x08r_\x08e_\x08c_\x08t_\x08o_\x08r_\x08y contents",
+            bounds,
+        )
+        .with_overstrike(true)
+        .into_styled(style);
+        let plain = TextBox::new("NAME\n  ls - list directory contents", bounds).into_styled(style);
+
+        assert_eq!(man_page.size(), plain.size());
+        assert_eq!(
+            man_page
+                .text_box
+                .parser()
+                .filter(|t| t == &Token::Backspace)
+                .count(),
+            15
+        );
+
+        // without overstrike, backspaces are part of the words
+        let text_box = TextBox::new("N\x08NAME", bounds).into_styled(style);
+        assert_eq!(
+            text_box.text_box.parser().collect::<Vec<_>>(),
+            [Token::Word("N\x08NAME")]
+        );
+    }
+
+    #[test]
+    fn zero_width_spaces_in_whitespace_runs_take_no_space() {
+        for &run in &[" \u{200B} ", "\u{200B}  ", "  \u{200B}"] {
+            let plain = run.replace('\u{200B}', "");
+
+            // the whitespace run ends up at different positions relative to the wrap points
+            for &prefix in &["a", "abc", "abcd", "abcdefghi"] {
+                let text = format!("{}{}word wrap", prefix, run);
+                let plain_text = format!("{}{}word wrap", prefix, plain);
+
+                let mut display = MockDisplay::new();
+                TextBox::new(&text, bounds())
+                    .into_styled(style())
+                    .draw(&mut display)
+                    .unwrap();
+
+                let mut expected = MockDisplay::new();
+                TextBox::new(&plain_text, bounds())
+                    .into_styled(style())
+                    .draw(&mut expected)
+                    .unwrap();
+
+                assert_eq!(display, expected, "{:?}", text);
+
+                let style = TextBoxStyleBuilder::new(Font6x8)
+                    .text_color(BinaryColor::On)
+                    .background_color(BinaryColor::Off)
+                    .build();
+
+                let mut display = MockDisplay::new();
+                TextBox::new(&text, bounds())
+                    .into_styled(style)
+                    .draw(&mut display)
+                    .unwrap();
+
+                let mut expected = MockDisplay::new();
+                TextBox::new(&plain_text, bounds())
+                    .into_styled(style)
+                    .draw(&mut expected)
+                    .unwrap();
+
+                assert_eq!(display, expected, "{:?}", text);
+            }
+        }
+    }
+
+    /// A display that only counts the pixels drawn to it.
+    #[derive(Default)]
+    struct CountingDisplay {
+        pixels: usize,
+    }
+
+    impl DrawTarget<BinaryColor> for CountingDisplay {
+        type Error = core::convert::Infallible;
+
+        fn draw_pixel(&mut self, _: Pixel<BinaryColor>) -> Result<(), Self::Error> {
+            self.pixels += 1;
+            Ok(())
+        }
+
+        fn size(&self) -> Size {
+            Size::new(60, 10_000)
+        }
+    }
+
+    #[test]
+    fn work_is_proportional_to_text_not_box_size() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let text = "Three lines of text";
+
+        let mut small = CountingDisplay::default();
+        TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 23)))
+            .into_styled(style)
+            .draw(&mut small)
+            .unwrap();
+
+        let mut tall = CountingDisplay::default();
+        TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 9_999)))
+            .into_styled(style)
+            .draw(&mut tall)
+            .unwrap();
+
+        // only the lines of text are drawn, the empty area below them is not touched
+        assert!(small.pixels <= 3 * 60 * 8);
+        assert_eq!(tall.pixels, small.pixels);
+    }
+
+    /// Returns the bounding box of the pixels drawn to the display.
+    fn drawn_bounds(display: &MockDisplay<BinaryColor>) -> Rectangle {
+        let mut bounds = Rectangle::new(Point::new(64, 64), Point::new(-1, -1));
+        for y in 0..64 {
+            for x in 0..64 {
+                if display.get_pixel(Point::new(x, y)).is_some() {
+                    bounds = bounds.union(Rectangle::new(Point::new(x, y), Point::new(x, y)));
+                }
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn text_bounds_cover_the_drawn_text() {
+        let text = "Some text\nthat is\n\nwrapped";
+        let bounds = Rectangle::new(Point::new(2, 1), Point::new(61, 62));
+
+        macro_rules! check {
+            ($style:expr) => {{
+                let styled = TextBox::new(text, bounds).into_styled($style);
+
+                let mut display = MockDisplay::new();
+                styled.draw(&mut display).unwrap();
+
+                assert_eq!(styled.text_bounds(), drawn_bounds(&display));
+                assert_eq!(styled.text_box.bounds, bounds);
+
+                let lines = styled
+                    .line_bounds()
+                    .filter(|line| RectExt::size(*line).width > 0)
+                    .fold(None, |acc: Option<Rectangle>, line| {
+                        Some(acc.map_or(line, |acc| acc.union(line)))
+                    });
+                assert_eq!(lines, Some(styled.text_bounds()));
+            }};
+        }
+
+        check!(TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build());
+        check!(TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .vertical_alignment(BottomAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .line_spacing(2)
+            .padding(Padding {
+                top: 1,
+                right: 2,
+                bottom: 3,
+                left: 4,
+            })
+            .build());
+        check!(TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .vertical_alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build());
+        check!(TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .line_direction(Direction::Rtl)
+            .build());
+    }
+
+    #[test]
+    fn line_bounds_follow_alignment() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let styled = TextBox::new(
+            "ab\n\ncd",
+            Rectangle::new(Point::zero(), Point::new(59, 15)),
+        )
+        .into_styled(style);
+        let mut lines = styled.line_bounds();
+
+        assert_eq!(
+            lines.next(),
+            Some(Rectangle::new(Point::new(24, 0), Point::new(35, 7)))
+        );
+        // empty lines have zero width
+        assert_eq!(
+            lines.next(),
+            Some(Rectangle::new(Point::new(30, 8), Point::new(29, 15)))
+        );
+        // the third line is not displayed
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn text_bounds_of_empty_text() {
+        let styled = TextBox::new("", bounds()).into_styled(style());
+
+        assert_eq!(RectExt::size(styled.text_bounds()), Size::zero());
+    }
+
+    #[test]
+    fn draw_with_result_reports_the_end_of_the_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let text = "Three lines of text";
+
+        let result = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 23)))
+            .into_styled(style)
+            .draw_with_result(&mut MockDisplay::new())
+            .unwrap();
+
+        assert_eq!(
+            result,
+            RenderResult {
+                next_position: Point::new(24, 16),
+                lines: 3,
+                consumed_bytes: text.len(),
+            }
+        );
+
+        // the last line does not fit into the text box
+        let result = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 15)))
+            .into_styled(style)
+            .draw_with_result(&mut MockDisplay::new())
+            .unwrap();
+
+        assert_eq!(result.lines, 3);
+        assert_eq!(&text[..result.consumed_bytes], "Three lines of ");
+    }
+
+    #[test]
+    fn clipped_drawing_matches_the_clipped_area_of_full_drawing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // the underline is turned on in a line that is skipped by some of the clips
+        let styled = TextBox::new(
+            "Some \x1b[4mtext that\nspans multiple lines",
+            Rectangle::new(Point::new(1, 2), Point::new(60, 40)),
+        )
+        .into_styled(style);
+
+        let mut full = MockDisplay::new();
+        styled.draw(&mut full).unwrap();
+
+        for &clip in &[
+            Rectangle::new(Point::new(0, 0), Point::new(63, 63)),
+            Rectangle::new(Point::new(10, 12), Point::new(40, 20)),
+            Rectangle::new(Point::new(0, 26), Point::new(63, 28)),
+            Rectangle::new(Point::new(0, 50), Point::new(63, 63)),
+        ] {
+            let mut expected = MockDisplay::new();
+            for y in 0..64 {
+                for x in 0..64 {
+                    let point = Point::new(x, y);
+                    if let (Some(color), true) = (full.get_pixel(point), clip.contains_point(point))
+                    {
+                        expected.set_pixel(point, Some(color));
+                    }
+                }
+            }
+
+            let mut clipped = MockDisplay::new();
+            styled.draw_clipped(&mut clipped, clip).unwrap();
+
+            assert_eq!(clipped, expected, "clip: {:?}", clip);
+        }
+    }
+
+    #[test]
+    fn palette_slots_are_resolved_when_drawing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color_ref(ColorRef::Slot(0))
+            .background_color_ref(ColorRef::Slot(1))
+            .build();
+        let styled =
+            TextBox::new("Hi", Rectangle::new(Point::zero(), Point::new(11, 7))).into_styled(style);
+
+        let mut day = MockDisplay::new();
+        styled
+            .draw_with_palette(&mut day, &[BinaryColor::On, BinaryColor::Off])
+            .unwrap();
+
+        let mut night = MockDisplay::new();
+        styled
+            .draw_with_palette(&mut night, &[BinaryColor::Off, BinaryColor::On])
+            .unwrap();
+
+        // every pixel is drawn with the opposite color
+        for y in 0..8 {
+            for x in 0..12 {
+                let point = Point::new(x, y);
+                let day = day.get_pixel(point).unwrap();
+                let night = night.get_pixel(point).unwrap();
+
+                assert_ne!(day, night, "{:?}", point);
+            }
+        }
+
+        // direct colors behave the same as without a palette
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let styled =
+            TextBox::new("Hi", Rectangle::new(Point::zero(), Point::new(11, 7))).into_styled(style);
+
+        let mut expected = MockDisplay::new();
+        styled.draw(&mut expected).unwrap();
+        let mut display = MockDisplay::new();
+        styled
+            .draw_with_palette(&mut display, &[BinaryColor::Off])
+            .unwrap();
+        assert_eq!(display, expected);
+
+        // missing slots are transparent
+        let mut display = MockDisplay::new();
+        TextBox::new("Hi", Rectangle::new(Point::zero(), Point::new(11, 7)))
+            .into_styled(
+                TextBoxStyleBuilder::new(Font6x8)
+                    .text_color(BinaryColor::On)
+                    .text_color_ref(ColorRef::Slot(2))
+                    .build(),
+            )
+            .draw_with_palette(&mut display, &[BinaryColor::Off])
+            .unwrap();
+        assert_eq!(display, MockDisplay::new());
+    }
+
+    /// Display that records the position of every drawn pixel.
+    struct RecordingDisplay<'r> {
+        drawn: &'r RefCell<Vec<Point>>,
+    }
+
+    impl DrawTarget<BinaryColor> for RecordingDisplay<'_> {
+        type Error = core::convert::Infallible;
+
+        fn draw_pixel(&mut self, pixel: Pixel<BinaryColor>) -> Result<(), Self::Error> {
+            self.drawn.borrow_mut().push(pixel.0);
+            Ok(())
+        }
+
+        fn size(&self) -> Size {
+            Size::new(64, 64)
+        }
+    }
+
+    #[test]
+    fn draw_with_progress_reports_lines_after_drawing_them() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let drawn = RefCell::new(Vec::new());
+        let mut lines = Vec::new();
+        TextBox::new(
+            "first line\nsecond\nthird line\nfourth",
+            Rectangle::new(Point::new(2, 1), Point::new(61, 32)),
+        )
+        .into_styled(style)
+        .draw_with_progress(&mut RecordingDisplay { drawn: &drawn }, |rows| {
+            lines.push((rows, drawn.borrow().len()))
+        })
+        .unwrap();
+
+        // the lines cover the text area from top to bottom
+        assert_eq!(
+            lines.iter().map(|(rows, _)| *rows).collect::<Vec<_>>(),
+            vec![
+                Rectangle::new(Point::new(2, 1), Point::new(61, 8)),
+                Rectangle::new(Point::new(2, 9), Point::new(61, 16)),
+                Rectangle::new(Point::new(2, 17), Point::new(61, 24)),
+                Rectangle::new(Point::new(2, 25), Point::new(61, 32)),
+            ]
+        );
+
+        // every line is reported right after its pixels are drawn
+        let drawn = drawn.borrow();
+        let mut start = 0;
+        for &(rows, end) in &lines {
+            assert!(end > start);
+            assert!(drawn[start..end].iter().all(|&p| rows.contains_point(p)));
+            start = end;
+        }
+        assert_eq!(start, drawn.len());
+    }
+
+    #[test]
+    fn pixels_match_drawn_output() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let text_box = TextBox::new(
+            "some text\nthat is wrapped",
+            Rectangle::new(Point::new(2, 1), Point::new(61, 32)),
+        )
+        .into_styled(style);
+
+        let pixels = text_box.pixels().collect::<Vec<_>>();
+        assert_eq!(pixels, text_box.pixels().collect::<Vec<_>>());
+
+        let mut expected = MockDisplay::new();
+        expected.draw_iter(pixels).unwrap();
+
+        // drawing the same text box twice yields the same output
+        for _ in 0..2 {
+            let mut display = MockDisplay::new();
+            text_box.draw(&mut display).unwrap();
+
+            assert_eq!(display, expected);
+        }
+    }
+
+    #[test]
+    fn caret_position_follows_the_layout() {
+        // offsets 3 to 7 are inside the escape sequence, 16 is inside the wrapped word
+        let text = "Hi \x1b[4mthere, everyone";
+        let bounds = Rectangle::new(Point::zero(), Point::new(35, 31));
+
+        let left = TextBox::new(text, bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        let center = TextBox::new(text, bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(CenterAligned)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+
+        for &(offset, left_x, center_x, y) in &[
+            (0, 0, 12, 0),
+            (5, 0, 0, 8),
+            (8, 6, 6, 8),
+            (16, 12, 12, 16),
+            (20, 0, 12, 24),
+            (text.len(), 12, 24, 24),
+        ] {
+            assert_eq!(left.caret_position(offset), Point::new(left_x, y));
+            assert_eq!(center.caret_position(offset), Point::new(center_x, y));
+        }
+
+        // the caret is drawn over the text
+        let mut expected = MockDisplay::new();
+        center.draw(&mut expected).unwrap();
+        for y in 16..24 {
+            expected.set_pixel(Point::new(12, y), Some(BinaryColor::On));
+        }
+
+        let mut display = MockDisplay::new();
+        center
+            .draw_with_caret(&mut display, 16, BinaryColor::On)
+            .unwrap();
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn char_at_and_char_bounds_round_trip() {
+        let text = "Hi\tyou, every\u{ad}body says hi";
+        let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(47, 47)))
+            .into_styled(
+                TextBoxStyleBuilder::new(Font6x8)
+                    .alignment(CenterAligned)
+                    .line_spacing(2)
+                    .text_color(BinaryColor::On)
+                    .build(),
+            );
+
+        // the character after the tab, and the last one before the inserted hyphen
+        assert_eq!(
+            text_box.char_bounds(3),
+            Some(Rectangle::new(Point::new(24, 0), Point::new(29, 7)))
+        );
+        assert_eq!(
+            text_box.char_bounds(12),
+            Some(Rectangle::new(Point::new(30, 10), Point::new(35, 17)))
+        );
+        assert_eq!(text_box.char_at(Point::new(27, 3)), Some(3));
+        assert_eq!(text_box.char_at(Point::new(38, 13)), None);
+
+        // whitespace, line spacing and margins
+        assert_eq!(text_box.char_bounds(2), None);
+        assert_eq!(text_box.char_at(Point::new(15, 3)), None);
+        assert_eq!(text_box.char_at(Point::new(8, 8)), None);
+        assert_eq!(text_box.char_at(Point::new(1, 12)), None);
+
+        let mut found = 0;
+        for y in 0..48 {
+            for x in 0..48 {
+                let point = Point::new(x, y);
+                if let Some(offset) = text_box.char_at(point) {
+                    assert!(text_box.char_bounds(offset).unwrap().contains_point(point));
+                    found += 1;
+                }
+            }
+        }
+        // every character is hit, except for the spaces, the tab and the soft hyphen
+        assert_eq!(found, (text.chars().count() - 5) * 6 * 8);
+    }
+
+    #[test]
+    fn offsets_around_a_non_breaking_hyphen_are_char_boundaries() {
+        // the non-breaking hyphen is displayed as '-', but it is 3 bytes long
+        let text = "a\u{2011}bc";
+        let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 7)))
+            .into_styled(
+                TextBoxStyleBuilder::new(Font6x8)
+                    .text_color(BinaryColor::On)
+                    .build(),
+            );
+
+        assert_eq!(text_box.caret_position(1), Point::new(6, 0));
+        assert_eq!(text_box.caret_position(4), Point::new(12, 0));
+        assert_eq!(text_box.offset_at(Point::new(12, 2)), 4);
+        assert_eq!(
+            text_box.char_bounds(1),
+            Some(Rectangle::new(Point::new(6, 0), Point::new(11, 7)))
+        );
+        assert_eq!(
+            text_box.char_bounds(4).map(|b| b.top_left),
+            Some(Point::new(12, 0))
+        );
+        assert_eq!(text_box.char_at(Point::new(8, 3)), Some(1));
+    }
+
+    #[test]
+    fn content_token_changes_with_every_style_field() {
+        type Style = TextBoxStyle<
+            BinaryColor,
+            Font6x8,
+            DynamicAlignment,
+            TopAligned,
+            FitToTextLimited<FullRowsOnly>,
+        >;
+
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 15));
+        let base: Style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(DynamicAlignment::Left)
+            .height_mode(FitToTextLimited(16, FullRowsOnly))
+            .text_color(BinaryColor::On)
+            .build();
+        let token = |style: Style| {
+            TextBox::new("Hello, World!", bounds)
+                .into_styled(style)
+                .content_token()
+        };
+
+        // Every field is listed without `..`, so a new field doesn't compile until it's changed
+        // below.
+        let TextBoxStyle {
+            text_style,
+            alignment,
+            vertical_alignment: _,
+            height_mode,
+            line_spacing,
+            letter_spacing,
+            paragraph_spacing,
+            first_line_indent,
+            hanging_indent,
+            word_spacing,
+            max_word_spacing,
+            justify_width,
+            tab_size,
+            underlined,
+            strikethrough,
+            bold,
+            bold_font,
+            padding,
+            break_strategy,
+            horizontal_overflow,
+            line_direction,
+            glyph_alignment,
+            orientation,
+            writing_mode,
+            text_overflow,
+            ellipsis,
+            truncate_at_word_boundary,
+            collapse_blank_lines,
+            break_character,
+            break_after,
+            no_break_before,
+            no_break_after,
+            text_color_slot,
+            background_color_slot,
+            ansi_palette,
+        } = base;
+
+        let changes: [Style; 36] = [
+            {
+                let mut text_style = text_style;
+                text_style.text_color = Some(BinaryColor::Off);
+                TextBoxStyle { text_style, ..base }
+            },
+            {
+                let mut text_style = text_style;
+                text_style.text_color = None;
+                TextBoxStyle { text_style, ..base }
+            },
+            {
+                let mut text_style = text_style;
+                text_style.background_color = Some(BinaryColor::Off);
+                TextBoxStyle { text_style, ..base }
+            },
+            TextBoxStyle {
+                alignment: match alignment {
+                    DynamicAlignment::Left => DynamicAlignment::Right,
+                    _ => DynamicAlignment::Left,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                height_mode: FitToTextLimited(height_mode.0 + 8, FullRowsOnly),
+                ..base
+            },
+            TextBoxStyle {
+                line_spacing: line_spacing + 1,
+                ..base
+            },
+            TextBoxStyle {
+                letter_spacing: letter_spacing + 1,
+                ..base
+            },
+            TextBoxStyle {
+                paragraph_spacing: paragraph_spacing + 1,
+                ..base
+            },
+            TextBoxStyle {
+                first_line_indent: first_line_indent + 1,
+                ..base
+            },
+            TextBoxStyle {
+                hanging_indent: hanging_indent + 1,
+                ..base
+            },
+            TextBoxStyle {
+                word_spacing: word_spacing + 1,
+                ..base
+            },
+            TextBoxStyle {
+                max_word_spacing: max_word_spacing.xor(Some(1)),
+                ..base
+            },
+            TextBoxStyle {
+                justify_width: match justify_width {
+                    JustifyWidth::Pixels(30) => JustifyWidth::Pixels(40),
+                    _ => JustifyWidth::Pixels(30),
+                },
+                ..base
+            },
+            TextBoxStyle {
+                tab_size: if tab_size == TabSize::spaces(2) {
+                    TabSize::spaces(3)
+                } else {
+                    TabSize::spaces(2)
+                },
+                ..base
+            },
+            TextBoxStyle {
+                underlined: !underlined,
+                ..base
+            },
+            TextBoxStyle {
+                strikethrough: !strikethrough,
+                ..base
+            },
+            TextBoxStyle {
+                bold: !bold,
+                ..base
+            },
+            TextBoxStyle {
+                bold_font: bold_font.xor(Some(BoldFont::new(Font6x6))),
+                ..base
+            },
+            TextBoxStyle {
+                padding: Padding::uniform(padding.top + 1),
+                ..base
+            },
+            TextBoxStyle {
+                break_strategy: match break_strategy {
+                    BreakStrategy::Balanced => BreakStrategy::Greedy,
+                    _ => BreakStrategy::Balanced,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                horizontal_overflow: match horizontal_overflow {
+                    HorizontalOverflow::Clip => HorizontalOverflow::Wrap,
+                    _ => HorizontalOverflow::Clip,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                line_direction: match line_direction {
+                    Direction::Rtl => Direction::Ltr,
+                    Direction::Ltr => Direction::Rtl,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                glyph_alignment: match glyph_alignment {
+                    GlyphAlignment::Baseline(2) => GlyphAlignment::Top,
+                    _ => GlyphAlignment::Baseline(2),
+                },
+                ..base
+            },
+            TextBoxStyle {
+                orientation: match orientation {
+                    Orientation::Rotated180 => Orientation::Horizontal,
+                    _ => Orientation::Rotated180,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                writing_mode: match writing_mode {
+                    WritingMode::VerticalRl => WritingMode::HorizontalTb,
+                    _ => WritingMode::VerticalRl,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                text_overflow: match text_overflow {
+                    TextOverflow::Ellipsis => TextOverflow::Clip,
+                    TextOverflow::Clip => TextOverflow::Ellipsis,
+                },
+                ..base
+            },
+            TextBoxStyle {
+                ellipsis: if ellipsis == ".." { "..." } else { ".." },
+                ..base
+            },
+            TextBoxStyle {
+                truncate_at_word_boundary: !truncate_at_word_boundary,
+                ..base
+            },
+            TextBoxStyle {
+                collapse_blank_lines: !collapse_blank_lines,
+                ..base
+            },
+            TextBoxStyle {
+                break_character: break_character.xor(Some('-')),
+                ..base
+            },
+            TextBoxStyle {
+                break_after: if break_after.is_empty() { &['/'] } else { &[] },
+                ..base
+            },
+            TextBoxStyle {
+                no_break_before: if no_break_before.is_empty() {
+                    &['!']
+                } else {
+                    &[]
+                },
+                ..base
+            },
+            TextBoxStyle {
+                no_break_after: if no_break_after.is_empty() {
+                    &['(']
+                } else {
+                    &[]
+                },
+                ..base
+            },
+            TextBoxStyle {
+                text_color_slot: text_color_slot.xor(Some(0)),
+                ..base
+            },
+            TextBoxStyle {
+                background_color_slot: background_color_slot.xor(Some(0)),
+                ..base
+            },
+            TextBoxStyle {
+                ansi_palette: match ansi_palette {
+                    Some(_) => None,
+                    None => Some(AnsiPalette::new(|_| None)),
+                },
+                ..base
+            },
+        ];
+
+        let base_token = token(base);
+        assert_eq!(token(base), base_token);
+        for (i, change) in changes.iter().enumerate() {
+            assert_ne!(token(*change), base_token, "change {}", i);
+        }
+
+        // the vertical alignment and the font are part of the type of the style
+        let bottom_aligned = TextBox::new("Hello, World!", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(DynamicAlignment::Left)
+                .vertical_alignment(BottomAligned)
+                .height_mode(FitToTextLimited(16, FullRowsOnly))
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        assert_ne!(bottom_aligned.content_token(), base_token);
+        let other_font = TextBox::new("Hello, World!", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x6)
+                .alignment(DynamicAlignment::Left)
+                .height_mode(FitToTextLimited(16, FullRowsOnly))
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        assert_ne!(other_font.content_token(), base_token);
+
+        // the text, the bounds and the vertical offset are also covered
+        let text_box = TextBox::new("Hello, World", bounds).into_styled(base);
+        assert_ne!(text_box.content_token(), base_token);
+        let text_box =
+            TextBox::new("Hello, World!", bounds.translate(Point::new(1, 0))).into_styled(base);
+        assert_ne!(text_box.content_token(), base_token);
+        let text_box = TextBox::new("Hello, World!", bounds)
+            .into_styled(base)
+            .with_vertical_offset(-8);
+        assert_ne!(text_box.content_token(), base_token);
+    }
+
+    #[test]
+    fn draw_if_changed_skips_unchanged_text_boxes() {
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 7));
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut token = 0;
+        let mut display = MockDisplay::new();
+        let text_box = TextBox::new("Hello", bounds).into_styled(style);
+        assert!(text_box.draw_if_changed(&mut display, &mut token).unwrap());
+        assert_eq!(token, text_box.content_token());
+
+        let mut display = MockDisplay::new();
+        assert!(!text_box.draw_if_changed(&mut display, &mut token).unwrap());
+        assert_eq!(display, MockDisplay::new());
+
+        let text_box = TextBox::new("World", bounds).into_styled(style);
+        assert!(text_box.draw_if_changed(&mut display, &mut token).unwrap());
+        assert_ne!(display, MockDisplay::new());
+    }
+
+    #[test]
+    fn caret_position_in_right_to_left_lines() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .line_direction(Direction::Rtl)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("ab cd", Rectangle::new(Point::zero(), Point::new(23, 15)))
+            .into_styled(style);
+
+        // the caret is on the right side of the character it's placed in front of
+        assert_eq!(text_box.caret_position(0), Point::new(24, 0));
+        assert_eq!(text_box.caret_position(1), Point::new(18, 0));
+        assert_eq!(text_box.caret_position(2), Point::new(12, 0));
+        assert_eq!(text_box.caret_position(3), Point::new(24, 8));
+        assert_eq!(text_box.caret_position(5), Point::new(12, 8));
+
+        assert_eq!(text_box.offset_at(Point::new(17, 1)), 1);
+        assert_eq!(text_box.offset_at(Point::new(0, 12)), 5);
+    }
+
+    #[test]
+    fn vertical_offset_slides_the_text_inside_the_bounds() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(Exact(Hidden))
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // both lines, drawn without an offset
+        let full = TextBox::new("AB\nCD", Rectangle::new(Point::zero(), Point::new(11, 15)))
+            .into_styled(style)
+            .pixels()
+            .collect::<Vec<_>>();
+
+        for offset in -8..=8 {
+            let mut display = MockDisplay::new();
+            TextBox::new("AB\nCD", Rectangle::new(Point::zero(), Point::new(11, 7)))
+                .into_styled(style)
+                .with_vertical_offset(offset)
+                .draw(&mut display)
+                .unwrap();
+
+            let mut expected = MockDisplay::new();
+            expected
+                .draw_iter(
+                    full.iter()
+                        .map(|&Pixel(p, c)| Pixel(p + Point::new(0, offset), c))
+                        .filter(|&Pixel(p, _)| p.y >= 0 && p.y < 8),
+                )
+                .unwrap();
+
+            assert_eq!(display, expected, "offset: {}", offset);
+        }
+    }
+
+    fn assert_too_narrow_text_is_not_drawn<A>(alignment: A)
+    where
+        A: HorizontalTextAlignment,
+        for<'a> StyledTextBox<'a, BinaryColor, Font6x8, A, TopAligned, FitToText>:
+            RendererFactory<'a, BinaryColor>,
+    {
+        let text = "Hello, World! Some\u{AD}text\twith\u{A0}spaces";
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(alignment)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // a zero pixel wide box can't be constructed, but it can be measured
+        assert_eq!(style.measure_text_height(text, 0), 0);
+
+        // 1 pixel, and 1 pixel narrower than a glyph
+        for &width in &[1, 5] {
+            assert_eq!(style.measure_text_height(text, width as u32), 0);
+
+            let text_box = TextBox::new(
+                text,
+                Rectangle::new(Point::zero(), Point::new(width - 1, 7)),
+            )
+            .into_styled(style);
+
+            assert_eq!(RectExt::size(text_box.text_box.bounds).height, 0);
+            assert_eq!(text_box.create_renderer().count(), 0);
+        }
+    }
+
+    #[test]
+    fn text_is_not_drawn_if_no_glyph_fits_the_line() {
+        assert_too_narrow_text_is_not_drawn(LeftAligned);
+        assert_too_narrow_text_is_not_drawn(RightAligned);
+        assert_too_narrow_text_is_not_drawn(CenterAligned);
+        assert_too_narrow_text_is_not_drawn(Justified);
+    }
+
+    #[test]
+    fn alignments_share_the_line_renderer() {
+        let text = "a b";
+        let style = || TextBoxStyleBuilder::new(Font6x8).text_color(BinaryColor::On);
+
+        let (_, _, left) = TextBox::new(text, bounds())
+            .into_styled(style().alignment(LeftAligned).build())
+            .create_renderer()
+            .find_line(|_, _| false)
+            .unwrap();
+        let (_, _, center) = TextBox::new(text, bounds())
+            .into_styled(style().alignment(CenterAligned).build())
+            .create_renderer()
+            .find_line(|_, _| false)
+            .unwrap();
+        let (_, _, right) = TextBox::new(text, bounds())
+            .into_styled(style().alignment(RightAligned).build())
+            .create_renderer()
+            .find_line(|_, _| false)
+            .unwrap();
+
+        // the lines have the same type, only the offset of the line differs
+        let lines: [StyledLinePixelIterator<'_, BinaryColor, Font6x8>; 3] = [left, center, right];
+        let starts: Vec<i32> = lines
+            .iter()
+            .map(|line| line.clone().map(|Pixel(p, _)| p.x).min().unwrap())
+            .collect();
+
+        assert_eq!(starts, [0, 21, 42]);
+    }
+
+    #[test]
+    fn rotated_text() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .orientation(Orientation::Rotated90)
+            .build();
+
+        // the lines are as long as the text box is tall
+        TextBox::new("Hi", Rectangle::new(Point::zero(), Point::new(7, 11)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                ".#######", "....#...", "....#...", "....#...", ".#######", "........", "........",
+                ".#...#..", ".#####.#", ".#......", "........", "........",
+            ])
+        );
+    }
+
+    #[test]
+    fn rotated_text_is_laid_out_like_horizontal_text() {
+        let text = "Some text\nthat is\n\nwrapped";
+        let bounds = Rectangle::new(Point::new(2, 3), Point::new(49, 40));
+        let style = |orientation| {
+            TextBoxStyleBuilder::new(Font6x8)
+                .alignment(CenterAligned)
+                .vertical_alignment(BottomAligned)
+                .padding(Padding {
+                    top: 1,
+                    right: 2,
+                    bottom: 3,
+                    left: 4,
+                })
+                .text_color(BinaryColor::On)
+                .background_color(BinaryColor::Off)
+                .orientation(orientation)
+                .build()
+        };
+        let horizontal = TextBox::new(text, bounds).into_styled(style(Orientation::Horizontal));
+
+        for &orientation in &[
+            Orientation::Rotated90,
+            Orientation::Rotated180,
+            Orientation::Rotated270,
+        ] {
+            // the horizontal text box is the rotated one before rotation
+            let rotated_bounds = orientation.layout_bounds(bounds);
+            let rotated = TextBox::new(text, rotated_bounds).into_styled(style(orientation));
+            let to_display = |point| orientation.to_display(point, rotated_bounds);
+
+            let expected = horizontal
+                .pixels()
+                .map(|Pixel(point, color)| Pixel(to_display(point), color))
+                .collect::<Vec<_>>();
+            assert_eq!(rotated.pixels().collect::<Vec<_>>(), expected);
+
+            assert_eq!(
+                rotated.text_bounds(),
+                orientation.rect_to_display(horizontal.text_bounds(), rotated_bounds)
+            );
+            assert_eq!(
+                rotated.text_area(),
+                orientation.rect_to_display(horizontal.text_area(), rotated_bounds)
+            );
+            assert_eq!(
+                rotated.caret_position(12),
+                to_display(horizontal.caret_position(12))
+            );
+            assert_eq!(rotated.offset_at(rotated.caret_position(12)), 12);
+            assert_eq!(
+                rotated.char_at(to_display(Point::new(20, 38))),
+                horizontal.char_at(Point::new(20, 38))
+            );
+        }
+    }
+
+    #[test]
+    fn rotated_text_box_fits_its_width_to_the_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .orientation(Orientation::Rotated270)
+            .height_mode(FitToText)
+            .build();
+
+        let text_box = TextBox::new(
+            "two\nlines",
+            Rectangle::new(Point::zero(), Point::new(59, 59)),
+        )
+        .into_styled(style);
+
+        assert_eq!(RectExt::size(text_box.text_box.bounds), Size::new(16, 60));
+    }
+
+    #[test]
+    fn rotated_text_is_clipped_in_display_coordinates() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .orientation(Orientation::Rotated180)
+            .build();
+        let text_box = TextBox::new(
+            "top\nbottom",
+            Rectangle::new(Point::zero(), Point::new(59, 15)),
+        )
+        .into_styled(style);
+
+        // the first line is drawn upside down, in the bottom half of the text box
+        let clip = Rectangle::new(Point::new(0, 8), Point::new(59, 15));
+        let mut display = MockDisplay::new();
+        text_box.draw_clipped(&mut display, clip).unwrap();
+
+        let mut expected = MockDisplay::new();
+        text_box
+            .pixels()
+            .filter(|Pixel(point, _)| point.y >= 8)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(
+            drawn_bounds(&display),
+            Rectangle::new(Point::new(43, 8), Point::new(59, 15))
+        );
+    }
+
+    fn draw_two_lines<A>(
+        text: &str,
+        style: TextBoxStyle<BinaryColor, Font6x8, A, TopAligned, Exact<FullRowsOnly>>,
+    ) -> MockDisplay<BinaryColor>
+    where
+        A: HorizontalTextAlignment,
+        for<'a> StyledTextBox<'a, BinaryColor, Font6x8, A, TopAligned, Exact<FullRowsOnly>>:
+            RendererFactory<'a, BinaryColor>,
+    {
+        let mut display = MockDisplay::new();
+        TextBox::new(text, Rectangle::new(Point::zero(), Point::new(53, 15)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        display
+    }
+
+    #[test]
+    fn ellipsis_replaces_the_end_of_the_last_displayed_line() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let ellipsis = TextBoxStyle {
+            text_overflow: TextOverflow::Ellipsis,
+            ..style
+        };
+
+        // three lines of text in a text box that is two lines tall
+        let text = "The quick brown fox jumps";
+
+        assert_eq!(
+            draw_two_lines(text, ellipsis),
+            draw_two_lines("The quick\nbrown fo…", style)
+        );
+        assert_eq!(
+            draw_two_lines(
+                text,
+                TextBoxStyle {
+                    ellipsis: "..",
+                    ..ellipsis
+                }
+            ),
+            draw_two_lines("The quick\nbrown f..", style)
+        );
+    }
+
+    #[test]
+    fn ellipsis_after_a_non_breaking_hyphen() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let draw = |text, width, style| {
+            let mut display = MockDisplay::new();
+            TextBox::new(
+                text,
+                Rectangle::new(Point::zero(), Point::new(width - 1, 7)),
+            )
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+            display
+        };
+
+        for &truncate_at_word_boundary in &[false, true] {
+            let ellipsis = TextBoxStyle {
+                text_overflow: TextOverflow::Ellipsis,
+                truncate_at_word_boundary,
+                ..style
+            };
+
+            assert_eq!(
+                draw("a\u{2011}b c", 18, ellipsis),
+                draw("a\u{2011}…", 18, style)
+            );
+            assert_eq!(draw("\u{2011} abc", 12, ellipsis), draw("-…", 12, style));
+        }
+    }
+
+    #[test]
+    fn ellipsis_is_not_drawn_after_whitespace() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let ellipsis = TextBoxStyle {
+            text_overflow: TextOverflow::Ellipsis,
+            ellipsis: "...",
+            ..style
+        };
+
+        assert_eq!(
+            draw_two_lines("The quick brown fox jumps", ellipsis),
+            draw_two_lines("The quick\nbrown...", style)
+        );
+    }
+
+    #[test]
+    fn ellipsis_follows_the_last_whole_word() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let ellipsis = TextBoxStyle {
+            text_overflow: TextOverflow::Ellipsis,
+            ..style
+        };
+        let word_boundary = TextBoxStyle {
+            truncate_at_word_boundary: true,
+            ..ellipsis
+        };
+
+        assert_eq!(
+            draw_two_lines("The quick brown fox jumps", word_boundary),
+            draw_two_lines("The quick\nbrown…", style)
+        );
+
+        // a word that is wrapped at a soft hyphen doesn't end the line
+        let text = "The quick brown ex\u{ad}traordinary fox";
+        assert_eq!(
+            draw_two_lines(text, ellipsis),
+            draw_two_lines("The quick\nbrown ex…", style)
+        );
+        assert_eq!(
+            draw_two_lines(text, word_boundary),
+            draw_two_lines("The quick\nbrown…", style)
+        );
+
+        // without a whole word, the line is cut off after a character
+        assert_eq!(
+            draw_two_lines("Thequickbrownfoxjumps", word_boundary),
+            draw_two_lines("Thequickb\nrownfoxj…", style)
+        );
+    }
+
+    #[test]
+    fn ellipsis_is_aligned_with_the_line() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let ellipsis = TextBoxStyle {
+            text_overflow: TextOverflow::Ellipsis,
+            ellipsis: "...",
+            ..style
+        };
+
+        assert_eq!(
+            draw_two_lines("The quick brown fox jumps", ellipsis),
+            draw_two_lines("The quick\nbrown...", style)
+        );
+    }
+
+    #[test]
+    fn ellipsis_is_not_drawn_if_the_text_fits() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let ellipsis = TextBoxStyle {
+            text_overflow: TextOverflow::Ellipsis,
+            ..style
+        };
+
+        for &text in &[
+            "The quick",
+            "The quick brown fox",
+            "The quick\nbrown fox\n \n",
+        ] {
+            assert_eq!(
+                draw_two_lines(text, ellipsis),
+                draw_two_lines(text, style),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn letter_spacing_after_the_ellipsis_does_not_need_to_fit() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .letter_spacing(1)
+            .build();
+        let draw = |text, style| {
+            let mut display = MockDisplay::new();
+            TextBox::new(text, Rectangle::new(Point::zero(), Point::new(40, 7)))
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+            display
+        };
+
+        // 6 characters fit into the line, the ellipsis takes up the place of the last one
+        assert_eq!(
+            draw(
+                "abcdefgh",
+                TextBoxStyle {
+                    text_overflow: TextOverflow::Ellipsis,
+                    ..style
+                }
+            ),
+            draw("abcde…", style)
+        );
+    }
+
+    #[test]
+    fn consumed_bytes_end_at_the_ellipsis() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .text_overflow(TextOverflow::Ellipsis)
+            .ellipsis("...")
+            .build();
+
+        let mut display = MockDisplay::new();
+        let result = TextBox::new(
+            "The quick brown fox jumps",
+            Rectangle::new(Point::zero(), Point::new(53, 15)),
+        )
+        .into_styled(style)
+        .draw_with_result(&mut display)
+        .unwrap();
+
+        assert_eq!(result.lines, 2);
+        assert_eq!(result.consumed_bytes, "The quick brown".len());
+    }
+
+    /// Draws four lines of text into a text box that is limited to 20px, so two lines fit.
+    fn draw_limited<V>(
+        vertical_alignment: V,
+        text_overflow: TextOverflow,
+    ) -> MockDisplay<BinaryColor>
+    where
+        V: VerticalTextAlignment,
+    {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(vertical_alignment)
+            .height_mode(FitToTextLimited(20, FullRowsOnly))
+            .text_overflow(text_overflow)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let mut display = MockDisplay::new();
+        let text_box = TextBox::new(
+            "1\n2\n3\n4",
+            Rectangle::new(Point::zero(), Point::new(11, 0)),
+        )
+        .into_styled(style);
+        assert_eq!(text_box.size(), Size::new(12, 20));
+        text_box.draw(&mut display).unwrap();
+
+        display
+    }
+
+    /// Draws two lines of text starting at `y`.
+    fn draw_lines_at(text: &str, y: i32) -> MockDisplay<BinaryColor> {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(
+            text,
+            Rectangle::new(Point::new(0, y), Point::new(11, y + 15)),
+        )
+        .into_styled(style)
+        .draw(&mut display)
+        .unwrap();
+
+        display
+    }
+
+    #[test]
+    fn vertical_alignment_of_text_taller_than_the_limit() {
+        // the whole text is positioned, lines that don't fit are cut off at the edges
+        assert_eq!(
+            draw_limited(TopAligned, TextOverflow::Clip),
+            draw_lines_at("1\n2", 0)
+        );
+        assert_eq!(
+            draw_limited(CenterAligned, TextOverflow::Clip),
+            draw_lines_at("2\n3", 2)
+        );
+        assert_eq!(
+            draw_limited(BottomAligned, TextOverflow::Clip),
+            draw_lines_at("3\n4", 4)
+        );
+
+        // the text is cut off at its end, only the displayed lines are positioned
+        assert_eq!(
+            draw_limited(TopAligned, TextOverflow::Ellipsis),
+            draw_lines_at("1\n2…", 0)
+        );
+        assert_eq!(
+            draw_limited(CenterAligned, TextOverflow::Ellipsis),
+            draw_lines_at("1\n2…", 2)
+        );
+        assert_eq!(
+            draw_limited(BottomAligned, TextOverflow::Ellipsis),
+            draw_lines_at("1\n2…", 4)
+        );
+    }
+
+    #[test]
+    fn vertical_text_is_drawn_in_columns() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        // draws the characters upright in the given cells
+        let expected = |cells: &[(char, i32, i32)]| {
+            let mut display = MockDisplay::new();
+            for &(c, x, y) in cells {
+                let mut buffer = [0; 4];
+                let top_left = Point::new(x, y);
+                TextBox::new(
+                    c.encode_utf8(&mut buffer),
+                    Rectangle::new(top_left, top_left + Point::new(5, 7)),
+                )
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+            }
+            display
+        };
+
+        let vertical = |writing_mode| {
+            let mut display = MockDisplay::new();
+            TextBox::new("abc de", Rectangle::new(Point::zero(), Point::new(13, 25)))
+                .into_styled(TextBoxStyle {
+                    writing_mode,
+                    letter_spacing: 1,
+                    line_spacing: 2,
+                    ..style
+                })
+                .draw(&mut display)
+                .unwrap();
+            display
+        };
+
+        assert_eq!(
+            vertical(WritingMode::VerticalRl),
+            expected(&[
+                ('a', 8, 0),
+                ('b', 8, 9),
+                ('c', 8, 18),
+                ('d', 0, 0),
+                ('e', 0, 9)
+            ])
+        );
+        assert_eq!(
+            vertical(WritingMode::VerticalLr),
+            expected(&[
+                ('a', 0, 0),
+                ('b', 0, 9),
+                ('c', 0, 18),
+                ('d', 8, 0),
+                ('e', 8, 9)
+            ])
+        );
+    }
+
+    #[test]
+    fn vertical_text_bounds_cover_the_columns() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .writing_mode(WritingMode::VerticalRl)
+            .build();
+
+        let text_box = TextBox::new("abc de", Rectangle::new(Point::zero(), Point::new(11, 23)))
+            .into_styled(style);
+
+        assert_eq!(
+            text_box.text_bounds(),
+            Rectangle::new(Point::zero(), Point::new(11, 23))
+        );
+        assert_eq!(
+            text_box.line_bounds().collect::<Vec<_>>(),
+            vec![
+                Rectangle::new(Point::new(6, 0), Point::new(11, 23)),
+                Rectangle::new(Point::zero(), Point::new(5, 15)),
+            ]
+        );
+    }
 }
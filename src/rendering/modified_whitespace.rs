@@ -59,6 +59,20 @@ where
     }
 }
 
+impl<C, F> ModifiedEmptySpaceIterator<C, F>
+where
+    C: PixelColor,
+    F: Font + Copy,
+{
+    /// Returns `true` if the row `y` is drawn using the text color.
+    fn is_decorated_row(&self, y: i32) -> bool {
+        let is_underline = self.underline && y as u32 == F::CHARACTER_SIZE.height;
+        let is_strikethrough = self.strikethrough && y as u32 == F::strikethrough_pos();
+
+        is_underline || is_strikethrough
+    }
+}
+
 impl<C, F> Iterator for ModifiedEmptySpaceIterator<C, F>
 where
     C: PixelColor,
@@ -73,6 +87,14 @@ where
                 // Done with this char, move on to the next one
                 break None;
             }
+            if self.char_walk.x == 0
+                && self.style.background_color.is_none()
+                && !self.is_decorated_row(self.char_walk.y)
+            {
+                // nothing is drawn in this row
+                self.char_walk.y += 1;
+                continue;
+            }
             let pos = self.char_walk;
 
             if pos.x < self.max_coordinates.x {
@@ -82,10 +104,7 @@ where
                 self.char_walk.y += 1;
             }
 
-            let is_underline = self.underline && pos.y as u32 == F::CHARACTER_SIZE.height;
-            let is_strikethrough = self.strikethrough && pos.y as u32 == F::strikethrough_pos();
-
-            let color = if is_underline || is_strikethrough {
+            let color = if self.is_decorated_row(pos.y) {
                 self.style.text_color
             } else {
                 self.style.background_color
@@ -9,6 +9,9 @@ pub trait SpaceConfig: Copy + Default {
     /// The font for which this space config belongs.
     type Font: Font;
 
+    /// Returns the width of a single space, before any adjustment made by the config.
+    fn base_width(&self) -> u32;
+
     /// Look at the size of next n spaces, without advancing.
     fn peek_next_width(&self, n: u32) -> u32;
 
@@ -25,21 +28,35 @@ pub struct UniformSpaceConfig<F: Font + Copy> {
     pub space_width: u32,
 }
 
-impl<F: Font + Copy> Default for UniformSpaceConfig<F> {
-    /// Creates a default space configuration object based on the current font.
+impl<F: Font + Copy> UniformSpaceConfig<F> {
+    /// Creates a space configuration object with the given space width.
     #[inline]
     #[must_use]
-    fn default() -> Self {
+    pub fn new(space_width: u32) -> Self {
         Self {
             _font: PhantomData,
-            space_width: F::total_char_width(' '),
+            space_width,
         }
     }
 }
 
+impl<F: Font + Copy> Default for UniformSpaceConfig<F> {
+    /// Creates a default space configuration object based on the current font.
+    #[inline]
+    #[must_use]
+    fn default() -> Self {
+        Self::new(F::total_char_width(' '))
+    }
+}
+
 impl<F: Font + Copy> SpaceConfig for UniformSpaceConfig<F> {
     type Font = F;
 
+    #[inline]
+    fn base_width(&self) -> u32 {
+        self.space_width
+    }
+
     #[inline]
     fn peek_next_width(&self, n: u32) -> u32 {
         n * self.space_width
@@ -0,0 +1,290 @@
+//! Vertical writing modes.
+//!
+//! Vertical text is laid out as horizontal lines of character cells, which are then drawn as
+//! columns: the first cell of a line is drawn at the top of a column, the next one below it, and
+//! so on. The glyphs are not rotated.
+use crate::{
+    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
+    style::{height_mode::HeightMode, Orientation, Padding, TextBoxStyle, WritingMode},
+    utils::{font_ext::FontExt, rect_ext::RectExt},
+    StyledTextBox, TextBox,
+};
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+/// Maps the character cells of horizontally laid out text to vertical columns.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VerticalLayout {
+    /// Whether columns follow each other from right to left.
+    right_to_left: bool,
+
+    /// The area of the display that the columns are drawn in.
+    area: Rectangle,
+
+    /// The size of a character cell.
+    cell: Size,
+
+    /// Space between the glyphs of a column, in pixels.
+    glyph_gap: i32,
+
+    /// Space between columns, in pixels.
+    column_gap: i32,
+
+    /// Pixels outside of this area of the display are not drawn.
+    clip: Option<Rectangle>,
+}
+
+/// Returns the number of cells of the given size that fit into `length` pixels, if there are
+/// `gap` pixels between them.
+fn cell_count(length: u32, size: u32, gap: i32) -> u32 {
+    if length < size {
+        return 0;
+    }
+
+    let pitch = (size as i32 + gap).max(1) as u32;
+
+    1 + (length - size) / pitch
+}
+
+impl VerticalLayout {
+    /// Creates the vertical layout of `styled`, or returns `None` if its text is laid out in
+    /// horizontal lines.
+    pub(crate) fn new<C, F, A, V, H>(styled: &StyledTextBox<'_, C, F, A, V, H>) -> Option<Self>
+    where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        let right_to_left = match styled.style.writing_mode {
+            WritingMode::HorizontalTb => return None,
+            WritingMode::VerticalRl => true,
+            WritingMode::VerticalLr => false,
+        };
+
+        Some(Self {
+            right_to_left,
+            area: styled.style.padding.apply(styled.text_box.bounds),
+            cell: Size::new(
+                F::CHARACTER_SIZE.width + F::CHARACTER_SPACING,
+                F::CHARACTER_SIZE.height,
+            ),
+            glyph_gap: styled.style.letter_spacing,
+            column_gap: styled.style.line_spacing,
+            clip: None,
+        })
+    }
+
+    /// Returns a copy of `styled` that lays out the text in horizontal lines of character cells.
+    ///
+    /// Every line is as long as a column, and there are as many lines as columns fit into the
+    /// text box. The spacing options are applied to the columns instead of the lines.
+    pub(crate) fn layout_text_box<'a, C, F, A, V, H>(
+        &self,
+        styled: &StyledTextBox<'a, C, F, A, V, H>,
+    ) -> StyledTextBox<'a, C, F, A, V, H>
+    where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        let size = RectExt::size(self.area);
+        let cells_per_column = cell_count(size.height, self.cell.height, self.glyph_gap);
+        let columns = cell_count(size.width, self.cell.width, self.column_gap);
+        let layout_size = Size::new(
+            cells_per_column * self.cell.width,
+            columns * self.cell.height,
+        );
+
+        // spaces are as wide as a cell, so every glyph stays inside of its cell
+        let space_width = F::total_char_width(' ');
+
+        StyledTextBox {
+            text_box: TextBox {
+                bounds: Rectangle::from_size(self.area.top_left, layout_size),
+                ..styled.text_box
+            },
+            style: TextBoxStyle {
+                line_spacing: 0,
+                letter_spacing: 0,
+                paragraph_spacing: 0,
+                first_line_indent: 0,
+                hanging_indent: 0,
+                word_spacing: space_width,
+                max_word_spacing: Some(space_width),
+                padding: Padding::default(),
+                orientation: Orientation::Horizontal,
+                writing_mode: WritingMode::HorizontalTb,
+                ..styled.style
+            },
+            color_spans: styled.color_spans,
+            alignment_spans: styled.alignment_spans,
+            vertical_offset: styled.vertical_offset,
+            height_cache: None,
+        }
+    }
+
+    /// Only draws the pixels inside `clip`.
+    pub(crate) fn clipped(self, clip: Rectangle) -> Self {
+        Self {
+            clip: Some(self.clip.map_or(clip, |current| {
+                // nothing is drawn if the clipping areas don't overlap
+                current
+                    .intersection(clip)
+                    .unwrap_or_else(|| Rectangle::from_size(clip.top_left, Size::zero()))
+            })),
+            ..self
+        }
+    }
+
+    /// Returns whether a pixel at the given point of the display is drawn.
+    pub(crate) fn is_drawn(self, point: Point) -> bool {
+        match self.clip {
+            Some(clip) => clip.contains_point(point),
+            None => true,
+        }
+    }
+
+    /// Returns the area of the display that the cell in the given column and row covers.
+    fn cell_area(self, column: i32, row: i32) -> Rectangle {
+        let width = self.cell.width as i32;
+        let height = self.cell.height as i32;
+
+        let offset = column * (width + self.column_gap);
+        let x = if self.right_to_left {
+            RectExt::size(self.area).width as i32 - width - offset
+        } else {
+            offset
+        };
+        let top_left = self.area.top_left + Point::new(x, row * (height + self.glyph_gap));
+
+        Rectangle::from_size(top_left, self.cell)
+    }
+
+    /// Maps a point of the laid out text to the display.
+    pub(crate) fn to_display(self, point: Point) -> Point {
+        let width = self.cell.width as i32;
+        let height = self.cell.height as i32;
+        let Point { x, y } = point - self.area.top_left;
+
+        let cell = self.cell_area(y.div_euclid(height), x.div_euclid(width));
+
+        cell.top_left + Point::new(x.rem_euclid(width), y.rem_euclid(height))
+    }
+
+    /// Maps an area of the laid out text to the display.
+    ///
+    /// The returned area covers every cell that the area touches.
+    pub(crate) fn rect_to_display(self, rect: Rectangle) -> Rectangle {
+        let size = RectExt::size(rect);
+        if size.width == 0 || size.height == 0 {
+            // empty areas only have a position
+            return Rectangle::from_size(self.to_display(rect.top_left), Size::zero());
+        }
+
+        let width = self.cell.width as i32;
+        let height = self.cell.height as i32;
+        let top_left = rect.top_left - self.area.top_left;
+        let bottom_right = rect.bottom_right - self.area.top_left;
+
+        let first = self.cell_area(top_left.y.div_euclid(height), top_left.x.div_euclid(width));
+        let last = self.cell_area(
+            bottom_right.y.div_euclid(height),
+            bottom_right.x.div_euclid(width),
+        );
+
+        first.union(last)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+
+    fn layout(writing_mode: WritingMode) -> VerticalLayout {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .letter_spacing(1)
+            .line_spacing(2)
+            .writing_mode(writing_mode)
+            .build();
+
+        let styled = TextBox::new("", Rectangle::new(Point::new(1, 2), Point::new(24, 29)))
+            .into_styled(style);
+
+        VerticalLayout::new(&styled).unwrap()
+    }
+
+    #[test]
+    fn horizontal_text_has_no_vertical_layout() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let styled =
+            TextBox::new("", Rectangle::new(Point::zero(), Point::new(23, 23))).into_styled(style);
+
+        assert!(VerticalLayout::new(&styled).is_none());
+    }
+
+    #[test]
+    fn lines_are_as_long_as_columns() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .writing_mode(WritingMode::VerticalRl)
+            .build();
+        let styled = TextBox::new("", Rectangle::new(Point::new(1, 2), Point::new(24, 29)))
+            .into_styled(style);
+
+        let layout = layout(WritingMode::VerticalRl).layout_text_box(&styled);
+
+        // 28px tall columns fit 3 glyphs with 1px gaps, 24px fit 3 columns with 2px gaps
+        assert_eq!(
+            layout.text_box.bounds,
+            Rectangle::new(Point::new(1, 2), Point::new(18, 25))
+        );
+        assert_eq!(layout.style.letter_spacing, 0);
+        assert_eq!(layout.style.line_spacing, 0);
+    }
+
+    #[test]
+    fn cells_are_mapped_to_columns() {
+        let right_to_left = layout(WritingMode::VerticalRl);
+        let left_to_right = layout(WritingMode::VerticalLr);
+
+        // the second glyph of the first line
+        assert_eq!(
+            right_to_left.to_display(Point::new(8, 3)),
+            Point::new(20, 12)
+        );
+        assert_eq!(
+            left_to_right.to_display(Point::new(8, 3)),
+            Point::new(2, 12)
+        );
+
+        // the first glyph of the second line
+        assert_eq!(
+            right_to_left.to_display(Point::new(1, 10)),
+            Point::new(11, 2)
+        );
+        assert_eq!(
+            left_to_right.to_display(Point::new(1, 10)),
+            Point::new(9, 2)
+        );
+    }
+
+    #[test]
+    fn areas_cover_every_touched_cell() {
+        let layout = layout(WritingMode::VerticalRl);
+
+        // the first two glyphs of the first two lines
+        assert_eq!(
+            layout.rect_to_display(Rectangle::new(Point::new(1, 2), Point::new(12, 17))),
+            Rectangle::new(Point::new(11, 2), Point::new(24, 18))
+        );
+    }
+}
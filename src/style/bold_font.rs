@@ -0,0 +1,130 @@
+//! Bold font support.
+//!
+//! Text that is turned bold by the `\x1b[1m` escape sequence is drawn using the bold font of the
+//! [`TextBoxStyle`]. Fonts are separate types in embedded-graphics, so the bold font is stored as a
+//! [`BoldFont`], which refers to the glyphs and character widths of any font type.
+//!
+//! ```rust
+//! use embedded_text::prelude::*;
+//! use embedded_graphics::{
+//!     fonts::{Font12x16, Font8x16},
+//!     pixelcolor::BinaryColor,
+//!     prelude::*,
+//! };
+//!
+//! let style = TextBoxStyleBuilder::new(Font8x16)
+//!     .bold_font(Font12x16)
+//!     .text_color(BinaryColor::On)
+//!     .build();
+//!
+//! // "bold" is drawn using `Font12x16`
+//! let text_box = TextBox::new(
+//!     "Some \x1b[1mbold\x1b[22m text",
+//!     Rectangle::new(Point::zero(), Point::new(191, 15)),
+//! )
+//! .into_styled(style);
+//! ```
+//!
+//! [`TextBoxStyle`]: ../struct.TextBoxStyle.html
+//! [`BoldFont`]: struct.BoldFont.html
+use crate::{rendering::character::Glyph, utils::font_ext::FontExt};
+use core::{
+    any::type_name,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+use embedded_graphics::{fonts::Font, geometry::Point};
+
+/// The glyphs and character widths of a font that bold text is drawn with.
+///
+/// Bold characters are laid out using the widths of the bold font. Their glyphs are drawn in the
+/// rows of the regular font, so the bold font should be as tall as the regular font: rows below
+/// the height of the regular font are not drawn. Spaces keep the width of the regular font.
+///
+/// Bold fonts are compared by the name of their font type.
+#[derive(Copy, Clone)]
+pub struct BoldFont {
+    name: &'static str,
+    glyph_width: fn(char) -> u32,
+    total_char_width: fn(char) -> u32,
+    glyph_offset: fn(char) -> u32,
+    glyph_point: fn(u32, Point) -> bool,
+}
+
+impl BoldFont {
+    /// Creates a bold font from a font.
+    #[inline]
+    #[must_use]
+    pub fn new<F: Font>(_font: F) -> Self {
+        Self {
+            name: type_name::<F>(),
+            glyph_width: F::char_width,
+            total_char_width: F::total_char_width,
+            glyph_offset: |c| Glyph::<F>::new(c).offset(),
+            glyph_point: |offset, p| Glyph::<F>::from_offset(offset).point(p),
+        }
+    }
+
+    /// Returns the width of the glyph of `c`, without character spacing.
+    #[inline]
+    pub(crate) fn glyph_width(self, c: char) -> u32 {
+        (self.glyph_width)(c)
+    }
+
+    /// Returns the width of `c`, including character spacing.
+    #[inline]
+    pub(crate) fn total_char_width(self, c: char) -> u32 {
+        (self.total_char_width)(c)
+    }
+
+    /// Returns the position of the glyph of `c` in the font image.
+    #[inline]
+    pub(crate) fn glyph_offset(self, c: char) -> u32 {
+        (self.glyph_offset)(c)
+    }
+
+    /// Returns whether the point `p` of the glyph at `offset` in the font image is a foreground
+    /// pixel.
+    #[inline]
+    pub(crate) fn glyph_point(self, offset: u32, p: Point) -> bool {
+        (self.glyph_point)(offset, p)
+    }
+}
+
+impl fmt::Debug for BoldFont {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoldFont").field(&self.name).finish()
+    }
+}
+
+impl PartialEq for BoldFont {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for BoldFont {}
+
+impl PartialOrd for BoldFont {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BoldFont {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name.cmp(other.name)
+    }
+}
+
+impl Hash for BoldFont {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
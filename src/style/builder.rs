@@ -2,10 +2,14 @@
 use crate::{
     alignment::{HorizontalTextAlignment, LeftAligned, TopAligned, VerticalTextAlignment},
     style::{
+        bold_font::BoldFont,
+        color::{AnsiColor, AnsiPalette, ColorRef},
         height_mode::{Exact, HeightMode},
         vertical_overdraw::FullRowsOnly,
-        TabSize, TextBoxStyle,
+        BreakStrategy, Direction, GlyphAlignment, HorizontalOverflow, JustifyWidth, Orientation,
+        Padding, TabAlignment, TabSize, TextBoxStyle, TextOverflow, WritingMode,
     },
+    utils::font_ext::FontExt,
 };
 use embedded_graphics::{
     prelude::*,
@@ -28,9 +32,35 @@ where
     vertical_alignment: V,
     height_mode: H,
     line_spacing: i32,
+    letter_spacing: i32,
+    paragraph_spacing: i32,
+    first_line_indent: i32,
+    hanging_indent: u32,
+    word_spacing: u32,
+    max_word_spacing: Option<u32>,
+    justify_width: JustifyWidth,
     tab_size: TabSize<F>,
     underlined: bool,
     strikethrough: bool,
+    bold_font: Option<BoldFont>,
+    padding: Padding,
+    break_strategy: BreakStrategy,
+    horizontal_overflow: HorizontalOverflow,
+    line_direction: Direction,
+    glyph_alignment: GlyphAlignment,
+    orientation: Orientation,
+    writing_mode: WritingMode,
+    text_overflow: TextOverflow,
+    ellipsis: &'static str,
+    truncate_at_word_boundary: bool,
+    collapse_blank_lines: bool,
+    break_character: Option<char>,
+    break_after: &'static [char],
+    no_break_before: &'static [char],
+    no_break_after: &'static [char],
+    text_color_slot: Option<u8>,
+    background_color_slot: Option<u8>,
+    ansi_palette: Option<AnsiPalette<C>>,
 }
 
 impl<C, F> TextBoxStyleBuilder<C, F, LeftAligned, TopAligned, Exact<FullRowsOnly>>
@@ -47,6 +77,9 @@ where
     ///  - Background color: transparent
     ///  - Height mode: [`Exact`]
     ///  - Line spacing: 0px
+    ///  - Letter spacing: 0px
+    ///  - Paragraph spacing: 0px
+    ///  - Word spacing: the width of the space character of the font
     #[inline]
     #[must_use]
     pub fn new(font: F) -> Self {
@@ -56,9 +89,35 @@ where
             vertical_alignment: TopAligned,
             height_mode: Exact(FullRowsOnly),
             line_spacing: 0,
+            letter_spacing: 0,
+            paragraph_spacing: 0,
+            first_line_indent: 0,
+            hanging_indent: 0,
+            word_spacing: F::total_char_width(' '),
+            max_word_spacing: None,
+            justify_width: JustifyWidth::default(),
             tab_size: TabSize::default(),
             underlined: false,
             strikethrough: false,
+            bold_font: None,
+            padding: Padding::default(),
+            break_strategy: BreakStrategy::default(),
+            horizontal_overflow: HorizontalOverflow::default(),
+            line_direction: Direction::default(),
+            glyph_alignment: GlyphAlignment::default(),
+            orientation: Orientation::default(),
+            writing_mode: WritingMode::default(),
+            text_overflow: TextOverflow::default(),
+            ellipsis: "…",
+            truncate_at_word_boundary: false,
+            collapse_blank_lines: false,
+            break_character: Some('-'),
+            break_after: &[],
+            no_break_before: &[],
+            no_break_after: &[],
+            text_color_slot: None,
+            background_color_slot: None,
+            ansi_palette: None,
         }
     }
 
@@ -124,6 +183,7 @@ where
     pub fn text_color(self, text_color: C) -> Self {
         Self {
             text_style_builder: self.text_style_builder.text_color(text_color),
+            text_color_slot: None,
             ..self
         }
     }
@@ -151,6 +211,199 @@ where
         }
     }
 
+    /// Sets the additional vertical space after each paragraph, in pixels.
+    ///
+    /// Paragraph spacing is added after explicit line breaks (`\n`) on top of the line spacing, but
+    /// not after lines that are wrapped.
+    ///
+    /// *Note:* You can set negative values as paragraph spacing. The next paragraph will never
+    /// start above the last line of the previous one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .paragraph_spacing(4)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn paragraph_spacing(self, paragraph_spacing: i32) -> Self {
+        Self {
+            paragraph_spacing,
+            ..self
+        }
+    }
+
+    /// Sets the indentation of the first line of each paragraph, in pixels.
+    ///
+    /// A paragraph is the text between explicit line breaks (`\n`). The first line starts this
+    /// many pixels to the right of the other lines, and its available width is reduced by the same
+    /// amount. A negative indentation moves the first line to the left of the other lines instead.
+    /// Lines never start outside of the text area, so in this case every other line is indented.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .first_line_indent(12)
+    ///     .build();
+    ///
+    /// // the first line only has room for 10 of the 12 characters, so the text is wrapped
+    /// assert_eq!(style.measure_text_height("Lorem ipsum", 72), 16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn first_line_indent(self, first_line_indent: i32) -> Self {
+        Self {
+            first_line_indent,
+            ..self
+        }
+    }
+
+    /// Sets the indentation of every line of a paragraph except the first one, in pixels.
+    ///
+    /// A paragraph is the text between explicit line breaks (`\n`). Hanging indentation is useful
+    /// for lists, where the wrapped lines of an item line up with its text instead of the bullet.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// // wrapped lines start below the text, after the 3 characters wide bullet
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .hanging_indent(3 * 6)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn hanging_indent(self, hanging_indent: u32) -> Self {
+        Self {
+            hanging_indent,
+            ..self
+        }
+    }
+
+    /// Sets the additional horizontal space after each character, in pixels.
+    ///
+    /// *Note:* You can set negative values as letter spacing if you wish your characters to
+    /// overlap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .letter_spacing(2)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn letter_spacing(self, letter_spacing: i32) -> Self {
+        Self {
+            letter_spacing,
+            ..self
+        }
+    }
+
+    /// Sets the width of space characters, in pixels.
+    ///
+    /// By default, spaces are as wide as the space character of the font.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .word_spacing(10)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn word_spacing(self, word_spacing: u32) -> Self {
+        Self {
+            word_spacing,
+            ..self
+        }
+    }
+
+    /// Sets the maximum width of a space character in [`Justified`] text, in pixels.
+    ///
+    /// Justified lines are stretched by making the spaces wider. If a line would need wider
+    /// spaces than the given limit, its spaces are only stretched up to the limit and the line is
+    /// left aligned. This avoids large gaps in lines that contain only a few words.
+    ///
+    /// The limit is never smaller than the width set by [`word_spacing`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .alignment(Justified)
+    ///     .text_color(BinaryColor::On)
+    ///     .max_word_spacing(12)
+    ///     .build();
+    /// ```
+    ///
+    /// [`Justified`]: ../../alignment/justified/struct.Justified.html
+    /// [`word_spacing`]: #method.word_spacing
+    #[inline]
+    #[must_use]
+    pub fn max_word_spacing(self, max_word_spacing: u32) -> Self {
+        Self {
+            max_word_spacing: Some(max_word_spacing),
+            ..self
+        }
+    }
+
+    /// Sets the width that [`Justified`] lines are stretched to.
+    ///
+    /// By default, lines are stretched to the width of the text area. Stretching to a smaller width
+    /// is useful if the text box is wider than the visible area.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::JustifyWidth};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .alignment(Justified)
+    ///     .text_color(BinaryColor::On)
+    ///     .justify_to(JustifyWidth::Pixels(128))
+    ///     .build();
+    /// ```
+    ///
+    /// [`Justified`]: ../../alignment/justified/struct.Justified.html
+    #[inline]
+    #[must_use]
+    pub fn justify_to(self, justify_width: JustifyWidth) -> Self {
+        Self {
+            justify_width,
+            ..self
+        }
+    }
+
     /// Sets the background color.
     ///
     /// *Note:* once the background color is set, there is no way to reset it to transparent.
@@ -170,6 +423,88 @@ where
     pub fn background_color(self, background_color: C) -> Self {
         Self {
             text_style_builder: self.text_style_builder.background_color(background_color),
+            background_color_slot: None,
+            ..self
+        }
+    }
+
+    /// Sets the text color to a color or to a palette slot.
+    ///
+    /// Palette slots are resolved by [`StyledTextBox::draw_with_palette`]. The text is transparent
+    /// when the style is drawn without a palette.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::color::ColorRef};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::<BinaryColor, _, _, _, _>::new(Font6x8)
+    ///     .text_color_ref(ColorRef::Slot(0))
+    ///     .background_color_ref(ColorRef::Slot(1))
+    ///     .build();
+    /// ```
+    ///
+    /// [`StyledTextBox::draw_with_palette`]: ../../struct.StyledTextBox.html#method.draw_with_palette
+    #[inline]
+    #[must_use]
+    pub fn text_color_ref(self, text_color: ColorRef<C>) -> Self {
+        match text_color {
+            ColorRef::Direct(color) => self.text_color(color),
+            ColorRef::Slot(slot) => Self {
+                text_color_slot: Some(slot),
+                ..self
+            },
+        }
+    }
+
+    /// Sets the background color to a color or to a palette slot.
+    ///
+    /// Palette slots are resolved by [`StyledTextBox::draw_with_palette`]. The background is
+    /// transparent when the style is drawn without a palette.
+    ///
+    /// [`StyledTextBox::draw_with_palette`]: ../../struct.StyledTextBox.html#method.draw_with_palette
+    #[inline]
+    #[must_use]
+    pub fn background_color_ref(self, background_color: ColorRef<C>) -> Self {
+        match background_color {
+            ColorRef::Direct(color) => self.background_color(color),
+            ColorRef::Slot(slot) => Self {
+                background_color_slot: Some(slot),
+                ..self
+            },
+        }
+    }
+
+    /// Sets the function that maps the colors of ANSI escape sequences to display colors.
+    ///
+    /// The function is called with the color of every `ESC [ 30–37 m`, `ESC [ 90–97 m`,
+    /// `ESC [ 38 ; ... m` escape sequence and their background color counterparts. If it returns
+    /// `None`, the escape sequence doesn't change the color, so returning `None` for every color
+    /// ignores the color codes of the text.
+    ///
+    /// By default, colors are converted using `From<Rgb>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::color::AnsiColor};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// // red is drawn as `Off`, every other color code is ignored
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .ansi_palette(|color| match color {
+    ///         AnsiColor::Indexed(1) => Some(BinaryColor::Off),
+    ///         _ => None,
+    ///     })
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn ansi_palette(self, ansi_palette: fn(AnsiColor) -> Option<C>) -> Self {
+        Self {
+            ansi_palette: Some(AnsiPalette::new(ansi_palette)),
             ..self
         }
     }
@@ -226,11 +561,37 @@ where
             text_style_builder: self.text_style_builder,
             alignment,
             line_spacing: self.line_spacing,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            first_line_indent: self.first_line_indent,
+            hanging_indent: self.hanging_indent,
+            word_spacing: self.word_spacing,
+            max_word_spacing: self.max_word_spacing,
+            justify_width: self.justify_width,
             vertical_alignment: self.vertical_alignment,
             height_mode: self.height_mode,
             tab_size: self.tab_size,
             underlined: self.underlined,
             strikethrough: self.strikethrough,
+            bold_font: self.bold_font,
+            padding: self.padding,
+            break_strategy: self.break_strategy,
+            horizontal_overflow: self.horizontal_overflow,
+            line_direction: self.line_direction,
+            glyph_alignment: self.glyph_alignment,
+            orientation: self.orientation,
+            writing_mode: self.writing_mode,
+            text_overflow: self.text_overflow,
+            ellipsis: self.ellipsis,
+            truncate_at_word_boundary: self.truncate_at_word_boundary,
+            collapse_blank_lines: self.collapse_blank_lines,
+            break_character: self.break_character,
+            break_after: self.break_after,
+            no_break_before: self.no_break_before,
+            no_break_after: self.no_break_after,
+            text_color_slot: self.text_color_slot,
+            background_color_slot: self.background_color_slot,
+            ansi_palette: self.ansi_palette,
         }
     }
 
@@ -245,11 +606,37 @@ where
             text_style_builder: self.text_style_builder,
             alignment: self.alignment,
             line_spacing: self.line_spacing,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            first_line_indent: self.first_line_indent,
+            hanging_indent: self.hanging_indent,
+            word_spacing: self.word_spacing,
+            max_word_spacing: self.max_word_spacing,
+            justify_width: self.justify_width,
             vertical_alignment,
             height_mode: self.height_mode,
             tab_size: self.tab_size,
             underlined: self.underlined,
             strikethrough: self.strikethrough,
+            bold_font: self.bold_font,
+            padding: self.padding,
+            break_strategy: self.break_strategy,
+            horizontal_overflow: self.horizontal_overflow,
+            line_direction: self.line_direction,
+            glyph_alignment: self.glyph_alignment,
+            orientation: self.orientation,
+            writing_mode: self.writing_mode,
+            text_overflow: self.text_overflow,
+            ellipsis: self.ellipsis,
+            truncate_at_word_boundary: self.truncate_at_word_boundary,
+            collapse_blank_lines: self.collapse_blank_lines,
+            break_character: self.break_character,
+            break_after: self.break_after,
+            no_break_before: self.no_break_before,
+            no_break_after: self.no_break_after,
+            text_color_slot: self.text_color_slot,
+            background_color_slot: self.background_color_slot,
+            ansi_palette: self.ansi_palette,
         }
     }
 
@@ -264,11 +651,37 @@ where
             text_style_builder: self.text_style_builder,
             alignment: self.alignment,
             line_spacing: self.line_spacing,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            first_line_indent: self.first_line_indent,
+            hanging_indent: self.hanging_indent,
+            word_spacing: self.word_spacing,
+            max_word_spacing: self.max_word_spacing,
+            justify_width: self.justify_width,
             vertical_alignment: self.vertical_alignment,
             height_mode,
             tab_size: self.tab_size,
             underlined: self.underlined,
             strikethrough: self.strikethrough,
+            bold_font: self.bold_font,
+            padding: self.padding,
+            break_strategy: self.break_strategy,
+            horizontal_overflow: self.horizontal_overflow,
+            line_direction: self.line_direction,
+            glyph_alignment: self.glyph_alignment,
+            orientation: self.orientation,
+            writing_mode: self.writing_mode,
+            text_overflow: self.text_overflow,
+            ellipsis: self.ellipsis,
+            truncate_at_word_boundary: self.truncate_at_word_boundary,
+            collapse_blank_lines: self.collapse_blank_lines,
+            break_character: self.break_character,
+            break_after: self.break_after,
+            no_break_before: self.no_break_before,
+            no_break_after: self.no_break_after,
+            text_color_slot: self.text_color_slot,
+            background_color_slot: self.background_color_slot,
+            ansi_palette: self.ansi_palette,
         }
     }
 
@@ -279,6 +692,32 @@ where
         Self { tab_size, ..self }
     }
 
+    /// Sets explicit tab stops with the alignment of the text that follows them.
+    ///
+    /// See [`TabSize::with_stops`] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::TabAlignment};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .tab_stops_aligned(&[(60, TabAlignment::Left), (120, TabAlignment::Right)])
+    ///     .build();
+    /// ```
+    ///
+    /// [`TabSize::with_stops`]: ../struct.TabSize.html#method.with_stops
+    #[inline]
+    #[must_use]
+    pub fn tab_stops_aligned(self, stops: &'static [(u32, TabAlignment)]) -> Self {
+        Self {
+            tab_size: self.tab_size.with_stops(stops),
+            ..self
+        }
+    }
+
     /// Enables or disables underlined text.
     #[inline]
     #[must_use]
@@ -296,21 +735,472 @@ where
         }
     }
 
+    /// Sets the font that bold text is drawn with.
+    ///
+    /// Text is drawn using the bold font between the `\x1b[1m` and `\x1b[22m` escape sequences.
+    /// Without a bold font, bold text is drawn using the regular font. See [`BoldFont`] for the
+    /// requirements of the bold font.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{
+    ///     fonts::{Font12x16, Font8x16},
+    ///     pixelcolor::BinaryColor,
+    /// };
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font8x16)
+    ///     .bold_font(Font12x16)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    /// ```
+    ///
+    /// [`BoldFont`]: ../bold_font/struct.BoldFont.html
+    #[inline]
+    #[must_use]
+    pub fn bold_font<BF: Font>(self, bold_font: BF) -> Self {
+        Self {
+            bold_font: Some(BoldFont::new(bold_font)),
+            ..self
+        }
+    }
+
+    /// Sets the inner padding.
+    ///
+    /// The text is laid out inside the bounding box, shrunk by the padding. The size of the
+    /// [`StyledTextBox`] is not affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::Padding};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .padding(Padding::uniform(2))
+    ///     .build();
+    /// ```
+    ///
+    /// [`StyledTextBox`]: ../../struct.StyledTextBox.html
+    #[inline]
+    #[must_use]
+    pub fn padding(self, padding: Padding) -> Self {
+        Self { padding, ..self }
+    }
+
+    /// Sets the line breaking strategy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::BreakStrategy};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .break_strategy(BreakStrategy::Balanced)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn break_strategy(self, break_strategy: BreakStrategy) -> Self {
+        Self {
+            break_strategy,
+            ..self
+        }
+    }
+
+    /// Sets how to display words that are wider than a line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::HorizontalOverflow};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .horizontal_overflow(HorizontalOverflow::Clip)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn horizontal_overflow(self, horizontal_overflow: HorizontalOverflow) -> Self {
+        Self {
+            horizontal_overflow,
+            ..self
+        }
+    }
+
+    /// Sets the base direction of the lines.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::Direction};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .line_direction(Direction::Rtl)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn line_direction(self, line_direction: Direction) -> Self {
+        Self {
+            line_direction,
+            ..self
+        }
+    }
+
+    /// Sets the vertical position of the glyphs inside the lines.
+    ///
+    /// # Example: line up the baseline of a smaller font with a larger one
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::GlyphAlignment};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// // The baseline of `Font6x8` is 7 pixels higher than the baseline of `Font12x16`
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .glyph_alignment(GlyphAlignment::Baseline(7))
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn glyph_alignment(self, glyph_alignment: GlyphAlignment) -> Self {
+        Self {
+            glyph_alignment,
+            ..self
+        }
+    }
+
+    /// Rotates the text on the display.
+    ///
+    /// See [`Orientation`] for how the layout options are applied to rotated text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::Orientation};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// // The display is mounted upside down
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .orientation(Orientation::Rotated180)
+    ///     .build();
+    /// ```
+    ///
+    /// [`Orientation`]: ../enum.Orientation.html
+    #[inline]
+    #[must_use]
+    pub fn orientation(self, orientation: Orientation) -> Self {
+        Self {
+            orientation,
+            ..self
+        }
+    }
+
+    /// Sets the direction of the lines and of the characters inside them.
+    ///
+    /// See [`WritingMode`] for how the layout options are applied to vertical text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::WritingMode};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// // Columns of text that follow each other from right to left
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .writing_mode(WritingMode::VerticalRl)
+    ///     .build();
+    /// ```
+    ///
+    /// [`WritingMode`]: ../enum.WritingMode.html
+    #[inline]
+    #[must_use]
+    pub fn writing_mode(self, writing_mode: WritingMode) -> Self {
+        Self {
+            writing_mode,
+            ..self
+        }
+    }
+
+    /// Sets how text that doesn't fit into the text box is displayed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::TextOverflow};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .text_overflow(TextOverflow::Ellipsis)
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn text_overflow(self, text_overflow: TextOverflow) -> Self {
+        Self {
+            text_overflow,
+            ..self
+        }
+    }
+
+    /// Sets the string that replaces the end of the text if it doesn't fit into the text box.
+    ///
+    /// The ellipsis is only displayed if the text overflow is set to [`TextOverflow::Ellipsis`].
+    /// The default ellipsis is `"…"`, which most fonts of embedded-graphics can't display, so it
+    /// is drawn as a `?` by them. Use `"..."` for these fonts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::TextOverflow};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .text_overflow(TextOverflow::Ellipsis)
+    ///     .ellipsis("...")
+    ///     .build();
+    /// ```
+    ///
+    /// [`TextOverflow::Ellipsis`]: ../enum.TextOverflow.html#variant.Ellipsis
+    #[inline]
+    #[must_use]
+    pub fn ellipsis(self, ellipsis: &'static str) -> Self {
+        Self { ellipsis, ..self }
+    }
+
+    /// Cuts off text at the end of a word instead of in the middle of it.
+    ///
+    /// If enabled, a word that is cut off by [`HorizontalOverflow::Clip`] is only displayed up to
+    /// the last non-breaking space that fits into the line, and [`TextOverflow::Ellipsis`] puts
+    /// the ellipsis after the last word that fits completely. If not even the first word fits,
+    /// the text is cut off after the last character that fits, like without this option.
+    /// [`HorizontalOverflow::Hide`] never displays a part of a word, so it's not affected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::{prelude::*, style::TextOverflow};
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .text_overflow(TextOverflow::Ellipsis)
+    ///     .ellipsis("...")
+    ///     .truncate_at_word_boundary(true)
+    ///     .build();
+    /// ```
+    ///
+    /// [`HorizontalOverflow::Clip`]: ../enum.HorizontalOverflow.html#variant.Clip
+    /// [`HorizontalOverflow::Hide`]: ../enum.HorizontalOverflow.html#variant.Hide
+    /// [`TextOverflow::Ellipsis`]: ../enum.TextOverflow.html#variant.Ellipsis
+    #[inline]
+    #[must_use]
+    pub fn truncate_at_word_boundary(self, truncate_at_word_boundary: bool) -> Self {
+        Self {
+            truncate_at_word_boundary,
+            ..self
+        }
+    }
+
+    /// Treats lines that only contain whitespace as empty lines, and collapses consecutive empty
+    /// lines into a single one.
+    ///
+    /// Only lines that start a paragraph are affected. Blank lines at the end of the text are
+    /// removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .collapse_blank_lines(true)
+    ///     .build();
+    ///
+    /// assert_eq!(
+    ///     style.measure_text_height("para1\n   \n\t\n\npara2", 60),
+    ///     style.measure_text_height("para1\n\npara2", 60),
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn collapse_blank_lines(self, collapse_blank_lines: bool) -> Self {
+        Self {
+            collapse_blank_lines,
+            ..self
+        }
+    }
+
+    /// Sets the character that is displayed when a line is wrapped at a soft hyphen.
+    ///
+    /// By default, a `'-'` character is displayed. If set to `None`, lines are wrapped at soft
+    /// hyphens without displaying anything.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .break_character(Some('~'))
+    ///     .build();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn break_character(self, break_character: Option<char>) -> Self {
+        Self {
+            break_character,
+            ..self
+        }
+    }
+
+    /// Sets additional characters that lines may be wrapped after.
+    ///
+    /// Lines are wrapped at spaces and soft hyphens by default. The characters set here, for
+    /// example `/` in URLs or `-` in compound words, are kept at the end of the line when the line
+    /// is wrapped after them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .break_after(&['/', '-'])
+    ///     .build();
+    ///
+    /// // "foo/" | "bar-" | "baz"
+    /// assert_eq!(style.measure_text_height("foo/bar-baz", 36), 24);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn break_after(self, break_after: &'static [char]) -> Self {
+        Self {
+            break_after,
+            ..self
+        }
+    }
+
+    /// Sets the characters that must not start a line.
+    ///
+    /// Punctuation that is not allowed at the start of a line stays attached to the preceding
+    /// character: the break opportunity before it is ignored, and a word that is wrapped at a
+    /// character boundary moves the preceding character to the next line together with the
+    /// punctuation. By default, any character can start a line. Use [`CJK_NO_BREAK_BEFORE`] for
+    /// Japanese punctuation like `、` and `。`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .no_break_before(&['!'])
+    ///     .build();
+    ///
+    /// // "ab" | "c!" instead of "abc" | "!"
+    /// assert_eq!(style.measure_text_width("ab\u{200b}c\u{200b}!", 18), 12);
+    /// ```
+    ///
+    /// [`CJK_NO_BREAK_BEFORE`]: ../../chars/constant.CJK_NO_BREAK_BEFORE.html
+    #[inline]
+    #[must_use]
+    pub fn no_break_before(self, no_break_before: &'static [char]) -> Self {
+        Self {
+            no_break_before,
+            ..self
+        }
+    }
+
+    /// Sets the characters that must not end a line.
+    ///
+    /// Characters that are not allowed at the end of a line, like opening brackets, stay attached
+    /// to the following character. By default, any character can end a line. Use
+    /// [`CJK_NO_BREAK_AFTER`] for Japanese opening brackets like `「`.
+    ///
+    /// [`CJK_NO_BREAK_AFTER`]: ../../chars/constant.CJK_NO_BREAK_AFTER.html
+    #[inline]
+    #[must_use]
+    pub fn no_break_after(self, no_break_after: &'static [char]) -> Self {
+        Self {
+            no_break_after,
+            ..self
+        }
+    }
+
     /// Builds the [`TextBoxStyle`].
     ///
     /// [`TextBoxStyle`]: ../struct.TextBoxStyle.html
     #[inline]
     #[must_use]
     pub fn build(self) -> TextBoxStyle<C, F, A, V, H> {
+        let mut text_style = self.text_style_builder.build();
+
+        // colors that were set before a palette slot are replaced by the slot
+        if self.text_color_slot.is_some() {
+            text_style.text_color = None;
+        }
+        if self.background_color_slot.is_some() {
+            text_style.background_color = None;
+        }
+
         TextBoxStyle {
-            text_style: self.text_style_builder.build(),
+            text_style,
             alignment: self.alignment,
             line_spacing: self.line_spacing,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            first_line_indent: self.first_line_indent,
+            hanging_indent: self.hanging_indent,
+            word_spacing: self.word_spacing,
+            max_word_spacing: self.max_word_spacing,
+            justify_width: self.justify_width,
             vertical_alignment: self.vertical_alignment,
             height_mode: self.height_mode,
             tab_size: self.tab_size,
             underlined: self.underlined,
             strikethrough: self.strikethrough,
+            bold: false,
+            bold_font: self.bold_font,
+            padding: self.padding,
+            break_strategy: self.break_strategy,
+            horizontal_overflow: self.horizontal_overflow,
+            line_direction: self.line_direction,
+            glyph_alignment: self.glyph_alignment,
+            orientation: self.orientation,
+            writing_mode: self.writing_mode,
+            text_overflow: self.text_overflow,
+            ellipsis: self.ellipsis,
+            truncate_at_word_boundary: self.truncate_at_word_boundary,
+            collapse_blank_lines: self.collapse_blank_lines,
+            break_character: self.break_character,
+            break_after: self.break_after,
+            no_break_before: self.no_break_before,
+            no_break_after: self.no_break_after,
+            text_color_slot: self.text_color_slot,
+            background_color_slot: self.background_color_slot,
+            ansi_palette: self.ansi_palette,
         }
     }
 }
@@ -1,8 +1,56 @@
 //! Colors.
-use embedded_graphics::pixelcolor::{BinaryColor, Rgb555, Rgb565, Rgb888};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+};
+use embedded_graphics::pixelcolor::{BinaryColor, PixelColor, Rgb555, Rgb565, Rgb888};
+
+/// A color of a style, either given directly or as a slot of a palette.
+///
+/// Palette slots are resolved when the text box is drawn using
+/// [`StyledTextBox::draw_with_palette`], so the same style can be drawn with different themes.
+/// Slots that are not present in the palette, or that are drawn without a palette, are
+/// transparent.
+///
+/// [`StyledTextBox::draw_with_palette`]: ../../struct.StyledTextBox.html#method.draw_with_palette
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ColorRef<C> {
+    /// The given color.
+    Direct(C),
+
+    /// The color at the given index of the palette.
+    Slot(u8),
+}
+
+impl<C> ColorRef<C>
+where
+    C: PixelColor,
+{
+    /// Returns the color that `self` refers to in `palette`.
+    #[inline]
+    #[must_use]
+    pub fn resolve(self, palette: &[C]) -> Option<C> {
+        match self {
+            ColorRef::Direct(color) => Some(color),
+            ColorRef::Slot(slot) => palette.get(usize::from(slot)).copied(),
+        }
+    }
+}
+
+impl<C> From<C> for ColorRef<C>
+where
+    C: PixelColor,
+{
+    #[inline]
+    fn from(color: C) -> Self {
+        ColorRef::Direct(color)
+    }
+}
 
 /// 24bit RGB color
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgb {
     pub(crate) r: u8,
     pub(crate) g: u8,
@@ -17,6 +65,170 @@ impl Rgb {
     }
 }
 
+/// A color set by an ANSI escape sequence.
+///
+/// By default, these colors are converted to the color type of the display using `From<Rgb>`. A
+/// different mapping can be set using [`TextBoxStyleBuilder::ansi_palette`].
+///
+/// [`TextBoxStyleBuilder::ansi_palette`]: ../builder/struct.TextBoxStyleBuilder.html#method.ansi_palette
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum AnsiColor {
+    /// A color of the 256 color palette.
+    ///
+    /// Colors 0-7 are the standard colors set by `ESC [ 30–37 m` and `ESC [ 40–47 m`, colors 8-15
+    /// are the bright colors set by `ESC [ 90–97 m` and `ESC [ 100–107 m`.
+    Indexed(u8),
+
+    /// A 24bit color.
+    Rgb(Rgb),
+}
+
+impl AnsiColor {
+    /// Returns the RGB value of the color.
+    ///
+    /// The 16 standard colors use the color scheme of PowerShell 6 in Windows 10.
+    #[inline]
+    #[must_use]
+    pub fn to_rgb(self) -> Rgb {
+        match self {
+            AnsiColor::Rgb(rgb) => rgb,
+            AnsiColor::Indexed(index) => indexed_to_rgb(index),
+        }
+    }
+}
+
+/// Maps the colors of ANSI escape sequences to display colors.
+///
+/// See [`TextBoxStyleBuilder::ansi_palette`] for more information. Palettes are compared by the
+/// address of their function.
+///
+/// [`TextBoxStyleBuilder::ansi_palette`]: ../builder/struct.TextBoxStyleBuilder.html#method.ansi_palette
+pub struct AnsiPalette<C> {
+    map: fn(AnsiColor) -> Option<C>,
+}
+
+impl<C> AnsiPalette<C> {
+    /// Creates a palette that maps colors using `map`.
+    #[inline]
+    #[must_use]
+    pub fn new(map: fn(AnsiColor) -> Option<C>) -> Self {
+        Self { map }
+    }
+
+    /// Returns the display color of `color`, or `None` if the color is ignored.
+    #[inline]
+    #[must_use]
+    pub fn map(self, color: AnsiColor) -> Option<C> {
+        (self.map)(color)
+    }
+
+    fn address(self) -> usize {
+        self.map as usize
+    }
+}
+
+impl<C> Clone for AnsiPalette<C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for AnsiPalette<C> {}
+
+impl<C> fmt::Debug for AnsiPalette<C> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnsiPalette")
+            .field(&(self.address() as *const ()))
+            .finish()
+    }
+}
+
+impl<C> PartialEq for AnsiPalette<C> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.address() == other.address()
+    }
+}
+
+impl<C> Eq for AnsiPalette<C> {}
+
+impl<C> PartialOrd for AnsiPalette<C> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C> Ord for AnsiPalette<C> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.address().cmp(&other.address())
+    }
+}
+
+impl<C> Hash for AnsiPalette<C> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address().hash(state);
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> Rgb {
+    match index {
+        //   0-  7:  standard colors (as in ESC [ 30–37 m)
+        //   8- 15:  high intensity colors (as in ESC [ 90–97 m)
+        0..=15 => standard_to_rgb(index),
+
+        //  16-231:  6 × 6 × 6 cube (216 colors): 16 + 36 × r + 6 × g + b (0 ≤ r, g, b ≤ 5)
+        16..=231 => {
+            let color = index - 16;
+            let extend_6 = |c| c * 51;
+
+            let b = extend_6(color % 6);
+            let color = color / 6;
+
+            let g = extend_6(color % 6);
+            let color = color / 6;
+
+            let r = extend_6(color % 6);
+
+            Rgb::new(r, g, b)
+        }
+
+        // 232-255:  grayscale from black to white in 24 steps
+        232..=255 => {
+            let level = index - 232;
+            let g = if level == 23 { 255 } else { level * 11 };
+            Rgb::new(g, g, g)
+        }
+    }
+}
+
+fn standard_to_rgb(idx: u8) -> Rgb {
+    // These colors are used in PowerShell 6 in Windows 10
+    match idx {
+        0 => Rgb::new(12, 12, 12),
+        1 => Rgb::new(197, 15, 31),
+        2 => Rgb::new(19, 161, 14),
+        3 => Rgb::new(193, 156, 0),
+        4 => Rgb::new(0, 55, 218),
+        5 => Rgb::new(136, 23, 152),
+        6 => Rgb::new(58, 150, 221),
+        7 => Rgb::new(204, 204, 204),
+
+        8 => Rgb::new(118, 118, 118),
+        9 => Rgb::new(231, 72, 86),
+        10 => Rgb::new(22, 198, 12),
+        11 => Rgb::new(249, 241, 165),
+        12 => Rgb::new(59, 120, 255),
+        13 => Rgb::new(180, 0, 158),
+        14 => Rgb::new(97, 214, 214),
+        _ => Rgb::new(242, 242, 242),
+    }
+}
+
 impl From<Rgb> for BinaryColor {
     #[inline]
     fn from(rgb: Rgb) -> Self {
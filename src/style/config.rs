@@ -0,0 +1,325 @@
+//! Style configuration.
+//!
+//! The alignment and height mode of a [`TextBoxStyle`] are type parameters, and the colors use the
+//! color type of the display, so a style can't be stored or loaded directly. A
+//! [`TextBoxStyleConfig`] holds the same options as plain data, which can be turned into a
+//! [`TextBoxStyleBuilder`] once the font and the color type are known.
+//!
+//! When the `serde` feature is enabled, `TextBoxStyleConfig` implements `Serialize` and
+//! `Deserialize`, so layouts can be stored in flash or produced by other tools. Missing fields are
+//! set to their default values.
+//!
+//! # Example
+//!
+//! ```rust
+//! use embedded_text::{prelude::*, style::config::TextBoxStyleConfig};
+//! use embedded_graphics::{fonts::Font6x8, pixelcolor::Rgb565, prelude::*};
+//!
+//! let config = TextBoxStyleConfig {
+//!     alignment: DynamicAlignment::Center,
+//!     height_mode: DynamicHeightMode::FitToText,
+//!     line_spacing: 2,
+//!     ..TextBoxStyleConfig::default()
+//! };
+//!
+//! let style = config.builder::<Rgb565, _>(Font6x8).build();
+//! ```
+//!
+//! With the `serde` feature, the configuration can be stored using any serde data format and
+//! loaded again, for example using `serde_json`:
+//!
+//! ```rust
+//! # #[cfg(feature = "serde")]
+//! # {
+//! # use embedded_text::{prelude::*, style::config::TextBoxStyleConfig};
+//! # use embedded_graphics::{fonts::Font6x8, pixelcolor::Rgb565};
+//! # let config = TextBoxStyleConfig::default();
+//! let json = serde_json::to_string(&config).unwrap();
+//! let loaded: TextBoxStyleConfig = serde_json::from_str(&json).unwrap();
+//! assert_eq!(loaded, config);
+//!
+//! let style = loaded.builder::<Rgb565, _>(Font6x8).build();
+//! # }
+//! ```
+//!
+//! [`TextBoxStyle`]: ../struct.TextBoxStyle.html
+//! [`TextBoxStyleConfig`]: struct.TextBoxStyleConfig.html
+//! [`TextBoxStyleBuilder`]: ../builder/struct.TextBoxStyleBuilder.html
+use crate::{
+    alignment::{DynamicAlignment, TopAligned},
+    style::{
+        color::Rgb, height_mode::DynamicHeightMode, BreakStrategy, Direction, GlyphAlignment,
+        HorizontalOverflow, Orientation, Padding, TabSize, TextBoxStyleBuilder, TextOverflow,
+        WritingMode,
+    },
+};
+use embedded_graphics::{fonts::Font, pixelcolor::PixelColor};
+
+/// The options of a [`TextBoxStyle`] as plain data.
+///
+/// See the [module level documentation] for more information.
+///
+/// Options that refer to static data, like tab stops, the ellipsis and the characters that lines
+/// can be broken after, or that depend on the font, like the bold font, are not part of the
+/// configuration. They can be set on the builder returned by [`builder`]. The vertical alignment
+/// is always [`TopAligned`], it can be changed using [`TextBoxStyleBuilder::vertical_alignment`].
+///
+/// [`TextBoxStyle`]: ../struct.TextBoxStyle.html
+/// [module level documentation]: index.html
+/// [`builder`]: #method.builder
+/// [`TopAligned`]: ../../alignment/top/struct.TopAligned.html
+/// [`TextBoxStyleBuilder::vertical_alignment`]: ../builder/struct.TextBoxStyleBuilder.html#method.vertical_alignment
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TextBoxStyleConfig {
+    /// Horizontal alignment.
+    pub alignment: DynamicAlignment,
+
+    /// Height mode.
+    pub height_mode: DynamicHeightMode,
+
+    /// Text color, or `None` for transparent text.
+    pub text_color: Option<Rgb>,
+
+    /// Background color, or `None` for a transparent background.
+    pub background_color: Option<Rgb>,
+
+    /// Line spacing, in pixels.
+    pub line_spacing: i32,
+
+    /// Letter spacing, in pixels.
+    pub letter_spacing: i32,
+
+    /// Paragraph spacing, in pixels.
+    pub paragraph_spacing: i32,
+
+    /// Indentation of the first line of each paragraph, in pixels.
+    pub first_line_indent: i32,
+
+    /// Indentation of the other lines of each paragraph, in pixels.
+    pub hanging_indent: u32,
+
+    /// Word spacing, in pixels, or `None` to use the width of the space character of the font.
+    pub word_spacing: Option<u32>,
+
+    /// Tab size, in spaces.
+    pub tab_size: u32,
+
+    /// Whether the text is underlined.
+    pub underlined: bool,
+
+    /// Whether the text is crossed out.
+    pub strikethrough: bool,
+
+    /// Inner padding.
+    pub padding: Padding,
+
+    /// Line breaking strategy.
+    pub break_strategy: BreakStrategy,
+
+    /// How words that are wider than a line are displayed.
+    pub horizontal_overflow: HorizontalOverflow,
+
+    /// Base direction of the lines.
+    pub line_direction: Direction,
+
+    /// Vertical position of the glyphs inside a line.
+    pub glyph_alignment: GlyphAlignment,
+
+    /// Orientation of the text on the display.
+    pub orientation: Orientation,
+
+    /// Direction of the lines and of the characters inside them.
+    pub writing_mode: WritingMode,
+
+    /// How text that doesn't fit into the text box is displayed.
+    pub text_overflow: TextOverflow,
+
+    /// Whether text is only cut off at the end of a word.
+    pub truncate_at_word_boundary: bool,
+
+    /// Whether consecutive blank lines are collapsed into one.
+    pub collapse_blank_lines: bool,
+
+    /// The character that is drawn when a word is broken at a soft hyphen.
+    pub break_character: Option<char>,
+}
+
+impl Default for TextBoxStyleConfig {
+    /// Returns the configuration of the default style of [`TextBoxStyleBuilder::new`].
+    ///
+    /// [`TextBoxStyleBuilder::new`]: ../builder/struct.TextBoxStyleBuilder.html#method.new
+    #[inline]
+    fn default() -> Self {
+        Self {
+            alignment: DynamicAlignment::Left,
+            height_mode: DynamicHeightMode::Exact,
+            text_color: None,
+            background_color: None,
+            line_spacing: 0,
+            letter_spacing: 0,
+            paragraph_spacing: 0,
+            first_line_indent: 0,
+            hanging_indent: 0,
+            word_spacing: None,
+            tab_size: 4,
+            underlined: false,
+            strikethrough: false,
+            padding: Padding::default(),
+            break_strategy: BreakStrategy::default(),
+            horizontal_overflow: HorizontalOverflow::default(),
+            line_direction: Direction::default(),
+            glyph_alignment: GlyphAlignment::default(),
+            orientation: Orientation::default(),
+            writing_mode: WritingMode::default(),
+            text_overflow: TextOverflow::default(),
+            truncate_at_word_boundary: false,
+            collapse_blank_lines: false,
+            break_character: Some('-'),
+        }
+    }
+}
+
+impl TextBoxStyleConfig {
+    /// Creates a [`TextBoxStyleBuilder`] that is set up using the configuration.
+    ///
+    /// Colors are converted to the color type of the display using `From<Rgb>`.
+    ///
+    /// [`TextBoxStyleBuilder`]: ../builder/struct.TextBoxStyleBuilder.html
+    #[inline]
+    #[must_use]
+    pub fn builder<C, F>(
+        &self,
+        font: F,
+    ) -> TextBoxStyleBuilder<C, F, DynamicAlignment, TopAligned, DynamicHeightMode>
+    where
+        C: PixelColor + From<Rgb>,
+        F: Font + Copy,
+    {
+        let mut builder = TextBoxStyleBuilder::new(font)
+            .alignment(self.alignment)
+            .height_mode(self.height_mode)
+            .line_spacing(self.line_spacing)
+            .letter_spacing(self.letter_spacing)
+            .paragraph_spacing(self.paragraph_spacing)
+            .first_line_indent(self.first_line_indent)
+            .hanging_indent(self.hanging_indent)
+            .tab_size(TabSize::spaces(self.tab_size))
+            .underlined(self.underlined)
+            .strikethrough(self.strikethrough)
+            .padding(self.padding)
+            .break_strategy(self.break_strategy)
+            .horizontal_overflow(self.horizontal_overflow)
+            .line_direction(self.line_direction)
+            .glyph_alignment(self.glyph_alignment)
+            .orientation(self.orientation)
+            .writing_mode(self.writing_mode)
+            .text_overflow(self.text_overflow)
+            .truncate_at_word_boundary(self.truncate_at_word_boundary)
+            .collapse_blank_lines(self.collapse_blank_lines)
+            .break_character(self.break_character);
+
+        if let Some(color) = self.text_color {
+            builder = builder.text_color(C::from(color));
+        }
+
+        if let Some(color) = self.background_color {
+            builder = builder.background_color(C::from(color));
+        }
+
+        if let Some(word_spacing) = self.word_spacing {
+            builder = builder.word_spacing(word_spacing);
+        }
+
+        builder
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{prelude::*, style::height_mode::Exact, style::vertical_overdraw::FullRowsOnly};
+    use embedded_graphics::{
+        fonts::Font6x8,
+        pixelcolor::{BinaryColor, Rgb888},
+    };
+
+    #[test]
+    fn default_config_builds_the_default_style() {
+        let style = TextBoxStyleConfig::default()
+            .builder::<BinaryColor, _>(Font6x8)
+            .build();
+        let default = TextBoxStyleBuilder::<BinaryColor, _, _, _, _>::new(Font6x8).build();
+
+        assert_eq!(style.alignment, DynamicAlignment::Left);
+        assert_eq!(style.height_mode, DynamicHeightMode::Exact);
+        assert_eq!(default.height_mode, Exact(FullRowsOnly));
+        assert_eq!(style.text_style, default.text_style);
+        assert_eq!(style.line_spacing, default.line_spacing);
+        assert_eq!(style.word_spacing, default.word_spacing);
+        assert_eq!(style.tab_size, default.tab_size);
+        assert_eq!(style.break_character, default.break_character);
+    }
+
+    #[test]
+    fn config_sets_style_options() {
+        let config = TextBoxStyleConfig {
+            alignment: DynamicAlignment::Justified,
+            height_mode: DynamicHeightMode::FitToTextLimited(32),
+            text_color: Some(Rgb::new(255, 0, 0)),
+            line_spacing: 2,
+            word_spacing: Some(3),
+            tab_size: 2,
+            underlined: true,
+            padding: Padding::uniform(1),
+            ..TextBoxStyleConfig::default()
+        };
+
+        let style = config.builder::<Rgb888, _>(Font6x8).build();
+
+        assert_eq!(style.alignment, DynamicAlignment::Justified);
+        assert_eq!(style.height_mode, DynamicHeightMode::FitToTextLimited(32));
+        assert_eq!(style.text_style.text_color, Some(Rgb888::new(255, 0, 0)));
+        assert_eq!(style.text_style.background_color, None);
+        assert_eq!(style.line_spacing, 2);
+        assert_eq!(style.word_spacing, 3);
+        assert_eq!(style.tab_size, TabSize::spaces(2));
+        assert!(style.underlined);
+        assert_eq!(style.padding, Padding::uniform(1));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_round_trip() {
+        let config = TextBoxStyleConfig {
+            alignment: DynamicAlignment::Right,
+            height_mode: DynamicHeightMode::FitToTextLimited(24),
+            text_color: Some(Rgb::new(1, 2, 3)),
+            glyph_alignment: GlyphAlignment::Baseline(2),
+            break_character: None,
+            ..TextBoxStyleConfig::default()
+        };
+
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: TextBoxStyleConfig = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn missing_fields_use_defaults() {
+        let loaded: TextBoxStyleConfig =
+            serde_json::from_str(r#"{ "alignment": "Center", "line_spacing": 2 }"#).unwrap();
+
+        assert_eq!(
+            loaded,
+            TextBoxStyleConfig {
+                alignment: DynamicAlignment::Center,
+                line_spacing: 2,
+                ..TextBoxStyleConfig::default()
+            }
+        );
+    }
+}
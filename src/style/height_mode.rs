@@ -8,7 +8,8 @@
 use crate::{
     alignment::{HorizontalTextAlignment, VerticalTextAlignment},
     rendering::cursor::Cursor,
-    style::vertical_overdraw::VerticalOverdraw,
+    style::vertical_overdraw::{FullRowsOnly, VerticalOverdraw},
+    utils::rect_ext::RectExt,
     StyledTextBox,
 };
 use core::ops::Range;
@@ -24,7 +25,7 @@ pub trait HeightMode: Copy {
     /// called manually.
     ///
     /// [`TextBox::into_styled`]: ../../struct.TextBox.html#method.into_styled
-    fn apply<C, F, A, V, H>(text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    fn apply<C, F, A, V, H>(&self, text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
     where
         C: PixelColor,
         F: Font + Copy,
@@ -66,7 +67,7 @@ pub trait HeightMode: Copy {
 /// ```
 ///
 /// [`TextBox`]: ../../struct.TextBox.html
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Exact<OV: VerticalOverdraw>(pub OV);
 
 impl<OV> HeightMode for Exact<OV>
@@ -74,7 +75,7 @@ where
     OV: VerticalOverdraw,
 {
     #[inline]
-    fn apply<C, F, A, V, H>(_text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    fn apply<C, F, A, V, H>(&self, _text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
     where
         C: PixelColor,
         F: Font + Copy,
@@ -143,12 +144,12 @@ where
 ///
 /// [`TopAligned`]: ../../alignment/top/struct.TopAligned.html
 /// [`TextBox`]: ../../struct.TextBox.html
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FitToText;
 
 impl HeightMode for FitToText {
     #[inline]
-    fn apply<C, F, A, V, H>(text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    fn apply<C, F, A, V, H>(&self, text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
     where
         C: PixelColor,
         F: Font + Copy,
@@ -167,6 +168,65 @@ impl HeightMode for FitToText {
     }
 }
 
+/// Sets the height of the [`StyledTextBox`] to fit the text, but at most to the given height.
+///
+/// If the text is taller than the limit, the text box will be as tall as the limit and the text
+/// is cut off according to the [`VerticalOverdraw`] option. Use [`StyledTextBox::is_truncated`] to
+/// check whether the text fits into the text box. Which lines are displayed depends on the
+/// vertical alignment and the [`TextOverflow`] option.
+///
+/// # Example: `FitToTextLimited` grows the [`TextBox`] up to the limit.
+///
+/// ```rust
+/// use embedded_text::{prelude::*, style::vertical_overdraw::FullRowsOnly};
+/// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+///
+/// // This TextBox contains three lines of text, but is 1px high
+/// let text_box = TextBox::new(
+///     "Three\nlines\nof text",
+///     Rectangle::new(Point::zero(), Point::new(59, 0)),
+/// );
+///
+/// // Set style, use 6x8 font so the 3 lines are 24px high, but only allow 16px.
+/// let style = TextBoxStyleBuilder::new(Font6x8)
+///     .height_mode(FitToTextLimited(16, FullRowsOnly))
+///     .text_color(BinaryColor::On)
+///     .build();
+///
+/// let styled = text_box.into_styled(style);
+/// assert_eq!(styled.size(), Size::new(60, 16));
+/// assert!(styled.is_truncated());
+/// ```
+///
+/// [`TextBox`]: ../../struct.TextBox.html
+/// [`VerticalOverdraw`]: ../vertical_overdraw/trait.VerticalOverdraw.html
+/// [`StyledTextBox::is_truncated`]: ../../struct.StyledTextBox.html#method.is_truncated
+/// [`TextOverflow`]: ../enum.TextOverflow.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FitToTextLimited<OV: VerticalOverdraw>(pub u32, pub OV);
+
+impl<OV> HeightMode for FitToTextLimited<OV>
+where
+    OV: VerticalOverdraw,
+{
+    #[inline]
+    fn apply<C, F, A, V, H>(&self, text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        text_box.fit_height_limited(self.0);
+    }
+
+    #[inline]
+    fn calculate_displayed_row_range<F: Font>(cursor: &Cursor<F>) -> Range<i32> {
+        OV::calculate_displayed_row_range(cursor)
+    }
+}
+
 /// If the text does not fill the bounding box, shrink the [`StyledTextBox`] to be as tall as the
 /// text.
 ///
@@ -215,7 +275,7 @@ impl HeightMode for FitToText {
 /// ```
 ///
 /// [`TextBox`]: ../../struct.TextBox.html
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ShrinkToText<OV: VerticalOverdraw>(pub OV);
 
 impl<OV> HeightMode for ShrinkToText<OV>
@@ -223,7 +283,7 @@ where
     OV: VerticalOverdraw,
 {
     #[inline]
-    fn apply<C, F, A, V, H>(text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    fn apply<C, F, A, V, H>(&self, text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
     where
         C: PixelColor,
         F: Font + Copy,
@@ -231,7 +291,8 @@ where
         V: VerticalTextAlignment,
         H: HeightMode,
     {
-        text_box.fit_height_limited(text_box.size().height);
+        let height = RectExt::size(text_box.layout_bounds()).height;
+        text_box.fit_height_limited(height);
     }
 
     #[inline]
@@ -239,3 +300,406 @@ where
         OV::calculate_displayed_row_range(cursor)
     }
 }
+
+/// Sets the size of the [`StyledTextBox`] to exactly fit the text.
+///
+/// The text is wrapped using the original width of the [`TextBox`], then the width is reduced to
+/// the width of the widest line and the height is set to the height of the text. Shrinking the
+/// width does not change how the text is wrapped.
+///
+/// Note: in this mode, vertical alignment is meaningless. Make sure to use [`TopAligned`] for
+/// efficiency.
+///
+/// # Example: `ShrinkToContent` sets both the width and the height of the [`TextBox`].
+///
+/// ```rust
+/// use embedded_text::prelude::*;
+/// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+///
+/// let text_box = TextBox::new(
+///     "Two lines\nof text",
+///     Rectangle::new(Point::zero(), Point::new(119, 0)),
+/// );
+///
+/// // Set style, use 6x8 font so the 2 lines are 16px high and the widest one is 54px wide.
+/// let style = TextBoxStyleBuilder::new(Font6x8)
+///     .height_mode(ShrinkToContent)
+///     .text_color(BinaryColor::On)
+///     .build();
+///
+/// let size = text_box.into_styled(style).size();
+/// assert_eq!(size, Size::new(54, 16));
+/// ```
+///
+/// [`TopAligned`]: ../../alignment/top/struct.TopAligned.html
+/// [`TextBox`]: ../../struct.TextBox.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShrinkToContent;
+
+impl HeightMode for ShrinkToContent {
+    #[inline]
+    fn apply<C, F, A, V, H>(&self, text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        text_box.fit_height().fit_width();
+    }
+
+    #[inline]
+    fn calculate_displayed_row_range<F: Font>(_: &Cursor<F>) -> Range<i32> {
+        // ShrinkToContent always sets the bounding box to the exact size of the text, so every row
+        // is always fully displayed
+        0..F::CHARACTER_SIZE.height as i32
+    }
+}
+
+/// Height mode that can be changed without changing the type of the style.
+///
+/// The static height modes ([`Exact`], [`FitToText`], [`FitToTextLimited`], [`ShrinkToText`] and
+/// [`ShrinkToContent`]) are resolved at compile time. Use `DynamicHeightMode` when the height mode
+/// is only known at runtime, for example when it's loaded from a [`TextBoxStyleConfig`].
+///
+/// Rows that are only partially inside the text box are not drawn, like with [`FullRowsOnly`].
+///
+/// # Example
+///
+/// ```rust
+/// use embedded_text::prelude::*;
+/// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+///
+/// let text_box = TextBox::new(
+///     "Two lines\nof text",
+///     Rectangle::new(Point::zero(), Point::new(59, 0)),
+/// );
+///
+/// let style = TextBoxStyleBuilder::new(Font6x8)
+///     .height_mode(DynamicHeightMode::FitToText)
+///     .text_color(BinaryColor::On)
+///     .build();
+///
+/// let size = text_box.into_styled(style).size();
+/// assert_eq!(size, Size::new(60, 16));
+/// ```
+///
+/// [`Exact`]: struct.Exact.html
+/// [`FitToText`]: struct.FitToText.html
+/// [`FitToTextLimited`]: struct.FitToTextLimited.html
+/// [`ShrinkToText`]: struct.ShrinkToText.html
+/// [`ShrinkToContent`]: struct.ShrinkToContent.html
+/// [`FullRowsOnly`]: ../vertical_overdraw/struct.FullRowsOnly.html
+/// [`TextBoxStyleConfig`]: ../config/struct.TextBoxStyleConfig.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DynamicHeightMode {
+    /// Keep the original height, like `Exact`.
+    Exact,
+
+    /// Fit the height to the text, like `FitToText`.
+    FitToText,
+
+    /// Fit the height to the text, but at most to the given height, like `FitToTextLimited`.
+    FitToTextLimited(u32),
+
+    /// Shrink the height to the text, like `ShrinkToText`.
+    ShrinkToText,
+
+    /// Shrink both the width and the height to the text, like `ShrinkToContent`.
+    ShrinkToContent,
+}
+
+impl Default for DynamicHeightMode {
+    #[inline]
+    fn default() -> Self {
+        Self::Exact
+    }
+}
+
+impl HeightMode for DynamicHeightMode {
+    #[inline]
+    fn apply<C, F, A, V, H>(&self, text_box: &mut StyledTextBox<'_, C, F, A, V, H>)
+    where
+        C: PixelColor,
+        F: Font + Copy,
+        A: HorizontalTextAlignment,
+        V: VerticalTextAlignment,
+        H: HeightMode,
+    {
+        match *self {
+            DynamicHeightMode::Exact => Exact(FullRowsOnly).apply(text_box),
+            DynamicHeightMode::FitToText => FitToText.apply(text_box),
+            DynamicHeightMode::FitToTextLimited(max_height) => {
+                FitToTextLimited(max_height, FullRowsOnly).apply(text_box)
+            }
+            DynamicHeightMode::ShrinkToText => ShrinkToText(FullRowsOnly).apply(text_box),
+            DynamicHeightMode::ShrinkToContent => ShrinkToContent.apply(text_box),
+        }
+    }
+
+    #[inline]
+    fn calculate_displayed_row_range<F: Font>(cursor: &Cursor<F>) -> Range<i32> {
+        FullRowsOnly::calculate_displayed_row_range(cursor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alignment::CenterAligned,
+        style::{
+            height_mode::{FitToText, FitToTextLimited, ShrinkToContent, ShrinkToText},
+            vertical_overdraw::FullRowsOnly,
+            Padding, TextBoxStyleBuilder,
+        },
+        StyledTextBox, TextBox,
+    };
+    use embedded_graphics::{
+        fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+        primitives::Rectangle,
+    };
+
+    #[test]
+    fn fit_to_text_limited_stops_growing_at_limit() {
+        let text = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n17\n18\n19\n20";
+
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(FitToTextLimited(5 * 8, FullRowsOnly))
+            .text_color(BinaryColor::On)
+            .build();
+
+        let styled =
+            TextBox::new(text, Rectangle::new(Point::zero(), Point::new(59, 0))).into_styled(style);
+
+        assert_eq!(styled.size(), Size::new(60, 5 * 8));
+        assert!(styled.is_truncated());
+    }
+
+    #[test]
+    fn fit_to_text_limited_fits_short_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(FitToTextLimited(5 * 8, FullRowsOnly))
+            .text_color(BinaryColor::On)
+            .build();
+
+        let styled = TextBox::new("1\n2", Rectangle::new(Point::zero(), Point::new(59, 59)))
+            .into_styled(style);
+
+        assert_eq!(styled.size(), Size::new(60, 2 * 8));
+        assert!(!styled.is_truncated());
+    }
+
+    #[test]
+    fn fit_to_text_follows_set_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(FitToText)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut styled =
+            TextBox::new("1", Rectangle::new(Point::zero(), Point::new(59, 0))).into_styled(style);
+        assert_eq!(styled.size(), Size::new(60, 8));
+
+        styled.set_text("1\n2\n3");
+        assert_eq!(styled.size(), Size::new(60, 3 * 8));
+        assert!(!styled.is_truncated());
+
+        styled.set_text("");
+        assert_eq!(styled.size(), Size::new(60, 0));
+    }
+
+    #[test]
+    fn fit_height_matches_the_measured_text_height() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::new(0, 5), Point::new(59, 40));
+
+        // empty text results in a box with no height, right above the top edge
+        let mut styled = TextBox::new("", bounds).into_styled(style);
+        styled.fit_height();
+        assert_eq!(styled.size(), Size::new(60, 0));
+        assert_eq!(styled.text_box.bounds.bottom_right.y, 4);
+
+        // exactly one line, the last row of the line is drawn
+        let mut styled = TextBox::new("_", bounds).into_styled(style);
+        styled.fit_height();
+        assert_eq!(styled.size(), Size::new(60, 8));
+
+        let mut display = MockDisplay::new();
+        styled.draw(&mut display).unwrap();
+        assert_eq!(display.get_pixel(Point::new(0, 12)), Some(BinaryColor::On));
+
+        // text taller than the limit
+        let mut styled = TextBox::new("1\n2\n3", bounds).into_styled(style);
+        styled.fit_height_limited(12);
+        assert_eq!(styled.size(), Size::new(60, 12));
+        styled.fit_height_limited(0);
+        assert_eq!(styled.size(), Size::new(60, 0));
+
+        // the padding of empty text remains
+        let padded = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .padding(Padding {
+                top: 2,
+                right: 0,
+                bottom: 3,
+                left: 0,
+            })
+            .build();
+        let mut styled = TextBox::new("", bounds).into_styled(padded);
+        styled.fit_height();
+        assert_eq!(styled.size(), Size::new(60, 5));
+        styled.fit_height_limited(4);
+        assert_eq!(styled.size(), Size::new(60, 4));
+
+        // the height modes agree with the methods
+        let fit = TextBox::new("1\n2\n3", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .height_mode(FitToText)
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        let shrink = TextBox::new("1\n2\n3", bounds).into_styled(
+            TextBoxStyleBuilder::new(Font6x8)
+                .height_mode(ShrinkToText(FullRowsOnly))
+                .text_color(BinaryColor::On)
+                .build(),
+        );
+        assert_eq!(fit.size(), Size::new(60, 24));
+        assert_eq!(shrink.size(), Size::new(60, 24));
+
+        // line spacing is only added between lines
+        for &line_spacing in &[2, -3] {
+            let spaced = TextBoxStyleBuilder::new(Font6x8)
+                .line_spacing(line_spacing)
+                .height_mode(FitToText)
+                .text_color(BinaryColor::On)
+                .build();
+            assert_eq!(spaced.measure_text_height("", 60), 0);
+            assert_eq!(
+                TextBox::new("", bounds).into_styled(spaced).size(),
+                Size::new(60, 0)
+            );
+
+            let mut styled = TextBox::new("", bounds).into_styled(spaced);
+            styled.fit_height();
+            assert_eq!(styled.size(), Size::new(60, 0));
+            styled.fit_height_limited(16);
+            assert_eq!(styled.size(), Size::new(60, 0));
+
+            let mut styled = TextBox::new("1\n2\n3", bounds).into_styled(spaced);
+            styled.fit_height();
+            assert_eq!(styled.size(), Size::new(60, (24 + 2 * line_spacing) as u32));
+            assert_eq!(
+                styled.size().height,
+                spaced.measure_text_height("1\n2\n3", 60)
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hyphenation")]
+    fn fit_height_follows_hyphenation() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let text = "extraordinarily incomprehensibilities";
+        let bounds = Rectangle::new(Point::zero(), Point::new(47, 0));
+
+        let mut styled = TextBox::new(text, bounds).into_styled(style);
+        styled.fit_height();
+        let unhyphenated = styled.size().height;
+
+        styled.text_box.hyphenation = true;
+        styled.fit_height();
+        let hyphenated = styled.size().height;
+
+        let mut expected = TextBox::new(text, bounds)
+            .with_hyphenation(true)
+            .into_styled(style);
+        expected.fit_height();
+
+        assert_ne!(hyphenated, unhyphenated);
+        assert_eq!(hyphenated, expected.size().height);
+
+        styled.text_box.hyphenation = false;
+        styled.fit_height();
+        assert_eq!(styled.size().height, unhyphenated);
+    }
+
+    #[test]
+    fn constructors_apply_height_mode() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .height_mode(ShrinkToContent)
+            .text_color(BinaryColor::On)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 59));
+
+        let expected = TextBox::new("a\nbc", bounds)
+            .into_styled(style)
+            .text_box
+            .bounds;
+        assert_eq!(expected, Rectangle::new(Point::zero(), Point::new(11, 15)));
+
+        assert_eq!(
+            StyledTextBox::new("a\nbc", bounds, style).text_box.bounds,
+            expected
+        );
+        assert_eq!(
+            TextBox::with_style("a\nbc", bounds, style).text_box.bounds,
+            expected
+        );
+    }
+
+    #[test]
+    fn shrink_to_content_does_not_rewrap_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .height_mode(ShrinkToContent)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // "a bb" | "ccc d" when wrapped at 6 characters
+        let styled = TextBox::new(
+            "a bb ccc d",
+            Rectangle::new(Point::zero(), Point::new(35, 0)),
+        )
+        .into_styled(style);
+
+        assert_eq!(styled.size(), Size::new(5 * 6, 2 * 8));
+        assert_eq!(styled.style.measure_text_height("a bb ccc d", 5 * 6), 2 * 8);
+    }
+
+    #[test]
+    fn fit_width_shrinks_centered_word_to_glyph_width() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let mut styled = TextBox::new("word", Rectangle::new(Point::new(10, 0), Point::new(69, 7)))
+            .into_styled(style);
+        styled.fit_width();
+
+        assert_eq!(styled.size(), Size::new(4 * 6, 8));
+
+        let mut display = MockDisplay::new();
+        styled.draw(&mut display).unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "                                #",
+                "                                #",
+                "          #   #  ###  # ##   ## #",
+                "          #   # #   # ##  # #  ##",
+                "          # # # #   # #     #   #",
+                "          # # # #   # #     #   #",
+                "           # #   ###  #      ####",
+            ])
+        );
+    }
+}
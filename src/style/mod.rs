@@ -29,6 +29,9 @@
 //! > *Note:* if `embedded-text` fails to parse an escape sequence, it will ignore the `\x1b` character
 //! and display the rest as normal text.
 //!
+//! To check which escape sequences of a text are supported, for example when the text comes from an
+//! untrusted source, use [`rendering::ansi::validate`].
+//!
 //! All escape sequences start with the `\x1b[` sequence, where `\x1b` is the ASCII `escape`
 //! character. `embedded-text` supports a subset of the `SGR` parameters, which are numeric codes
 //! with specific functions, followed by a number of parameters and end with the `m` character.
@@ -118,6 +121,8 @@
 //! The following [`Sgr`] sequences are supported:
 //!
 //!  * `\x1b[0m`: Reset everything
+//!  * `\x1b[1m`: Bold text, drawn using the [bold font] of the style
+//!  * `\x1b[22m`: Turn off bold text
 //!  * `\x1b[4m`: Underlined text
 //!  * `\x1b[24m`: Turn off text underline
 //!  * `\x1b[9m`: Crossed out/strikethrough text
@@ -129,10 +134,12 @@
 //! ------------------------------
 //!
 //! `embedded-text` supports the `Reset all` (`\x1b[0m`), `Default text color` (`\x1b[39m`) and
-//! `Default background color` (`\x1b[49m`) codes. These codes can be used to reset colors to
+//! `Default background color` (`\x1b[49m`) codes. These codes reset the colors to the ones
+//! configured in the [`TextBoxStyle`]. If the style doesn't set a color, it is reset to
 //! *transparent* (i.e. no pixels drawn for text or background).
 //!
-//! In addition, `Reset all` turns off the underlined and crossed out styles.
+//! In addition, `Reset all` restores the underlined and crossed out styles of the
+//! [`TextBoxStyle`], and turns bold text off.
 //!
 //! Other supported ANSI escape codes
 //! ---------------------------------
@@ -148,6 +155,8 @@
 //!    of line.
 //!
 //! [`Sgr`]: ../rendering/ansi/enum.Sgr.html
+//! [bold font]: bold_font/index.html
+//! [`rendering::ansi::validate`]: ../rendering/ansi/fn.validate.html
 //! [`Rgb`]: ./color/struct.Rgb.html
 //! [`TextBox`]: ../struct.TextBox.html
 //! [`TextBoxStyle`]: struct.TextBoxStyle.html
@@ -155,35 +164,66 @@
 //! [`TextBoxStyleBuilder::new`]: builder/struct.TextBoxStyleBuilder.html#method.new
 //! [`TextBox::into_styled`]: ../struct.TextBox.html#method.into_styled
 
+pub mod bold_font;
 pub mod builder;
 pub mod color;
+pub mod config;
 pub mod height_mode;
 pub mod vertical_overdraw;
 
 use crate::{
-    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
-    parser::{Parser, Token},
+    alignment::{HorizontalTextAlignment, LeftAligned, TopAligned, VerticalTextAlignment},
+    parser::{is_non_breaking_space, Parser, Token},
     rendering::{
         ansi::Sgr,
         cursor::Cursor,
-        line_iter::{LineElementIterator, RenderElement},
+        line_iter::{
+            line_start_offset, CarriedToken, LineElementIterator, LineResult, RenderElement,
+        },
         space_config::UniformSpaceConfig,
     },
-    style::height_mode::HeightMode,
-    utils::font_ext::FontExt,
+    style::{
+        bold_font::BoldFont,
+        color::{AnsiPalette, ColorRef},
+        height_mode::{Exact, HeightMode},
+        vertical_overdraw::FullRowsOnly,
+    },
+    utils::{
+        font_ext::FontExt,
+        rect_ext::{IntoWellFormed, RectExt},
+    },
+    TextBox,
 };
-use core::marker::PhantomData;
+use core::{marker::PhantomData, ops::Range};
 use embedded_graphics::{prelude::*, primitives::Rectangle, style::TextStyle};
 
 pub use builder::TextBoxStyleBuilder;
 
+/// Alignment of the text that follows a tab stop.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TabAlignment {
+    /// The text after the tab starts at the tab stop.
+    Left,
+
+    /// The text after the tab ends at the tab stop.
+    ///
+    /// The text is measured up to the next tab or line break.
+    Right,
+}
+
 /// Tab size helper
 ///
 /// This type makes it more obvious what unit is used to define the width of tabs.
 /// The default tab size is 4 spaces.
+///
+/// Optionally, explicit tab stops can be set using [`with_stops`]. Tabs that are past the last
+/// tab stop use the tab size.
+///
+/// [`with_stops`]: #method.with_stops
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TabSize<F: Font> {
     pub(crate) width: i32,
+    pub(crate) stops: &'static [(u32, TabAlignment)],
     _font: PhantomData<F>,
 }
 
@@ -210,10 +250,39 @@ impl<F: Font> TabSize<F> {
     pub fn pixels(px: i32) -> Self {
         Self {
             width: px,
+            stops: &[],
             _font: PhantomData,
         }
     }
 
+    /// Sets explicit tab stops.
+    ///
+    /// Tab stops are given as pixel offsets from the start of the line, in increasing order, with
+    /// the alignment of the text that follows the tab.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::style::{TabAlignment, TabSize};
+    /// use embedded_graphics::fonts::Font6x8;
+    ///
+    /// // labels start at 0px, numbers end at 60px
+    /// let tab_size: TabSize<Font6x8> = TabSize::default().with_stops(&[(60, TabAlignment::Right)]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_stops(self, stops: &'static [(u32, TabAlignment)]) -> Self {
+        Self { stops, ..self }
+    }
+
+    /// Returns the first tab stop after the given position.
+    pub(crate) fn next_stop(self, pos: i32) -> Option<(i32, TabAlignment)> {
+        self.stops
+            .iter()
+            .map(|&(stop, alignment)| (stop as i32, alignment))
+            .find(|&(stop, _)| stop > pos)
+    }
+
     /// Calculate the rendered with of the next tab
     #[inline]
     pub fn next_width(self, pos: i32) -> u32 {
@@ -222,6 +291,482 @@ impl<F: Font> TabSize<F> {
     }
 }
 
+/// Line breaking strategy.
+///
+/// The break strategy only affects where lines are wrapped. Both measuring and rendering text
+/// use the same strategy so they always agree on the number of lines.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BreakStrategy {
+    /// Fit as many words in each line as possible.
+    ///
+    /// This is the default strategy.
+    Greedy,
+
+    /// Break lines early if it results in a less ragged paragraph.
+    ///
+    /// When a line is wrapped, this strategy compares the leftover space in the current and next
+    /// line with the leftover space when the last word of the current line is moved to the next
+    /// line, and picks the option with the more even result. The last line of a paragraph is
+    /// allowed to be short, and moving a word never adds an extra line to the paragraph.
+    ///
+    /// This strategy only looks at two lines at a time so it does not need extra memory, but it is
+    /// slower than [`Greedy`].
+    ///
+    /// [`Greedy`]: #variant.Greedy
+    Balanced,
+}
+
+impl Default for BreakStrategy {
+    #[inline]
+    fn default() -> Self {
+        Self::Greedy
+    }
+}
+
+/// Specifies how to display words that are wider than a line.
+///
+/// Words that are glued together using non-breaking spaces (`\u{A0}`) are not wrapped at these
+/// spaces, so they can easily become wider than the text box.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HorizontalOverflow {
+    /// Wrap the word at any character, even at non-breaking spaces.
+    ///
+    /// This is the default behavior.
+    Wrap,
+
+    /// Draw the beginning of the word and cut it off at the edge of the text area.
+    ///
+    /// The rest of the word is not displayed and the text continues in the next line.
+    Clip,
+
+    /// Don't display the word at all.
+    Hide,
+}
+
+impl Default for HorizontalOverflow {
+    #[inline]
+    fn default() -> Self {
+        Self::Wrap
+    }
+}
+
+/// Specifies how to display text that doesn't fit into the text box.
+///
+/// The text overflow also decides how the vertical alignment positions text that is taller than
+/// the text box, e.g. because of a [`FitToTextLimited`] height mode:
+///
+///  * `Clip` positions the whole text. [`TopAligned`] text displays the first lines,
+///    [`CenterAligned`] text the middle ones and [`BottomAligned`] text the last ones.
+///  * `Ellipsis` always cuts the text off at its end. Only the first lines, which are displayed
+///    completely, are positioned by the vertical alignment.
+///
+/// [`FitToTextLimited`]: height_mode/struct.FitToTextLimited.html
+/// [`TopAligned`]: ../alignment/top/struct.TopAligned.html
+/// [`CenterAligned`]: ../alignment/center/struct.CenterAligned.html
+/// [`BottomAligned`]: ../alignment/bottom/struct.BottomAligned.html
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextOverflow {
+    /// Lines that don't fit into the text box are not displayed.
+    ///
+    /// This is the default behavior.
+    Clip,
+
+    /// The end of the last displayed line is replaced by an ellipsis if the text continues in a
+    /// line that is not displayed.
+    ///
+    /// As many characters are kept as fit into the line together with the ellipsis, and
+    /// whitespace before the ellipsis is removed. The ellipsis is part of the line, so it's
+    /// aligned together with the rest of the line. The ellipsis string can be set using
+    /// [`TextBoxStyleBuilder::ellipsis`].
+    ///
+    /// [`TextBoxStyleBuilder::ellipsis`]: builder/struct.TextBoxStyleBuilder.html#method.ellipsis
+    Ellipsis,
+}
+
+impl Default for TextOverflow {
+    #[inline]
+    fn default() -> Self {
+        Self::Clip
+    }
+}
+
+/// The width that [`Justified`] lines are stretched to.
+///
+/// [`Justified`]: ../alignment/justified/struct.Justified.html
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum JustifyWidth {
+    /// Lines are stretched to the width of the text area.
+    ///
+    /// This is the default width.
+    Bounds,
+
+    /// Lines are stretched to the given width, in pixels, but not wider than the text area.
+    ///
+    /// The stretched lines start at the same edge as [`LeftAligned`] text, and the rest of the
+    /// text area is left empty. Lines are still wrapped using the width of the text area, so lines
+    /// that are wider than the given width are not stretched.
+    ///
+    /// [`LeftAligned`]: ../alignment/left/struct.LeftAligned.html
+    Pixels(u32),
+}
+
+impl Default for JustifyWidth {
+    #[inline]
+    fn default() -> Self {
+        Self::Bounds
+    }
+}
+
+/// Base direction of the lines of text.
+///
+/// Horizontal alignments are interpreted relative to the line direction: [`LeftAligned`] text
+/// starts at the right edge of the text box when the direction is [`Rtl`], and [`RightAligned`]
+/// text ends at the left edge.
+///
+/// With [`Rtl`], the first character of a line is placed at the right edge and the following
+/// characters are placed to its left. The text is expected to be in logical order, no
+/// bidirectional reordering is done.
+///
+/// [`LeftAligned`]: ../alignment/left/struct.LeftAligned.html
+/// [`RightAligned`]: ../alignment/right/struct.RightAligned.html
+/// [`Rtl`]: #variant.Rtl
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    /// Lines start at the left edge and advance to the right.
+    ///
+    /// This is the default direction.
+    Ltr,
+
+    /// Lines start at the right edge and advance to the left.
+    Rtl,
+}
+
+impl Default for Direction {
+    #[inline]
+    fn default() -> Self {
+        Self::Ltr
+    }
+}
+
+/// Vertical position of the glyphs inside a line.
+///
+/// By default, glyphs are drawn at the top of the line. Moving the glyphs down makes it possible to
+/// line up the baselines of text boxes that use different fonts.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GlyphAlignment {
+    /// Glyphs are drawn at the top of the line.
+    ///
+    /// This is the default alignment.
+    Top,
+
+    /// Glyphs are moved down by the given number of pixels.
+    ///
+    /// To line up the baselines of two text boxes that are placed at the same height, set the
+    /// value to the difference between the baselines of the larger and the smaller font in the
+    /// text box that uses the smaller font.
+    Baseline(u32),
+
+    /// Glyphs are drawn at the bottom of the line, below the line spacing.
+    ///
+    /// This is the same as [`Top`] if the line spacing is not positive.
+    ///
+    /// [`Top`]: #variant.Top
+    Bottom,
+}
+
+impl Default for GlyphAlignment {
+    #[inline]
+    fn default() -> Self {
+        Self::Top
+    }
+}
+
+/// Orientation of the text on the display.
+///
+/// Rotated text is laid out as if the display was rotated: for example, lines of text that is
+/// rotated by 90 degrees run from the top of the text box to the bottom, and the first line is at
+/// the right edge. This makes it possible to display text on panels that are mounted sideways,
+/// without rotating the frame buffer.
+///
+/// Every layout option is applied to the rotated text. If the text is rotated by 90 or 270
+/// degrees, the lines are as long as the text box is tall, the top padding is on the right or left
+/// side of the text box, and the height modes change the width of the text box. Positions and
+/// areas returned by a [`StyledTextBox`], like [`text_bounds`] and [`caret_position`], are
+/// display coordinates.
+///
+/// The measurement methods of [`TextBoxStyle`] don't depend on the orientation. To measure text
+/// that is rotated by 90 or 270 degrees, pass the height of the text area as the width.
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+/// [`text_bounds`]: ../struct.StyledTextBox.html#method.text_bounds
+/// [`caret_position`]: ../struct.StyledTextBox.html#method.caret_position
+/// [`TextBoxStyle`]: struct.TextBoxStyle.html
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// Text is not rotated.
+    ///
+    /// This is the default orientation.
+    Horizontal,
+
+    /// Text is rotated clockwise by 90 degrees.
+    Rotated90,
+
+    /// Text is rotated by 180 degrees.
+    Rotated180,
+
+    /// Text is rotated clockwise by 270 degrees.
+    Rotated270,
+}
+
+impl Default for Orientation {
+    #[inline]
+    fn default() -> Self {
+        Self::Horizontal
+    }
+}
+
+/// Direction of the lines and of the characters inside them.
+///
+/// In the vertical writing modes, characters follow each other from the top of the text box to
+/// the bottom, and lines are columns that follow each other horizontally. This is used to display
+/// Japanese and Chinese text. Glyphs are not rotated, so words in Latin script are displayed
+/// upright, one character below the other.
+///
+/// Vertical text is laid out in cells that are as large as a glyph of the font, so only
+/// monospaced fonts are supported. Every layout option is applied to the columns: for example, the
+/// letter spacing is the space between the glyphs of a column, the line spacing is the space
+/// between columns and the horizontal alignment positions the text inside the columns. The
+/// orientation, the paragraph spacing and the word spacing are ignored.
+///
+/// *Note:* The height modes and the methods of [`StyledTextBox`] that measure text, map positions
+/// to characters or draw a caret don't support vertical writing modes yet, they lay out the text
+/// in horizontal lines.
+///
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WritingMode {
+    /// Lines run from left to right and follow each other from top to bottom.
+    ///
+    /// This is the default writing mode.
+    HorizontalTb,
+
+    /// Columns run from top to bottom and follow each other from right to left.
+    VerticalRl,
+
+    /// Columns run from top to bottom and follow each other from left to right.
+    VerticalLr,
+}
+
+impl Default for WritingMode {
+    #[inline]
+    fn default() -> Self {
+        Self::HorizontalTb
+    }
+}
+
+impl Orientation {
+    /// Returns `true` if lines run vertically on the display.
+    pub(crate) fn is_vertical(self) -> bool {
+        match self {
+            Orientation::Rotated90 | Orientation::Rotated270 => true,
+            Orientation::Horizontal | Orientation::Rotated180 => false,
+        }
+    }
+
+    /// Returns the area that text is laid out in before it's rotated into `bounds`.
+    ///
+    /// The area has the same top left corner as `bounds`. If lines run vertically, its width and
+    /// height are swapped.
+    pub(crate) fn layout_bounds(self, bounds: Rectangle) -> Rectangle {
+        if self.is_vertical() {
+            let size = RectExt::size(bounds);
+
+            Rectangle::from_size(bounds.top_left, Size::new(size.height, size.width))
+        } else {
+            bounds
+        }
+    }
+
+    /// Maps a point of the layout area of `bounds` to the display.
+    pub(crate) fn to_display(self, point: Point, bounds: Rectangle) -> Point {
+        let size = RectExt::size(bounds);
+        let right = size.width as i32 - 1;
+        let bottom = size.height as i32 - 1;
+        let Point { x, y } = point - bounds.top_left;
+
+        let rotated = match self {
+            Orientation::Horizontal => return point,
+            Orientation::Rotated90 => Point::new(right - y, x),
+            Orientation::Rotated180 => Point::new(right - x, bottom - y),
+            Orientation::Rotated270 => Point::new(y, bottom - x),
+        };
+
+        bounds.top_left + rotated
+    }
+
+    /// Maps a point of the display to the layout area of `bounds`.
+    pub(crate) fn to_layout(self, point: Point, bounds: Rectangle) -> Point {
+        let size = RectExt::size(bounds);
+        let right = size.width as i32 - 1;
+        let bottom = size.height as i32 - 1;
+        let Point { x, y } = point - bounds.top_left;
+
+        let rotated = match self {
+            Orientation::Horizontal => return point,
+            Orientation::Rotated90 => Point::new(y, right - x),
+            Orientation::Rotated180 => Point::new(right - x, bottom - y),
+            Orientation::Rotated270 => Point::new(bottom - y, x),
+        };
+
+        bounds.top_left + rotated
+    }
+
+    /// Maps an area of the layout area of `bounds` to the display.
+    pub(crate) fn rect_to_display(self, rect: Rectangle, bounds: Rectangle) -> Rectangle {
+        self.map_rect(rect, |point| self.to_display(point, bounds))
+    }
+
+    /// Maps an area of the display to the layout area of `bounds`.
+    pub(crate) fn rect_to_layout(self, rect: Rectangle, bounds: Rectangle) -> Rectangle {
+        self.map_rect(rect, |point| self.to_layout(point, bounds))
+    }
+
+    fn map_rect(self, rect: Rectangle, map: impl Fn(Point) -> Point) -> Rectangle {
+        if self == Orientation::Horizontal {
+            return rect;
+        }
+
+        let size = RectExt::size(rect);
+        if size.width == 0 || size.height == 0 {
+            // empty areas only have a position
+            return Rectangle::from_size(map(rect.top_left), Size::zero());
+        }
+
+        Rectangle::new(map(rect.top_left), map(rect.bottom_right)).into_well_formed()
+    }
+}
+
+/// Inner padding of a [`TextBox`].
+///
+/// Padding defines empty space between the edges of the bounding box and the text, in pixels.
+/// The text is laid out inside the bounding box shrunk by the padding, but the size of the
+/// [`StyledTextBox`] remains unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use embedded_text::{prelude::*, style::Padding};
+/// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+///
+/// let style = TextBoxStyleBuilder::new(Font6x8)
+///     .text_color(BinaryColor::On)
+///     .padding(Padding {
+///         top: 2,
+///         right: 4,
+///         bottom: 2,
+///         left: 4,
+///     })
+///     .build();
+/// ```
+///
+/// [`TextBox`]: ../struct.TextBox.html
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Padding {
+    /// Space above the text.
+    pub top: u32,
+
+    /// Space to the right of the text.
+    pub right: u32,
+
+    /// Space below the text.
+    pub bottom: u32,
+
+    /// Space to the left of the text.
+    pub left: u32,
+}
+
+impl Padding {
+    /// Creates a `Padding` object with the same amount of space on every side.
+    #[inline]
+    #[must_use]
+    pub fn uniform(padding: u32) -> Self {
+        Self {
+            top: padding,
+            right: padding,
+            bottom: padding,
+            left: padding,
+        }
+    }
+
+    /// Returns the total horizontal padding.
+    #[inline]
+    #[must_use]
+    pub fn horizontal(self) -> u32 {
+        self.left.saturating_add(self.right)
+    }
+
+    /// Returns the total vertical padding.
+    #[inline]
+    #[must_use]
+    pub fn vertical(self) -> u32 {
+        self.top.saturating_add(self.bottom)
+    }
+
+    /// Shrinks a rectangle by the padding.
+    ///
+    /// If the padding is larger than the rectangle, the returned rectangle will have zero width
+    /// and/or height.
+    #[inline]
+    #[must_use]
+    pub fn apply(self, bounds: Rectangle) -> Rectangle {
+        fn to_i32(n: u32) -> i32 {
+            n.min(i32::max_value() as u32) as i32
+        }
+
+        let left = bounds.top_left.x.saturating_add(to_i32(self.left));
+        let top = bounds.top_left.y.saturating_add(to_i32(self.top));
+        let right = bounds.bottom_right.x.saturating_sub(to_i32(self.right));
+        let bottom = bounds.bottom_right.y.saturating_sub(to_i32(self.bottom));
+
+        // Rectangles are inclusive, so a zero sized rectangle ends one pixel before it starts.
+        Rectangle::new(
+            Point::new(left, top),
+            Point::new(
+                right.max(left.saturating_sub(1)),
+                bottom.max(top.saturating_sub(1)),
+            ),
+        )
+    }
+}
+
+/// The measured size of a text.
+///
+/// See [`TextBoxStyle::measure_text`] for more information.
+///
+/// [`TextBoxStyle::measure_text`]: struct.TextBoxStyle.html#method.measure_text
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct TextMetrics {
+    /// The width of the widest line of text, in pixels.
+    pub width: u32,
+
+    /// The height of the text, in pixels.
+    pub height: u32,
+
+    /// The number of lines the text is laid out in.
+    ///
+    /// Lines that are overwritten because of a carriage return are not counted separately.
+    pub line_count: u32,
+}
+
 /// Styling options of a [`TextBox`].
 ///
 /// `TextBoxStyle` contains the `Font`, foreground and background `PixelColor`, line spacing,
@@ -261,6 +806,30 @@ where
     /// Desired space between lines, in pixels
     pub line_spacing: i32,
 
+    /// Desired space after each character, in pixels
+    pub letter_spacing: i32,
+
+    /// Desired space between paragraphs, in addition to the line spacing, in pixels
+    pub paragraph_spacing: i32,
+
+    /// Indentation of the first line of each paragraph, in pixels, see
+    /// [`TextBoxStyleBuilder::first_line_indent`]
+    ///
+    /// [`TextBoxStyleBuilder::first_line_indent`]: builder/struct.TextBoxStyleBuilder.html#method.first_line_indent
+    pub first_line_indent: i32,
+
+    /// Indentation of every line of a paragraph except the first one, in pixels
+    pub hanging_indent: u32,
+
+    /// Desired width of a space character, in pixels
+    pub word_spacing: u32,
+
+    /// Maximum width of a stretched space character in justified text, in pixels
+    pub max_word_spacing: Option<u32>,
+
+    /// The width that justified lines are stretched to
+    pub justify_width: JustifyWidth,
+
     /// Desired column width for tabs
     pub tab_size: TabSize<F>,
 
@@ -269,6 +838,85 @@ where
 
     /// If true, the text will be crossed out
     pub strikethrough: bool,
+
+    /// If true, the text will be drawn using the bold font
+    pub bold: bool,
+
+    /// The font of bold text, see [`BoldFont`]
+    ///
+    /// [`BoldFont`]: bold_font/struct.BoldFont.html
+    pub bold_font: Option<BoldFont>,
+
+    /// Space between the bounding box and the text
+    pub padding: Padding,
+
+    /// Line breaking strategy
+    pub break_strategy: BreakStrategy,
+
+    /// How to display words that are wider than a line
+    pub horizontal_overflow: HorizontalOverflow,
+
+    /// Base direction of the lines
+    pub line_direction: Direction,
+
+    /// Vertical position of the glyphs inside the lines
+    pub glyph_alignment: GlyphAlignment,
+
+    /// Orientation of the text on the display
+    pub orientation: Orientation,
+
+    /// Direction of the lines and of the characters inside them
+    pub writing_mode: WritingMode,
+
+    /// How to display text that doesn't fit into the text box
+    pub text_overflow: TextOverflow,
+
+    /// The string that replaces the end of the text if it doesn't fit into the text box, see
+    /// [`TextOverflow::Ellipsis`]
+    ///
+    /// [`TextOverflow::Ellipsis`]: enum.TextOverflow.html#variant.Ellipsis
+    pub ellipsis: &'static str,
+
+    /// If true, text is only cut off at the end of a word, see
+    /// [`TextBoxStyleBuilder::truncate_at_word_boundary`]
+    ///
+    /// [`TextBoxStyleBuilder::truncate_at_word_boundary`]: builder/struct.TextBoxStyleBuilder.html#method.truncate_at_word_boundary
+    pub truncate_at_word_boundary: bool,
+
+    /// If true, blank lines are displayed as a single empty line
+    pub collapse_blank_lines: bool,
+
+    /// The character displayed at the end of lines that are wrapped at a soft hyphen
+    pub break_character: Option<char>,
+
+    /// Characters that lines may be wrapped after, in addition to spaces and soft hyphens
+    pub break_after: &'static [char],
+
+    /// Characters that must not start a line, see [`TextBoxStyleBuilder::no_break_before`]
+    ///
+    /// [`TextBoxStyleBuilder::no_break_before`]: builder/struct.TextBoxStyleBuilder.html#method.no_break_before
+    pub no_break_before: &'static [char],
+
+    /// Characters that must not end a line, see [`TextBoxStyleBuilder::no_break_after`]
+    ///
+    /// [`TextBoxStyleBuilder::no_break_after`]: builder/struct.TextBoxStyleBuilder.html#method.no_break_after
+    pub no_break_after: &'static [char],
+
+    /// Palette slot of the text color, see [`ColorRef`]
+    ///
+    /// [`ColorRef`]: color/enum.ColorRef.html
+    pub text_color_slot: Option<u8>,
+
+    /// Palette slot of the background color, see [`ColorRef`]
+    ///
+    /// [`ColorRef`]: color/enum.ColorRef.html
+    pub background_color_slot: Option<u8>,
+
+    /// Maps the colors of ANSI escape sequences to display colors, see
+    /// [`TextBoxStyleBuilder::ansi_palette`]
+    ///
+    /// [`TextBoxStyleBuilder::ansi_palette`]: builder/struct.TextBoxStyleBuilder.html#method.ansi_palette
+    pub ansi_palette: Option<AnsiPalette<C>>,
 }
 
 impl<C, F, A, V, H> TextBoxStyle<C, F, A, V, H>
@@ -294,9 +942,36 @@ where
             vertical_alignment,
             height_mode,
             line_spacing: 0,
+            letter_spacing: 0,
+            paragraph_spacing: 0,
+            first_line_indent: 0,
+            hanging_indent: 0,
+            word_spacing: F::total_char_width(' '),
+            max_word_spacing: None,
+            justify_width: JustifyWidth::default(),
             tab_size: TabSize::default(),
             underlined: false,
             strikethrough: false,
+            bold: false,
+            bold_font: None,
+            padding: Padding::default(),
+            break_strategy: BreakStrategy::default(),
+            horizontal_overflow: HorizontalOverflow::default(),
+            line_direction: Direction::default(),
+            glyph_alignment: GlyphAlignment::default(),
+            orientation: Orientation::default(),
+            writing_mode: WritingMode::default(),
+            text_overflow: TextOverflow::default(),
+            ellipsis: "…",
+            truncate_at_word_boundary: false,
+            collapse_blank_lines: false,
+            break_character: Some('-'),
+            break_after: &[],
+            no_break_before: &[],
+            no_break_after: &[],
+            text_color_slot: None,
+            background_color_slot: None,
+            ansi_palette: None,
         }
     }
 
@@ -314,15 +989,63 @@ where
             vertical_alignment,
             height_mode,
             line_spacing: 0,
+            letter_spacing: 0,
+            paragraph_spacing: 0,
+            first_line_indent: 0,
+            hanging_indent: 0,
+            word_spacing: F::total_char_width(' '),
+            max_word_spacing: None,
+            justify_width: JustifyWidth::default(),
             tab_size: TabSize::default(),
             underlined: false,
             strikethrough: false,
+            bold: false,
+            bold_font: None,
+            padding: Padding::default(),
+            break_strategy: BreakStrategy::default(),
+            horizontal_overflow: HorizontalOverflow::default(),
+            line_direction: Direction::default(),
+            glyph_alignment: GlyphAlignment::default(),
+            orientation: Orientation::default(),
+            writing_mode: WritingMode::default(),
+            text_overflow: TextOverflow::default(),
+            ellipsis: "…",
+            truncate_at_word_boundary: false,
+            collapse_blank_lines: false,
+            break_character: Some('-'),
+            break_after: &[],
+            no_break_before: &[],
+            no_break_after: &[],
+            text_color_slot: None,
+            background_color_slot: None,
+            ansi_palette: None,
+        }
+    }
+
+    /// Returns a copy of the style with the palette slots replaced by the colors of `palette`.
+    ///
+    /// Slots that are not present in `palette` are transparent.
+    #[inline]
+    #[must_use]
+    pub fn with_palette(&self, palette: &[C]) -> Self {
+        let mut style = *self;
+
+        if let Some(slot) = self.text_color_slot {
+            style.text_style.text_color = ColorRef::Slot(slot).resolve(palette);
         }
+        if let Some(slot) = self.background_color_slot {
+            style.text_style.background_color = ColorRef::Slot(slot).resolve(palette);
+        }
+
+        style
     }
 
     /// Measure the width and count spaces in a single line of text.
     ///
-    /// Returns (width, rendered space count, carried token)
+    /// Returns (width, rendered space count, carried token, underlined)
+    ///
+    /// Only the spaces after the last tab of the line are counted, because the position of the
+    /// text before a tab does not affect the position of the text after it.
     ///
     /// Instead of peeking ahead when processing tokens, this function advances the parser before
     /// processing a token. If a token opens a new line, it will be returned as the carried token.
@@ -332,37 +1055,149 @@ where
     pub fn measure_line<'a>(
         &self,
         parser: &mut Parser<'a>,
-        carried_token: Option<Token<'a>>,
+        carried_token: Option<CarriedToken<'a>>,
         max_line_width: u32,
-    ) -> (u32, u32, Option<Token<'a>>, bool) {
-        let cursor: Cursor<F> = Cursor::new(
-            Rectangle::new(
-                Point::zero(),
-                Point::new(
-                    max_line_width.saturating_sub(1) as i32,
-                    F::CHARACTER_SIZE.height.saturating_sub(1) as i32,
-                ),
-            ),
-            self.line_spacing,
-        );
-        let mut iter: LineElementIterator<'_, F, _, A> = LineElementIterator::new(
-            parser.clone(),
+    ) -> (u32, u32, Option<CarriedToken<'a>>, bool) {
+        let (width, _, spaces, carried, underlined, _, _) =
+            self.measure_line_segments(parser, carried_token, max_line_width);
+
+        (width, spaces, carried, underlined)
+    }
+
+    /// Lays out the next line of text, without drawing it.
+    ///
+    /// This is the line breaking step that the text box renderer and the measuring methods use, so
+    /// custom renderers can break lines at the same positions as the text box. `carried` is the way
+    /// the previous line ended, `None` for the first line. The parser and `carried` are updated to
+    /// the start of the next line.
+    ///
+    /// The text is laid out using the style of the text box. Style changes made by escape
+    /// sequences in the line are not carried over to the next line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    /// use embedded_text::{parser::Parser, style::builder::TextBoxStyleBuilder};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let mut parser = Parser::parse("Hello, World!");
+    /// let mut carried = None;
+    ///
+    /// // the space at the end of the first line is not drawn
+    /// let first = style.fit_next_line(&mut parser, &mut carried, 60);
+    /// assert_eq!(first.bytes, 0..7);
+    /// assert_eq!(first.width, 36);
+    ///
+    /// let second = style.fit_next_line(&mut parser, &mut carried, 60);
+    /// assert_eq!(second.bytes, 7..13);
+    /// assert_eq!(second.end, None);
+    /// ```
+    #[inline]
+    pub fn fit_next_line<'a>(
+        &self,
+        parser: &mut Parser<'a>,
+        carried: &mut Option<CarriedToken<'a>>,
+        max_line_width: u32,
+    ) -> LineResult<'a> {
+        *parser = self.line_breaking(parser.clone());
+        let start = line_start_offset(*carried, parser);
+
+        self.skip_blank_lines(*carried, parser);
+        let (width, _, _, end, _, _, _) =
+            self.measure_line_segments(parser, *carried, max_line_width);
+        *carried = end;
+
+        LineResult {
+            bytes: start..line_start_offset(end, parser),
+            width,
+            end,
+        }
+    }
+
+    /// Returns an iterator that lays out the next line starting at `cursor` without stretching
+    /// the spaces, the way lines are measured.
+    pub(crate) fn measuring_iterator<'a>(
+        &self,
+        parser: Parser<'a>,
+        carried_token: Option<CarriedToken<'a>>,
+        cursor: Cursor<F>,
+    ) -> LineElementIterator<'a, F> {
+        LineElementIterator::new(
+            parser,
             cursor,
-            UniformSpaceConfig::default(),
-            carried_token.clone(),
+            UniformSpaceConfig::new(self.word_spacing),
+            carried_token,
             self.tab_size,
-        );
+            self.break_strategy,
+            self.alignment,
+        )
+        .with_horizontal_overflow(self.horizontal_overflow)
+        .with_word_boundary_truncation(self.truncate_at_word_boundary)
+        .with_break_character(self.break_character)
+    }
+
+    /// Measures a line like [`measure_line`], but also returns the number of spaces that are
+    /// rendered before the last tab of the line.
+    ///
+    /// Returns (width, spaces before the last tab, spaces after the last tab, carried token,
+    /// underlined, bold at the end of the line, underline at the end of the line)
+    ///
+    /// The underline at the end of the line is `None` if it was reset to the default of the
+    /// style.
+    ///
+    /// [`measure_line`]: #method.measure_line
+    pub(crate) fn measure_line_segments<'a>(
+        &self,
+        parser: &mut Parser<'a>,
+        carried_token: Option<CarriedToken<'a>>,
+        max_line_width: u32,
+    ) -> (
+        u32,
+        u32,
+        u32,
+        Option<CarriedToken<'a>>,
+        bool,
+        bool,
+        Option<bool>,
+    ) {
+        let cursor: Cursor<F> = Cursor::new(
+            Rectangle::from_size(
+                Point::zero(),
+                Size::new(max_line_width, F::CHARACTER_SIZE.height),
+            ),
+            self.line_spacing,
+            self.letter_spacing,
+            self.paragraph_spacing,
+        )
+        .with_bold_font(self.bold_font, self.bold);
+        let mut iter = self.measuring_iterator(parser.clone(), carried_token, cursor);
 
         let mut current_width = 0;
         let mut last_spaces = 0;
         let mut total_spaces = 0;
+        let mut rendered_spaces = 0;
+        let mut spaces_before_tab = 0;
         let mut underlined = self.underlined;
+        let mut underline_at_end = Some(self.underlined);
         while let Some(token) = iter.next() {
             match token {
-                RenderElement::Space(_, count) => {
-                    if A::ENDING_SPACES {
+                RenderElement::Space(space_width, count) => {
+                    rendered_spaces += count;
+                    if iter.tab {
+                        // text after a tab starts at the tab stop, start counting again
+                        spaces_before_tab = rendered_spaces;
+                        total_spaces = 0;
+                        last_spaces = 0;
+                    }
+
+                    if self.alignment.ending_spaces() {
                         // only track width if spaces are rendered at the end of a line
-                        current_width = iter.cursor.position.x;
+                        // the cursor may already be in the next line, so use the element position
+                        current_width = iter.pos.x + space_width as i32;
 
                         // in this case, count all spaces
                         total_spaces += count;
@@ -374,19 +1209,32 @@ where
                 }
 
                 RenderElement::PrintedCharacter(c) => {
-                    // the current width is always the position where the cursor is (left is 0)
-                    current_width = iter.cursor.position.x;
+                    if iter.overstruck == Some('_') {
+                        underlined = true;
+                    }
 
-                    if c == '\u{A0}' {
+                    // the current width is the end of the rightmost character (left is 0),
+                    // without the letter spacing after it
+                    // the cursor may already be in the next line, e.g. after an inserted hyphen
+                    let char_end = iter.pos.x + iter.cursor.font_char_width(c) as i32;
+                    // a clipped character may not fit into the line completely
+                    current_width = char_end.min(max_line_width as i32).max(current_width);
+
+                    if is_non_breaking_space(c) {
                         total_spaces += 1;
-                    } else if !A::ENDING_SPACES {
-                        // if ENDING_SPACES is true, spaces have already been counted and
+                    } else if !self.alignment.ending_spaces() {
+                        // if ending spaces are rendered, spaces have already been counted and
                         // last_spaces is 0
                         total_spaces = last_spaces;
                     }
                 }
 
-                RenderElement::Sgr(Sgr::Underline) => underlined = true,
+                RenderElement::Sgr(Sgr::Underline) => {
+                    underlined = true;
+                    underline_at_end = Some(true);
+                }
+                RenderElement::Sgr(Sgr::UnderlineOff) => underline_at_end = Some(false),
+                RenderElement::Sgr(Sgr::Reset) => underline_at_end = None,
 
                 // Ignore color changes
                 _ => {}
@@ -394,12 +1242,106 @@ where
         }
 
         let carried = iter.remaining_token();
+        let bold = iter.cursor.bold_font().is_some();
         *parser = iter.parser;
-        (current_width as u32, total_spaces, carried, underlined)
+        (
+            current_width as u32,
+            spaces_before_tab,
+            total_spaces,
+            carried,
+            underlined,
+            bold,
+            underline_at_end,
+        )
+    }
+
+    /// Measures the width of the widest line of text when rendered using a given width.
+    ///
+    /// # Example: measure width of text when rendered using a 6x8 font and 72px width.
+    ///
+    /// ```rust
+    /// # use embedded_text::style::builder::TextBoxStyleBuilder;
+    /// # use embedded_graphics::fonts::Font6x8;
+    /// # use embedded_graphics::pixelcolor::BinaryColor;
+    /// #
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let width = style.measure_text_width("Lorem Ipsum is simply dummy text", 72);
+    ///
+    /// // Expect 3 lines of text, wrapped in something like the following:
+    ///
+    /// // |Lorem Ipsum |
+    /// // |is simply   |
+    /// // |dummy text  |
+    ///
+    /// assert_eq!(11 * 6, width);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn measure_text_width(&self, text: &str, max_width: u32) -> u32 {
+        self.measure_width(Parser::parse(text), max_width, &[])
+    }
+
+    /// Measures the width of the widest line of the text processed by `parser`.
+    pub(crate) fn measure_width(
+        &self,
+        parser: Parser<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+    ) -> u32 {
+        let (_, _, _, width) = self.count_lines(parser, max_width, alignment_spans);
+
+        width
+    }
+
+    /// Measures the size of text when rendered using a given width.
+    ///
+    /// This method measures the width, height and number of lines of the text in a single pass.
+    /// The width of lines follows the rules of the horizontal alignment, so for example trailing
+    /// spaces are only measured if the alignment renders them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    /// use embedded_text::style::{builder::TextBoxStyleBuilder, TextMetrics};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let metrics = style.measure_text("Lorem Ipsum is simply dummy text", 72);
+    ///
+    /// assert_eq!(
+    ///     metrics,
+    ///     TextMetrics {
+    ///         width: 11 * 6,
+    ///         height: 3 * 8,
+    ///         line_count: 3,
+    ///     }
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn measure_text(&self, text: &str, max_width: u32) -> TextMetrics {
+        let (n_lines, n_paragraphs, underlined, width) =
+            self.count_lines(Parser::parse(text), max_width, &[]);
+
+        TextMetrics {
+            width,
+            height: self.height_of_lines(n_lines, n_paragraphs, underlined),
+            line_count: n_lines,
+        }
     }
 
     /// Measures text height when rendered using a given width.
     ///
+    /// Text is laid out until a character is found that doesn't fit into an empty line. That
+    /// character and the rest of the text are not displayed, so if `max_width` is narrower than
+    /// the first character, the measured height is 0.
+    ///
     /// # Example: measure height of text when rendered using a 6x8 font and 72px width.
     ///
     /// ```rust
@@ -431,42 +1373,528 @@ where
     #[inline]
     #[must_use]
     pub fn measure_text_height(&self, text: &str, max_width: u32) -> u32 {
-        let mut n_lines = 0_i32;
-        let mut parser = Parser::parse(text);
+        self.measure_height(Parser::parse(text), max_width, &[])
+    }
+
+    /// Measures the height of text that is split into multiple parts when rendered using a given
+    /// width.
+    ///
+    /// The parts are measured as if they were concatenated. This method can be used to measure
+    /// the text of a [`TextBox`] that was created using [`TextBox::from_parts`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::builder::TextBoxStyleBuilder;
+    /// # use embedded_graphics::fonts::Font6x8;
+    /// # use embedded_graphics::pixelcolor::BinaryColor;
+    /// #
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let parts = ["Lorem Ipsum is simply ", "dummy", " text"];
+    ///
+    /// assert_eq!(
+    ///     style.measure_text_height("Lorem Ipsum is simply dummy text", 72),
+    ///     style.measure_parts_height(&parts, 72)
+    /// );
+    /// ```
+    ///
+    /// [`TextBox`]: ../struct.TextBox.html
+    /// [`TextBox::from_parts`]: ../struct.TextBox.html#method.from_parts
+    #[inline]
+    #[must_use]
+    pub fn measure_parts_height(&self, parts: &[&str], max_width: u32) -> u32 {
+        self.measure_height(Parser::parse_parts(parts), max_width, &[])
+    }
+
+    /// Measures `text` once, so it can be drawn repeatedly without measuring it again.
+    ///
+    /// `max_width` is the width of the text area, which is the width of the text box without the
+    /// horizontal padding. Use [`StyledTextBox::from_layout`] to create text boxes from the
+    /// returned [`TextLayout`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*};
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .height_mode(FitToText)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// let layout = style.layout("Lorem Ipsum is simply dummy text", 72);
+    /// assert_eq!(layout.line_count(), 3);
+    /// assert_eq!(layout.height(), 24);
+    ///
+    /// // no need to measure the text again for every frame
+    /// let text_box = StyledTextBox::from_layout(
+    ///     &layout,
+    ///     Rectangle::new(Point::zero(), Point::new(71, 0)),
+    ///     style,
+    /// );
+    /// assert_eq!(text_box.size(), Size::new(72, 24));
+    /// ```
+    ///
+    /// [`StyledTextBox::from_layout`]: ../struct.StyledTextBox.html#method.from_layout
+    /// [`TextLayout`]: struct.TextLayout.html
+    #[inline]
+    #[must_use]
+    pub fn layout<'a>(&self, text: &'a str, max_width: u32) -> TextLayout<'a> {
+        let text_box = TextBox::new(text, Rectangle::new(Point::zero(), Point::zero()));
+        let (n_lines, n_paragraphs, underlined, _) =
+            self.count_lines(text_box.parser(), max_width, &[]);
+
+        TextLayout {
+            text,
+            key: self.measurement_key(&text_box, max_width, &[]),
+            height: self.height_of_lines(n_lines, n_paragraphs, underlined),
+            line_count: n_lines,
+        }
+    }
+
+    /// Measures the height of the text processed by `parser`.
+    pub(crate) fn measure_height(
+        &self,
+        parser: Parser<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+    ) -> u32 {
+        let (n_lines, n_paragraphs, underlined, _) =
+            self.count_lines(parser, max_width, alignment_spans);
+
+        self.height_of_lines(n_lines, n_paragraphs, underlined)
+    }
+
+    /// Measures the height of the first lines of the text processed by `parser` that fit into
+    /// `max_height` completely.
+    pub(crate) fn measure_displayed_height(
+        &self,
+        parser: Parser<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+        max_height: u32,
+    ) -> u32 {
+        let (n_lines, n_paragraphs, underlined, _) =
+            self.count_lines_limited(parser, max_width, alignment_spans, max_height);
+
+        self.height_of_lines(n_lines, n_paragraphs, underlined)
+            .min(max_height)
+    }
+
+    /// Sets up `parser` to wrap lines according to the style.
+    pub(crate) fn line_breaking<'a>(&self, parser: Parser<'a>) -> Parser<'a> {
+        parser
+            .with_break_after(self.break_after)
+            .with_no_break_before(self.no_break_before)
+            .with_no_break_after(self.no_break_after)
+    }
+
+    /// Returns the vertical offset of the glyphs from the top of their line, in pixels.
+    pub(crate) fn glyph_offset(&self) -> i32 {
+        match self.glyph_alignment {
+            GlyphAlignment::Top => 0,
+            GlyphAlignment::Baseline(offset) => offset as i32,
+            GlyphAlignment::Bottom => self.line_spacing.max(0),
+        }
+    }
+
+    /// Returns the height of `n_lines` lines of text, `n_paragraphs` of which start a new
+    /// paragraph.
+    fn height_of_lines(&self, n_lines: u32, n_paragraphs: u32, underlined: bool) -> u32 {
+        if n_lines == 0 {
+            return 0;
+        }
+
+        let n_lines = n_lines as i32;
+        let n_paragraphs = n_paragraphs as i32;
+
+        let line_step = F::CHARACTER_SIZE.height as i32 + self.line_spacing;
+        let paragraph_step = (line_step + self.paragraph_spacing).max(0);
+
+        let mut height = (n_lines * F::CHARACTER_SIZE.height as i32
+            + (n_lines - 1).max(0) * self.line_spacing
+            + n_paragraphs * (paragraph_step - line_step))
+            .max(0) as u32;
+
+        if underlined {
+            height += 1;
+        }
+
+        // the glyphs of the last line are moved down
+        height += self.glyph_offset() as u32;
+
+        height
+    }
+
+    /// Measures the number of lines the text takes up when wrapped into `max_width`.
+    ///
+    /// Lines that are overwritten because of a carriage return are not counted separately.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    /// use embedded_text::style::builder::TextBoxStyleBuilder;
+    ///
+    /// let style = TextBoxStyleBuilder::new(Font6x8)
+    ///     .text_color(BinaryColor::On)
+    ///     .build();
+    ///
+    /// assert_eq!(2, style.measure_line_count("Hello, World!", 72));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn measure_line_count(&self, text: &str, max_width: u32) -> u32 {
+        self.line_count(Parser::parse(text), max_width, &[])
+    }
+
+    /// Measures the number of lines of the text processed by `parser`.
+    pub(crate) fn line_count(
+        &self,
+        parser: Parser<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+    ) -> u32 {
+        let (n_lines, _, _, _) = self.count_lines(parser, max_width, alignment_spans);
+
+        n_lines
+    }
+
+    /// Skips the whitespace of blank lines if the style collapses them.
+    ///
+    /// If the line that starts after `carried` is blank, the parser is moved to the newline of the
+    /// last blank line of the run, so the next line is laid out as a single empty line. Blank
+    /// lines at the end of the text are skipped completely.
+    pub(crate) fn skip_blank_lines<'a>(
+        &self,
+        carried: Option<CarriedToken<'a>>,
+        parser: &mut Parser<'a>,
+    ) {
+        if !self.collapse_blank_lines
+            || (carried.is_some() && carried != Some(CarriedToken::NewLine))
+        {
+            return;
+        }
+
+        let mut lookahead = parser.clone();
+        loop {
+            let before = lookahead.clone();
+            match lookahead.next() {
+                Some(Token::Whitespace(_))
+                | Some(Token::SizedSpace(_))
+                | Some(Token::Tab)
+                | Some(Token::Break(None)) => {}
+                Some(Token::NewLine) => *parser = before,
+                Some(_) => return,
+                None => {
+                    *parser = lookahead;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns the indentation of the line that starts after `carried`, or `None` if the line is
+    /// drawn over the previous one after a carriage return and keeps its indentation.
+    pub(crate) fn line_indent(&self, carried: Option<CarriedToken<'_>>) -> Option<u32> {
+        match carried {
+            None | Some(CarriedToken::NewLine) | Some(CarriedToken::FormFeed) => {
+                Some(self.first_line_indent.max(0) as u32)
+            }
+            Some(CarriedToken::CarriageReturn) => None,
+            Some(_) => {
+                // a negative first line indentation indents the other lines instead
+                let outdent = self.first_line_indent.min(0).wrapping_neg() as u32;
+                Some(self.hanging_indent.saturating_add(outdent))
+            }
+        }
+    }
+
+    /// Returns a copy of the style that uses the alignment of the first span of `alignment_spans`
+    /// that contains `offset`.
+    pub(crate) fn with_alignment_at(
+        &self,
+        alignment_spans: &[(Range<usize>, A)],
+        offset: usize,
+    ) -> Self {
+        let mut style = *self;
+        if let Some((_, alignment)) = alignment_spans
+            .iter()
+            .find(|(range, _)| range.contains(&offset))
+        {
+            style.alignment = *alignment;
+        }
+
+        style
+    }
+
+    /// Returns the number of lines, the number of lines that start a new paragraph, whether the
+    /// last line is underlined and the width of the widest line.
+    ///
+    /// Each line is laid out using the alignment of the span in `alignment_spans` that contains
+    /// the start of the line, because the alignment decides whether spaces are rendered at the
+    /// ends of the line.
+    fn count_lines(
+        &self,
+        parser: Parser<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+    ) -> (u32, u32, bool, u32) {
+        self.count_lines_limited(parser, max_width, alignment_spans, u32::max_value())
+    }
+
+    /// Counts the lines like [`count_lines`], but stops before the first line that doesn't fit
+    /// into `max_height` completely.
+    ///
+    /// [`count_lines`]: #method.count_lines
+    fn count_lines_limited(
+        &self,
+        parser: Parser<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+        max_height: u32,
+    ) -> (u32, u32, bool, u32) {
+        let mut parser = self.line_breaking(parser);
+        let mut width = 0;
+        let mut n_lines = 0;
+        let mut n_paragraphs = 0;
         let mut carry = None;
+        let mut bold = self.bold;
+        let mut underline = self.underlined;
+        let mut indent = 0;
+        let mut last_underlined = false;
 
         loop {
-            let (w, _, t, underlined) = self.measure_line(&mut parser, carry.clone(), max_width);
+            if let Some(line_indent) = self.line_indent(carry) {
+                indent = line_indent.min(max_width.saturating_sub(1));
+            }
 
-            if (w != 0 || t.is_some()) && carry != Some(Token::CarriageReturn) {
+            self.skip_blank_lines(carry, &mut parser);
+            let mut style = self.with_alignment_at(alignment_spans, parser.offset());
+            // bold and underlined text may continue in the next line
+            style.bold = bold;
+            style.underlined = underline;
+            let (w, _, _, t, underlined, bold_at_end, underline_at_end) =
+                style.measure_line_segments(&mut parser, carry, max_width - indent);
+            bold = bold_at_end;
+            underline = underline_at_end.unwrap_or(self.underlined);
+            // the default underline applies to the rest of the line after a reset
+            let underlined = underlined || (underline_at_end.is_none() && self.underlined);
+
+            let counted = (w != 0 || t.is_some()) && carry != Some(CarriedToken::CarriageReturn);
+            if counted {
                 // something was in this line, increment height
                 // if last carried token was a carriage return, we already counted the height
+                let starts_paragraph =
+                    carry == Some(CarriedToken::NewLine) || carry == Some(CarriedToken::FormFeed);
+                let n_paragraphs_after = n_paragraphs + starts_paragraph as u32;
+                if self.height_of_lines(n_lines + 1, n_paragraphs_after, false) > max_height {
+                    return (n_lines, n_paragraphs, last_underlined, width);
+                }
+
                 n_lines += 1;
+                n_paragraphs = n_paragraphs_after;
             }
 
-            if t.is_none() {
-                let mut height = (n_lines * F::CHARACTER_SIZE.height as i32
-                    + n_lines.saturating_sub(1) * self.line_spacing)
-                    as u32;
-
-                if underlined {
-                    height += 1;
-                }
+            if w != 0 {
+                width = width.max(indent + w);
+            }
 
-                return height;
+            if t.is_none() {
+                // an empty last line doesn't hide the underline of the line before it
+                let underlined = underlined || (!counted && last_underlined);
+                return (n_lines, n_paragraphs, underlined, width);
             }
 
+            if counted {
+                last_underlined = underlined;
+            }
             carry = t;
         }
     }
+
+    /// Returns the inputs that the height of the text of the given `TextBox` depends on.
+    pub(crate) fn measurement_key(
+        &self,
+        text_box: &TextBox<'_>,
+        max_width: u32,
+        alignment_spans: &[(Range<usize>, A)],
+    ) -> MeasurementKey {
+        MeasurementKey {
+            text: text_box.text.as_ptr() as usize,
+            text_len: text_box.text.len(),
+            continuation: text_box.continuation.as_ptr() as usize,
+            continuation_len: text_box.continuation.len(),
+            alignment_spans: alignment_spans.as_ptr() as usize,
+            alignment_spans_len: alignment_spans.len(),
+            overstrike: text_box.overstrike,
+            #[cfg(feature = "hyphenation")]
+            hyphenation: text_box.hyphenation,
+            max_width,
+            line_spacing: self.line_spacing,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            first_line_indent: self.first_line_indent,
+            hanging_indent: self.hanging_indent,
+            word_spacing: self.word_spacing,
+            tab_width: self.tab_size.width,
+            tab_stops: self.tab_size.stops,
+            underlined: self.underlined,
+            bold: self.bold,
+            bold_font: self.bold_font,
+            glyph_offset: self.glyph_offset(),
+            break_strategy: self.break_strategy,
+            horizontal_overflow: self.horizontal_overflow,
+            starting_spaces: self.alignment.starting_spaces(),
+            ending_spaces: self.alignment.ending_spaces(),
+            collapse_blank_lines: self.collapse_blank_lines,
+            break_character: self.break_character,
+            break_after: self.break_after,
+            no_break_before: self.no_break_before,
+            no_break_after: self.no_break_after,
+        }
+    }
+}
+
+impl<C, F> From<TextStyle<C, F>>
+    for TextBoxStyle<C, F, LeftAligned, TopAligned, Exact<FullRowsOnly>>
+where
+    C: PixelColor,
+    F: Font + Copy,
+{
+    /// Creates a `TextBoxStyle` with the same default settings as [`TextBoxStyleBuilder::new`].
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor};
+    ///
+    /// let style = TextBoxStyle::from(TextStyle::new(Font6x8, BinaryColor::On));
+    ///
+    /// assert_eq!(style, TextBoxStyleBuilder::new(Font6x8).text_color(BinaryColor::On).build());
+    /// ```
+    ///
+    /// [`TextBoxStyleBuilder::new`]: builder/struct.TextBoxStyleBuilder.html#method.new
+    #[inline]
+    fn from(text_style: TextStyle<C, F>) -> Self {
+        Self::from_text_style(text_style, LeftAligned, TopAligned, Exact(FullRowsOnly))
+    }
+}
+
+/// The measured layout of a text, created by [`TextBoxStyle::layout`].
+///
+/// A `TextLayout` remembers the text, the width and the style that it was measured with. A
+/// [`StyledTextBox`] created using [`StyledTextBox::from_layout`] uses the stored height instead
+/// of measuring the text, which makes [`FitToText`], vertical alignment and
+/// [`StyledTextBox::is_truncated`] cheap for large, static texts. If the text box is created with a
+/// different text, text area width or style, the layout no longer applies and the text is measured
+/// again.
+///
+/// The layout doesn't store the position of every line break, because that would need an
+/// allocation. Lines are still broken while the text is drawn, which is needed to place the glyphs
+/// anyway.
+///
+/// [`TextBoxStyle::layout`]: struct.TextBoxStyle.html#method.layout
+/// [`StyledTextBox`]: ../struct.StyledTextBox.html
+/// [`StyledTextBox::from_layout`]: ../struct.StyledTextBox.html#method.from_layout
+/// [`StyledTextBox::is_truncated`]: ../struct.StyledTextBox.html#method.is_truncated
+/// [`FitToText`]: height_mode/struct.FitToText.html
+#[derive(Copy, Clone, Debug)]
+pub struct TextLayout<'a> {
+    pub(crate) text: &'a str,
+    pub(crate) key: MeasurementKey,
+    pub(crate) height: u32,
+    line_count: u32,
+}
+
+impl<'a> TextLayout<'a> {
+    /// Returns the text that was measured.
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns the height of the text, in pixels.
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the number of lines the text is broken into.
+    #[inline]
+    #[must_use]
+    pub fn line_count(&self) -> u32 {
+        self.line_count
+    }
+}
+
+/// The text and style properties that the measured height of a text depends on.
+///
+/// The text is identified by its address and length. A `&str` can not change while it is borrowed,
+/// so if both are equal, the text is the same. The same applies to the additional parts of the text
+/// and the alignment spans.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct MeasurementKey {
+    text: usize,
+    text_len: usize,
+    continuation: usize,
+    continuation_len: usize,
+    alignment_spans: usize,
+    alignment_spans_len: usize,
+    overstrike: bool,
+    #[cfg(feature = "hyphenation")]
+    hyphenation: bool,
+    max_width: u32,
+    line_spacing: i32,
+    letter_spacing: i32,
+    paragraph_spacing: i32,
+    first_line_indent: i32,
+    hanging_indent: u32,
+    word_spacing: u32,
+    tab_width: i32,
+    tab_stops: &'static [(u32, TabAlignment)],
+    underlined: bool,
+    bold: bool,
+    bold_font: Option<BoldFont>,
+    glyph_offset: i32,
+    break_strategy: BreakStrategy,
+    horizontal_overflow: HorizontalOverflow,
+    starting_spaces: bool,
+    ending_spaces: bool,
+    collapse_blank_lines: bool,
+    break_character: Option<char>,
+    break_after: &'static [char],
+    no_break_before: &'static [char],
+    no_break_after: &'static [char],
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{alignment::*, parser::Parser, style::builder::TextBoxStyleBuilder};
+    use crate::{
+        alignment::*,
+        chars::{CJK_NO_BREAK_AFTER, CJK_NO_BREAK_BEFORE},
+        parser::Parser,
+        rendering::line_iter::{CarriedToken, LineResult},
+        style::{
+            builder::TextBoxStyleBuilder,
+            height_mode::{FitToText, HeightMode},
+            GlyphAlignment, HorizontalOverflow, Padding, TextBoxStyle, TextLayout, TextMetrics,
+        },
+        utils::rect_ext::RectExt,
+        StyledTextBox, TextBox,
+    };
+    use core::ops::Range;
     use embedded_graphics::{
-        fonts::{Font, Font6x8},
+        fonts::{Font, Font12x16, Font6x8},
+        mock_display::MockDisplay,
         pixelcolor::BinaryColor,
+        prelude::*,
+        primitives::Rectangle,
     };
 
     #[test]
@@ -485,6 +1913,9 @@ mod test {
             (" ", 5, 8),
             (" ", 6, 8),
             ("\n", 6, 8),
+            ("a\n", 6, 8),
+            ("a\n\n", 6, 16),
+            ("\n\na", 6, 24),
             ("\n ", 6, 16),
             ("word", 4 * 6, 8), // exact fit into 1 line
             ("word", 4 * 6 - 1, 16),
@@ -497,8 +1928,10 @@ mod test {
             ("verylongword", 50, 16),
             ("some verylongword", 50, 24),
             ("1 23456 12345 61234 561", 36, 40),
-            ("    Word      ", 36, 24),
+            ("    Word      ", 36, 16), // spaces carried by wrapping don't start a new line
             ("Longer\rnowrap", 36, 8),
+            ("super\u{AD}californian", 48, 24),
+            ("abcdefgh\u{AD}ijklmnop", 48, 16), // the hyphen doesn't start the second line
         ];
         let textbox_style = TextBoxStyleBuilder::new(Font6x8)
             .text_color(BinaryColor::On)
@@ -538,6 +1971,192 @@ mod test {
         }
     }
 
+    #[test]
+    fn measure_text_follows_alignment_rules() {
+        let left = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let center = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // trailing spaces are only measured if they are rendered
+        assert_eq!(
+            left.measure_text("ab  ", 60),
+            TextMetrics {
+                width: 24,
+                height: 8,
+                line_count: 1,
+            }
+        );
+        assert_eq!(center.measure_text("ab  ", 60).width, 12);
+
+        // the inserted hyphen is part of the line
+        assert_eq!(
+            center.measure_text("abc\u{AD}def", 24),
+            TextMetrics {
+                width: 24,
+                height: 16,
+                line_count: 2,
+            }
+        );
+
+        // tabs advance to the next tab stop
+        assert_eq!(center.measure_text("a\tb", 60).width, 30);
+    }
+
+    #[test]
+    fn fit_next_line_breaks() {
+        let data: [(&str, u32, &[(Range<usize>, u32, Option<CarriedToken>)]); 9] = [
+            ("", 60, &[(0..0, 0, None)]),
+            (
+                "Hello, World!",
+                60,
+                &[(0..7, 36, Some(CarriedToken::Wrapped)), (7..13, 36, None)],
+            ),
+            (
+                "a b c d e",
+                18,
+                &[
+                    (0..4, 18, Some(CarriedToken::Wrapped)),
+                    (4..8, 18, Some(CarriedToken::Wrapped)),
+                    (8..9, 6, None),
+                ],
+            ),
+            (
+                "supercalifragilistic",
+                36,
+                &[
+                    (0..6, 36, Some(CarriedToken::Word("alifragilistic"))),
+                    (6..12, 36, Some(CarriedToken::Word("gilistic"))),
+                    (12..18, 36, Some(CarriedToken::Word("ic"))),
+                    (18..20, 12, None),
+                ],
+            ),
+            (
+                // the inserted hyphen is part of the first line
+                "soft\u{AD}hyphen words",
+                36,
+                &[
+                    (0..6, 30, Some(CarriedToken::Wrapped)),
+                    (6..13, 36, Some(CarriedToken::Wrapped)),
+                    (13..18, 30, None),
+                ],
+            ),
+            (
+                "trailing   spaces",
+                54,
+                &[
+                    (0..11, 48, Some(CarriedToken::Whitespace(1))),
+                    (11..17, 36, None),
+                ],
+            ),
+            (
+                "line\nbreak",
+                60,
+                &[(0..5, 24, Some(CarriedToken::NewLine)), (5..10, 30, None)],
+            ),
+            (
+                "a\n\nb",
+                60,
+                &[
+                    (0..2, 6, Some(CarriedToken::NewLine)),
+                    (2..3, 0, Some(CarriedToken::NewLine)),
+                    (3..4, 6, None),
+                ],
+            ),
+            (
+                "over\rwrite",
+                60,
+                &[
+                    (0..5, 24, Some(CarriedToken::CarriageReturn)),
+                    (5..10, 30, None),
+                ],
+            ),
+        ];
+        let textbox_style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        for (text, width, expected) in data.iter() {
+            let mut parser = Parser::parse(text);
+            let mut carried = None;
+            for (bytes, line_width, end) in expected.iter() {
+                let line = textbox_style.fit_next_line(&mut parser, &mut carried, *width);
+                assert_eq!(
+                    line,
+                    LineResult {
+                        bytes: bytes.clone(),
+                        width: *line_width,
+                        end: *end,
+                    },
+                    "{:?} at width {}",
+                    text,
+                    width
+                );
+                assert_eq!(carried, *end);
+            }
+        }
+    }
+
+    #[test]
+    fn japanese_punctuation_does_not_start_lines() {
+        fn lines<A, V, H>(
+            style: TextBoxStyle<BinaryColor, Font6x8, A, V, H>,
+            text: &str,
+            columns: u32,
+        ) -> Vec<String>
+        where
+            A: HorizontalTextAlignment,
+            V: VerticalTextAlignment,
+            H: HeightMode,
+        {
+            let mut parser = Parser::parse(text);
+            let mut carried = None;
+            let mut lines = Vec::new();
+            loop {
+                let line = style.fit_next_line(&mut parser, &mut carried, columns * 6);
+                lines.push(text[line.bytes].replace('\u{200b}', ""));
+                if line.end.is_none() {
+                    break lines;
+                }
+            }
+        }
+
+        let no_rules = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .no_break_before(CJK_NO_BREAK_BEFORE)
+            .no_break_after(CJK_NO_BREAK_AFTER)
+            .build();
+
+        // the full stop is wrapped together with the preceding word
+        let text = "これは\u{200b}ペン\u{200b}です\u{200b}。";
+        assert_eq!(lines(no_rules, text, 7), ["これはペンです", "。"]);
+        assert_eq!(lines(style, text, 7), ["これはペン", "です。"]);
+
+        // ... or with the preceding character, if the word is wrapped at any character
+        let text = "日本語の文章です。";
+        assert_eq!(lines(no_rules, text, 8), ["日本語の文章です", "。"]);
+        assert_eq!(lines(style, text, 8), ["日本語の文章で", "す。"]);
+
+        let text = "はい、\u{200b}そうです。";
+        assert_eq!(
+            lines(no_rules, text, 2),
+            ["はい", "、", "そう", "です", "。"]
+        );
+        assert_eq!(lines(style, text, 2), ["は", "い、", "そう", "で", "す。"]);
+
+        // opening brackets stay with the following word
+        let text = "あいう\u{200b}「\u{200b}えお」";
+        assert_eq!(lines(no_rules, text, 4), ["あいう「", "えお」"]);
+        assert_eq!(lines(style, text, 4), ["あいう", "「えお」"]);
+
+        // a single character fits the line, the punctuation can't be kept with it
+        assert_eq!(lines(style, "です。", 1), ["で", "す", "。"]);
+    }
     #[test]
     fn test_measure_line() {
         let textbox_style = TextBoxStyleBuilder::new(Font6x8)
@@ -568,6 +2187,22 @@ mod test {
         assert_eq!(s, 1);
     }
 
+    #[test]
+    fn test_measure_line_word_spacing() {
+        let textbox_style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .word_spacing(2)
+            .build();
+
+        let mut text = Parser::parse("123 45");
+
+        let (w, s, _, _) =
+            textbox_style.measure_line(&mut text, None, 6 * Font6x8::CHARACTER_SIZE.width);
+        assert_eq!(w, 5 * Font6x8::CHARACTER_SIZE.width + 2);
+        assert_eq!(s, 1);
+    }
+
     #[test]
     fn test_measure_height_nbsp() {
         let textbox_style = TextBoxStyleBuilder::new(Font6x8)
@@ -592,6 +2227,26 @@ mod test {
         assert_eq!(height, 4 * Font6x8::CHARACTER_SIZE.height);
     }
 
+    #[test]
+    fn indentation_reduces_the_width_of_lines() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .hanging_indent(18)
+            .build();
+
+        // "1. a bunch of" fits into the first line, the wrapped lines have room for 11 characters
+        let metrics = style.measure_text("1. a bunch of wrapping words\nsecond item", 84);
+        assert_eq!(metrics.line_count, 4);
+        assert_eq!(metrics.width, 13 * 6);
+
+        // the indentation is part of the width of the line
+        let style = TextBoxStyle {
+            first_line_indent: 30,
+            ..style
+        };
+        assert_eq!(style.measure_text_width("ab\ncd", 84), 30 + 2 * 6);
+    }
+
     #[test]
     fn height_with_line_spacing() {
         let style = TextBoxStyleBuilder::new(Font6x8)
@@ -606,4 +2261,445 @@ mod test {
 
         assert_eq!(height, 7 * 8 + 6 * 2);
     }
+
+    #[test]
+    fn height_with_paragraph_spacing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .line_spacing(2)
+            .paragraph_spacing(3)
+            .build();
+
+        // "ab" | "cd" | "ef", only the first line ends a paragraph
+        let height = style.measure_text_height("ab\ncd ef", 6 * 2);
+
+        assert_eq!(height, 3 * 8 + 2 * 2 + 3);
+    }
+
+    #[test]
+    fn blank_lines_are_collapsed() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .collapse_blank_lines(true)
+            .build();
+
+        for text in &[
+            "ab\n   \ncd",
+            "ab\n\t\ncd",
+            "ab\n \t \n\n\t\n   \ncd",
+            "ab\n\n\ncd  \n\t",
+        ] {
+            assert_eq!(style.measure_line_count(text, 60), 3, "{:?}", text);
+            assert_eq!(style.measure_text_height(text, 60), 3 * 8, "{:?}", text);
+        }
+
+        // blank lines at the start of the text are displayed as a single empty line
+        assert_eq!(style.measure_line_count("  \n\t\nab", 60), 2);
+
+        // whitespace in other lines is kept
+        assert_eq!(style.measure_text_width("ab\n   cd", 60), 5 * 6);
+
+        // by default, whitespace lines are displayed
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+        assert_eq!(style.measure_line_count("ab\n \t \n\n\t\n   \ncd", 60), 6);
+    }
+
+    #[test]
+    fn blank_lines_are_drawn_as_empty_lines() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .height_mode(FitToText)
+            .collapse_blank_lines(true)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), Point::new(23, 47));
+
+        let mut display = MockDisplay::new();
+        TextBox::new("a\n  \n\t\n\nb", bounds)
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("a\n\nb", bounds)
+            .into_styled(style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+    }
+
+    #[test]
+    fn height_with_negative_paragraph_spacing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .line_spacing(2)
+            .paragraph_spacing(-100)
+            .build();
+
+        // the second line is drawn over the first one, the third line is placed normally
+        let height = style.measure_text_height("ab\ncd ef", 6 * 2);
+
+        assert_eq!(height, 2 * 8 + 2);
+    }
+
+    #[test]
+    fn glyph_alignment_lines_up_baselines_of_different_fonts() {
+        let mut display = MockDisplay::new();
+
+        let large = TextBoxStyleBuilder::new(Font12x16)
+            .text_color(BinaryColor::On)
+            .build();
+        TextBox::new("xo", Rectangle::new(Point::zero(), Point::new(23, 15)))
+            .into_styled(large)
+            .draw(&mut display)
+            .unwrap();
+
+        let small = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .glyph_alignment(GlyphAlignment::Baseline(7))
+            .build();
+        TextBox::new("xo", Rectangle::new(Point::new(24, 0), Point::new(35, 15)))
+            .into_styled(small)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "                                   ",
+                "                                   ",
+                "                                   ",
+                "                                   ",
+                "##      ##    ######               ",
+                "##      ##    ######               ",
+                "  ##  ##    ##      ##             ",
+                "  ##  ##    ##      ##             ",
+                "    ##      ##      ##             ",
+                "    ##      ##      ##  #   #  ### ",
+                "  ##  ##    ##      ##   # #  #   #",
+                "  ##  ##    ##      ##    #   #   #",
+                "##      ##    ######     # #  #   #",
+                "##      ##    ######    #   #  ### ",
+            ])
+        );
+    }
+
+    #[test]
+    fn glyph_alignment_is_measured() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .line_spacing(2)
+            .glyph_alignment(GlyphAlignment::Bottom)
+            .build();
+
+        assert_eq!(style.measure_text_height("a\nb", 60), 2 * 10);
+    }
+
+    #[test]
+    fn horizontal_overflow_wrap() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .horizontal_overflow(HorizontalOverflow::Wrap)
+            .build();
+
+        // the glued word is one and a half times as wide as the text box
+        let text = "ab foobar\u{A0}bazqux";
+        assert_eq!(style.measure_text_height(text, 52), 3 * 8);
+
+        let mut display = MockDisplay::new();
+        TextBox::new(text, Rectangle::new(Point::zero(), Point::new(51, 31)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      #                                        ",
+                "      #                                        ",
+                " ###  # ##                                     ",
+                "    # ##  #                                    ",
+                " #### #   #                                    ",
+                "#   # #   #                                    ",
+                " #### ####                                     ",
+                "                                               ",
+                "  ##              #                       #    ",
+                " #  #             #                       #    ",
+                " #     ###   ###  # ##   ###  # ##        # ## ",
+                "###   #   # #   # ##  #     # ##  #       ##  #",
+                " #    #   # #   # #   #  #### #           #   #",
+                " #    #   # #   # #   # #   # #           #   #",
+                " #     ###   ###  ####   #### #           #### ",
+                "                                               ",
+                "                                               ",
+                "                                               ",
+                " ###  #####  #### #   # #   #                  ",
+                "    #    #  #   # #   #  # #                   ",
+                " ####   #   #   # #   #   #                    ",
+                "#   #  #     #### #  ##  # #                   ",
+                " #### #####     #  ## # #   #                  ",
+                "                #                              ",
+            ])
+        );
+    }
+
+    #[test]
+    fn horizontal_overflow_clip() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .horizontal_overflow(HorizontalOverflow::Clip)
+            .build();
+
+        // the glued word is one and a half times as wide as the text box
+        let text = "ab foobar\u{A0}bazqux";
+        assert_eq!(style.measure_text_height(text, 52), 2 * 8);
+
+        let mut display = MockDisplay::new();
+        TextBox::new(text, Rectangle::new(Point::zero(), Point::new(51, 31)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      #                                             ",
+                "      #                                             ",
+                " ###  # ##                                          ",
+                "    # ##  #                                         ",
+                " #### #   #                                         ",
+                "#   # #   #                                         ",
+                " #### ####                                          ",
+                "                                                    ",
+                "  ##              #                       #         ",
+                " #  #             #                       #         ",
+                " #     ###   ###  # ##   ###  # ##        # ##   ###",
+                "###   #   # #   # ##  #     # ##  #       ##  #     ",
+                " #    #   # #   # #   #  #### #           #   #  ###",
+                " #    #   # #   # #   # #   # #           #   # #   ",
+                " #     ###   ###  ####   #### #           ####   ###",
+            ])
+        );
+    }
+
+    #[test]
+    fn horizontal_overflow_clip_at_word_boundary() {
+        let clip = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .horizontal_overflow(HorizontalOverflow::Clip)
+            .build();
+        let word_boundary = TextBoxStyle {
+            truncate_at_word_boundary: true,
+            ..clip
+        };
+        let draw = |text, style| {
+            let mut display = MockDisplay::new();
+            TextBox::new(text, Rectangle::new(Point::zero(), Point::new(51, 31)))
+                .into_styled(style)
+                .draw(&mut display)
+                .unwrap();
+            display
+        };
+
+        // the part of the glued word that doesn't fit is not displayed
+        let text = "ab foobar\u{A0}bazqux";
+        assert_eq!(word_boundary.measure_text_height(text, 52), 2 * 8);
+        assert_eq!(draw(text, word_boundary), draw("ab\nfoobar", clip));
+
+        // if not even the first part fits, the word is clipped after a character
+        let text = "abcdefghij\u{A0}kl";
+        assert_eq!(draw(text, word_boundary), draw(text, clip));
+    }
+
+    #[test]
+    fn horizontal_overflow_hide() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .horizontal_overflow(HorizontalOverflow::Hide)
+            .build();
+
+        // the glued word is one and a half times as wide as the text box
+        let text = "ab foobar\u{A0}bazqux";
+        assert_eq!(style.measure_text_height(text, 52), 8);
+
+        let mut display = MockDisplay::new();
+        TextBox::new(text, Rectangle::new(Point::zero(), Point::new(51, 31)))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "      #    ",
+                "      #    ",
+                " ###  # ## ",
+                "    # ##  #",
+                " #### #   #",
+                "#   # #   #",
+                " #### #### ",
+            ])
+        );
+    }
+
+    #[test]
+    fn padding_shrinks_text_area() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .padding(Padding {
+                top: 1,
+                right: 0,
+                bottom: 0,
+                left: 2,
+            })
+            .build();
+
+        let text_box = TextBox::new("word", Rectangle::new(Point::zero(), Point::new(25, 8)))
+            .into_styled(style);
+
+        // bounding box is unchanged
+        assert_eq!(text_box.size(), Size::new(26, 9));
+
+        text_box.draw(&mut display).unwrap();
+
+        assert_eq!(
+            display,
+            MockDisplay::from_pattern(&[
+                "                          ",
+                "  ......................#.",
+                "  ......................#.",
+                "  #...#..###..#.##...##.#.",
+                "  #...#.#...#.##..#.#..##.",
+                "  #.#.#.#...#.#.....#...#.",
+                "  #.#.#.#...#.#.....#...#.",
+                "  .#.#...###..#......####.",
+                "  ........................",
+            ])
+        );
+    }
+
+    #[test]
+    fn padding_reduces_available_width() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .height_mode(FitToText)
+            .padding(Padding {
+                top: 1,
+                right: 6,
+                bottom: 2,
+                left: 0,
+            })
+            .build();
+
+        // 4 characters would fit into the bounding box, but only 3 fit into the text area
+        let text_box = TextBox::new("word", Rectangle::new(Point::zero(), Point::new(23, 0)))
+            .into_styled(style);
+
+        assert_eq!(RectExt::size(text_box.text_area()), Size::new(18, 16));
+        assert_eq!(text_box.size(), Size::new(24, 16 + 3));
+    }
+
+    #[test]
+    fn padding_larger_than_box_renders_nothing() {
+        let mut display = MockDisplay::new();
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .padding(Padding::uniform(100))
+            .build();
+
+        let text_box = TextBox::new(
+            "Some words\tand\u{A0}spaces",
+            Rectangle::new(Point::zero(), Point::new(23, 7)),
+        )
+        .into_styled(style);
+
+        assert_eq!(RectExt::size(text_box.text_area()), Size::new(0, 0));
+
+        text_box.draw(&mut display).unwrap();
+
+        assert_eq!(display, MockDisplay::new());
+    }
+
+    #[test]
+    fn padding_larger_than_box_fits_to_padding() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .height_mode(FitToText)
+            .padding(Padding::uniform(30))
+            .build();
+
+        let text_box = TextBox::new("word", Rectangle::new(Point::zero(), Point::new(23, 0)))
+            .into_styled(style);
+
+        assert_eq!(text_box.size(), Size::new(24, 60));
+    }
+
+    #[test]
+    fn layout_matches_the_measured_height() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .height_mode(FitToText)
+            .line_spacing(2)
+            .build();
+        let text = "The quick brown fox jumps over the lazy dog.";
+
+        let layout = style.layout(text, 60);
+
+        assert_eq!(layout.text(), text);
+        assert_eq!(layout.line_count(), 5);
+        assert_eq!(layout.height(), style.measure_text_height(text, 60));
+
+        let bounds = Rectangle::new(Point::zero(), Point::new(59, 0));
+        let from_layout = StyledTextBox::from_layout(&layout, bounds, style);
+        let measured = StyledTextBox::new(text, bounds, style);
+
+        assert_eq!(from_layout.text_box.bounds, measured.text_box.bounds);
+    }
+
+    #[test]
+    fn from_layout_uses_the_stored_height() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .height_mode(FitToText)
+            .build();
+        let text = "The quick brown fox";
+        let layout = style.layout(text, 60);
+
+        // the text is not measured again, so a different stored height is used as is
+        let tampered = TextLayout {
+            height: 80,
+            ..layout
+        };
+        let text_box = StyledTextBox::from_layout(
+            &tampered,
+            Rectangle::new(Point::zero(), Point::new(59, 0)),
+            style,
+        );
+        assert_eq!(text_box.size(), Size::new(60, 80));
+
+        // a different width invalidates the layout
+        let text_box = StyledTextBox::from_layout(
+            &tampered,
+            Rectangle::new(Point::zero(), Point::new(29, 0)),
+            style,
+        );
+        assert_eq!(text_box.size(), Size::new(30, 32));
+
+        // so does a different style
+        let text_box = StyledTextBox::from_layout(
+            &tampered,
+            Rectangle::new(Point::zero(), Point::new(59, 0)),
+            TextBoxStyle {
+                line_spacing: 1,
+                ..style
+            },
+        );
+        assert_eq!(text_box.size(), Size::new(60, 17));
+    }
 }
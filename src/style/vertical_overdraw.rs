@@ -1,4 +1,12 @@
 //! Vertical overdraw options.
+//!
+//! The options apply to both edges of the text box. Text that is taller than the text box is cut
+//! off at the bottom edge if it's [`TopAligned`], at the top edge if it's [`BottomAligned`], and at
+//! both edges if it's [`CenterAligned`].
+//!
+//! [`TopAligned`]: ../../alignment/top/struct.TopAligned.html
+//! [`BottomAligned`]: ../../alignment/bottom/struct.BottomAligned.html
+//! [`CenterAligned`]: ../../alignment/center/struct.CenterAligned.html
 use crate::rendering::cursor::Cursor;
 use core::ops::Range;
 use embedded_graphics::fonts::Font;
@@ -10,7 +18,9 @@ pub trait VerticalOverdraw: Copy {
 }
 
 /// Only render full rows of text.
-#[derive(Copy, Clone, Debug)]
+///
+/// Lines that are cut off by an edge of the text box are not drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FullRowsOnly;
 impl VerticalOverdraw for FullRowsOnly {
     #[inline]
@@ -24,7 +34,9 @@ impl VerticalOverdraw for FullRowsOnly {
 }
 
 /// Render partially visible rows, but only inside the bounding box.
-#[derive(Copy, Clone, Debug)]
+///
+/// Lines that are cut off by an edge of the text box are clipped to the part that is inside.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Hidden;
 impl VerticalOverdraw for Hidden {
     #[inline]
@@ -41,7 +53,9 @@ impl VerticalOverdraw for Hidden {
 }
 
 /// Display text even if it's outside the bounding box.
-#[derive(Copy, Clone, Debug)]
+///
+/// Lines that are cut off by an edge of the text box are drawn above or below the text box.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Visible;
 impl VerticalOverdraw for Visible {
     #[inline]
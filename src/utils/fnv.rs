@@ -0,0 +1,48 @@
+//! FNV-1a hashing.
+use core::hash::Hasher;
+
+/// 32 bit FNV-1a hasher.
+pub(crate) struct Fnv32(u32);
+
+impl Fnv32 {
+    pub(crate) fn new() -> Self {
+        Self(0x811c_9dc5)
+    }
+
+    /// Returns the hash of the data written so far.
+    pub(crate) fn finish32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Hasher for Fnv32 {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u32::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0193);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        u64::from(self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Fnv32;
+    use core::hash::Hasher;
+
+    #[test]
+    fn known_hashes() {
+        for &(input, hash) in &[
+            ("", 0x811c_9dc5),
+            ("a", 0xe40c_292c),
+            ("foobar", 0xbf9c_f968),
+        ] {
+            let mut hasher = Fnv32::new();
+            hasher.write(input.as_bytes());
+            assert_eq!(hasher.finish32(), hash, "{:?}", input);
+        }
+    }
+}
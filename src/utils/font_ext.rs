@@ -1,6 +1,7 @@
 //! Font helper extensions.
 //!
 //! Extends font types with some helper methods.
+use crate::parser::{displayed_char, is_non_breaking_space, SpaceWidth, SPEC_CHAR_NNBSP};
 use embedded_graphics::fonts::Font;
 
 /// `Font` extensions
@@ -80,11 +81,14 @@ where
 {
     #[inline]
     fn total_char_width(c: char) -> u32 {
-        if c == '\u{A0}' {
+        if is_non_breaking_space(c) {
             // A non-breaking space is as wide as a regular one
             return F::char_width(' ') + F::CHARACTER_SPACING;
         }
-        F::char_width(c) + F::CHARACTER_SPACING
+        if c == SPEC_CHAR_NNBSP {
+            return SpaceWidth::Thin.width(F::CHARACTER_SIZE.height);
+        }
+        F::char_width(displayed_char(c)) + F::CHARACTER_SPACING
     }
 
     #[inline]
@@ -1,4 +1,5 @@
 //! Module of small helpers
 
+pub(crate) mod fnv;
 pub mod font_ext;
 pub mod rect_ext;
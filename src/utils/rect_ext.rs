@@ -1,42 +1,201 @@
 //! Rectangle helper extensions.
 //!
 //! Extends the embedded-graphics [`Rectangle`] struct with some helper methods.
+//!
+//! The corners of a [`Rectangle`] are inclusive in embedded-graphics 0.6, which makes it easy to be
+//! off by one pixel when a rectangle is calculated from a size. The crate uses [`RectExt::from_size`]
+//! and [`RectExt::size`] instead of calculating with the corners directly, so the representation
+//! of rectangles is only relied upon in this module.
+//!
+//! [`RectExt::from_size`]: trait.RectExt.html#tymethod.from_size
+//! [`RectExt::size`]: trait.RectExt.html#tymethod.size
 use embedded_graphics::{prelude::*, primitives::Rectangle};
 
 /// [`Rectangle`] extensions
 pub trait RectExt {
+    /// Creates a [`Rectangle`] with the given top left corner and size.
+    ///
+    /// A rectangle with zero width or height is not drawn, but still has a position. If the
+    /// rectangle doesn't fit into the coordinate space, it is cut off at its edge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::utils::rect_ext::RectExt;
+    /// use embedded_graphics::{prelude::*, primitives::Rectangle};
+    ///
+    /// let rectangle = Rectangle::from_size(Point::new(3, 4), Size::new(60, 0));
+    ///
+    /// assert_eq!(rectangle.top_left, Point::new(3, 4));
+    /// assert_eq!(RectExt::size(rectangle), Size::new(60, 0));
+    /// ```
+    fn from_size(top_left: Point, size: Size) -> Rectangle
+    where
+        Self: Sized;
+
     /// Returns the (correct) size of a [`Rectangle`].
     fn size(self) -> Size;
 
-    /// Sorts the coordinates of a [`Rectangle`] so that `top` < `bottom` and `left` < `right`.
+    /// Returns the smallest [`Rectangle`] that contains both rectangles.
+    fn union(self, other: Rectangle) -> Rectangle;
+
+    /// Returns `true` if the given point is inside the [`Rectangle`].
+    ///
+    /// Both corners are inside the rectangle. This can be used to check whether a touch hits a
+    /// text box.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::utils::rect_ext::RectExt;
+    /// use embedded_graphics::{prelude::*, primitives::Rectangle};
+    ///
+    /// let bounds = Rectangle::from_size(Point::new(10, 10), Size::new(20, 8));
+    ///
+    /// assert!(bounds.contains_point(Point::new(29, 17)));
+    /// assert!(!bounds.contains_point(Point::new(30, 17)));
+    /// ```
+    fn contains_point(self, point: Point) -> bool;
+
+    /// Returns the area that is covered by both rectangles.
+    ///
+    /// Returns `None` if the rectangles don't overlap. Rectangles that only touch, without sharing
+    /// a pixel, don't overlap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::utils::rect_ext::RectExt;
+    /// use embedded_graphics::{prelude::*, primitives::Rectangle};
+    ///
+    /// let a = Rectangle::from_size(Point::zero(), Size::new(20, 8));
+    /// let b = Rectangle::from_size(Point::new(15, 4), Size::new(20, 8));
+    /// let c = Rectangle::from_size(Point::new(20, 0), Size::new(20, 8));
+    ///
+    /// let overlap = a.intersection(b).unwrap();
+    /// assert_eq!(overlap.top_left, Point::new(15, 4));
+    /// assert_eq!(RectExt::size(overlap), Size::new(5, 4));
+    ///
+    /// assert_eq!(a.intersection(c), None);
+    /// ```
+    fn intersection(self, other: Rectangle) -> Option<Rectangle>;
+}
+
+/// Conversion of a [`Rectangle`] given by any two opposite corners.
+///
+/// This trait is part of the prelude, so it can be used to normalize rectangles that come from
+/// user input, e.g. a touch drag gesture. [`TextBox::new`] normalizes its bounds the same way.
+///
+/// [`TextBox::new`]: ../../struct.TextBox.html#method.new
+pub trait IntoWellFormed {
+    /// Sorts the coordinates of a [`Rectangle`] so that `top` <= `bottom` and `left` <= `right`.
+    ///
+    /// The corners are treated as two opposite corners of the rectangle, which covers both of
+    /// them. A rectangle with equal corners is one pixel large. The returned rectangle is already
+    /// well formed, so converting it again returns the same rectangle. The coordinates are only
+    /// compared and never calculated with, so this method never overflows.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use embedded_text::prelude::*;
+    /// use embedded_graphics::prelude::*;
+    ///
+    /// let rectangle = Rectangle::new(Point::new(10, 2), Point::new(3, 8)).into_well_formed();
+    ///
+    /// assert_eq!(rectangle, Rectangle::new(Point::new(3, 2), Point::new(10, 8)));
+    /// assert_eq!(rectangle.into_well_formed(), rectangle);
+    /// ```
     fn into_well_formed(self) -> Rectangle;
 }
 
+impl IntoWellFormed for Rectangle {
+    #[inline]
+    #[must_use]
+    fn into_well_formed(self) -> Rectangle {
+        Rectangle::new(
+            Point::new(
+                self.top_left.x.min(self.bottom_right.x),
+                self.top_left.y.min(self.bottom_right.y),
+            ),
+            Point::new(
+                self.top_left.x.max(self.bottom_right.x),
+                self.top_left.y.max(self.bottom_right.y),
+            ),
+        )
+    }
+}
+
 impl RectExt for Rectangle {
+    #[inline]
+    fn from_size(top_left: Point, size: Size) -> Rectangle {
+        fn to_i32(n: u32) -> i32 {
+            n.min(i32::max_value() as u32) as i32
+        }
+
+        // Rectangles are inclusive, so a zero sized rectangle ends one pixel before it starts.
+        Rectangle::new(
+            top_left,
+            Point::new(
+                top_left.x.saturating_add(to_i32(size.width) - 1),
+                top_left.y.saturating_add(to_i32(size.height) - 1),
+            ),
+        )
+    }
+
     #[inline]
     #[must_use]
     fn size(self) -> Size {
         // TODO: remove if fixed in embedded-graphics
-        let width = (self.bottom_right.x - self.top_left.x) as u32 + 1;
-        let height = (self.bottom_right.y - self.top_left.y) as u32 + 1;
+        // zero sized rectangles have their bottom right corner before their top left corner
+        let width = (self.bottom_right.x - self.top_left.x + 1).max(0) as u32;
+        let height = (self.bottom_right.y - self.top_left.y + 1).max(0) as u32;
 
         Size::new(width, height)
     }
 
     #[inline]
-    #[must_use]
-    fn into_well_formed(self) -> Rectangle {
+    fn union(self, other: Rectangle) -> Rectangle {
         Rectangle::new(
             Point::new(
-                self.top_left.x.min(self.bottom_right.x),
-                self.top_left.y.min(self.bottom_right.y),
+                self.top_left.x.min(other.top_left.x),
+                self.top_left.y.min(other.top_left.y),
             ),
             Point::new(
-                self.top_left.x.max(self.bottom_right.x),
-                self.top_left.y.max(self.bottom_right.y),
+                self.bottom_right.x.max(other.bottom_right.x),
+                self.bottom_right.y.max(other.bottom_right.y),
             ),
         )
     }
+
+    #[inline]
+    fn contains_point(self, point: Point) -> bool {
+        point.x >= self.top_left.x
+            && point.x <= self.bottom_right.x
+            && point.y >= self.top_left.y
+            && point.y <= self.bottom_right.y
+    }
+
+    #[inline]
+    fn intersection(self, other: Rectangle) -> Option<Rectangle> {
+        let intersection = Rectangle::new(
+            Point::new(
+                self.top_left.x.max(other.top_left.x),
+                self.top_left.y.max(other.top_left.y),
+            ),
+            Point::new(
+                self.bottom_right.x.min(other.bottom_right.x),
+                self.bottom_right.y.min(other.bottom_right.y),
+            ),
+        );
+
+        let size = RectExt::size(intersection);
+        if size.width == 0 || size.height == 0 {
+            None
+        } else {
+            Some(intersection)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -50,4 +209,166 @@ mod test {
             Rectangle::new(Point::new(3, -4), Point::zero()).into_well_formed()
         );
     }
+
+    #[test]
+    fn well_formed_with_equal_corners_is_one_pixel() {
+        let rectangle = Rectangle::new(Point::new(3, 4), Point::new(3, 4));
+
+        assert_eq!(rectangle.into_well_formed(), rectangle);
+        assert_eq!(RectExt::size(rectangle.into_well_formed()), Size::new(1, 1));
+    }
+
+    #[test]
+    fn well_formed_with_extreme_coordinates() {
+        let rectangle = Rectangle::new(
+            Point::new(i32::max_value(), i32::min_value()),
+            Point::new(i32::min_value(), 0),
+        );
+
+        assert_eq!(
+            rectangle.into_well_formed(),
+            Rectangle::new(
+                Point::new(i32::min_value(), i32::min_value()),
+                Point::new(i32::max_value(), 0)
+            )
+        );
+    }
+
+    #[test]
+    fn well_formed_is_idempotent() {
+        // xorshift, with some extreme values mixed in
+        let mut state = 0x2545_f491_u32;
+        let mut random = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            match state % 8 {
+                0 => i32::min_value(),
+                1 => i32::max_value(),
+                2 => 0,
+                _ => state as i32 >> (state % 24),
+            }
+        };
+
+        for _ in 0..1000 {
+            let a = Point::new(random(), random());
+            let b = Point::new(random(), random());
+
+            let well_formed = Rectangle::new(a, b).into_well_formed();
+
+            assert!(well_formed.top_left.x <= well_formed.bottom_right.x);
+            assert!(well_formed.top_left.y <= well_formed.bottom_right.y);
+            assert_eq!(well_formed.into_well_formed(), well_formed);
+
+            // the order of the corners doesn't matter
+            assert_eq!(Rectangle::new(b, a).into_well_formed(), well_formed);
+            assert_eq!(
+                Rectangle::new(Point::new(a.x, b.y), Point::new(b.x, a.y)).into_well_formed(),
+                well_formed
+            );
+
+            // both corners are inside the rectangle
+            assert!(well_formed.contains_point(a));
+            assert!(well_formed.contains_point(b));
+        }
+    }
+
+    #[test]
+    fn test_zero_size() {
+        assert_eq!(
+            Size::new(0, 0),
+            RectExt::size(Rectangle::new(Point::new(3, 4), Point::new(2, 3)))
+        );
+    }
+
+    #[test]
+    fn from_size_round_trips() {
+        for &size in &[
+            Size::zero(),
+            Size::new(1, 1),
+            Size::new(60, 0),
+            Size::new(7, 13),
+        ] {
+            let rectangle = Rectangle::from_size(Point::new(-3, 4), size);
+
+            assert_eq!(rectangle.top_left, Point::new(-3, 4));
+            assert_eq!(RectExt::size(rectangle), size);
+        }
+    }
+
+    #[test]
+    fn from_size_is_cut_off_at_the_edge() {
+        let rectangle = Rectangle::from_size(
+            Point::new(i32::max_value() - 1, 0),
+            Size::new(u32::max_value(), 2),
+        );
+
+        assert_eq!(rectangle.bottom_right, Point::new(i32::max_value(), 1));
+    }
+
+    #[test]
+    fn contains_point_is_inclusive() {
+        let rectangle = Rectangle::from_size(Point::new(-2, 3), Size::new(4, 2));
+
+        for &(point, inside) in &[
+            (Point::new(-2, 3), true),
+            (Point::new(1, 4), true),
+            (Point::new(0, 3), true),
+            (Point::new(-3, 3), false),
+            (Point::new(2, 4), false),
+            (Point::new(1, 5), false),
+            (Point::new(1, 2), false),
+        ] {
+            assert_eq!(rectangle.contains_point(point), inside, "{:?}", point);
+        }
+
+        // zero sized rectangles contain nothing
+        let empty = Rectangle::from_size(Point::new(-2, 3), Size::new(4, 0));
+        assert!(!empty.contains_point(Point::new(-2, 3)));
+    }
+
+    #[test]
+    fn intersection_of_disjoint_rectangles_is_empty() {
+        let a = Rectangle::from_size(Point::zero(), Size::new(4, 4));
+        let b = Rectangle::from_size(Point::new(10, -10), Size::new(4, 4));
+
+        assert_eq!(a.intersection(b), None);
+        assert_eq!(b.intersection(a), None);
+
+        // overlapping in one direction only
+        let beside = Rectangle::from_size(Point::new(2, 10), Size::new(4, 4));
+        assert_eq!(a.intersection(beside), None);
+    }
+
+    #[test]
+    fn intersection_of_touching_rectangles() {
+        let a = Rectangle::from_size(Point::zero(), Size::new(4, 4));
+
+        // next to each other, without sharing a pixel
+        let right = Rectangle::from_size(Point::new(4, 0), Size::new(4, 4));
+        let below = Rectangle::from_size(Point::new(0, 4), Size::new(4, 4));
+        assert_eq!(a.intersection(right), None);
+        assert_eq!(a.intersection(below), None);
+        assert_eq!(right.intersection(a), None);
+
+        // sharing the last column
+        let shared = Rectangle::from_size(Point::new(3, 0), Size::new(4, 4));
+        let overlap = a.intersection(shared).unwrap();
+        assert_eq!(overlap.top_left, Point::new(3, 0));
+        assert_eq!(RectExt::size(overlap), Size::new(1, 4));
+    }
+
+    #[test]
+    fn intersection_of_nested_rectangles_is_the_inner_one() {
+        let outer = Rectangle::from_size(Point::new(-5, -5), Size::new(20, 20));
+        let inner = Rectangle::from_size(Point::new(2, 3), Size::new(4, 5));
+
+        assert_eq!(outer.intersection(inner), Some(inner));
+        assert_eq!(inner.intersection(outer), Some(inner));
+        assert_eq!(inner.intersection(inner), Some(inner));
+
+        // an empty rectangle doesn't overlap anything, not even the area around it
+        let empty = Rectangle::from_size(Point::new(2, 3), Size::new(4, 0));
+        assert_eq!(outer.intersection(empty), None);
+    }
 }
@@ -0,0 +1,256 @@
+//! Formatted text.
+//!
+//! [`TextBoxWriter`] owns its text in a fixed capacity buffer and implements
+//! [`core::fmt::Write`], so text that changes every frame, like status lines with numbers, can be
+//! formatted using `write!` and drawn without an allocator or an additional string type.
+//!
+//! This module is only available if the `writer` feature is enabled.
+//!
+//! ```rust
+//! use core::fmt::Write;
+//! use embedded_text::{prelude::*, writer::TextBoxWriter};
+//! use embedded_graphics::{
+//!     fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+//! };
+//! use heapless::consts::U32;
+//!
+//! let style = TextBoxStyleBuilder::new(Font6x8)
+//!     .text_color(BinaryColor::On)
+//!     .build();
+//!
+//! let mut writer: TextBoxWriter<U32, _, _, _, _, _> =
+//!     TextBoxWriter::new(Rectangle::new(Point::zero(), Point::new(59, 7)), style);
+//!
+//! let temperature = 21;
+//! write!(writer, "T={}°C", temperature).unwrap();
+//! assert_eq!(writer.text(), "T=21°C");
+//!
+//! let mut display = MockDisplay::new();
+//! writer.draw(&mut display).unwrap();
+//! ```
+//!
+//! [`TextBoxWriter`]: struct.TextBoxWriter.html
+//! [`core::fmt::Write`]: https://doc.rust-lang.org/core/fmt/trait.Write.html
+use crate::{
+    alignment::{HorizontalTextAlignment, VerticalTextAlignment},
+    rendering::RendererFactory,
+    style::{height_mode::HeightMode, TextBoxStyle},
+    utils::rect_ext::IntoWellFormed,
+    StyledTextBox, TextBox,
+};
+use core::fmt;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use heapless::{ArrayLength, String};
+
+/// A text box that owns its text and can be written to using `write!`.
+///
+/// The text is stored in a buffer that can hold `N` bytes. By default, writing text that doesn't
+/// fit into the buffer returns [`fmt::Error`] and leaves the text that didn't fit out. Use
+/// [`with_truncation`] to silently cut off the text instead.
+///
+/// See the [module-level documentation] for an example.
+///
+/// [`fmt::Error`]: https://doc.rust-lang.org/core/fmt/struct.Error.html
+/// [`with_truncation`]: #method.with_truncation
+/// [module-level documentation]: index.html
+#[derive(Clone, Debug)]
+pub struct TextBoxWriter<N, C, F, A, V, H>
+where
+    N: ArrayLength<u8>,
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// The bounding box of the text box.
+    pub bounds: Rectangle,
+
+    /// The style of the text box.
+    pub style: TextBoxStyle<C, F, A, V, H>,
+
+    text: String<N>,
+    truncate: bool,
+    truncated: bool,
+}
+
+impl<N, C, F, A, V, H> TextBoxWriter<N, C, F, A, V, H>
+where
+    N: ArrayLength<u8>,
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    /// Creates an empty text box.
+    #[inline]
+    #[must_use]
+    pub fn new(bounds: Rectangle, style: TextBoxStyle<C, F, A, V, H>) -> Self {
+        Self {
+            bounds: bounds.into_well_formed(),
+            style,
+            text: String::new(),
+            truncate: false,
+            truncated: false,
+        }
+    }
+
+    /// Silently cuts off text that doesn't fit into the buffer instead of returning an error.
+    ///
+    /// Only whole characters are written, so the text always remains valid. Use
+    /// [`is_truncated`] to check whether text was cut off.
+    ///
+    /// [`is_truncated`]: #method.is_truncated
+    #[inline]
+    #[must_use]
+    pub fn with_truncation(self) -> Self {
+        Self {
+            truncate: true,
+            ..self
+        }
+    }
+
+    /// Returns the text.
+    #[inline]
+    #[must_use]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns whether some of the text written since the last [`clear`] didn't fit into the
+    /// buffer.
+    ///
+    /// [`clear`]: #method.clear
+    #[inline]
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Removes the text, so the next frame can be written.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.text = String::new();
+        self.truncated = false;
+    }
+
+    /// Returns a [`StyledTextBox`] that displays the text.
+    ///
+    /// [`StyledTextBox`]: ../struct.StyledTextBox.html
+    #[inline]
+    #[must_use]
+    pub fn styled(&self) -> StyledTextBox<'_, C, F, A, V, H> {
+        TextBox::new(self.text(), self.bounds).into_styled(self.style)
+    }
+
+    /// Draws the text.
+    #[inline]
+    pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<C>,
+        for<'a> StyledTextBox<'a, C, F, A, V, H>: RendererFactory<'a, C>,
+    {
+        display.draw_iter(self.styled().create_renderer())
+    }
+}
+
+impl<N, C, F, A, V, H> fmt::Write for TextBoxWriter<N, C, F, A, V, H>
+where
+    N: ArrayLength<u8>,
+    C: PixelColor,
+    F: Font + Copy,
+    A: HorizontalTextAlignment,
+    V: VerticalTextAlignment,
+    H: HeightMode,
+{
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.text.push_str(s).is_ok() {
+            return Ok(());
+        }
+
+        self.truncated = true;
+        if !self.truncate {
+            return Err(fmt::Error);
+        }
+
+        // push the longest prefix of whole characters that fits
+        let space = self.text.capacity() - self.text.len();
+        let end = s
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .take_while(|&end| end <= space)
+            .last()
+            .unwrap_or(0);
+
+        // can't fail, the prefix fits into the buffer
+        let _ = self.text.push_str(&s[..end]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use core::fmt::Write;
+    use embedded_graphics::{fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor};
+    use heapless::consts::U8;
+
+    type Writer = TextBoxWriter<U8, BinaryColor, Font6x8, LeftAligned, TopAligned, FitToText>;
+
+    fn writer() -> Writer {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .height_mode(FitToText)
+            .build();
+
+        TextBoxWriter::new(Rectangle::new(Point::zero(), Point::new(47, 0)), style)
+    }
+
+    #[test]
+    fn formatted_text_is_drawn_like_a_text_box() {
+        let mut writer = writer();
+        let temperature = 21;
+        write!(writer, "T={}", temperature).unwrap();
+
+        let mut display = MockDisplay::new();
+        writer.draw(&mut display).unwrap();
+
+        let mut expected = MockDisplay::new();
+        TextBox::new("T=21", Rectangle::new(Point::zero(), Point::new(47, 0)))
+            .into_styled(writer.style)
+            .draw(&mut expected)
+            .unwrap();
+
+        assert_eq!(display, expected);
+        assert_eq!(writer.styled().size(), Size::new(48, 8));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let mut writer = writer();
+        let temperature = 2100;
+
+        assert!(write!(writer, "T={}°C", temperature).is_err());
+        assert_eq!(writer.text(), "T=2100");
+        assert!(writer.is_truncated());
+
+        writer.clear();
+        assert_eq!(writer.text(), "");
+        assert!(!writer.is_truncated());
+    }
+
+    #[test]
+    fn overflow_can_truncate_the_text() {
+        let mut writer = writer().with_truncation();
+        let temperature = 21000;
+
+        // the degree sign is two bytes long and doesn't fit into the last byte
+        assert!(write!(writer, "T={}°C", temperature).is_ok());
+        assert_eq!(writer.text(), "T=21000");
+        assert!(writer.is_truncated());
+    }
+}
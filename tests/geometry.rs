@@ -0,0 +1,149 @@
+//! Checks the geometry of text boxes through the public embedded-graphics API.
+//!
+//! Bounding boxes are created with `RectExt::from_size` and checked using `Dimensions`, and drawn
+//! pixels are compared to the expected area by position and size. No test depends on whether the
+//! corners of a `Rectangle` are inclusive, so these tests catch text that moves by one pixel when
+//! the representation of rectangles changes.
+use embedded_graphics::{
+    fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+};
+use embedded_text::{
+    prelude::*,
+    style::{vertical_overdraw::FullRowsOnly, Padding},
+    utils::rect_ext::RectExt,
+};
+
+fn bounds(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+    Rectangle::from_size(Point::new(x, y), Size::new(width, height))
+}
+
+/// Returns the top left corner and the size of the area covered by the drawn pixels.
+fn drawn_area(display: &MockDisplay<BinaryColor>) -> Option<(Point, Size)> {
+    let mut area: Option<(Point, Point)> = None;
+    for y in 0..64 {
+        for x in 0..64 {
+            if display.get_pixel(Point::new(x, y)).is_some() {
+                let p = Point::new(x, y);
+                area = Some(match area {
+                    None => (p, p),
+                    Some((min, max)) => (
+                        Point::new(min.x.min(x), min.y.min(y)),
+                        Point::new(max.x.max(x), max.y.max(y)),
+                    ),
+                });
+            }
+        }
+    }
+
+    area.map(|(min, max)| {
+        let extent = max - min;
+        (min, Size::new(extent.x as u32 + 1, extent.y as u32 + 1))
+    })
+}
+
+fn style<H: HeightMode>(
+    height_mode: H,
+) -> TextBoxStyle<BinaryColor, Font6x8, LeftAligned, TopAligned, H> {
+    TextBoxStyleBuilder::new(Font6x8)
+        .text_color(BinaryColor::On)
+        .background_color(BinaryColor::Off)
+        .height_mode(height_mode)
+        .build()
+}
+
+#[test]
+fn text_box_has_the_size_of_its_bounds() {
+    let text_box = TextBox::new("Hello", bounds(3, 4, 30, 16));
+
+    assert_eq!(text_box.top_left(), Point::new(3, 4));
+    assert_eq!(text_box.size(), Size::new(30, 16));
+
+    let moved = text_box.translate(Point::new(5, -2));
+    assert_eq!(moved.top_left(), Point::new(8, 2));
+    assert_eq!(moved.size(), Size::new(30, 16));
+}
+
+#[test]
+fn drawn_area_matches_the_bounds() {
+    let text_box =
+        TextBox::new("Hello\nWorld", bounds(3, 4, 30, 16)).into_styled(style(Exact(FullRowsOnly)));
+
+    let mut display = MockDisplay::new();
+    text_box.draw(&mut display).unwrap();
+
+    // the background fills every character cell
+    assert_eq!(
+        drawn_area(&display),
+        Some((Point::new(3, 4), Size::new(30, 16)))
+    );
+}
+
+#[test]
+fn fitted_height_is_a_multiple_of_the_line_height() {
+    for lines in 0..4 {
+        let text = &"1\n2\n3\n4"[..(2 * lines as usize).saturating_sub(1)];
+        let text_box = TextBox::new(text, bounds(0, 5, 12, 1)).into_styled(style(FitToText));
+
+        assert_eq!(text_box.top_left(), Point::new(0, 5));
+        assert_eq!(text_box.size(), Size::new(12, 8 * lines));
+    }
+}
+
+#[test]
+fn padding_is_subtracted_from_the_size() {
+    let style = TextBoxStyleBuilder::new(Font6x8)
+        .text_color(BinaryColor::On)
+        .padding(Padding {
+            top: 2,
+            right: 3,
+            bottom: 4,
+            left: 1,
+        })
+        .height_mode(FitToText)
+        .build();
+
+    let text_box = TextBox::new("Hello", bounds(0, 0, 34, 1)).into_styled(style);
+    let text_area = text_box.text_area();
+
+    assert_eq!(text_box.size(), Size::new(34, 14));
+    assert_eq!(text_area.top_left, Point::new(1, 2));
+    assert_eq!(RectExt::size(text_area), Size::new(30, 8));
+}
+
+#[test]
+fn text_bounds_cover_the_drawn_text() {
+    let text_box =
+        TextBox::new("Hello\nWorld!", bounds(2, 3, 60, 24)).into_styled(style(FitToText));
+    let text_bounds = text_box.text_bounds();
+
+    let mut display = MockDisplay::new();
+    text_box.draw(&mut display).unwrap();
+
+    assert_eq!(
+        drawn_area(&display),
+        Some((text_bounds.top_left, RectExt::size(text_bounds)))
+    );
+    assert_eq!(RectExt::size(text_bounds), Size::new(36, 16));
+}
+
+#[test]
+fn empty_text_box_draws_nothing() {
+    let text_box = TextBox::new("", bounds(3, 4, 30, 16)).into_styled(style(FitToText));
+
+    assert_eq!(text_box.top_left(), Point::new(3, 4));
+    assert_eq!(text_box.size(), Size::new(30, 0));
+
+    let mut display = MockDisplay::new();
+    text_box.draw(&mut display).unwrap();
+
+    assert_eq!(drawn_area(&display), None);
+}
+
+#[test]
+fn character_bounds_have_the_size_of_a_glyph_cell() {
+    let text_box = TextBox::new("Hello", bounds(3, 4, 60, 8)).into_styled(style(FitToText));
+    let bounds = text_box.char_bounds(1).unwrap();
+
+    assert_eq!(bounds.top_left, Point::new(9, 4));
+    assert_eq!(RectExt::size(bounds), Size::new(6, 8));
+}
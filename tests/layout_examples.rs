@@ -0,0 +1,338 @@
+//! Executable examples of the layout rules.
+//!
+//! Every test in this file pins down a number that users may depend on: where aligned text is
+//! placed, how far tabs advance, how far apart lines are and how tall a text box becomes. If a
+//! change breaks one of these tests, the change alters the layout of existing applications.
+//!
+//! All examples use `Font6x8`, so every character is 6 pixels wide and every line is 8 pixels
+//! tall.
+use embedded_graphics::{
+    fonts::Font6x8, mock_display::MockDisplay, pixelcolor::BinaryColor, prelude::*,
+};
+use embedded_text::{
+    prelude::*,
+    style::{vertical_overdraw::FullRowsOnly, TabSize},
+    utils::rect_ext::RectExt,
+};
+
+/// Returns a row of the display as a string, using the same characters as
+/// `MockDisplay::from_pattern`.
+fn row(display: &MockDisplay<BinaryColor>, y: i32, width: i32) -> String {
+    (0..width)
+        .map(|x| match display.get_pixel(Point::new(x, y)) {
+            Some(BinaryColor::On) => '#',
+            Some(BinaryColor::Off) => '.',
+            None => ' ',
+        })
+        .collect()
+}
+
+fn bounds(width: i32, height: i32) -> Rectangle {
+    Rectangle::new(Point::zero(), Point::new(width - 1, height - 1))
+}
+
+fn line(left: i32, top: i32, width: i32) -> Rectangle {
+    Rectangle::new(Point::new(left, top), Point::new(left + width - 1, top + 7))
+}
+
+mod horizontal_alignment {
+    use super::*;
+
+    #[test]
+    fn left_aligned_text_starts_at_the_left_edge() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(LeftAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("Hello", bounds(60, 8)).into_styled(style);
+
+        // x = 0
+        assert_eq!(text_box.text_bounds(), line(0, 0, 30));
+    }
+
+    #[test]
+    fn right_aligned_text_ends_at_the_right_edge() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("Hello", bounds(60, 8)).into_styled(style);
+
+        // x = box width - text width = 60 - 30
+        assert_eq!(text_box.text_bounds(), line(30, 0, 30));
+    }
+
+    #[test]
+    fn center_aligned_text_rounds_the_left_margin_up() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(CenterAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // x = (box width - text width + 1) / 2
+        let text_box = TextBox::new("Hello", bounds(60, 8)).into_styled(style);
+        assert_eq!(text_box.text_bounds(), line(15, 0, 30));
+
+        // an odd number of free pixels: (61 - 30 + 1) / 2 = 16
+        let text_box = TextBox::new("Hello", bounds(61, 8)).into_styled(style);
+        assert_eq!(text_box.text_bounds(), line(16, 0, 30));
+    }
+
+    #[test]
+    fn justified_lines_are_stretched_except_the_last_one() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(Justified)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("a b c d e f", bounds(60, 16)).into_styled(style);
+
+        // "a b c d e" is stretched to the full width, the last line is left aligned
+        let lines = text_box.line_bounds().collect::<Vec<_>>();
+        assert_eq!(lines, vec![line(0, 0, 60), line(0, 8, 6)]);
+
+        // the 4 gaps share the 60 - 5 * 6 = 30 free pixels, the first gaps are wider: 8, 8, 7, 7
+        for &(offset, x) in &[(0, 0), (2, 14), (4, 28), (6, 41), (8, 54)] {
+            assert_eq!(text_box.caret_position(offset), Point::new(x, 0));
+        }
+    }
+
+    #[test]
+    fn trailing_spaces_do_not_move_right_aligned_text() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .alignment(RightAligned)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("Hello   ", bounds(60, 8)).into_styled(style);
+
+        assert_eq!(text_box.text_bounds(), line(30, 0, 30));
+    }
+}
+
+mod vertical_alignment {
+    use super::*;
+
+    fn text_top<V: VerticalTextAlignment>(alignment: V) -> i32 {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(alignment)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("Hello", bounds(60, 24)).into_styled(style);
+
+        text_box.text_bounds().top_left.y
+    }
+
+    #[test]
+    fn vertical_offsets() {
+        // y = 0
+        assert_eq!(text_top(TopAligned), 0);
+
+        // y = (box height - text height) / 2 = (24 - 8) / 2
+        assert_eq!(text_top(CenterAligned), 8);
+
+        // y = box height - text height = 24 - 8
+        assert_eq!(text_top(BottomAligned), 16);
+    }
+
+    #[test]
+    fn space_between_distributes_the_free_space_between_lines() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .vertical_alignment(SpaceBetween)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // 40 - 3 * 8 = 16 free pixels, 8 between each line
+        let text_box = TextBox::new("a\nb\nc", bounds(60, 40)).into_styled(style);
+        let tops = text_box
+            .line_bounds()
+            .map(|line| line.top_left.y)
+            .collect::<Vec<_>>();
+        assert_eq!(tops, vec![0, 16, 32]);
+
+        // a single line is top aligned
+        let text_box = TextBox::new("a", bounds(60, 40)).into_styled(style);
+        assert_eq!(text_box.text_bounds().top_left.y, 0);
+    }
+}
+
+mod tabs {
+    use super::*;
+
+    #[test]
+    fn tabs_advance_to_the_next_multiple_of_four_spaces() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::new("a\tb\n1234\tc", bounds(120, 16)).into_styled(style);
+
+        // "b" is at the first tab stop, 4 * 6 = 24 pixels
+        assert_eq!(text_box.caret_position(2), Point::new(24, 0));
+
+        // a tab that starts at a tab stop advances to the next one
+        assert_eq!(text_box.caret_position(9), Point::new(48, 8));
+    }
+
+    #[test]
+    fn tab_width_can_be_set_in_pixels() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .tab_size(TabSize::pixels(10))
+            .build();
+
+        let text_box = TextBox::new("a\tb", bounds(120, 8)).into_styled(style);
+
+        assert_eq!(text_box.caret_position(2), Point::new(10, 0));
+    }
+}
+
+mod line_spacing {
+    use super::*;
+
+    fn line_tops(line_spacing: i32) -> Vec<i32> {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .line_spacing(line_spacing)
+            .build();
+
+        let text_box = TextBox::new("a b c", bounds(6, 40)).into_styled(style);
+
+        text_box.line_bounds().map(|line| line.top_left.y).collect()
+    }
+
+    #[test]
+    fn lines_advance_by_font_height_plus_line_spacing() {
+        assert_eq!(line_tops(0), vec![0, 8, 16]);
+        assert_eq!(line_tops(2), vec![0, 10, 20]);
+        assert_eq!(line_tops(-2), vec![0, 6, 12]);
+    }
+
+    #[test]
+    fn measured_height_includes_spacing_between_lines_only() {
+        for &(line_spacing, height) in &[(0, 24), (2, 28), (-2, 20)] {
+            let style = TextBoxStyleBuilder::new(Font6x8)
+                .text_color(BinaryColor::On)
+                .line_spacing(line_spacing)
+                .build();
+
+            // 3 * 8 + 2 * line spacing
+            assert_eq!(style.measure_text_height("a b c", 6), height);
+        }
+    }
+
+    #[test]
+    fn paragraph_spacing_is_added_after_line_breaks() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .paragraph_spacing(3)
+            .build();
+
+        // "a" | "b" are wrapped, "c" starts a new paragraph
+        let text_box = TextBox::new("a b\nc", bounds(6, 40)).into_styled(style);
+        let tops = text_box
+            .line_bounds()
+            .map(|line| line.top_left.y)
+            .collect::<Vec<_>>();
+
+        assert_eq!(tops, vec![0, 8, 19]);
+        assert_eq!(style.measure_text_height("a b\nc", 6), 3 * 8 + 3);
+    }
+
+    #[test]
+    fn overlapping_lines_are_drawn_over_each_other() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .line_spacing(-4)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new("_ O", bounds(6, 12))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        // the second line starts at 8 - 4 = 4
+        assert_eq!(row(&display, 4, 6), " ###  ");
+
+        // row 7 contains both the underscore and the 4th row of the "O"
+        assert_eq!(row(&display, 7, 6), "##### ");
+    }
+}
+
+mod height_modes {
+    use super::*;
+
+    const TEXT: &str = "Hello, World!";
+
+    fn height<H: HeightMode>(height_mode: H, box_height: i32) -> u32 {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .height_mode(height_mode)
+            .build();
+
+        // two lines: "Hello," and "World!"
+        let text_box = TextBox::new(TEXT, bounds(60, box_height)).into_styled(style);
+
+        RectExt::size(text_box.text_box.bounds).height
+    }
+
+    #[test]
+    fn exact_keeps_the_height() {
+        assert_eq!(height(Exact(FullRowsOnly), 40), 40);
+        assert_eq!(height(Exact(FullRowsOnly), 4), 4);
+    }
+
+    #[test]
+    fn fit_to_text_sets_the_height_of_the_lines() {
+        // 2 * 8
+        assert_eq!(height(FitToText, 40), 16);
+        assert_eq!(height(FitToText, 4), 16);
+    }
+
+    #[test]
+    fn fit_to_text_limited_does_not_grow_over_the_limit() {
+        assert_eq!(height(FitToTextLimited(12, FullRowsOnly), 40), 12);
+        assert_eq!(height(FitToTextLimited(20, FullRowsOnly), 40), 16);
+    }
+
+    #[test]
+    fn shrink_to_text_only_shrinks() {
+        assert_eq!(height(ShrinkToText(FullRowsOnly), 40), 16);
+        assert_eq!(height(ShrinkToText(FullRowsOnly), 10), 10);
+    }
+
+    #[test]
+    fn fit_to_text_includes_line_spacing() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .line_spacing(3)
+            .height_mode(FitToText)
+            .build();
+
+        let text_box = TextBox::new(TEXT, bounds(60, 8)).into_styled(style);
+
+        // 2 * 8 + 1 * 3
+        assert_eq!(RectExt::size(text_box.text_box.bounds).height, 19);
+    }
+
+    #[test]
+    fn full_rows_only_hides_partial_lines() {
+        let style = TextBoxStyleBuilder::new(Font6x8)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::new(TEXT, bounds(60, 12))
+            .into_styled(style)
+            .draw(&mut display)
+            .unwrap();
+
+        // the second line does not fit, so nothing is drawn below the first one
+        assert_eq!(row(&display, 8, 60), " ".repeat(60));
+    }
+}
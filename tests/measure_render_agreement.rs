@@ -0,0 +1,188 @@
+//! Checks that measurement agrees with rendering.
+//!
+//! Every text of the corpus is rendered at every width from 1 to 120 pixels, using every
+//! horizontal alignment. The number of rendered lines must be equal to the measured line count,
+//! and every drawn pixel must be inside the measured height and the width of the text box.
+use core::convert::Infallible;
+use embedded_graphics::{fonts::Font6x8, pixelcolor::BinaryColor, prelude::*, DrawTarget};
+use embedded_text::{
+    chars::{CJK_NO_BREAK_AFTER, CJK_NO_BREAK_BEFORE},
+    prelude::*,
+};
+
+const CORPUS: &[&str] = &[
+    "Hello, World!",
+    "The quick brown fox jumps over the lazy dog.",
+    "trailing spaces   ",
+    "   leading spaces",
+    "multiple    spaces    between    words",
+    "supercalifragilisticexpialidocious",
+    "a supercalifragilisticexpialidocious word",
+    "soft\u{ad}hyphen\u{ad}ated\u{ad}words are\u{ad}wrapped",
+    "\u{ad}\u{ad}leading and trailing soft hyphens\u{ad}\u{ad}",
+    "zero\u{200b}width\u{200b}space\u{200b}separated\u{200b}words",
+    "spaces \u{200b} around \u{200b}\u{200b} zero width spaces",
+    "no\u{a0}break\u{a0}spaces glue\u{a0}words\u{a0}together",
+    "ab\u{a0}cd\u{a0}efghij ghijkl",
+    "ab\u{a0}",
+    "x\u{202f}",
+    "narrow\u{202f}no-break and figure\u{2007}spaces",
+    "non\u{2011}breaking\u{2011}hyphens",
+    "em\u{2003}and\u{2002}en\u{2009}thin spaces",
+    "\ttab at the start",
+    "tabs\tin\tthe\tmiddle\tof\ttext",
+    "trailing tab\t",
+    "tab\t\t\ttabs\t\tand spaces \t mixed",
+    "line\nbreaks\n\nand\n\n\nblank lines",
+    "\n\nleading newlines",
+    "trailing newlines\n\n",
+    "carriage\rreturn overwrites\rtext",
+    "vertical\x0btab and\x0cform feed",
+    "\x1b[4munderlined\x1b[24m and \x1b[9mcrossed out\x1b[29m words",
+    "\x1b[31mcol\x1b[32mored\x1b[0m text with \x1b[41mbackground\x1b[49m",
+    "cursor\x1b[3Cforward and\x1b[2Dback",
+    "\x1b[4ma\nb",
+    "escape\x1b[1;2;3min\x1b[4mthe\x1b[0mmiddle of words",
+    "long\u{ad}words\u{200b}with\tevery\u{a0}kind of \x1b[4mspecial\x1b[24m\u{ad}character",
+    "これは\u{200b}ペン\u{200b}です\u{200b}。「\u{200b}引用」\u{200b}です、\u{200b}はい。",
+    "日本語の文章です。次の文章です、",
+    "  \t \n \u{200b} \n\u{ad}",
+];
+
+/// A display that records the extent of the drawn pixels.
+struct Canvas {
+    max_x: i32,
+    max_y: i32,
+    min: Point,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self {
+            max_x: -1,
+            max_y: -1,
+            min: Point::zero(),
+        }
+    }
+}
+
+impl DrawTarget<BinaryColor> for Canvas {
+    type Error = Infallible;
+
+    fn draw_pixel(&mut self, Pixel(point, _): Pixel<BinaryColor>) -> Result<(), Self::Error> {
+        self.max_x = self.max_x.max(point.x);
+        self.max_y = self.max_y.max(point.y);
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        Size::new(120, 4096)
+    }
+}
+
+macro_rules! check_alignment {
+    ($alignment:expr) => {
+        check_alignment!($alignment, 0);
+    };
+    ($alignment:expr, $letter_spacing:expr) => {
+        check_alignment!($alignment, $letter_spacing, (0, 0));
+    };
+    ($alignment:expr, $letter_spacing:expr, $indent:expr) => {
+        let (first_line_indent, hanging_indent) = $indent;
+        for &text in CORPUS {
+            for width in 1..=120 {
+                let style = TextBoxStyleBuilder::new(Font6x8)
+                    .alignment($alignment)
+                    .letter_spacing($letter_spacing)
+                    .first_line_indent(first_line_indent)
+                    .hanging_indent(hanging_indent)
+                    .no_break_before(CJK_NO_BREAK_BEFORE)
+                    .no_break_after(CJK_NO_BREAK_AFTER)
+                    .text_color(BinaryColor::On)
+                    .background_color(BinaryColor::Off)
+                    .build();
+                let bounds = Rectangle::new(Point::zero(), Point::new(width - 1, 4095));
+
+                let mut canvas = Canvas::new();
+                let result = TextBox::new(text, bounds)
+                    .into_styled(style)
+                    .draw_with_result(&mut canvas)
+                    .unwrap();
+
+                let lines = style.measure_line_count(text, width as u32);
+                let height = style.measure_text_height(text, width as u32) as i32;
+
+                assert_eq!(
+                    result.lines,
+                    lines,
+                    "line count of {:?} at width {} using {:?} with letter spacing {} and indentation {:?}",
+                    text,
+                    width,
+                    $alignment,
+                    $letter_spacing,
+                    $indent
+                );
+                assert!(
+                    canvas.max_y < height,
+                    "{:?} is drawn below the measured height {} at width {} using {:?} with letter spacing {} and indentation {:?}",
+                    text,
+                    height,
+                    width,
+                    $alignment,
+                    $letter_spacing,
+                    $indent
+                );
+                assert!(
+                    canvas.min.x >= 0 && canvas.min.y >= 0 && canvas.max_x < width,
+                    "{:?} is drawn outside of the text box at width {} using {:?} with letter spacing {} and indentation {:?}",
+                    text,
+                    width,
+                    $alignment,
+                    $letter_spacing,
+                    $indent
+                );
+            }
+        }
+    };
+}
+
+#[test]
+fn left_aligned() {
+    check_alignment!(LeftAligned);
+}
+
+#[test]
+fn center_aligned() {
+    check_alignment!(CenterAligned);
+}
+
+#[test]
+fn right_aligned() {
+    check_alignment!(RightAligned);
+}
+
+#[test]
+fn justified() {
+    check_alignment!(Justified);
+}
+
+#[test]
+fn letter_spacing() {
+    for &letter_spacing in &[-2, 1, 3] {
+        check_alignment!(LeftAligned, letter_spacing);
+        check_alignment!(CenterAligned, letter_spacing);
+        check_alignment!(RightAligned, letter_spacing);
+        check_alignment!(Justified, letter_spacing);
+    }
+}
+
+#[test]
+fn indentation() {
+    for &indent in &[(12, 0), (0, 18), (-18, 0), (-7, 5), (200, 200)] {
+        check_alignment!(LeftAligned, 0, indent);
+        check_alignment!(CenterAligned, 0, indent);
+        check_alignment!(RightAligned, 0, indent);
+        check_alignment!(Justified, 0, indent);
+    }
+}